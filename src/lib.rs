@@ -7,12 +7,13 @@
 //! complexity of inserting, deleting or replacing a piece of text is always
 //! logarithmic in the size of the `Rope`.
 //!
-//! The crate has a relatively straightforward API. There are 3 structs to be
+//! The crate has a relatively straightforward API. There are 4 structs to be
 //! aware of:
 //!
 //! - [`Rope`]: the star of the crate;
 //! - [`RopeSlice`]: an immutable slice of a `Rope`;
-//! - [`RopeBuilder`]: an incremental `Rope` builder.
+//! - [`RopeBuilder`]: an incremental `Rope` builder;
+//! - [`RopeCursor`]: a cursor that can be moved back and forth over a `Rope`.
 //!
 //! plus the [`iter`] module which contains iterators over `Rope`s and
 //! `RopeSlice`s. That's it.
@@ -130,14 +131,63 @@
 //!
 //! - `simd` (enabled by default): enables SIMD on supported platforms;
 //!
+//! - `char-metric` (disabled by default): makes the `Rope` and `RopeSlice`
+//!   track the number of [`char`]s they contain, allowing them to efficiently
+//!   convert char offsets to and from byte offsets in logarithmic time;
+//!
 //! - `graphemes` (disabled by default): enables a few grapheme-oriented APIs
 //!   on `Rope`s and `RopeSlice`s such as the
 //!   [`Graphemes`](crate::iter::Graphemes) iterator and others;
 //!
+//! - `grapheme-metric` (disabled by default): makes the `Rope` index its
+//!   extended grapheme clusters, allowing it to efficiently convert grapheme
+//!   offsets to and from byte offsets in logarithmic time. Implies
+//!   `graphemes`;
+//!
+//! - `lsp` (disabled by default): enables the [`lsp`] module and the
+//!   [`Rope::apply_content_changes()`] and [`Rope::apply_text_edits()`]
+//!   methods, which apply the edits sent by a Language Server Protocol
+//!   client. Implies `utf16-metric`;
+//!
+//! - `unicode-lines` (disabled by default): besides `\n`, also treats the
+//!   other single-char line terminators defined by Unicode as line breaks,
+//!   i.e. vertical tab (U+000B), form feed (U+000C), carriage return (U+000D),
+//!   next line (U+0085), line separator (U+2028) and paragraph separator
+//!   (U+2029). This affects every line-oriented API, from
+//!   [`line_len()`](crate::Rope::line_len()) to the
+//!   [`Lines`](crate::iter::Lines) and [`RawLines`](crate::iter::RawLines)
+//!   iterators. A `\r` followed by a `\n` still counts as a single line
+//!   break;
+//!
+//! - `regex` (disabled by default): enables the [`regex`] module, whose
+//!   [`Regex`](crate::regex::Regex) finds the matches and capture groups of a
+//!   regular expression in a `Rope` or `RopeSlice` by running the lazy DFAs
+//!   of [regex-automata](https://docs.rs/regex-automata) over its chunks.
+//!   Implies `std`;
+//!
+//! - `sentences` (disabled by default): enables the
+//!   [`Sentences`](crate::iter::Sentences) iterator, which segments a `Rope`
+//!   or `RopeSlice` into Unicode sentences;
+//!
+//! - `tree-sitter` (disabled by default): enables
+//!   [`Rope::tree_sitter_parse()`] and a few other APIs to parse a `Rope` with
+//!   [tree-sitter](https://tree-sitter.github.io) incrementally and without
+//!   copying its text. Implies `std`;
+//!
+//! - `unicode-width` (disabled by default): makes the `Rope` and `RopeSlice`
+//!   track the number of columns their text takes up in a terminal according
+//!   to the East Asian Width rules, allowing them to convert between byte
+//!   offsets and display columns in logarithmic time;
+//!
 //! - `utf16-metric` (disabled by default): makes the `Rope` and `RopeSlice`
 //!   track the UTF-16 code units they'd have if their content was stored as
 //!   UTF-16 instead of UTF-8, allowing them to efficiently convert UTF-16
-//!   code unit offsets to and from byte offsets in logarithmic time.
+//!   code unit offsets to and from byte offsets in logarithmic time;
+//!
+//! - `words` (disabled by default): enables the
+//!   [`WordBounds`](crate::iter::WordBounds) and [`Words`](crate::iter::Words)
+//!   iterators and a few other APIs to find the Unicode word boundaries of a
+//!   `Rope` or `RopeSlice`, e.g. to implement word-wise cursor movements.
 
 #![cfg_attr(not(any(test, feature = "std")), no_std)]
 #![allow(clippy::explicit_auto_deref)]
@@ -159,6 +209,25 @@ pub mod iter {
     pub use crate::rope::iterators::*;
 }
 
+#[cfg_attr(docsrs, doc(cfg(feature = "lsp")))]
+#[cfg(feature = "lsp")]
+pub mod lsp {
+    //! Types used to apply the edits sent by a Language Server Protocol
+    //! client to a [`Rope`](crate::Rope).
+
+    pub use crate::rope::lsp::*;
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "regex")))]
+#[cfg(feature = "regex")]
+pub mod regex {
+    //! Regex searches over [`Rope`](crate::Rope)s and
+    //! [`RopeSlice`](crate::RopeSlice)s which don't need to copy their text
+    //! into a contiguous string.
+
+    pub use crate::rope::regex::*;
+}
+
 mod rope;
 
 #[doc(hidden)]
@@ -172,7 +241,22 @@ pub use rope::{
     gap_slice::GapSlice,
     metrics::ChunkSummary,
 };
-pub use rope::{Rope, RopeBuilder, RopeSlice};
+pub use rope::{
+    DetectedLineEnding,
+    FoldMap,
+    InlayMap,
+    LineEnding,
+    Pattern,
+    Point,
+    PositionEncoding,
+    Rope,
+    RopeBuilder,
+    RopeCursor,
+    RopeError,
+    RopeSlice,
+    Unit,
+    WrapMap,
+};
 
 #[inline]
 pub(crate) fn range_bounds_to_start_end<T, B>(
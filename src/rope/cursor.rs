@@ -0,0 +1,464 @@
+use super::metrics::ByteMetric;
+use super::utils::panic_messages as panic;
+use super::RopeSlice;
+
+/// A cursor over a [`Rope`](crate::Rope) or a [`RopeSlice`] which can be
+/// placed at any byte offset and moved back and forth from there.
+///
+/// A `RopeCursor` is created by calling [`Rope::cursor_at()`] or
+/// [`RopeSlice::cursor_at()`], which run in logarithmic time. Moving it by a
+/// byte or a char runs in constant time as long as it doesn't leave the
+/// current chunk, and in logarithmic time otherwise.
+///
+/// [`Rope::cursor_at()`]: crate::Rope::cursor_at()
+///
+/// # Examples
+///
+/// ```
+/// # use crop::Rope;
+/// #
+/// let r = Rope::from("foo\nb🐸r\n");
+///
+/// let mut cursor = r.cursor_at(5);
+///
+/// assert_eq!(cursor.next_char(), Some('🐸'));
+/// assert_eq!(cursor.byte_offset(), 9);
+///
+/// assert_eq!(cursor.prev_line().unwrap(), "b🐸");
+/// assert_eq!(cursor.prev_line().unwrap(), "foo\n");
+/// assert_eq!(cursor.prev_line(), None);
+///
+/// assert_eq!(cursor.next_line().unwrap(), "foo\n");
+/// assert_eq!(cursor.line_offset(), 1);
+/// ```
+#[derive(Clone)]
+pub struct RopeCursor<'a> {
+    slice: RopeSlice<'a>,
+    byte_offset: usize,
+
+    /// The last chunk the cursor was in. This is empty until the cursor is
+    /// moved for the first time.
+    chunk: &'a str,
+
+    /// The byte offset of `chunk` in `slice`.
+    chunk_byte_offset: usize,
+}
+
+impl<'a> RopeCursor<'a> {
+    /// Returns the byte offset of the cursor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo");
+    /// let mut cursor = r.cursor_at(1);
+    ///
+    /// assert_eq!(cursor.byte_offset(), 1);
+    ///
+    /// cursor.next_byte();
+    /// assert_eq!(cursor.byte_offset(), 2);
+    /// ```
+    #[inline]
+    pub fn byte_offset(&self) -> usize {
+        self.byte_offset
+    }
+
+    /// Returns the chunk containing the byte at the given index, together
+    /// with its byte offset, loading it from the tree if it's not the current
+    /// one.
+    #[inline]
+    fn chunk_containing(&mut self, byte_index: usize) -> (&'a str, usize) {
+        debug_assert!(byte_index < self.slice.byte_len());
+
+        if byte_index < self.chunk_byte_offset
+            || byte_index >= self.chunk_byte_offset + self.chunk.len()
+        {
+            let (chunk, ByteMetric(chunk_byte_offset)) = self
+                .slice
+                .tree_slice
+                .leaf_at_measure(ByteMetric(byte_index + 1));
+
+            if byte_index - chunk_byte_offset < chunk.len_left() {
+                self.chunk = chunk.left_chunk();
+                self.chunk_byte_offset = chunk_byte_offset;
+            } else {
+                self.chunk = chunk.right_chunk();
+                self.chunk_byte_offset = chunk_byte_offset + chunk.len_left();
+            }
+        }
+
+        (self.chunk, self.chunk_byte_offset)
+    }
+
+    /// Returns the line offset of the cursor, i.e. the index of the line it's
+    /// on.
+    ///
+    /// This runs in logarithmic time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo\nbar\n");
+    ///
+    /// assert_eq!(r.cursor_at(3).line_offset(), 0);
+    /// assert_eq!(r.cursor_at(4).line_offset(), 1);
+    /// assert_eq!(r.cursor_at(8).line_offset(), 2);
+    /// ```
+    #[inline]
+    pub fn line_offset(&self) -> usize {
+        self.slice.line_of_byte(self.byte_offset)
+    }
+
+    #[inline]
+    pub(super) fn new(slice: RopeSlice<'a>, byte_offset: usize) -> Self {
+        let mut cursor =
+            Self { slice, byte_offset: 0, chunk: "", chunk_byte_offset: 0 };
+        cursor.seek(byte_offset);
+        cursor
+    }
+
+    /// Moves the cursor forward by one byte, returning the byte it moved
+    /// over, or `None` if the cursor was already at the end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("ab");
+    /// let mut cursor = r.cursor_at(0);
+    ///
+    /// assert_eq!(cursor.next_byte(), Some(b'a'));
+    /// assert_eq!(cursor.next_byte(), Some(b'b'));
+    /// assert_eq!(cursor.next_byte(), None);
+    /// ```
+    #[inline]
+    pub fn next_byte(&mut self) -> Option<u8> {
+        if self.byte_offset == self.slice.byte_len() {
+            return None;
+        }
+
+        let (chunk, chunk_byte_offset) =
+            self.chunk_containing(self.byte_offset);
+
+        let byte = chunk.as_bytes()[self.byte_offset - chunk_byte_offset];
+
+        self.byte_offset += 1;
+
+        Some(byte)
+    }
+
+    /// Moves the cursor forward to the end of the char containing the byte
+    /// after it, returning that char, or `None` if the cursor was already at
+    /// the end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("a🐸");
+    /// let mut cursor = r.cursor_at(0);
+    ///
+    /// assert_eq!(cursor.next_char(), Some('a'));
+    /// assert_eq!(cursor.next_char(), Some('🐸'));
+    /// assert_eq!(cursor.byte_offset(), 5);
+    /// assert_eq!(cursor.next_char(), None);
+    /// ```
+    #[inline]
+    pub fn next_char(&mut self) -> Option<char> {
+        if self.byte_offset == self.slice.byte_len() {
+            return None;
+        }
+
+        let (chunk, chunk_byte_offset) =
+            self.chunk_containing(self.byte_offset);
+
+        let mut char_start = self.byte_offset - chunk_byte_offset;
+
+        while !chunk.is_char_boundary(char_start) {
+            char_start -= 1;
+        }
+
+        // SAFETY: the byte at `char_start` is part of the chunk, so there's
+        // at least one char after it.
+        let ch =
+            unsafe { chunk[char_start..].chars().next().unwrap_unchecked() };
+
+        self.byte_offset = chunk_byte_offset + char_start + ch.len_utf8();
+
+        Some(ch)
+    }
+
+    /// Moves the cursor forward to the next grapheme boundary, returning the
+    /// grapheme it moved over, or `None` if the cursor was already at the
+    /// end.
+    ///
+    /// This runs in logarithmic time plus the size of the grapheme.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cursor is not on a char boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("a\r\n🇷🇸");
+    /// let mut cursor = r.cursor_at(0);
+    ///
+    /// assert_eq!(cursor.next_grapheme().unwrap(), "a");
+    /// assert_eq!(cursor.next_grapheme().unwrap(), "\r\n");
+    /// assert_eq!(cursor.next_grapheme().unwrap(), "🇷🇸");
+    /// assert_eq!(cursor.next_grapheme(), None);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "graphemes")))]
+    #[cfg(feature = "graphemes")]
+    #[track_caller]
+    #[inline]
+    pub fn next_grapheme(&mut self) -> Option<RopeSlice<'a>> {
+        if self.byte_offset == self.slice.byte_len() {
+            return None;
+        }
+
+        let start = self.byte_offset;
+        let end = self.slice.next_grapheme_boundary(start);
+        self.byte_offset = end;
+        Some(self.slice.byte_slice(start..end))
+    }
+
+    /// Moves the cursor forward to the start of the next line, or to the end
+    /// if it's on the last line, returning the text it moved over (including
+    /// the line break), or `None` if the cursor was already at the end.
+    ///
+    /// This runs in logarithmic time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cursor is not on a char boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo\r\nbar");
+    /// let mut cursor = r.cursor_at(1);
+    ///
+    /// assert_eq!(cursor.next_line().unwrap(), "oo\r\n");
+    /// assert_eq!(cursor.next_line().unwrap(), "bar");
+    /// assert_eq!(cursor.next_line(), None);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn next_line(&mut self) -> Option<RopeSlice<'a>> {
+        if self.byte_offset == self.slice.byte_len() {
+            return None;
+        }
+
+        let start = self.byte_offset;
+        let line = self.slice.line_of_byte(start);
+        let end = self.slice.byte_of_line(line + 1);
+        self.byte_offset = end;
+        Some(self.slice.byte_slice(start..end))
+    }
+
+    /// Moves the cursor backward by one byte, returning the byte it moved
+    /// over, or `None` if the cursor was already at the start.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("ab");
+    /// let mut cursor = r.cursor_at(2);
+    ///
+    /// assert_eq!(cursor.prev_byte(), Some(b'b'));
+    /// assert_eq!(cursor.prev_byte(), Some(b'a'));
+    /// assert_eq!(cursor.prev_byte(), None);
+    /// ```
+    #[inline]
+    pub fn prev_byte(&mut self) -> Option<u8> {
+        if self.byte_offset == 0 {
+            return None;
+        }
+
+        let (chunk, chunk_byte_offset) =
+            self.chunk_containing(self.byte_offset - 1);
+
+        self.byte_offset -= 1;
+
+        Some(chunk.as_bytes()[self.byte_offset - chunk_byte_offset])
+    }
+
+    /// Moves the cursor backward to the start of the char containing the
+    /// byte before it, returning that char, or `None` if the cursor was
+    /// already at the start.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("a🐸");
+    /// let mut cursor = r.cursor_at(5);
+    ///
+    /// assert_eq!(cursor.prev_char(), Some('🐸'));
+    /// assert_eq!(cursor.byte_offset(), 1);
+    /// assert_eq!(cursor.prev_char(), Some('a'));
+    /// assert_eq!(cursor.prev_char(), None);
+    /// ```
+    #[inline]
+    pub fn prev_char(&mut self) -> Option<char> {
+        if self.byte_offset == 0 {
+            return None;
+        }
+
+        let (chunk, chunk_byte_offset) =
+            self.chunk_containing(self.byte_offset - 1);
+
+        let mut char_start = self.byte_offset - 1 - chunk_byte_offset;
+
+        while !chunk.is_char_boundary(char_start) {
+            char_start -= 1;
+        }
+
+        // SAFETY: the byte at `char_start` is part of the chunk, so there's
+        // at least one char after it.
+        let ch =
+            unsafe { chunk[char_start..].chars().next().unwrap_unchecked() };
+
+        self.byte_offset = chunk_byte_offset + char_start;
+
+        Some(ch)
+    }
+
+    /// Moves the cursor backward to the previous grapheme boundary,
+    /// returning the grapheme it moved over, or `None` if the cursor was
+    /// already at the start.
+    ///
+    /// This runs in logarithmic time plus the size of the grapheme.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cursor is not on a char boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("a\r\n🇷🇸");
+    /// let mut cursor = r.cursor_at(r.byte_len());
+    ///
+    /// assert_eq!(cursor.prev_grapheme().unwrap(), "🇷🇸");
+    /// assert_eq!(cursor.prev_grapheme().unwrap(), "\r\n");
+    /// assert_eq!(cursor.prev_grapheme().unwrap(), "a");
+    /// assert_eq!(cursor.prev_grapheme(), None);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "graphemes")))]
+    #[cfg(feature = "graphemes")]
+    #[track_caller]
+    #[inline]
+    pub fn prev_grapheme(&mut self) -> Option<RopeSlice<'a>> {
+        if self.byte_offset == 0 {
+            return None;
+        }
+
+        let end = self.byte_offset;
+        let start = self.slice.prev_grapheme_boundary(end);
+        self.byte_offset = start;
+        Some(self.slice.byte_slice(start..end))
+    }
+
+    /// Moves the cursor backward to the start of the line it's on, or to the
+    /// start of the previous line if it's already at the start of a line,
+    /// returning the text it moved over, or `None` if the cursor was already
+    /// at the start.
+    ///
+    /// This runs in logarithmic time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cursor is not on a char boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo\nbar");
+    /// let mut cursor = r.cursor_at(6);
+    ///
+    /// assert_eq!(cursor.prev_line().unwrap(), "ba");
+    /// assert_eq!(cursor.prev_line().unwrap(), "foo\n");
+    /// assert_eq!(cursor.prev_line(), None);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn prev_line(&mut self) -> Option<RopeSlice<'a>> {
+        if self.byte_offset == 0 {
+            return None;
+        }
+
+        let end = self.byte_offset;
+        let line = self.slice.line_of_byte(end);
+        let mut start = self.slice.byte_of_line(line);
+
+        if start == end {
+            start = self.slice.byte_of_line(line - 1);
+        }
+
+        self.byte_offset = start;
+        Some(self.slice.byte_slice(start..end))
+    }
+
+    /// Moves the cursor to the given byte offset.
+    ///
+    /// The offset doesn't need to lie on a char boundary, but the methods
+    /// moving by graphemes or lines will panic if it doesn't.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than the
+    /// length of the `Rope` or `RopeSlice` the cursor was created from).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo\nbar");
+    /// let mut cursor = r.cursor_at(0);
+    ///
+    /// cursor.seek(5);
+    /// assert_eq!(cursor.next_char(), Some('a'));
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn seek(&mut self, byte_offset: usize) {
+        if byte_offset > self.slice.byte_len() {
+            panic::byte_offset_out_of_bounds(
+                byte_offset,
+                self.slice.byte_len(),
+            );
+        }
+
+        self.byte_offset = byte_offset;
+    }
+}
+
+impl core::fmt::Debug for RopeCursor<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RopeCursor")
+            .field("byte_offset", &self.byte_offset)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,58 @@
+//! Conversions between byte offsets and the columns text takes up when
+//! displayed in a terminal.
+
+use super::metrics::{ByteMetric, DisplayWidthMetric};
+use super::utils::panic_messages as panic;
+use super::RopeSlice;
+
+/// Returns the number of columns the slice takes up.
+#[inline]
+pub(super) fn width(slice: RopeSlice<'_>) -> usize {
+    slice.tree_slice.summary().display_width()
+}
+
+/// Returns the byte offset of the char displayed at the given column of the
+/// line, or the length of the line if the column is equal to its width.
+///
+/// Zero-width chars are attributed to the char before them, so the offset is
+/// never between a char and the combining marks following it.
+#[track_caller]
+#[inline]
+pub(super) fn byte_of_column(line: RopeSlice<'_>, column: usize) -> usize {
+    let line_width = width(line);
+
+    if column > line_width {
+        panic::display_column_out_of_bounds(column, line_width);
+    }
+
+    if column == line_width {
+        return line.byte_len();
+    }
+
+    // This is the end of the first char whose last column is past the
+    // requested one, i.e. of the char displayed at that column.
+    let ByteMetric(char_end) =
+        line.tree_slice.convert_measure(DisplayWidthMetric(column + 1));
+
+    let ch = line.chars_at(char_end).prev().unwrap();
+
+    char_end - ch.len_utf8()
+}
+
+/// Returns the column of the given byte offset within its line.
+#[track_caller]
+#[inline]
+pub(super) fn column_of_byte(
+    slice: RopeSlice<'_>,
+    byte_offset: usize,
+) -> usize {
+    let line_start = slice.byte_of_line(slice.line_of_byte(byte_offset));
+
+    let DisplayWidthMetric(line_start_column) =
+        slice.tree_slice.convert_measure(ByteMetric(line_start));
+
+    let DisplayWidthMetric(column) =
+        slice.tree_slice.convert_measure(ByteMetric(byte_offset));
+
+    column - line_start_column
+}
@@ -0,0 +1,137 @@
+//! The error type returned by the fallible methods of `Rope`s and
+//! `RopeSlice`s.
+
+/// The unit an offset, an index or a length passed to or returned by a
+/// [`RopeError`] is measured in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Unit {
+    /// Bytes.
+    Byte,
+
+    /// Unicode scalar values.
+    Char,
+
+    /// Extended grapheme clusters.
+    Grapheme,
+
+    /// Lines.
+    Line,
+
+    /// UTF-16 code units.
+    Utf16CodeUnit,
+}
+
+impl core::fmt::Display for Unit {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::Byte => "byte",
+            Self::Char => "char",
+            Self::Grapheme => "grapheme",
+            Self::Line => "line",
+            Self::Utf16CodeUnit => "UTF-16",
+        })
+    }
+}
+
+/// The error returned by the `try_*` methods of [`Rope`](crate::Rope) and
+/// [`RopeSlice`](crate::RopeSlice) in all the cases where their panicking
+/// counterparts would panic.
+///
+/// # Examples
+///
+/// ```
+/// # use crop::{Rope, RopeError, Unit};
+/// #
+/// let r = Rope::from("Hello, 世界!");
+///
+/// assert_eq!(
+///     r.try_byte_slice(..42),
+///     Err(RopeError::OffsetOutOfBounds {
+///         unit: Unit::Byte,
+///         offset: 42,
+///         len: 14
+///     })
+/// );
+///
+/// assert_eq!(
+///     r.try_byte_slice(..8),
+///     Err(RopeError::NotCharBoundary { byte_offset: 8 })
+/// );
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RopeError {
+    /// An index was greater than or equal to the length of the text.
+    IndexOutOfBounds {
+        /// The unit of the index and of the length.
+        unit: Unit,
+
+        /// The index.
+        index: usize,
+
+        /// The length of the text.
+        len: usize,
+    },
+
+    /// An offset was greater than the length of the text.
+    OffsetOutOfBounds {
+        /// The unit of the offset and of the length.
+        unit: Unit,
+
+        /// The offset.
+        offset: usize,
+
+        /// The length of the text.
+        len: usize,
+    },
+
+    /// A byte offset was inside a multi-byte character.
+    NotCharBoundary {
+        /// The byte offset.
+        byte_offset: usize,
+    },
+
+    /// The start of a range was greater than its end.
+    StartAfterEnd {
+        /// The unit of the range.
+        unit: Unit,
+
+        /// The start of the range.
+        start: usize,
+
+        /// The end of the range.
+        end: usize,
+    },
+}
+
+impl core::fmt::Display for RopeError {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::IndexOutOfBounds { unit, index, len } => write!(
+                f,
+                "{unit} index out of bounds: the index is {index} but the \
+                 length is {len}"
+            ),
+
+            Self::OffsetOutOfBounds { unit, offset, len } => write!(
+                f,
+                "{unit} offset out of bounds: the offset is {offset} but the \
+                 length is {len}"
+            ),
+
+            Self::NotCharBoundary { byte_offset } => {
+                write!(f, "byte offset {byte_offset} is not a char boundary")
+            },
+
+            Self::StartAfterEnd { unit, start, end } => write!(
+                f,
+                "{unit} start after end: the start is {start} but the end is \
+                 {end}"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RopeError {}
@@ -0,0 +1,1619 @@
+//! This module contains the [`FoldMap`], which collapses byte ranges of a
+//! `Rope` into placeholders.
+//!
+//! The map is a `Tree` whose leaves store the `Rope`'s text as a sequence of
+//! segments, each of them either visible or folded. Every segment knows its
+//! byte length and how many line breaks it contains both before and after
+//! folding, so summing up the summaries of the leaves is enough to translate
+//! offsets and lines between the two coordinate systems in logarithmic time.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::{Add, AddAssign, Range, RangeBounds, Sub, SubAssign};
+
+use super::iterators::Chunks;
+use super::metrics::{ByteMetric, RawLineMetric};
+use super::utils::panic_messages as panic;
+use super::Rope;
+use crate::range_bounds_to_start_end;
+use crate::tree::{
+    AsSlice,
+    BalancedLeaf,
+    BaseMeasured,
+    Leaves,
+    Metric,
+    ReplaceableLeaf,
+    SlicingMetric,
+    Summarize,
+    Tree,
+};
+
+#[cfg(any(test, feature = "small_chunks"))]
+const MAX_SEGMENTS: usize = 4;
+
+#[cfg(not(any(test, feature = "small_chunks")))]
+const MAX_SEGMENTS: usize = 64;
+
+const MIN_SEGMENTS: usize = MAX_SEGMENTS / 4;
+
+type FoldTree = Tree<{ Rope::arity() }, FoldSegments>;
+
+/// A set of folds hiding byte ranges of a [`Rope`], each of them displayed
+/// as a placeholder string.
+///
+/// The folds are kept sorted and never overlap: folding a range which
+/// overlaps existing folds merges them all into a single one, while folds
+/// which only touch each other are kept separate.
+///
+/// The map doesn't hold on to the `Rope`, so the methods that need to look at
+/// its text take it as an argument, and it has to be kept up to date by
+/// calling [`edit()`](Self::edit()) after every edit, or by editing the
+/// `Rope` via [`replace()`](Self::replace()). Text inserted inside a fold
+/// becomes part of it, text inserted at either of its ends doesn't, and
+/// folds whose text is deleted entirely are removed.
+///
+/// All the conversions between the offsets and lines of the `Rope` and the
+/// ones of the text displayed after folding run in logarithmic time.
+#[derive(Clone, Debug)]
+pub struct FoldMap {
+    tree: FoldTree,
+    placeholder: String,
+}
+
+impl FoldMap {
+    /// Returns the byte offset in the `Rope` of the given offset in the
+    /// folded text.
+    ///
+    /// Offsets inside a placeholder are mapped to the start of its fold.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offset is out of bounds (i.e. greater than
+    /// [`display_len()`](Self::display_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{FoldMap, Rope};
+    /// #
+    /// let r = Rope::from("fn foo() {\n    bar();\n}\n");
+    ///
+    /// let mut map = FoldMap::new(&r, "…");
+    /// map.fold(&r, 10..22);
+    ///
+    /// assert_eq!(map.byte_of_display_offset(10), 10);
+    /// assert_eq!(map.byte_of_display_offset(11), 10);
+    /// assert_eq!(map.byte_of_display_offset(13), 22);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn byte_of_display_offset(&self, display_offset: usize) -> usize {
+        if display_offset > self.display_len() {
+            panic::byte_offset_out_of_bounds(
+                display_offset,
+                self.display_len(),
+            );
+        }
+
+        let SegmentMetric(segment_index) =
+            self.tree.convert_measure(DisplayMetric(display_offset));
+
+        if segment_index == self.segment_len() {
+            return self.byte_len();
+        }
+
+        let ByteMetric(segment_start) =
+            self.tree.convert_measure(SegmentMetric(segment_index));
+
+        if self.segment(segment_index).is_fold {
+            return segment_start;
+        }
+
+        let DisplayMetric(display_start) =
+            self.tree.convert_measure(SegmentMetric(segment_index));
+
+        segment_start + display_offset - display_start
+    }
+
+    /// Returns an iterator over the chunks of the folded text, which yields
+    /// the chunks of the visible parts of the `Rope` interleaved with the
+    /// placeholders of the folds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{FoldMap, Rope};
+    /// #
+    /// let r = Rope::from("fn foo() {\n    bar();\n}\n");
+    ///
+    /// let mut map = FoldMap::new(&r, "…");
+    /// map.fold(&r, 10..22);
+    ///
+    /// assert_eq!(map.chunks(&r).collect::<String>(), "fn foo() {…}\n");
+    /// ```
+    #[inline]
+    pub fn chunks<'a>(&'a self, rope: &'a Rope) -> FoldChunks<'a> {
+        FoldChunks {
+            rope,
+            placeholder: &self.placeholder,
+            leaves: self.tree.leaves(),
+            segments: &[],
+            offset: 0,
+            chunks: None,
+        }
+    }
+
+    /// Returns the byte length of the text after folding.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{FoldMap, Rope};
+    /// #
+    /// let r = Rope::from("foo bar baz");
+    ///
+    /// let mut map = FoldMap::new(&r, "…");
+    /// assert_eq!(map.display_len(), 11);
+    ///
+    /// map.fold(&r, 4..7);
+    /// assert_eq!(map.display_len(), 8 + "…".len());
+    /// ```
+    #[inline]
+    pub fn display_len(&self) -> usize {
+        self.tree.summary().display_bytes
+    }
+
+    /// Returns the index of the line of the folded text on which the given
+    /// line of the `Rope` starts.
+    ///
+    /// The lines of a `Rope` which start inside a fold, or right at its end,
+    /// are all displayed on the same line as its placeholder.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the line index is greater than the number of line breaks in
+    /// the `Rope`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{FoldMap, Rope};
+    /// #
+    /// let r = Rope::from("fn foo() {\n    bar();\n}\nfn baz() {}\n");
+    ///
+    /// let mut map = FoldMap::new(&r, "…");
+    /// map.fold(&r, 10..22);
+    ///
+    /// assert_eq!(map.display_line_of_line(0), 0);
+    /// assert_eq!(map.display_line_of_line(1), 0);
+    /// assert_eq!(map.display_line_of_line(2), 0);
+    /// assert_eq!(map.display_line_of_line(3), 1);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn display_line_of_line(&self, line_index: usize) -> usize {
+        let line_breaks = self.tree.summary().line_breaks;
+
+        if line_index > line_breaks {
+            panic::line_index_out_of_bounds(line_index, line_breaks + 1);
+        }
+
+        if line_index == 0 {
+            return 0;
+        }
+
+        // The segment containing the line break right before the start of
+        // the line.
+        let SegmentMetric(segment_index) =
+            self.tree.convert_measure(RawLineMetric(line_index));
+
+        let DisplayLineMetric(display_lines_before) =
+            self.tree.convert_measure(SegmentMetric(segment_index));
+
+        if self.segment(segment_index).is_fold {
+            return display_lines_before;
+        }
+
+        let RawLineMetric(lines_before) =
+            self.tree.convert_measure(SegmentMetric(segment_index));
+
+        display_lines_before + line_index - lines_before
+    }
+
+    /// Returns the offset in the folded text of the given byte offset in the
+    /// `Rope`.
+    ///
+    /// Byte offsets inside a fold are mapped to the start of its placeholder.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than the
+    /// byte length of the `Rope`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{FoldMap, Rope};
+    /// #
+    /// let r = Rope::from("fn foo() {\n    bar();\n}\n");
+    ///
+    /// let mut map = FoldMap::new(&r, "…");
+    /// map.fold(&r, 10..22);
+    ///
+    /// assert_eq!(map.display_offset_of_byte(10), 10);
+    /// assert_eq!(map.display_offset_of_byte(15), 10);
+    /// assert_eq!(map.display_offset_of_byte(22), 13);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn display_offset_of_byte(&self, byte_offset: usize) -> usize {
+        if byte_offset > self.byte_len() {
+            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
+        }
+
+        let SegmentMetric(segment_index) =
+            self.tree.convert_measure(ByteMetric(byte_offset));
+
+        if segment_index == self.segment_len() {
+            return self.display_len();
+        }
+
+        let DisplayMetric(display_start) =
+            self.tree.convert_measure(SegmentMetric(segment_index));
+
+        if self.segment(segment_index).is_fold {
+            return display_start;
+        }
+
+        let ByteMetric(segment_start) =
+            self.tree.convert_measure(SegmentMetric(segment_index));
+
+        display_start + byte_offset - segment_start
+    }
+
+    /// Updates the map after the byte range of the `Rope` it was created
+    /// from was replaced with `text_len` bytes of text, where `rope` is the
+    /// `Rope` after the edit.
+    ///
+    /// The byte range is in the coordinates of the text before the edit,
+    /// exactly like the one passed to [`Rope::replace()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than its end, or if its
+    /// end is greater than the byte length of the text before the edit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{FoldMap, Rope};
+    /// #
+    /// let mut r = Rope::from("foo(bar)");
+    ///
+    /// let mut map = FoldMap::new(&r, "…");
+    /// map.fold(&r, 4..7);
+    ///
+    /// r.insert(5, "aaa");
+    /// map.edit(&r, 5..5, 3);
+    /// assert_eq!(map.folds().collect::<Vec<_>>(), [4..10]);
+    ///
+    /// r.insert(4, "x");
+    /// map.edit(&r, 4..4, 1);
+    /// assert_eq!(map.folds().collect::<Vec<_>>(), [5..11]);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn edit<R>(&mut self, rope: &Rope, byte_range: R, text_len: usize)
+    where
+        R: RangeBounds<usize>,
+    {
+        let old_len = self.byte_len();
+
+        let (start, end) = range_bounds_to_start_end(byte_range, 0, old_len);
+
+        if start > end {
+            panic::byte_start_after_end(start, end);
+        }
+
+        if end > old_len {
+            panic::byte_offset_out_of_bounds(end, old_len);
+        }
+
+        debug_assert_eq!(rope.byte_len(), old_len - (end - start) + text_len);
+
+        let shift = |offset: usize| offset - (end - start) + text_len;
+
+        // The start of a fold is pushed after any text inserted at it, while
+        // its end stays before it.
+        let map_start = |offset: usize| {
+            if offset < start {
+                offset
+            } else if offset >= end {
+                shift(offset)
+            } else {
+                start + text_len
+            }
+        };
+
+        let map_end = |offset: usize| {
+            if offset <= start {
+                offset
+            } else if offset > end {
+                shift(offset)
+            } else {
+                start
+            }
+        };
+
+        let (segments, byte_range) = self.region(start, end);
+
+        let folds = self
+            .folds_in(segments.clone(), byte_range.start)
+            .map(|fold| map_start(fold.start)..map_end(fold.end))
+            .filter(|fold| fold.start < fold.end)
+            .collect();
+
+        let byte_range = byte_range.start..shift(byte_range.end);
+
+        self.replace_segments(rope, segments, byte_range, folds);
+    }
+
+    /// Folds the given byte range of the `Rope`, merging it with all the
+    /// existing folds it overlaps with.
+    ///
+    /// Folding an empty range doesn't do anything.
+    ///
+    /// With the `unicode-lines` feature a fold never starts between the `\r`
+    /// and the `\n` of a CRLF pair, so its start is moved back to the `\r`,
+    /// both here and after an edit that completes the pair.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than the end, or if the
+    /// end is out of bounds (i.e. greater than the byte length of the
+    /// `Rope`), or if either of them doesn't lie on a char boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{FoldMap, Rope};
+    /// #
+    /// let r = Rope::from("foo bar baz qux");
+    ///
+    /// let mut map = FoldMap::new(&r, "…");
+    ///
+    /// map.fold(&r, 4..7);
+    /// map.fold(&r, 8..11);
+    /// assert_eq!(map.folds().collect::<Vec<_>>(), [4..7, 8..11]);
+    ///
+    /// map.fold(&r, 6..9);
+    /// assert_eq!(map.folds().collect::<Vec<_>>(), [4..11]);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn fold<R>(&mut self, rope: &Rope, byte_range: R)
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) =
+            range_bounds_to_start_end(byte_range, 0, rope.byte_len());
+
+        // This panics if the range is invalid.
+        let _ = rope.byte_slice(start..end);
+
+        if start == end {
+            return;
+        }
+
+        let (segments, byte_range) = self.region(start, end);
+
+        let mut new_fold = start..end;
+
+        let mut folds = Vec::new();
+
+        for fold in self.folds_in(segments.clone(), byte_range.start) {
+            if fold.start < new_fold.end && new_fold.start < fold.end {
+                new_fold.start = new_fold.start.min(fold.start);
+                new_fold.end = new_fold.end.max(fold.end);
+            } else {
+                folds.push(fold);
+            }
+        }
+
+        let idx = folds.partition_point(|fold| fold.start < new_fold.start);
+
+        folds.insert(idx, new_fold);
+
+        self.replace_segments(rope, segments, byte_range, folds);
+    }
+
+    /// Returns an iterator over the byte ranges of the folds, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{FoldMap, Rope};
+    /// #
+    /// let r = Rope::from("foo bar baz");
+    ///
+    /// let mut map = FoldMap::new(&r, "…");
+    /// map.fold(&r, 8..11);
+    /// map.fold(&r, 0..3);
+    ///
+    /// assert_eq!(map.folds().collect::<Vec<_>>(), [0..3, 8..11]);
+    /// ```
+    #[inline]
+    pub fn folds(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        let mut offset = 0;
+
+        self.tree
+            .leaves()
+            .flat_map(|leaf| leaf.segments.iter())
+            .filter_map(move |segment| {
+                let start = offset;
+                offset += segment.len;
+                segment.is_fold.then_some(start..offset)
+            })
+    }
+
+    /// Returns the index of the line of the `Rope` whose start is displayed
+    /// at the start of the given line of the folded text.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the line index is greater than the number of line breaks in
+    /// the folded text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{FoldMap, Rope};
+    /// #
+    /// let r = Rope::from("fn foo() {\n    bar();\n}\nfn baz() {}\n");
+    ///
+    /// let mut map = FoldMap::new(&r, "…");
+    /// map.fold(&r, 10..22);
+    ///
+    /// assert_eq!(map.line_of_display_line(0), 0);
+    /// assert_eq!(map.line_of_display_line(1), 3);
+    /// assert_eq!(map.line_of_display_line(2), 4);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn line_of_display_line(&self, display_line_index: usize) -> usize {
+        let display_line_breaks = self.tree.summary().display_line_breaks;
+
+        if display_line_index > display_line_breaks {
+            panic::line_index_out_of_bounds(
+                display_line_index,
+                display_line_breaks + 1,
+            );
+        }
+
+        if display_line_index == 0 {
+            return 0;
+        }
+
+        // The segment containing the line break right before the start of
+        // the display line, which can't be a fold.
+        let SegmentMetric(segment_index) =
+            self.tree.convert_measure(DisplayLineMetric(display_line_index));
+
+        let DisplayLineMetric(display_lines_before) =
+            self.tree.convert_measure(SegmentMetric(segment_index));
+
+        let RawLineMetric(lines_before) =
+            self.tree.convert_measure(SegmentMetric(segment_index));
+
+        lines_before + display_line_index - display_lines_before
+    }
+
+    /// Creates a new `FoldMap` without any folds, which will display them
+    /// with the given placeholder.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the placeholder is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{FoldMap, Rope};
+    /// #
+    /// let r = Rope::from("foo\nbar\n");
+    ///
+    /// let map = FoldMap::new(&r, "…");
+    ///
+    /// assert_eq!(map.folds().count(), 0);
+    /// assert_eq!(map.display_len(), r.byte_len());
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn new(rope: &Rope, placeholder: &str) -> Self {
+        if placeholder.is_empty() {
+            panic::empty_fold_placeholder();
+        }
+
+        let mut map = Self {
+            tree: FoldTree::default(),
+            placeholder: String::from(placeholder),
+        };
+
+        map.replace_segments(rope, 0..0, 0..rope.byte_len(), Vec::new());
+
+        map
+    }
+
+    /// Returns the placeholder the folds are displayed with.
+    #[inline]
+    pub fn placeholder(&self) -> &str {
+        &self.placeholder
+    }
+
+    /// Replaces the contents of the `Rope` in the given byte range with the
+    /// given text, updating the folds accordingly.
+    ///
+    /// This is equivalent to calling [`Rope::replace()`] followed by
+    /// [`edit()`](Self::edit()).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than the end, or if the
+    /// end is out of bounds (i.e. greater than the byte length of the
+    /// `Rope`), or if either of them doesn't lie on a char boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{FoldMap, Rope};
+    /// #
+    /// let mut r = Rope::from("foo bar baz");
+    ///
+    /// let mut map = FoldMap::new(&r, "…");
+    /// map.fold(&r, 4..7);
+    ///
+    /// map.replace(&mut r, 0..3, "hello");
+    ///
+    /// assert_eq!(map.folds().collect::<Vec<_>>(), [6..9]);
+    /// assert_eq!(map.chunks(&r).collect::<String>(), "hello … baz");
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn replace<R, T>(&mut self, rope: &mut Rope, byte_range: R, text: T)
+    where
+        R: RangeBounds<usize>,
+        T: AsRef<str>,
+    {
+        let (start, end) =
+            range_bounds_to_start_end(byte_range, 0, rope.byte_len());
+
+        let text = text.as_ref();
+
+        rope.replace(start..end, text);
+
+        self.edit(rope, start..end, text.len());
+    }
+
+    /// Removes all the folds intersecting the given byte range. If the range
+    /// is empty this removes the fold containing its offset, if any.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than the end, or if the
+    /// end is out of bounds (i.e. greater than the byte length of the
+    /// `Rope`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{FoldMap, Rope};
+    /// #
+    /// let r = Rope::from("foo bar baz qux");
+    ///
+    /// let mut map = FoldMap::new(&r, "…");
+    /// map.fold(&r, 0..3);
+    /// map.fold(&r, 4..7);
+    /// map.fold(&r, 8..11);
+    ///
+    /// map.unfold(&r, 5..5);
+    /// assert_eq!(map.folds().collect::<Vec<_>>(), [0..3, 8..11]);
+    ///
+    /// map.unfold(&r, ..);
+    /// assert_eq!(map.folds().count(), 0);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn unfold<R>(&mut self, rope: &Rope, byte_range: R)
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) =
+            range_bounds_to_start_end(byte_range, 0, rope.byte_len());
+
+        if start > end {
+            panic::byte_start_after_end(start, end);
+        }
+
+        if end > rope.byte_len() {
+            panic::byte_offset_out_of_bounds(end, rope.byte_len());
+        }
+
+        let (segments, byte_range) = self.region(start, end);
+
+        let folds = self
+            .folds_in(segments.clone(), byte_range.start)
+            .filter(|fold| {
+                !(fold.start < end.max(start + 1) && start < fold.end)
+            })
+            .collect();
+
+        self.replace_segments(rope, segments, byte_range, folds);
+    }
+
+    #[inline]
+    fn byte_len(&self) -> usize {
+        self.tree.summary().bytes
+    }
+
+    /// Returns an iterator over the byte ranges of the folds in the given
+    /// range of segments, the first of which starts at `byte_offset`.
+    #[inline]
+    fn folds_in(
+        &self,
+        segments: Range<usize>,
+        mut byte_offset: usize,
+    ) -> impl Iterator<Item = Range<usize>> + '_ {
+        let slice = if segments.is_empty() {
+            None
+        } else {
+            Some(
+                self.tree.slice(
+                    SegmentMetric(segments.start)..SegmentMetric(segments.end),
+                ),
+            )
+        };
+
+        slice
+            .into_iter()
+            .flat_map(|slice| slice.leaves())
+            .flat_map(|leaf| leaf.segments.iter())
+            .filter_map(move |segment| {
+                let start = byte_offset;
+                byte_offset += segment.len;
+                segment.is_fold.then_some(start..byte_offset)
+            })
+    }
+
+    /// Returns the range of segments that have to be rebuilt after editing
+    /// the `start..end` byte range, together with the byte range they span.
+    ///
+    /// The range always starts at the start of the text or right after a
+    /// fold, and ends at the end of the text or right before a fold, so that
+    /// the visible segments at its edges can be merged with the new ones.
+    ///
+    /// With the `unicode-lines` feature it also includes the segments right
+    /// before and after `start..end`, since joining or splitting a CRLF pair
+    /// at the edges of the range changes how they count their line breaks.
+    #[inline]
+    fn region(
+        &self,
+        start: usize,
+        end: usize,
+    ) -> (Range<usize>, Range<usize>) {
+        let segment_len = self.segment_len();
+
+        if segment_len == 0 {
+            return (0..0, 0..0);
+        }
+
+        #[cfg(feature = "unicode-lines")]
+        let (start, end) =
+            (start.saturating_sub(1), (end + 1).min(self.byte_len()));
+
+        let segment_of_byte = |byte_offset: usize| {
+            let SegmentMetric(segment_index) =
+                self.tree.convert_measure(ByteMetric(byte_offset));
+
+            segment_index.min(segment_len - 1)
+        };
+
+        let mut first = segment_of_byte(start);
+
+        if first > 0
+            && self.segment(first).is_fold
+            && !self.segment(first - 1).is_fold
+        {
+            first -= 1;
+        }
+
+        let mut last = segment_of_byte(end);
+
+        if last + 1 < segment_len
+            && self.segment(last).is_fold
+            && !self.segment(last + 1).is_fold
+        {
+            last += 1;
+        }
+
+        let ByteMetric(byte_start) =
+            self.tree.convert_measure(SegmentMetric(first));
+
+        let ByteMetric(byte_end) =
+            self.tree.convert_measure(SegmentMetric(last + 1));
+
+        (first..last + 1, byte_start..byte_end)
+    }
+
+    /// Replaces the given range of segments with the ones obtained by
+    /// folding the given ranges of the `Rope`, which have to be sorted and
+    /// contained in `byte_range`.
+    #[inline]
+    fn replace_segments(
+        &mut self,
+        rope: &Rope,
+        segments: Range<usize>,
+        byte_range: Range<usize>,
+        folds: Vec<Range<usize>>,
+    ) {
+        let line_breaks = |range: Range<usize>| {
+            rope.byte_slice(range).tree_slice.summary().line_breaks()
+        };
+
+        #[cfg(feature = "unicode-lines")]
+        let folds = snap_folds(rope, folds);
+
+        let mut new_segments = Vec::with_capacity(2 * folds.len() + 1);
+
+        let mut offset = byte_range.start;
+
+        for fold in folds {
+            debug_assert!(offset <= fold.start);
+
+            if offset < fold.start {
+                new_segments.push(Segment::visible(
+                    fold.start - offset,
+                    line_breaks(offset..fold.start),
+                ));
+            }
+
+            new_segments.push(Segment::fold(
+                fold.end - fold.start,
+                line_breaks(fold.clone()),
+                self.placeholder.len(),
+            ));
+
+            offset = fold.end;
+        }
+
+        debug_assert!(offset <= byte_range.end);
+
+        if offset < byte_range.end {
+            new_segments.push(Segment::visible(
+                byte_range.end - offset,
+                line_breaks(offset..byte_range.end),
+            ));
+        }
+
+        if segments.start == 0 && segments.end == self.segment_len() {
+            self.tree =
+                Tree::from_leaves(FoldSegments::segment(new_segments));
+        } else {
+            self.tree.replace(
+                SegmentMetric(segments.start)..SegmentMetric(segments.end),
+                &new_segments,
+            );
+        }
+    }
+
+    #[inline]
+    fn segment(&self, segment_index: usize) -> Segment {
+        let (slice, SegmentMetric(segments_before)) =
+            self.tree.leaf_at_measure(SegmentMetric(segment_index + 1));
+
+        slice.segments[segment_index - segments_before]
+    }
+
+    #[inline]
+    fn segment_len(&self) -> usize {
+        self.tree.summary().segments
+    }
+}
+
+/// Moves the start of every fold that falls between the `\r` and the `\n`
+/// of a CRLF pair back to the `\r`, merging the folds that overlap as a
+/// result.
+///
+/// Otherwise the visible text before the fold would end with a `\r` that's
+/// displayed as a line break but isn't counted as one by the `Rope`, which
+/// counts the pair on its `\n`.
+#[cfg(feature = "unicode-lines")]
+#[inline]
+fn snap_folds(rope: &Rope, folds: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    let mut snapped: Vec<Range<usize>> = Vec::with_capacity(folds.len());
+
+    for mut fold in folds {
+        if fold.start > 0
+            && rope.byte(fold.start - 1) == b'\r'
+            && rope.byte(fold.start) == b'\n'
+        {
+            fold.start -= 1;
+        }
+
+        match snapped.last_mut() {
+            Some(last) if last.end > fold.start => {
+                last.end = last.end.max(fold.end);
+            },
+            _ => snapped.push(fold),
+        }
+    }
+
+    snapped
+}
+
+/// An iterator over the chunks of the text displayed by a [`FoldMap`].
+///
+/// This struct is created by [`FoldMap::chunks()`]. See its documentation for
+/// more.
+#[derive(Clone)]
+pub struct FoldChunks<'a> {
+    rope: &'a Rope,
+    placeholder: &'a str,
+    leaves: Leaves<'a, { Rope::arity() }, FoldSegments>,
+
+    /// The segments of the current leaf that haven't been yielded yet.
+    segments: &'a [Segment],
+
+    /// The byte offset of the start of the first segment in `segments`.
+    offset: usize,
+
+    /// The chunks of the visible segment currently being yielded.
+    chunks: Option<Chunks<'a>>,
+}
+
+impl<'a> Iterator for FoldChunks<'a> {
+    type Item = &'a str;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(chunk) = self.chunks.as_mut().and_then(Iterator::next)
+            {
+                return Some(chunk);
+            }
+
+            self.chunks = None;
+
+            let Some((segment, rest)) = self.segments.split_first() else {
+                self.segments = self.leaves.next()?.segments;
+                continue;
+            };
+
+            self.segments = rest;
+
+            let start = self.offset;
+
+            self.offset += segment.len;
+
+            if segment.is_fold {
+                return Some(self.placeholder);
+            }
+
+            self.chunks =
+                Some(self.rope.byte_slice(start..self.offset).chunks());
+        }
+    }
+}
+
+impl core::iter::FusedIterator for FoldChunks<'_> {}
+
+/// A run of text which is either entirely visible or entirely folded.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Segment {
+    /// The byte length of the text.
+    len: usize,
+
+    /// The number of line breaks in the text.
+    line_breaks: usize,
+
+    /// The byte length of the text once folded, i.e. `len` for visible
+    /// segments and the length of the placeholder for folds.
+    display_len: usize,
+
+    is_fold: bool,
+}
+
+impl Segment {
+    #[inline]
+    fn fold(len: usize, line_breaks: usize, placeholder_len: usize) -> Self {
+        Self { len, line_breaks, display_len: placeholder_len, is_fold: true }
+    }
+
+    #[inline]
+    fn visible(len: usize, line_breaks: usize) -> Self {
+        Self { len, line_breaks, display_len: len, is_fold: false }
+    }
+}
+
+impl Summarize for Segment {
+    type Summary = FoldSummary;
+
+    #[inline]
+    fn summarize(&self) -> Self::Summary {
+        FoldSummary {
+            bytes: self.len,
+            line_breaks: self.line_breaks,
+            display_bytes: self.display_len,
+            display_line_breaks: if self.is_fold {
+                0
+            } else {
+                self.line_breaks
+            },
+            segments: 1,
+        }
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+struct FoldSegments {
+    segments: Vec<Segment>,
+}
+
+impl FoldSegments {
+    /// Splits a sequence of segments into leaves containing between
+    /// `MIN_SEGMENTS` and `MAX_SEGMENTS` segments each.
+    ///
+    /// The only exception is if there are at most `MAX_SEGMENTS` segments in
+    /// total, in which case they're all put in a single leaf.
+    #[inline]
+    fn segment(segments: Vec<Segment>) -> alloc::vec::IntoIter<Self> {
+        if segments.len() <= MAX_SEGMENTS {
+            return alloc::vec![Self { segments }].into_iter();
+        }
+
+        let num_leaves = (segments.len() + MAX_SEGMENTS - 1) / MAX_SEGMENTS;
+
+        let min_len = segments.len() / num_leaves;
+
+        let mut longer = segments.len() % num_leaves;
+
+        let mut segments = segments.into_iter();
+
+        let mut leaves = Vec::with_capacity(num_leaves);
+
+        for _ in 0..num_leaves {
+            let len = min_len + (longer > 0) as usize;
+            longer = longer.saturating_sub(1);
+            let segments = segments.by_ref().take(len).collect();
+            leaves.push(Self { segments });
+        }
+
+        debug_assert!(leaves
+            .iter()
+            .all(|leaf| leaf.segments.len() >= MIN_SEGMENTS));
+
+        leaves.into_iter()
+    }
+}
+
+impl Summarize for FoldSegments {
+    type Summary = FoldSummary;
+
+    #[inline]
+    fn summarize(&self) -> Self::Summary {
+        self.as_slice().summarize()
+    }
+}
+
+impl BaseMeasured for FoldSegments {
+    type BaseMetric = SegmentMetric;
+}
+
+impl AsSlice for FoldSegments {
+    type Slice<'a> = FoldSegmentsSlice<'a>;
+
+    #[inline]
+    fn as_slice(&self) -> FoldSegmentsSlice<'_> {
+        FoldSegmentsSlice { segments: &self.segments }
+    }
+}
+
+impl From<FoldSegmentsSlice<'_>> for FoldSegments {
+    #[inline]
+    fn from(slice: FoldSegmentsSlice<'_>) -> Self {
+        Self { segments: slice.segments.to_vec() }
+    }
+}
+
+impl BalancedLeaf for FoldSegments {
+    #[inline]
+    fn is_underfilled(&self, _: &FoldSummary) -> bool {
+        self.segments.len() < MIN_SEGMENTS
+    }
+
+    #[inline]
+    fn balance_leaves(
+        (left, left_summary): (&mut Self, &mut FoldSummary),
+        (right, right_summary): (&mut Self, &mut FoldSummary),
+    ) {
+        // The two leaves can be combined in a single leaf.
+        if left.segments.len() + right.segments.len() <= MAX_SEGMENTS {
+            left.segments.append(&mut right.segments);
+            *left_summary += &*right_summary;
+            *right_summary = FoldSummary::default();
+            return;
+        }
+
+        // The left side is underfilled => take segments from the right side.
+        if left.segments.len() < MIN_SEGMENTS {
+            let moved = MIN_SEGMENTS - left.segments.len();
+            left.segments.extend(right.segments.drain(..moved));
+        }
+        // The right side is underfilled => take segments from the left side.
+        else if right.segments.len() < MIN_SEGMENTS {
+            let moved = MIN_SEGMENTS - right.segments.len();
+            let split = left.segments.len() - moved;
+            right.segments.splice(..0, left.segments.drain(split..));
+        } else {
+            return;
+        }
+
+        *left_summary = left.summarize();
+        *right_summary = right.summarize();
+
+        debug_assert!(left.segments.len() >= MIN_SEGMENTS);
+        debug_assert!(right.segments.len() >= MIN_SEGMENTS);
+    }
+}
+
+impl ReplaceableLeaf<SegmentMetric> for FoldSegments {
+    type Replacement<'a> = &'a [Segment];
+
+    type ExtraLeaves = alloc::vec::IntoIter<Self>;
+
+    #[inline]
+    fn replace<R>(
+        &mut self,
+        summary: &mut FoldSummary,
+        range: R,
+        replacement: &[Segment],
+    ) -> Option<Self::ExtraLeaves>
+    where
+        R: RangeBounds<SegmentMetric>,
+    {
+        let (start, end) =
+            range_bounds_to_start_end(range, 0, summary.segments);
+
+        debug_assert!(start <= end);
+        debug_assert!(end <= summary.segments);
+
+        let new_len = self.segments.len() - (end - start) + replacement.len();
+
+        if new_len <= MAX_SEGMENTS {
+            self.segments.splice(start..end, replacement.iter().copied());
+            *summary = self.summarize();
+            return None;
+        }
+
+        let mut segments = Vec::with_capacity(new_len);
+
+        segments.extend(self.segments.drain(..start));
+        segments.extend(replacement.iter().copied());
+        segments.extend(self.segments.drain(end - start..));
+
+        let mut leaves = Self::segment(segments);
+        *self = leaves.next().unwrap();
+        *summary = self.summarize();
+        Some(leaves)
+    }
+
+    #[inline]
+    fn remove_up_to(
+        &mut self,
+        summary: &mut FoldSummary,
+        SegmentMetric(up_to): SegmentMetric,
+    ) {
+        self.segments.drain(..up_to);
+        *summary = self.summarize();
+    }
+}
+
+#[derive(Copy, Clone, Default, Debug)]
+struct FoldSegmentsSlice<'a> {
+    segments: &'a [Segment],
+}
+
+impl FoldSegmentsSlice<'_> {
+    /// Splits the slice right before the first segment for which `stop`
+    /// returns `true` when called with the summary of the segments up to and
+    /// including it, or at the end if there isn't one.
+    #[inline]
+    fn split_where<F>(self, mut stop: F) -> (Self, Self)
+    where
+        F: FnMut(&FoldSummary) -> bool,
+    {
+        let mut summary = FoldSummary::default();
+
+        let mut split = 0;
+
+        for segment in self.segments {
+            summary += &segment.summarize();
+
+            if stop(&summary) {
+                break;
+            }
+
+            split += 1;
+        }
+
+        self.split_at(split)
+    }
+
+    #[inline]
+    fn split_at(self, segment_offset: usize) -> (Self, Self) {
+        let (left, right) = self.segments.split_at(segment_offset);
+        (Self { segments: left }, Self { segments: right })
+    }
+}
+
+impl Summarize for FoldSegmentsSlice<'_> {
+    type Summary = FoldSummary;
+
+    #[inline]
+    fn summarize(&self) -> Self::Summary {
+        self.segments.iter().fold(FoldSummary::default(), |mut summary, seg| {
+            summary += &seg.summarize();
+            summary
+        })
+    }
+}
+
+#[derive(Copy, Clone, Default, Debug, PartialEq)]
+struct FoldSummary {
+    bytes: usize,
+    line_breaks: usize,
+    display_bytes: usize,
+    display_line_breaks: usize,
+    segments: usize,
+}
+
+impl Add<&Self> for FoldSummary {
+    type Output = Self;
+
+    #[inline]
+    fn add(mut self, rhs: &Self) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+impl Sub<&Self> for FoldSummary {
+    type Output = Self;
+
+    #[inline]
+    fn sub(mut self, rhs: &Self) -> Self {
+        self -= rhs;
+        self
+    }
+}
+
+impl AddAssign<&Self> for FoldSummary {
+    #[inline]
+    fn add_assign(&mut self, rhs: &Self) {
+        self.bytes += rhs.bytes;
+        self.line_breaks += rhs.line_breaks;
+        self.display_bytes += rhs.display_bytes;
+        self.display_line_breaks += rhs.display_line_breaks;
+        self.segments += rhs.segments;
+    }
+}
+
+impl SubAssign<&Self> for FoldSummary {
+    #[inline]
+    fn sub_assign(&mut self, rhs: &Self) {
+        self.bytes -= rhs.bytes;
+        self.line_breaks -= rhs.line_breaks;
+        self.display_bytes -= rhs.display_bytes;
+        self.display_line_breaks -= rhs.display_line_breaks;
+        self.segments -= rhs.segments;
+    }
+}
+
+impl Metric<FoldSummary> for ByteMetric {
+    #[inline]
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    #[inline]
+    fn one() -> Self {
+        Self(1)
+    }
+
+    #[inline]
+    fn measure(summary: &FoldSummary) -> Self {
+        Self(summary.bytes)
+    }
+}
+
+/// Slicing up to a byte offset keeps all the segments ending at or before
+/// it, so the left side contains as many segments as the index of the
+/// segment containing the offset.
+impl SlicingMetric<FoldSegments> for ByteMetric {
+    #[inline]
+    fn slice_up_to<'a>(
+        slice: FoldSegmentsSlice<'a>,
+        ByteMetric(byte_offset): Self,
+        _: &FoldSummary,
+    ) -> (FoldSegmentsSlice<'a>, FoldSummary)
+    where
+        'a: 'a,
+    {
+        let (left, _) = slice.split_where(|s| s.bytes > byte_offset);
+        (left, left.summarize())
+    }
+
+    #[inline]
+    fn slice_from<'a>(
+        slice: FoldSegmentsSlice<'a>,
+        ByteMetric(byte_offset): Self,
+        _: &FoldSummary,
+    ) -> (FoldSegmentsSlice<'a>, FoldSummary)
+    where
+        'a: 'a,
+    {
+        let (_, right) = slice.split_where(|s| s.bytes > byte_offset);
+        (right, right.summarize())
+    }
+}
+
+impl Metric<FoldSummary> for RawLineMetric {
+    #[inline]
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    #[inline]
+    fn one() -> Self {
+        Self(1)
+    }
+
+    #[inline]
+    fn measure(summary: &FoldSummary) -> Self {
+        Self(summary.line_breaks)
+    }
+}
+
+/// Slicing up to `n` line breaks keeps all the segments before the one
+/// containing the `n`-th line break.
+impl SlicingMetric<FoldSegments> for RawLineMetric {
+    #[inline]
+    fn slice_up_to<'a>(
+        slice: FoldSegmentsSlice<'a>,
+        RawLineMetric(line_offset): Self,
+        _: &FoldSummary,
+    ) -> (FoldSegmentsSlice<'a>, FoldSummary)
+    where
+        'a: 'a,
+    {
+        let (left, _) = slice.split_where(|s| s.line_breaks >= line_offset);
+        (left, left.summarize())
+    }
+
+    #[inline]
+    fn slice_from<'a>(
+        slice: FoldSegmentsSlice<'a>,
+        RawLineMetric(line_offset): Self,
+        _: &FoldSummary,
+    ) -> (FoldSegmentsSlice<'a>, FoldSummary)
+    where
+        'a: 'a,
+    {
+        let (_, right) = slice.split_where(|s| s.line_breaks >= line_offset);
+        (right, right.summarize())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct DisplayMetric(usize);
+
+impl Add for DisplayMetric {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl Sub for DisplayMetric {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+impl AddAssign for DisplayMetric {
+    #[inline]
+    fn add_assign(&mut self, other: Self) {
+        self.0 += other.0
+    }
+}
+
+impl SubAssign for DisplayMetric {
+    #[inline]
+    fn sub_assign(&mut self, other: Self) {
+        self.0 -= other.0
+    }
+}
+
+impl Metric<FoldSummary> for DisplayMetric {
+    #[inline]
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    #[inline]
+    fn one() -> Self {
+        Self(1)
+    }
+
+    #[inline]
+    fn measure(summary: &FoldSummary) -> Self {
+        Self(summary.display_bytes)
+    }
+}
+
+/// Same as the `ByteMetric`, except it measures the folded text.
+impl SlicingMetric<FoldSegments> for DisplayMetric {
+    #[inline]
+    fn slice_up_to<'a>(
+        slice: FoldSegmentsSlice<'a>,
+        DisplayMetric(display_offset): Self,
+        _: &FoldSummary,
+    ) -> (FoldSegmentsSlice<'a>, FoldSummary)
+    where
+        'a: 'a,
+    {
+        let (left, _) =
+            slice.split_where(|s| s.display_bytes > display_offset);
+        (left, left.summarize())
+    }
+
+    #[inline]
+    fn slice_from<'a>(
+        slice: FoldSegmentsSlice<'a>,
+        DisplayMetric(display_offset): Self,
+        _: &FoldSummary,
+    ) -> (FoldSegmentsSlice<'a>, FoldSummary)
+    where
+        'a: 'a,
+    {
+        let (_, right) =
+            slice.split_where(|s| s.display_bytes > display_offset);
+        (right, right.summarize())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct DisplayLineMetric(usize);
+
+impl Add for DisplayLineMetric {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl Sub for DisplayLineMetric {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+impl AddAssign for DisplayLineMetric {
+    #[inline]
+    fn add_assign(&mut self, other: Self) {
+        self.0 += other.0
+    }
+}
+
+impl SubAssign for DisplayLineMetric {
+    #[inline]
+    fn sub_assign(&mut self, other: Self) {
+        self.0 -= other.0
+    }
+}
+
+impl Metric<FoldSummary> for DisplayLineMetric {
+    #[inline]
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    #[inline]
+    fn one() -> Self {
+        Self(1)
+    }
+
+    #[inline]
+    fn measure(summary: &FoldSummary) -> Self {
+        Self(summary.display_line_breaks)
+    }
+}
+
+/// Same as the `RawLineMetric`, except it measures the folded text.
+impl SlicingMetric<FoldSegments> for DisplayLineMetric {
+    #[inline]
+    fn slice_up_to<'a>(
+        slice: FoldSegmentsSlice<'a>,
+        DisplayLineMetric(line_offset): Self,
+        _: &FoldSummary,
+    ) -> (FoldSegmentsSlice<'a>, FoldSummary)
+    where
+        'a: 'a,
+    {
+        let (left, _) =
+            slice.split_where(|s| s.display_line_breaks >= line_offset);
+        (left, left.summarize())
+    }
+
+    #[inline]
+    fn slice_from<'a>(
+        slice: FoldSegmentsSlice<'a>,
+        DisplayLineMetric(line_offset): Self,
+        _: &FoldSummary,
+    ) -> (FoldSegmentsSlice<'a>, FoldSummary)
+    where
+        'a: 'a,
+    {
+        let (_, right) =
+            slice.split_where(|s| s.display_line_breaks >= line_offset);
+        (right, right.summarize())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct SegmentMetric(usize);
+
+impl Add for SegmentMetric {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl Sub for SegmentMetric {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+impl AddAssign for SegmentMetric {
+    #[inline]
+    fn add_assign(&mut self, other: Self) {
+        self.0 += other.0
+    }
+}
+
+impl SubAssign for SegmentMetric {
+    #[inline]
+    fn sub_assign(&mut self, other: Self) {
+        self.0 -= other.0
+    }
+}
+
+impl Add<usize> for SegmentMetric {
+    type Output = usize;
+
+    #[inline]
+    fn add(self, other: usize) -> usize {
+        self.0 + other
+    }
+}
+
+impl From<SegmentMetric> for usize {
+    #[inline]
+    fn from(SegmentMetric(value): SegmentMetric) -> usize {
+        value
+    }
+}
+
+impl Metric<FoldSummary> for SegmentMetric {
+    #[inline]
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    #[inline]
+    fn one() -> Self {
+        Self(1)
+    }
+
+    #[inline]
+    fn measure(summary: &FoldSummary) -> Self {
+        Self(summary.segments)
+    }
+}
+
+impl SlicingMetric<FoldSegments> for SegmentMetric {
+    #[inline]
+    fn slice_up_to<'a>(
+        slice: FoldSegmentsSlice<'a>,
+        SegmentMetric(segment_offset): Self,
+        _: &FoldSummary,
+    ) -> (FoldSegmentsSlice<'a>, FoldSummary)
+    where
+        'a: 'a,
+    {
+        let (left, _) = slice.split_at(segment_offset);
+        (left, left.summarize())
+    }
+
+    #[inline]
+    fn slice_from<'a>(
+        slice: FoldSegmentsSlice<'a>,
+        SegmentMetric(segment_offset): Self,
+        _: &FoldSummary,
+    ) -> (FoldSegmentsSlice<'a>, FoldSummary)
+    where
+        'a: 'a,
+    {
+        let (_, right) = slice.split_at(segment_offset);
+        (right, right.summarize())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replace_overflowing() {
+        let visible = |len| Segment::visible(len, 1);
+        let fold = |len| Segment::fold(len, 1, 3);
+
+        let mut leaf =
+            FoldSegments { segments: alloc::vec![visible(1), fold(2)] };
+        let mut summary = leaf.summarize();
+
+        let replacement = [visible(3), fold(4), visible(5), fold(6)];
+
+        let extras = leaf
+            .replace(
+                &mut summary,
+                SegmentMetric(1)..SegmentMetric(2),
+                &replacement,
+            )
+            .unwrap()
+            .collect::<Vec<_>>();
+
+        assert_eq!(summary, leaf.summarize());
+
+        let total =
+            extras.iter().fold(summary, |acc, leaf| acc + &leaf.summarize());
+
+        assert_eq!(
+            total,
+            FoldSummary {
+                bytes: 19,
+                line_breaks: 5,
+                display_bytes: 15,
+                display_line_breaks: 3,
+                segments: 5,
+            }
+        );
+    }
+
+}
@@ -129,9 +129,17 @@ impl<const MAX_BYTES: usize> GapBuffer<MAX_BYTES> {
         debug_assert!(self.len() + bytes_to_add <= MAX_BYTES);
 
         if bytes_to_add <= right.len_left() {
-            let (move_left, _) =
+            let (mut move_left, _) =
                 split_adjusted::<false>(right.left_chunk(), bytes_to_add);
 
+            // The gap could be splitting a CRLF pair, which we can't move
+            // only half of.
+            if move_left.len() == right.len_left()
+                && splits_crlf(move_left, right.right_chunk())
+            {
+                move_left = &move_left[..move_left.len() - 1];
+            }
+
             let summary = right.summarize_left_chunk_up_to(move_left.len());
 
             self.append_str(move_left);
@@ -186,7 +194,14 @@ impl<const MAX_BYTES: usize> GapBuffer<MAX_BYTES> {
 
         let len_left = self.len_left();
         let len_right = self.len_right();
+
+        // The last chunk is now followed by the other buffer's text.
         let right_summary = self.right_summary(summary);
+        let moved_summary = (self.left_summary + right_summary).with_next(
+            self.last_chunk(),
+            "",
+            other.first_chunk(),
+        );
 
         // Move this buffer's right chunk after its left chunk.
         self.bytes.copy_within(MAX_BYTES - len_right..MAX_BYTES, len_left);
@@ -199,7 +214,7 @@ impl<const MAX_BYTES: usize> GapBuffer<MAX_BYTES> {
         // Move the other buffer's right chunk to this buffer's right chunk.
         self.bytes[end..].copy_from_slice(other.right_chunk().as_bytes());
 
-        self.left_summary += right_summary;
+        self.left_summary = moved_summary;
         self.len_right = other.len() as u16;
 
         other.left_summary = ChunkSummary::new();
@@ -232,6 +247,11 @@ impl<const MAX_BYTES: usize> GapBuffer<MAX_BYTES> {
     pub fn append_str(&mut self, s: &str) {
         debug_assert!(s.len() <= self.len_gap());
 
+        if self.len_right() == 0 {
+            self.left_summary =
+                self.left_summary.with_next(self.left_chunk(), "", s);
+        }
+
         let start = MAX_BYTES - self.len_right();
 
         // Shift the second segment to the left.
@@ -265,6 +285,12 @@ impl<const MAX_BYTES: usize> GapBuffer<MAX_BYTES> {
     pub fn append_two(&mut self, a: &str, b: &str) {
         debug_assert!(a.len() + b.len() <= self.len_gap());
 
+        if self.len_right() == 0 {
+            let next = if a.is_empty() { b } else { a };
+            self.left_summary =
+                self.left_summary.with_next(self.left_chunk(), "", next);
+        }
+
         // Shift the second chunk to the left.
         let start = MAX_BYTES - self.len_right();
         self.bytes.copy_within(start.., start - a.len() - b.len());
@@ -309,6 +335,16 @@ impl<const MAX_BYTES: usize> GapBuffer<MAX_BYTES> {
         Self::min_bytes().saturating_sub(3)
     }
 
+    /// The left chunk if it's not empty, or the right one otherwise.
+    #[inline]
+    pub(super) fn first_chunk(&self) -> &str {
+        if self.len_left() == 0 {
+            self.right_chunk()
+        } else {
+            self.left_chunk()
+        }
+    }
+
     /// Creates a new `GapBuffer` from a slice of `&str`s.
     ///
     /// # Panics
@@ -393,21 +429,33 @@ impl<const MAX_BYTES: usize> GapBuffer<MAX_BYTES> {
                     start += segment.len();
                 }
 
-                return Self {
+                let mut buffer = Self {
                     bytes,
                     left_summary: summary_left,
                     len_right: len_right as u16,
                 };
+
+                // The chunks have been summarized independently, so a CRLF
+                // pair split between two of them has been counted twice, and
+                // the left chunk may end with the first half of one.
+                if cfg!(feature = "unicode-lines") {
+                    buffer.left_summary = ChunkSummary::from_followed_by(
+                        buffer.left_chunk(),
+                        buffer.right_chunk(),
+                    );
+                }
+
+                return buffer;
             }
         }
 
         unreachable!("This can only be reached if the total length is zero");
     }
 
-    /// Returns `true` if the buffer ends with a newline ('\n') character.
+    /// Returns `true` if the buffer ends with a line break.
     #[inline]
     pub(super) fn has_trailing_newline(&self) -> bool {
-        self.last_chunk().ends_with('\n')
+        ends_with_line_break(self.last_chunk())
     }
 
     /// Inserts the string at the given byte offset, moving the gap to the new
@@ -433,6 +481,18 @@ impl<const MAX_BYTES: usize> GapBuffer<MAX_BYTES> {
 
         debug_assert_eq!(insert_at, self.len_left());
 
+        if s.is_empty() {
+            return summary;
+        }
+
+        let old_left_summary = self.left_summary;
+
+        let new_left_summary = old_left_summary.with_next(
+            self.left_chunk(),
+            self.right_chunk(),
+            s,
+        ) + ChunkSummary::from_followed_by(s, self.right_chunk());
+
         let insert_range = {
             let start = self.len_left();
             let end = start + s.len();
@@ -441,11 +501,9 @@ impl<const MAX_BYTES: usize> GapBuffer<MAX_BYTES> {
 
         self.bytes[insert_range].copy_from_slice(s.as_bytes());
 
-        let inserted_summary = ChunkSummary::from(s);
-
-        self.left_summary += inserted_summary;
+        self.left_summary = new_left_summary;
 
-        summary + inserted_summary
+        summary - old_left_summary + new_left_summary
     }
 
     #[inline]
@@ -465,6 +523,37 @@ impl<const MAX_BYTES: usize> GapBuffer<MAX_BYTES> {
         self.len() == 0
     }
 
+    /// Moves the `\n` at the start of the first extra buffer to the end of
+    /// this one if it completes a CRLF pair, returning the new summary of
+    /// this buffer.
+    #[inline]
+    fn join_split_crlf(
+        &mut self,
+        summary: ChunkSummary,
+        extras: &mut Vec<Self>,
+    ) -> ChunkSummary {
+        let Some(first) = extras.first_mut() else { return summary };
+
+        if !splits_crlf(self.last_chunk(), first.first_chunk()) {
+            return summary;
+        }
+
+        debug_assert!(self.len() < MAX_BYTES);
+
+        let new_summary = summary.with_next(self.last_chunk(), "", "\n")
+            + ChunkSummary::from("\n");
+
+        self.append_str("\n");
+
+        first.remove_up_to(1, ChunkSummary::from("\n"));
+
+        if first.is_empty() {
+            extras.remove(0);
+        }
+
+        new_summary
+    }
+
     /// The right chunk if it's not empty, or the left one otherwise.
     #[inline]
     pub(super) fn last_chunk(&self) -> &str {
@@ -631,12 +720,21 @@ impl<const MAX_BYTES: usize> GapBuffer<MAX_BYTES> {
         debug_assert_eq!(summary, self.summarize());
 
         if bytes_to_move <= self.len_right() {
-            let (_, move_right) = split_adjusted::<true>(
+            let (_, mut move_right) = split_adjusted::<true>(
                 self.right_chunk(),
                 self.len_right() - bytes_to_move,
             );
 
-            let moved_summary = ChunkSummary::from(move_right);
+            // The gap could be splitting a CRLF pair, which we can't move
+            // only half of.
+            if move_right.len() == self.len_right()
+                && splits_crlf(self.left_chunk(), move_right)
+            {
+                move_right = &move_right[1..];
+            }
+
+            let moved_summary =
+                ChunkSummary::from_followed_by(move_right, right.first_chunk());
 
             right.prepend(move_right, moved_summary);
 
@@ -644,15 +742,33 @@ impl<const MAX_BYTES: usize> GapBuffer<MAX_BYTES> {
 
             moved_summary
         } else {
-            let (_, move_right) = split_adjusted::<true>(
+            let (_, mut move_right) = split_adjusted::<true>(
                 self.left_chunk(),
                 self.len_left() - (bytes_to_move - self.len_right()),
             );
 
-            let move_right_summary = ChunkSummary::from(move_right);
+            // Same as above.
+            if move_right.is_empty()
+                && splits_crlf(self.left_chunk(), self.right_chunk())
+            {
+                move_right = &self.left_chunk()[self.len_left() - 1..];
+            }
 
-            let moved_summary =
-                move_right_summary + self.right_summary(summary);
+            let after_move_right = if self.len_right() > 0 {
+                self.right_chunk()
+            } else {
+                right.first_chunk()
+            };
+
+            let move_right_summary =
+                ChunkSummary::from_followed_by(move_right, after_move_right);
+
+            let moved_summary = move_right_summary
+                + self.right_summary(summary).with_next(
+                    self.right_chunk(),
+                    "",
+                    right.first_chunk(),
+                );
 
             right.prepend_two(move_right, self.right_chunk(), moved_summary);
 
@@ -687,7 +803,10 @@ impl<const MAX_BYTES: usize> GapBuffer<MAX_BYTES> {
     #[inline]
     pub fn prepend(&mut self, s: &str, prepended_summary: ChunkSummary) {
         debug_assert!(s.len() <= self.len_gap());
-        debug_assert_eq!(prepended_summary, ChunkSummary::from(s));
+        debug_assert_eq!(
+            prepended_summary,
+            ChunkSummary::from_followed_by(s, self.first_chunk())
+        );
 
         // Shift the left chunk over.
         let len_left = self.len_left();
@@ -733,10 +852,11 @@ impl<const MAX_BYTES: usize> GapBuffer<MAX_BYTES> {
     ) {
         debug_assert!(a.len() + b.len() <= self.len_gap());
 
-        debug_assert_eq!(
-            prepended_summary,
-            ChunkSummary::from(a) + ChunkSummary::from(b)
-        );
+        debug_assert_eq!(prepended_summary, {
+            let after_a = if b.is_empty() { self.first_chunk() } else { b };
+            ChunkSummary::from_followed_by(a, after_a)
+                + ChunkSummary::from_followed_by(b, self.first_chunk())
+        });
 
         // Shift the first segment to the right.
         let len_first = self.len_left();
@@ -838,17 +958,31 @@ impl<const MAX_BYTES: usize> GapBuffer<MAX_BYTES> {
 
         self.move_gap(end, summary);
 
-        let removed_summary = self.summarize_range(start..end, summary);
+        let old_left_summary = self.left_summary;
 
-        let added_summary = ChunkSummary::from(s);
+        let new_left_summary = {
+            let (kept, removed) = self.left_chunk().split_at(start);
 
-        self.bytes[start..start + s.len()].copy_from_slice(s.as_bytes());
+            let removed_summary = self.summarize_range(start..end, summary);
 
-        self.left_summary -= removed_summary;
+            let old_next = if removed.is_empty() {
+                self.right_chunk()
+            } else {
+                removed
+            };
 
-        self.left_summary += added_summary;
+            let new_next = if s.is_empty() { self.right_chunk() } else { s };
 
-        summary - removed_summary + added_summary
+            (old_left_summary - removed_summary).with_next(
+                kept, old_next, new_next,
+            ) + ChunkSummary::from_followed_by(s, self.right_chunk())
+        };
+
+        self.bytes[start..start + s.len()].copy_from_slice(s.as_bytes());
+
+        self.left_summary = new_left_summary;
+
+        summary - old_left_summary + new_left_summary
     }
 
     /// Replaces the text in `byte_range` with the string `s`, where the
@@ -995,10 +1129,28 @@ impl<const MAX_BYTES: usize> GapBuffer<MAX_BYTES> {
 
             (new_summary, extras)
         } else {
-            let extras =
-                Resegmenter::new([s, extra_left, extra_right]).collect();
+            // If the replacement starts with the `\n` of a CRLF pair and
+            // there's no room left to complete the pair in this buffer, its
+            // `\r` goes to the extras too. Otherwise the `\n` is moved back
+            // here by `join_split_crlf()`.
+            let keep = if start == MAX_BYTES
+                && self.as_slice().byte(start - 1) == b'\r'
+                && splits_crlf("\r", s)
+            {
+                start - 1
+            } else {
+                start
+            };
 
-            let new_summary = self.truncate_from(start, summary);
+            let extras = Resegmenter::new([
+                &"\r"[..start - keep],
+                s,
+                extra_left,
+                extra_right,
+            ])
+            .collect();
+
+            let new_summary = self.truncate_from(keep, summary);
 
             (new_summary, extras)
         }
@@ -1035,11 +1187,14 @@ impl<const MAX_BYTES: usize> GapBuffer<MAX_BYTES> {
         debug_assert!(byte_offset <= self.len_left());
         debug_assert!(self.left_chunk().is_char_boundary(byte_offset));
 
+        let (up_to, after) = self.left_chunk().split_at(byte_offset);
+
         if byte_offset <= self.len_left() / 2 {
-            ChunkSummary::from(&self.left_chunk()[..byte_offset])
+            let next = if after.is_empty() { self.right_chunk() } else { after };
+            ChunkSummary::from_followed_by(up_to, next)
         } else {
             self.left_summary
-                - ChunkSummary::from(&self.left_chunk()[byte_offset..])
+                - ChunkSummary::from_followed_by(after, self.right_chunk())
         }
     }
 
@@ -1091,24 +1246,34 @@ impl<const MAX_BYTES: usize> GapBuffer<MAX_BYTES> {
             // The whole range is inside the left chunk.
             if end <= buffer.len_left() {
                 let chunk = &buffer.left_chunk()[start..end];
-                ChunkSummary::from(chunk)
+
+                let next = if end < buffer.len_left() {
+                    &buffer.left_chunk()[end..]
+                } else {
+                    buffer.right_chunk()
+                };
+
+                ChunkSummary::from_followed_by(chunk, next)
             }
             // The start is on the left chunk and the end is on the right.
             else if start <= buffer.len_left() {
                 let left_chunk = &buffer.left_chunk()[start..];
 
-                ChunkSummary::from(left_chunk)
-                    + buffer.summarize_right_chunk_up_to(
-                        end - buffer.len_left(),
-                        summary,
-                    )
+                ChunkSummary::from_followed_by(
+                    left_chunk,
+                    buffer.right_chunk(),
+                ) + buffer.summarize_right_chunk_up_to(
+                    end - buffer.len_left(),
+                    summary,
+                )
             }
             // The whole range is inside the right chunk.
             else {
                 start -= buffer.len_left();
                 end -= buffer.len_left();
                 let chunk = &buffer.right_chunk()[start..end];
-                ChunkSummary::from(chunk)
+                let next = &buffer.right_chunk()[end..];
+                ChunkSummary::from_followed_by(chunk, next)
             }
         }
 
@@ -1145,12 +1310,12 @@ impl<const MAX_BYTES: usize> GapBuffer<MAX_BYTES> {
         debug_assert!(self.right_chunk().is_char_boundary(byte_offset));
         debug_assert_eq!(summary, self.summarize());
 
+        let (up_to, after) = self.right_chunk().split_at(byte_offset);
+
         if byte_offset <= self.len_right() / 2 {
-            ChunkSummary::from(&self.right_chunk()[..byte_offset])
+            ChunkSummary::from_followed_by(up_to, after)
         } else {
-            summary
-                - self.left_summary
-                - ChunkSummary::from(&self.right_chunk()[byte_offset..])
+            summary - self.left_summary - ChunkSummary::from(after)
         }
     }
 
@@ -1173,7 +1338,14 @@ impl<const MAX_BYTES: usize> GapBuffer<MAX_BYTES> {
         debug_assert_eq!(summary, self.summarize());
 
         if byte_offset <= self.len_left() {
-            let new_summary = self.summarize_left_chunk_up_to(byte_offset);
+            let (up_to, after) = self.left_chunk().split_at(byte_offset);
+
+            let next = if after.is_empty() { self.right_chunk() } else { after };
+
+            // The kept text is now at the end of the buffer.
+            let new_summary = self
+                .summarize_left_chunk_up_to(byte_offset)
+                .with_next(up_to, next, "");
 
             self.left_summary = new_summary;
 
@@ -1183,8 +1355,12 @@ impl<const MAX_BYTES: usize> GapBuffer<MAX_BYTES> {
         } else {
             let offset = byte_offset - self.len_left();
 
-            let new_right_summary =
-                self.summarize_right_chunk_up_to(offset, summary);
+            let (up_to, after) = self.right_chunk().split_at(offset);
+
+            // Same as above.
+            let new_right_summary = self
+                .summarize_right_chunk_up_to(offset, summary)
+                .with_next(up_to, after, "");
 
             let range = {
                 let start = MAX_BYTES - self.len_right();
@@ -1225,11 +1401,16 @@ impl<const MAX_BYTES: usize> From<GapSlice<'_>> for GapBuffer<MAX_BYTES> {
         bytes[MAX_BYTES - slice.len_right()..]
             .copy_from_slice(slice.right_chunk().as_bytes());
 
-        Self {
-            bytes,
-            left_summary: slice.left_summary,
-            len_right: slice.len_right,
-        }
+        // The `\r` of a split CRLF is a line break on its own once it's at
+        // the end of the buffer.
+        let left_summary =
+            if slice.ends_in_split_crlf() && slice.len_right() == 0 {
+                ChunkSummary::from(slice.left_chunk())
+            } else {
+                slice.left_summary
+            };
+
+        Self { bytes, left_summary, len_right: slice.len_right }
     }
 }
 
@@ -1249,6 +1430,9 @@ impl<const MAX_BYTES: usize> AsSlice for GapBuffer<MAX_BYTES> {
             bytes,
             left_summary: self.left_summary,
             len_right: self.len_right,
+            // The halves of a CRLF pair are never split across leaves.
+            #[cfg(feature = "unicode-lines")]
+            ends_in_split_crlf: false,
         }
     }
 }
@@ -1264,10 +1448,20 @@ impl<const MAX_BYTES: usize> BalancedLeaf for GapBuffer<MAX_BYTES> {
         (left, left_summary): (&mut Self, &mut ChunkSummary),
         (right, right_summary): (&mut Self, &mut ChunkSummary),
     ) {
+        // The summaries of the two leaves don't take each other's text into
+        // account, so a CRLF pair split between them would be counted twice.
+        // While moving text around we use the summary of the left leaf as if
+        // it was followed by the right one, and undo that at the end.
+        let left_with_right = left_summary.with_next(
+            left.last_chunk(),
+            "",
+            right.first_chunk(),
+        );
+
         // The two leaves can be combined in a single chunk.
         if left.len() + right.len() <= MAX_BYTES {
             left.append_other(*left_summary, right);
-            *left_summary += *right_summary;
+            *left_summary = left_with_right + *right_summary;
             *right_summary = ChunkSummary::new();
 
             debug_assert!(right.is_empty());
@@ -1278,7 +1472,11 @@ impl<const MAX_BYTES: usize> BalancedLeaf for GapBuffer<MAX_BYTES> {
 
             let missing_left = Self::min_bytes() - left.len();
             let moved_left = left.add_from_right(missing_left, right);
-            *left_summary += moved_left;
+            *left_summary = (left_with_right + moved_left).with_next(
+                left.last_chunk(),
+                right.first_chunk(),
+                "",
+            );
             *right_summary -= moved_left;
 
             debug_assert!(left.len() >= Self::chunk_min());
@@ -1291,7 +1489,11 @@ impl<const MAX_BYTES: usize> BalancedLeaf for GapBuffer<MAX_BYTES> {
             let missing_right = Self::min_bytes() - right.len();
             let moved_right =
                 left.move_to_right(missing_right, right, *left_summary);
-            *left_summary -= moved_right;
+            *left_summary = (left_with_right - moved_right).with_next(
+                left.last_chunk(),
+                right.first_chunk(),
+                "",
+            );
             *right_summary += moved_right;
 
             debug_assert!(left.len() >= Self::chunk_min());
@@ -1342,9 +1544,11 @@ impl<const MAX_BYTES: usize> ReplaceableLeaf<ByteMetric>
 
             None
         } else {
-            let (new_summary, extras) =
+            let (new_summary, mut extras) =
                 self.replace_overflowing(start..end, replacement, *summary);
 
+            let new_summary = self.join_split_crlf(new_summary, &mut extras);
+
             debug_assert_eq!(new_summary, self.summarize());
 
             *summary = new_summary;
@@ -1444,6 +1648,69 @@ impl<'a, const CHUNKS: usize, const MAX_BYTES: usize>
         debug_assert!(total >= GapBuffer::<MAX_BYTES>::chunk_min());
         Self { total, segments, yielded: 0, start: 0 }
     }
+
+    /// Moves the end of the next buffer, which is currently between `left`
+    /// and `right` in the segment at `idx_last`, if it splits a CRLF pair.
+    ///
+    /// The `\r` is moved to the following buffer, unless it's the only byte
+    /// of the next one, in which case the `\n` is taken from the following
+    /// buffer instead.
+    #[inline]
+    fn avoid_splitting_crlf(
+        &self,
+        idx_last: usize,
+        left: &'a str,
+        right: &'a str,
+    ) -> (usize, &'a str, &'a str) {
+        let no_change = (idx_last, left, right);
+
+        let (idx_before, before) = if !left.is_empty() {
+            (idx_last, left)
+        } else {
+            match self.segments[self.start..idx_last]
+                .iter()
+                .enumerate()
+                .rev()
+                .find(|(_, s)| !s.is_empty())
+            {
+                Some((idx, &s)) => (self.start + idx, s),
+                None => return no_change,
+            }
+        };
+
+        let (idx_after, after) = if !right.is_empty() {
+            (idx_last, right)
+        } else {
+            match self.segments[idx_last + 1..]
+                .iter()
+                .enumerate()
+                .find(|(_, s)| !s.is_empty())
+            {
+                Some((idx, &s)) => (idx_last + 1 + idx, s),
+                None => return no_change,
+            }
+        };
+
+        if !splits_crlf(before, after) {
+            return no_change;
+        }
+
+        let len_next = self.segments[self.start..idx_before]
+            .iter()
+            .map(|s| s.len())
+            .sum::<usize>()
+            + before.len();
+
+        if len_next > 1 {
+            let (left, right) =
+                self.segments[idx_before].split_at(before.len() - 1);
+            (idx_before, left, right)
+        } else {
+            let segment = self.segments[idx_after];
+            let (left, right) = segment.split_at(segment.len() - after.len() + 1);
+            (idx_after, left, right)
+        }
+    }
 }
 
 impl<const CHUNKS: usize, const MAX_BYTES: usize> Iterator
@@ -1506,12 +1773,15 @@ impl<const CHUNKS: usize, const MAX_BYTES: usize> Iterator
                     self.segments[idx_last],
                     last_segment_len,
                 );
+            }
 
-                self.segments[idx_last] = left;
-            } else {
-                self.segments[idx_last] = left;
+            if cfg!(feature = "unicode-lines") {
+                (idx_last, left, right) =
+                    self.avoid_splitting_crlf(idx_last, left, right);
             }
 
+            self.segments[idx_last] = left;
+
             let next = GapBuffer::<MAX_BYTES>::from_chunks(
                 &self.segments[self.start..=idx_last],
             );
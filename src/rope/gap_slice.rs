@@ -1,5 +1,9 @@
 use super::metrics::{ChunkSummary, SummaryUpTo, ToByteOffset};
-use super::utils::{debug_no_quotes, panic_messages as panic};
+use super::utils::{
+    debug_no_quotes,
+    ends_with_line_break,
+    panic_messages as panic,
+};
 use crate::tree::{Metric, Summarize};
 
 /// A slice of a [`GapBuffer`](super::gap_buffer::GapBuffer).
@@ -8,6 +12,12 @@ pub struct GapSlice<'a> {
     pub(super) bytes: &'a [u8],
     pub(super) left_summary: ChunkSummary,
     pub(super) len_right: u16,
+
+    /// Whether the slice ends with the `\r` of a CRLF pair whose `\n` is
+    /// right after the end of the slice. That `\r` is then not counted as a
+    /// line break, since the line break is counted on the `\n`.
+    #[cfg(feature = "unicode-lines")]
+    pub(super) ends_in_split_crlf: bool,
 }
 
 impl core::fmt::Debug for GapSlice<'_> {
@@ -54,7 +64,20 @@ impl<'a> GapSlice<'a> {
     }
 
     pub(super) fn assert_invariants(&self) {
-        assert_eq!(self.left_summary, ChunkSummary::from(self.left_chunk()));
+        let after_left = if self.len_right() > 0 {
+            self.right_chunk()
+        } else {
+            self.after_last_chunk()
+        };
+
+        assert_eq!(
+            self.left_summary,
+            ChunkSummary::from_followed_by(self.left_chunk(), after_left)
+        );
+
+        if self.ends_in_split_crlf() {
+            assert!(self.last_chunk().ends_with('\r'));
+        }
 
         if self.len_right() == 0 {
             assert_eq!(self.len_left(), self.bytes.len());
@@ -80,6 +103,31 @@ impl<'a> GapSlice<'a> {
         }
     }
 
+    /// Returns `"\n"` if the slice [ends in a split
+    /// CRLF](Self::ends_in_split_crlf()), or an empty string otherwise.
+    #[inline]
+    fn after_last_chunk(&self) -> &'static str {
+        if self.ends_in_split_crlf() {
+            "\n"
+        } else {
+            ""
+        }
+    }
+
+    /// Returns `true` if the slice ends with the `\r` of a CRLF pair whose
+    /// `\n` is right after the end of the slice.
+    #[inline]
+    pub(super) fn ends_in_split_crlf(&self) -> bool {
+        #[cfg(feature = "unicode-lines")]
+        {
+            self.ends_in_split_crlf
+        }
+        #[cfg(not(feature = "unicode-lines"))]
+        {
+            false
+        }
+    }
+
     #[inline]
     fn left_measure<M>(&self) -> M
     where
@@ -98,15 +146,28 @@ impl<'a> GapSlice<'a> {
 
         use core::cmp::Ordering;
 
-        let last_char = self
-            .last_chunk()
+        let last_chunk = self.last_chunk();
+
+        let len_utf8 = last_chunk
             .chars()
             .next_back()
-            .expect("this slice isn't empty");
-
-        let removed_summary = ChunkSummary::from(last_char);
-
-        let len_utf8 = removed_summary.bytes();
+            .expect("this slice isn't empty")
+            .len_utf8();
+
+        let last_char = &last_chunk[last_chunk.len() - len_utf8..];
+
+        let removed_summary =
+            ChunkSummary::from_followed_by(last_char, self.after_last_chunk());
+
+        // If we're removing the `\n` of a CRLF pair, the slice now ends with
+        // the `\r` of a split CRLF.
+        #[cfg(feature = "unicode-lines")]
+        {
+            let rest = &last_chunk[..last_chunk.len() - len_utf8];
+            let rest = if rest.is_empty() { self.left_chunk() } else { rest };
+            self.ends_in_split_crlf =
+                super::utils::splits_crlf(rest, last_char);
+        }
 
         match self.len_right.cmp(&(len_utf8 as u16)) {
             // The slice doesn't have a right chunk, so we shorten the left
@@ -148,9 +209,11 @@ impl<'a> GapSlice<'a> {
             return summary;
         }
 
+        let is_lf = self.last_chunk().ends_with('\n');
+
         let mut new_summary = self.truncate_last_char(summary);
 
-        if self.last_chunk().ends_with('\r') {
+        if is_lf && self.last_chunk().ends_with('\r') {
             new_summary = self.truncate_last_char(new_summary)
         }
 
@@ -162,10 +225,10 @@ impl<'a> GapSlice<'a> {
         Self::default()
     }
 
-    /// Returns `true` if it ends with a newline.
+    /// Returns `true` if it ends with a line break.
     #[inline]
     pub(super) fn has_trailing_newline(&self) -> bool {
-        self.last_chunk().ends_with('\n')
+        ends_with_line_break(self.last_chunk()) && !self.ends_in_split_crlf()
     }
 
     #[inline]
@@ -284,12 +347,17 @@ impl<'a> GapSlice<'a> {
                 bytes: bytes_left,
                 left_summary: left_left_summary,
                 len_right: 0,
+                #[cfg(feature = "unicode-lines")]
+                ends_in_split_crlf: self.splits_crlf_at(byte_offset),
             };
 
             let right = Self {
                 bytes: bytes_right,
                 left_summary: self.left_summary - left_left_summary,
                 len_right: self.len_right,
+                #[cfg(feature = "unicode-lines")]
+                ends_in_split_crlf: self.ends_in_split_crlf
+                    && byte_offset < self.len(),
             };
 
             ((left, left.left_summary), (right, summary - left.left_summary))
@@ -312,18 +380,39 @@ impl<'a> GapSlice<'a> {
                 bytes: bytes_left,
                 left_summary: self.left_summary,
                 len_right: right_left_summary.bytes() as u16,
+                #[cfg(feature = "unicode-lines")]
+                ends_in_split_crlf: self
+                    .splits_crlf_at(self.len_left() + byte_offset),
             };
 
             let right = Self {
                 bytes: bytes_right,
                 left_summary: self.right_summary(summary) - right_left_summary,
                 len_right: 0,
+                #[cfg(feature = "unicode-lines")]
+                ends_in_split_crlf: self.ends_in_split_crlf
+                    && self.len_left() + byte_offset < self.len(),
             };
 
             ((left, summary - right.left_summary), (right, right.left_summary))
         }
     }
 
+    /// Returns `true` if splitting the slice at the given byte offset would
+    /// leave a CRLF pair split between the two halves, taking into account
+    /// the text after the end of the slice.
+    #[cfg(feature = "unicode-lines")]
+    #[inline]
+    fn splits_crlf_at(&self, byte_offset: usize) -> bool {
+        if byte_offset == self.len() {
+            self.ends_in_split_crlf
+        } else {
+            byte_offset > 0
+                && self.byte(byte_offset - 1) == b'\r'
+                && self.byte(byte_offset) == b'\n'
+        }
+    }
+
     #[inline]
     fn split_bytes(&self, byte_offset: usize) -> (&'a [u8], &'a [u8]) {
         debug_assert!(byte_offset <= self.len());
@@ -348,7 +437,10 @@ impl<'a> GapSlice<'a> {
 
     #[inline]
     fn summarize_right_chunk(&self) -> ChunkSummary {
-        ChunkSummary::from(self.right_chunk())
+        ChunkSummary::from_followed_by(
+            self.right_chunk(),
+            self.after_last_chunk(),
+        )
     }
 }
 
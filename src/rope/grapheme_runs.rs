@@ -0,0 +1,802 @@
+//! This module contains the leaves of the tree used by `Rope`s to index their
+//! extended grapheme clusters when the `grapheme-metric` feature is enabled.
+//!
+//! Whether a byte offset is a grapheme boundary depends on the text around
+//! it, so the number of graphemes in a string is not the sum of the graphemes
+//! in its sub-strings. This means it can't be stored in a `ChunkSummary`,
+//! whose fields have to add up no matter where a `GapBuffer` (or its gap)
+//! happens to split the text.
+//!
+//! Instead, a `Rope` keeps a second `Tree` whose leaves store the byte
+//! lengths of its graphemes, run-length encoded. Since every leaf starts and
+//! ends on a grapheme boundary their summaries can be added up like any
+//! other, and the tree is updated locally every time the `Rope` is edited.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::{
+    Add,
+    AddAssign,
+    ControlFlow,
+    Range,
+    RangeBounds,
+    Sub,
+    SubAssign,
+};
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::metrics::ByteMetric;
+use super::Rope;
+use crate::range_bounds_to_start_end;
+use crate::tree::{
+    AsSlice,
+    BalancedLeaf,
+    BaseMeasured,
+    Metric,
+    ReplaceableLeaf,
+    SlicingMetric,
+    Summarize,
+    Tree,
+};
+
+#[cfg(any(test, feature = "small_chunks"))]
+const MAX_RUNS: usize = 4;
+
+#[cfg(not(any(test, feature = "small_chunks")))]
+const MAX_RUNS: usize = 64;
+
+const MIN_RUNS: usize = MAX_RUNS / 4;
+
+pub(super) type GraphemeTree = Tree<{ Rope::arity() }, GraphemeRuns>;
+
+/// A sequence of `count` consecutive graphemes, each of them `len` bytes
+/// long.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) struct Run {
+    len: usize,
+    count: usize,
+}
+
+/// Pushes a run at the end of `runs`, merging it with the last one if their
+/// graphemes have the same length.
+#[inline]
+fn push_run(runs: &mut Vec<Run>, run: Run) {
+    if run.count == 0 {
+        return;
+    }
+
+    match runs.last_mut() {
+        Some(last) if last.len == run.len => last.count += run.count,
+        _ => runs.push(run),
+    }
+}
+
+/// Run-length encodes the byte lengths of a sequence of graphemes.
+#[inline]
+pub(super) fn runs<I>(grapheme_lens: I) -> Vec<Run>
+where
+    I: IntoIterator<Item = usize>,
+{
+    let mut runs = Vec::new();
+
+    for len in grapheme_lens {
+        push_run(&mut runs, Run { len, count: 1 });
+    }
+
+    runs
+}
+
+/// Calls `fun` with the byte length of every grapheme in the text obtained by
+/// concatenating the given chunks, stopping early if it returns
+/// [`ControlFlow::Break`].
+///
+/// Every chunk is segmented together with the last grapheme of the previous
+/// one, which always starts on a grapheme boundary. We don't use a
+/// `GraphemeCursor` fed one chunk at a time because it can get sequences of
+/// regional indicators spanning several chunks wrong.
+#[inline]
+pub(super) fn for_each_grapheme_len<'a, C, F>(chunks: C, mut fun: F)
+where
+    C: IntoIterator<Item = &'a str>,
+    F: FnMut(usize) -> ControlFlow<()>,
+{
+    let mut buffer = String::new();
+
+    // The length of the last grapheme in the buffer, which could continue in
+    // the next chunk.
+    let mut last_len = 0;
+
+    for chunk in chunks {
+        buffer.drain(..buffer.len() - last_len);
+        buffer.push_str(chunk);
+
+        let mut graphemes = buffer.graphemes(true).map(str::len);
+
+        last_len = graphemes.next_back().unwrap_or(0);
+
+        for len in graphemes {
+            if fun(len).is_break() {
+                return;
+            }
+        }
+    }
+
+    if last_len > 0 {
+        let _ = fun(last_len);
+    }
+}
+
+/// Returns an iterator over the byte lengths of all the graphemes indexed by
+/// the tree.
+#[inline]
+pub(super) fn grapheme_lens(
+    tree: &GraphemeTree,
+) -> impl Iterator<Item = usize> + '_ {
+    tree.leaves().flat_map(|slice| {
+        slice
+            .runs()
+            .flat_map(|run| core::iter::repeat(run.len).take(run.count))
+    })
+}
+
+/// Returns a tree indexing the graphemes of the `byte_range` of the text
+/// indexed by `tree`, whose chunks are given by `slice`.
+///
+/// The graphemes fully contained in the range are sliced out of `tree`.
+/// Cutting off the text before the range can move the boundaries after its
+/// start, so those are re-segmented up to the first boundary that didn't
+/// move, while cutting off the text after it only turns the partial grapheme
+/// at its end into a grapheme of its own.
+#[inline]
+pub(super) fn slice_tree<'a, C>(
+    tree: &GraphemeTree,
+    byte_range: Range<usize>,
+    slice: C,
+) -> GraphemeTree
+where
+    C: IntoIterator<Item = &'a str>,
+{
+    let Range { start, end } = byte_range;
+
+    // The first grapheme starting at or after `start`, and the one
+    // containing `end`.
+    let GraphemeMetric(first) = tree.convert_measure(ByteMetric(start));
+    let ByteMetric(first_start) = tree.convert_measure(GraphemeMetric(first));
+    let first = first + (first_start < start) as usize;
+    let GraphemeMetric(last) = tree.convert_measure(ByteMetric(end));
+
+    if first >= last {
+        return GraphemeRuns::tree(slice);
+    }
+
+    let mut graphemes: GraphemeTree =
+        Tree::from(tree.slice(GraphemeMetric(first)..GraphemeMetric(last)));
+
+    let ByteMetric(inner_start) = tree.convert_measure(GraphemeMetric(first));
+
+    // The end of the re-segmented graphemes, and of the sliced out ones
+    // they replace.
+    let mut new_end = 0;
+    let mut old_end = inner_start - start;
+
+    let mut grapheme_lens_new = Vec::new();
+
+    let mut found_boundary = false;
+
+    {
+        let mut grapheme_lens_old = grapheme_lens(&graphemes);
+
+        for_each_grapheme_len(slice, |len| {
+            grapheme_lens_new.push(len);
+
+            new_end += len;
+
+            while old_end < new_end {
+                match grapheme_lens_old.next() {
+                    Some(len) => old_end += len,
+                    None => return ControlFlow::Continue(()),
+                }
+            }
+
+            found_boundary = old_end == new_end;
+
+            if found_boundary {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+    }
+
+    // None of the sliced out boundaries is still a boundary, so the whole
+    // slice has been re-segmented.
+    if !found_boundary {
+        return Tree::from_leaves(GraphemeRuns::segment(runs(
+            grapheme_lens_new,
+        )));
+    }
+
+    graphemes.replace(
+        ByteMetric(0)..ByteMetric(old_end - (inner_start - start)),
+        &runs(grapheme_lens_new),
+    );
+
+    let ByteMetric(inner_end) = tree.convert_measure(GraphemeMetric(last));
+
+    if end > inner_end {
+        let run = Run { len: end - inner_end, count: 1 };
+
+        let ByteMetric(len) = graphemes.base_measure();
+
+        graphemes.replace(ByteMetric(len)..ByteMetric(len), &[run]);
+    }
+
+    graphemes
+}
+
+#[derive(Clone, Default, Debug)]
+pub(super) struct GraphemeRuns {
+    runs: Vec<Run>,
+}
+
+impl GraphemeRuns {
+    /// Splits a sequence of runs into leaves containing between `MIN_RUNS`
+    /// and `MAX_RUNS` runs each.
+    ///
+    /// The only exception is if there are at most `MAX_RUNS` runs in total,
+    /// in which case they're all put in a single leaf.
+    #[inline]
+    fn segment(runs: Vec<Run>) -> alloc::vec::IntoIter<Self> {
+        if runs.len() <= MAX_RUNS {
+            return alloc::vec![Self { runs }].into_iter();
+        }
+
+        let num_leaves = (runs.len() + MAX_RUNS - 1) / MAX_RUNS;
+
+        let min_len = runs.len() / num_leaves;
+
+        let mut longer = runs.len() % num_leaves;
+
+        let mut runs = runs.into_iter();
+
+        let mut leaves = Vec::with_capacity(num_leaves);
+
+        for _ in 0..num_leaves {
+            let len = min_len + (longer > 0) as usize;
+            longer = longer.saturating_sub(1);
+            leaves.push(Self { runs: runs.by_ref().take(len).collect() });
+        }
+
+        debug_assert!(leaves.iter().all(|leaf| leaf.runs.len() >= MIN_RUNS));
+
+        leaves.into_iter()
+    }
+
+    /// Creates a new tree indexing the graphemes of the text obtained by
+    /// concatenating the given chunks.
+    #[inline]
+    pub(super) fn tree<'a, C>(chunks: C) -> GraphemeTree
+    where
+        C: IntoIterator<Item = &'a str>,
+    {
+        let mut runs = Vec::new();
+
+        for_each_grapheme_len(chunks, |len| {
+            push_run(&mut runs, Run { len, count: 1 });
+            ControlFlow::Continue(())
+        });
+
+        Tree::from_leaves(Self::segment(runs))
+    }
+}
+
+impl Summarize for GraphemeRuns {
+    type Summary = GraphemeSummary;
+
+    #[inline]
+    fn summarize(&self) -> Self::Summary {
+        self.as_slice().summarize()
+    }
+}
+
+impl BaseMeasured for GraphemeRuns {
+    type BaseMetric = ByteMetric;
+}
+
+impl AsSlice for GraphemeRuns {
+    type Slice<'a> = GraphemeRunsSlice<'a>;
+
+    #[inline]
+    fn as_slice(&self) -> GraphemeRunsSlice<'_> {
+        GraphemeRunsSlice { runs: &self.runs, skip_first: 0, skip_last: 0 }
+    }
+}
+
+impl From<GraphemeRunsSlice<'_>> for GraphemeRuns {
+    #[inline]
+    fn from(slice: GraphemeRunsSlice<'_>) -> Self {
+        let mut runs = Vec::with_capacity(slice.runs.len());
+
+        for run in slice.runs() {
+            push_run(&mut runs, run);
+        }
+
+        Self { runs }
+    }
+}
+
+impl BalancedLeaf for GraphemeRuns {
+    #[inline]
+    fn is_underfilled(&self, _: &GraphemeSummary) -> bool {
+        self.runs.len() < MIN_RUNS
+    }
+
+    #[inline]
+    fn balance_leaves(
+        (left, left_summary): (&mut Self, &mut GraphemeSummary),
+        (right, right_summary): (&mut Self, &mut GraphemeSummary),
+    ) {
+        // The two leaves can be combined in a single leaf.
+        if left.runs.len() + right.runs.len() <= MAX_RUNS {
+            for run in right.runs.drain(..) {
+                push_run(&mut left.runs, run);
+            }
+            *left_summary += &*right_summary;
+            *right_summary = GraphemeSummary::default();
+            return;
+        }
+
+        // The left side is underfilled => take runs from the right side.
+        if left.runs.len() < MIN_RUNS {
+            let mut moved = 0;
+
+            while left.runs.len() < MIN_RUNS {
+                push_run(&mut left.runs, right.runs[moved]);
+                moved += 1;
+            }
+
+            right.runs.drain(..moved);
+        }
+        // The right side is underfilled => take runs from the left side.
+        else if right.runs.len() < MIN_RUNS {
+            while right.runs.len() < MIN_RUNS {
+                let run = left.runs.pop().unwrap();
+
+                match right.runs.first_mut() {
+                    Some(first) if first.len == run.len => {
+                        first.count += run.count
+                    },
+                    _ => right.runs.insert(0, run),
+                }
+            }
+        } else {
+            return;
+        }
+
+        *left_summary = left.summarize();
+        *right_summary = right.summarize();
+
+        debug_assert!(left.runs.len() >= MIN_RUNS);
+        debug_assert!(right.runs.len() >= MIN_RUNS);
+    }
+}
+
+impl ReplaceableLeaf<ByteMetric> for GraphemeRuns {
+    type Replacement<'a> = &'a [Run];
+
+    type ExtraLeaves = alloc::vec::IntoIter<Self>;
+
+    #[inline]
+    fn replace<R>(
+        &mut self,
+        summary: &mut GraphemeSummary,
+        range: R,
+        replacement: &[Run],
+    ) -> Option<Self::ExtraLeaves>
+    where
+        R: RangeBounds<ByteMetric>,
+    {
+        let (start, end) = range_bounds_to_start_end(range, 0, summary.bytes);
+
+        debug_assert!(start <= end);
+        debug_assert!(end <= summary.bytes);
+
+        let (left, rest) = self.as_slice().split_at_byte(start);
+        let (_, right) = rest.split_at_byte(end - start);
+
+        debug_assert_eq!(left.summarize().bytes, start);
+        debug_assert_eq!(right.summarize().bytes, summary.bytes - end);
+
+        let mut runs =
+            Vec::with_capacity(self.runs.len() + replacement.len() + 1);
+
+        for run in
+            left.runs().chain(replacement.iter().copied()).chain(right.runs())
+        {
+            push_run(&mut runs, run);
+        }
+
+        if runs.len() <= MAX_RUNS {
+            self.runs = runs;
+            *summary = self.summarize();
+            None
+        } else {
+            let mut leaves = Self::segment(runs);
+            *self = leaves.next().unwrap();
+            *summary = self.summarize();
+            Some(leaves)
+        }
+    }
+
+    #[inline]
+    fn remove_up_to(
+        &mut self,
+        summary: &mut GraphemeSummary,
+        up_to: ByteMetric,
+    ) {
+        self.replace(summary, ..up_to, &[]);
+    }
+}
+
+#[derive(Copy, Clone, Default, Debug)]
+pub(super) struct GraphemeRunsSlice<'a> {
+    runs: &'a [Run],
+
+    /// The number of graphemes at the start of the first run that are not
+    /// part of the slice.
+    skip_first: usize,
+
+    /// The number of graphemes at the end of the last run that are not part
+    /// of the slice.
+    skip_last: usize,
+}
+
+impl<'a> GraphemeRunsSlice<'a> {
+    /// Returns the start and end of the graphemes of the `idx`-th run that
+    /// are part of the slice.
+    #[inline]
+    fn run_range(&self, idx: usize) -> (usize, usize) {
+        let start = if idx == 0 { self.skip_first } else { 0 };
+
+        let end = if idx + 1 == self.runs.len() {
+            self.runs[idx].count - self.skip_last
+        } else {
+            self.runs[idx].count
+        };
+
+        (start, end)
+    }
+
+    /// Returns an iterator over the non-empty runs of the slice.
+    #[inline]
+    fn runs(self) -> impl Iterator<Item = Run> + 'a {
+        (0..self.runs.len())
+            .map(move |idx| {
+                let (start, end) = self.run_range(idx);
+                Run { len: self.runs[idx].len, count: end - start }
+            })
+            .filter(|run| run.count > 0)
+    }
+
+    /// Splits the slice at the last grapheme boundary before or at the given
+    /// byte offset.
+    #[inline]
+    fn split_at_byte(self, mut byte_offset: usize) -> (Self, Self) {
+        let mut graphemes = 0;
+
+        for run in self.runs() {
+            let run_bytes = run.len * run.count;
+
+            if byte_offset < run_bytes {
+                graphemes += byte_offset / run.len;
+                break;
+            }
+
+            byte_offset -= run_bytes;
+            graphemes += run.count;
+        }
+
+        self.split_at_grapheme(graphemes)
+    }
+
+    /// Splits the slice after its first `graphemes` graphemes.
+    #[inline]
+    fn split_at_grapheme(self, mut graphemes: usize) -> (Self, Self) {
+        for (idx, run) in self.runs.iter().enumerate() {
+            let (start, end) = self.run_range(idx);
+
+            if graphemes <= end - start {
+                let split = start + graphemes;
+
+                let left = Self {
+                    runs: &self.runs[..=idx],
+                    skip_first: self.skip_first,
+                    skip_last: run.count - split,
+                };
+
+                let right = Self {
+                    runs: &self.runs[idx..],
+                    skip_first: split,
+                    skip_last: self.skip_last,
+                };
+
+                return (left, right);
+            }
+
+            graphemes -= end - start;
+        }
+
+        debug_assert_eq!(graphemes, 0);
+
+        (self, Self::default())
+    }
+}
+
+impl Summarize for GraphemeRunsSlice<'_> {
+    type Summary = GraphemeSummary;
+
+    #[inline]
+    fn summarize(&self) -> Self::Summary {
+        self.runs().fold(GraphemeSummary::default(), |mut summary, run| {
+            summary.bytes += run.len * run.count;
+            summary.graphemes += run.count;
+            summary
+        })
+    }
+}
+
+#[derive(Copy, Clone, Default, Debug, PartialEq)]
+pub(super) struct GraphemeSummary {
+    bytes: usize,
+    graphemes: usize,
+}
+
+impl Add<&Self> for GraphemeSummary {
+    type Output = Self;
+
+    #[inline]
+    fn add(mut self, rhs: &Self) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+impl Sub<&Self> for GraphemeSummary {
+    type Output = Self;
+
+    #[inline]
+    fn sub(mut self, rhs: &Self) -> Self {
+        self -= rhs;
+        self
+    }
+}
+
+impl AddAssign<&Self> for GraphemeSummary {
+    #[inline]
+    fn add_assign(&mut self, rhs: &Self) {
+        self.bytes += rhs.bytes;
+        self.graphemes += rhs.graphemes;
+    }
+}
+
+impl SubAssign<&Self> for GraphemeSummary {
+    #[inline]
+    fn sub_assign(&mut self, rhs: &Self) {
+        self.bytes -= rhs.bytes;
+        self.graphemes -= rhs.graphemes;
+    }
+}
+
+impl Metric<GraphemeSummary> for ByteMetric {
+    #[inline]
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    #[inline]
+    fn one() -> Self {
+        Self(1)
+    }
+
+    #[inline]
+    fn measure(summary: &GraphemeSummary) -> Self {
+        Self(summary.bytes)
+    }
+}
+
+/// Byte offsets that don't lie on a grapheme boundary are rounded down to the
+/// start of the grapheme containing them.
+impl SlicingMetric<GraphemeRuns> for ByteMetric {
+    #[inline]
+    fn slice_up_to<'a>(
+        slice: GraphemeRunsSlice<'a>,
+        ByteMetric(byte_offset): Self,
+        _: &GraphemeSummary,
+    ) -> (GraphemeRunsSlice<'a>, GraphemeSummary)
+    where
+        'a: 'a,
+    {
+        let (left, _) = slice.split_at_byte(byte_offset);
+        (left, left.summarize())
+    }
+
+    #[inline]
+    fn slice_from<'a>(
+        slice: GraphemeRunsSlice<'a>,
+        ByteMetric(byte_offset): Self,
+        _: &GraphemeSummary,
+    ) -> (GraphemeRunsSlice<'a>, GraphemeSummary)
+    where
+        'a: 'a,
+    {
+        let (_, right) = slice.split_at_byte(byte_offset);
+        (right, right.summarize())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(super) struct GraphemeMetric(pub(super) usize);
+
+impl Add for GraphemeMetric {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl Sub for GraphemeMetric {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+impl AddAssign for GraphemeMetric {
+    #[inline]
+    fn add_assign(&mut self, other: Self) {
+        self.0 += other.0
+    }
+}
+
+impl SubAssign for GraphemeMetric {
+    #[inline]
+    fn sub_assign(&mut self, other: Self) {
+        self.0 -= other.0
+    }
+}
+
+impl Metric<GraphemeSummary> for GraphemeMetric {
+    #[inline]
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    #[inline]
+    fn one() -> Self {
+        Self(1)
+    }
+
+    #[inline]
+    fn measure(summary: &GraphemeSummary) -> Self {
+        Self(summary.graphemes)
+    }
+}
+
+impl SlicingMetric<GraphemeRuns> for GraphemeMetric {
+    #[inline]
+    fn slice_up_to<'a>(
+        slice: GraphemeRunsSlice<'a>,
+        GraphemeMetric(grapheme_offset): Self,
+        _: &GraphemeSummary,
+    ) -> (GraphemeRunsSlice<'a>, GraphemeSummary)
+    where
+        'a: 'a,
+    {
+        let (left, _) = slice.split_at_grapheme(grapheme_offset);
+        (left, left.summarize())
+    }
+
+    #[inline]
+    fn slice_from<'a>(
+        slice: GraphemeRunsSlice<'a>,
+        GraphemeMetric(grapheme_offset): Self,
+        _: &GraphemeSummary,
+    ) -> (GraphemeRunsSlice<'a>, GraphemeSummary)
+    where
+        'a: 'a,
+    {
+        let (_, right) = slice.split_at_grapheme(grapheme_offset);
+        (right, right.summarize())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slice(runs: &[Run]) -> GraphemeRunsSlice<'_> {
+        GraphemeRunsSlice { runs, skip_first: 0, skip_last: 0 }
+    }
+
+    #[test]
+    fn runs_merge_equal_lengths() {
+        let runs = runs([1, 1, 2, 2, 2, 1]);
+
+        assert_eq!(
+            runs,
+            [
+                Run { len: 1, count: 2 },
+                Run { len: 2, count: 3 },
+                Run { len: 1, count: 1 }
+            ]
+        );
+    }
+
+    #[test]
+    fn split_at_byte_rounds_down() {
+        let runs = runs([1, 1, 3, 3, 1]);
+
+        let (left, right) = slice(&runs).split_at_byte(4);
+        assert_eq!(
+            left.summarize(),
+            GraphemeSummary { bytes: 2, graphemes: 2 }
+        );
+        assert_eq!(
+            right.summarize(),
+            GraphemeSummary { bytes: 7, graphemes: 3 }
+        );
+
+        let (left, right) = slice(&runs).split_at_byte(5);
+        assert_eq!(
+            left.summarize(),
+            GraphemeSummary { bytes: 5, graphemes: 3 }
+        );
+        assert_eq!(
+            right.summarize(),
+            GraphemeSummary { bytes: 4, graphemes: 2 }
+        );
+    }
+
+    #[test]
+    fn split_at_grapheme_nested() {
+        let runs = runs([1, 1, 1, 2, 2, 2]);
+
+        let (_, rest) = slice(&runs).split_at_grapheme(1);
+        let (middle, _) = rest.split_at_grapheme(3);
+
+        assert_eq!(
+            middle.summarize(),
+            GraphemeSummary { bytes: 4, graphemes: 3 }
+        );
+
+        assert_eq!(
+            GraphemeRuns::from(middle).runs,
+            [Run { len: 1, count: 2 }, Run { len: 2, count: 1 }]
+        );
+    }
+
+    #[test]
+    fn replace_overflowing() {
+        let mut leaf = GraphemeRuns { runs: runs([1, 2, 1]) };
+        let mut summary = leaf.summarize();
+
+        let replacement = runs([3, 4, 5, 6]);
+
+        let extras = leaf
+            .replace(&mut summary, ByteMetric(1)..ByteMetric(3), &replacement)
+            .unwrap()
+            .collect::<Vec<_>>();
+
+        assert_eq!(summary, leaf.summarize());
+
+        let total =
+            extras.iter().fold(summary, |acc, leaf| acc + &leaf.summarize());
+
+        assert_eq!(total, GraphemeSummary { bytes: 20, graphemes: 6 });
+    }
+}
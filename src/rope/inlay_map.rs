@@ -0,0 +1,1265 @@
+//! This module contains the [`InlayMap`], which splices pieces of virtual
+//! text into the text of a `Rope`.
+//!
+//! The map is a `Tree` whose leaves store the `Rope`'s text as a sequence of
+//! segments, each of them either a run of the `Rope`'s text or an inlay.
+//! Inlays take up no space in the `Rope` but do in the displayed text, so
+//! summing up the byte lengths of the segments in both coordinate systems is
+//! enough to translate offsets between them in logarithmic time.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::{Add, AddAssign, Range, RangeBounds, Sub, SubAssign};
+
+use super::iterators::Chunks;
+use super::metrics::ByteMetric;
+use super::utils::panic_messages as panic;
+use super::Rope;
+use crate::range_bounds_to_start_end;
+use crate::tree::{
+    AsSlice,
+    BalancedLeaf,
+    BaseMeasured,
+    Leaves,
+    Metric,
+    ReplaceableLeaf,
+    SlicingMetric,
+    Summarize,
+    Tree,
+};
+
+#[cfg(any(test, feature = "small_chunks"))]
+const MAX_SEGMENTS: usize = 4;
+
+#[cfg(not(any(test, feature = "small_chunks")))]
+const MAX_SEGMENTS: usize = 64;
+
+const MIN_SEGMENTS: usize = MAX_SEGMENTS / 4;
+
+type InlayTree = Tree<{ Rope::arity() }, InlaySegments>;
+
+/// A set of inlays, i.e. pieces of virtual text displayed between the chars
+/// of a [`Rope`] without being part of it, like type annotations or
+/// parameter names.
+///
+/// Every inlay is anchored at a byte offset of the `Rope` and is displayed
+/// right before the char at that offset. Inlays anchored at the same offset
+/// are displayed in the order they were inserted in.
+///
+/// The map doesn't hold on to the `Rope`, so the methods that need to look at
+/// its text take it as an argument, and it has to be kept up to date by
+/// calling [`edit()`](Self::edit()) after every edit, or by editing the
+/// `Rope` via [`replace()`](Self::replace()). Text inserted at the anchor of
+/// an inlay ends up before it, and inlays anchored inside a deleted range are
+/// moved to the end of the text that replaced it.
+///
+/// All the conversions between the offsets of the `Rope` and the ones of the
+/// displayed text run in logarithmic time.
+#[derive(Clone, Debug)]
+pub struct InlayMap {
+    tree: InlayTree,
+}
+
+impl InlayMap {
+    /// Returns the byte offset in the `Rope` of the given offset in the
+    /// displayed text.
+    ///
+    /// Offsets inside an inlay are mapped to its anchor.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offset is out of bounds (i.e. greater than
+    /// [`display_len()`](Self::display_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{InlayMap, Rope};
+    /// #
+    /// let r = Rope::from("let x = foo(1);");
+    ///
+    /// let mut map = InlayMap::new(&r);
+    /// map.insert(&r, 5, ": u8");
+    ///
+    /// assert_eq!(map.byte_of_display_offset(5), 5);
+    /// assert_eq!(map.byte_of_display_offset(7), 5);
+    /// assert_eq!(map.byte_of_display_offset(10), 6);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn byte_of_display_offset(&self, display_offset: usize) -> usize {
+        if display_offset > self.display_len() {
+            panic::byte_offset_out_of_bounds(
+                display_offset,
+                self.display_len(),
+            );
+        }
+
+        if display_offset == 0 {
+            return 0;
+        }
+
+        // The segments starting before the offset, the last of which
+        // contains the byte right before it.
+        let SegmentMetric(segments) =
+            self.tree.convert_measure(DisplayMetric(display_offset));
+
+        let ByteMetric(byte_end) =
+            self.tree.convert_measure(SegmentMetric(segments));
+
+        if let Segment::Inlay(_) = self.segment(segments - 1) {
+            return byte_end;
+        }
+
+        let DisplayMetric(display_end) =
+            self.tree.convert_measure(SegmentMetric(segments));
+
+        byte_end - (display_end - display_offset)
+    }
+
+    /// Returns an iterator over the chunks of the displayed text, which
+    /// yields the chunks of the `Rope` interleaved with the inlays.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{InlayMap, Rope};
+    /// #
+    /// let r = Rope::from("foo(1, 2)");
+    ///
+    /// let mut map = InlayMap::new(&r);
+    /// map.insert(&r, 4, "a: ");
+    /// map.insert(&r, 7, "b: ");
+    ///
+    /// assert_eq!(map.chunks(&r).collect::<String>(), "foo(a: 1, b: 2)");
+    /// ```
+    #[inline]
+    pub fn chunks<'a>(&'a self, rope: &'a Rope) -> InlayChunks<'a> {
+        InlayChunks {
+            rope,
+            leaves: self.tree.leaves(),
+            segments: &[],
+            offset: 0,
+            chunks: None,
+        }
+    }
+
+    /// Returns the byte length of the displayed text, i.e. of the `Rope`
+    /// plus all the inlays.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{InlayMap, Rope};
+    /// #
+    /// let r = Rope::from("foo");
+    ///
+    /// let mut map = InlayMap::new(&r);
+    /// assert_eq!(map.display_len(), 3);
+    ///
+    /// map.insert(&r, 3, ": &str");
+    /// assert_eq!(map.display_len(), 9);
+    /// ```
+    #[inline]
+    pub fn display_len(&self) -> usize {
+        self.tree.summary().display_bytes
+    }
+
+    /// Returns the offset in the displayed text of the given byte offset in
+    /// the `Rope`.
+    ///
+    /// The offset is the one right before the inlays anchored at the byte
+    /// offset, if there are any.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than the
+    /// byte length of the `Rope`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{InlayMap, Rope};
+    /// #
+    /// let r = Rope::from("let x = foo(1);");
+    ///
+    /// let mut map = InlayMap::new(&r);
+    /// map.insert(&r, 5, ": u8");
+    ///
+    /// assert_eq!(map.display_offset_of_byte(5), 5);
+    /// assert_eq!(map.display_offset_of_byte(6), 10);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn display_offset_of_byte(&self, byte_offset: usize) -> usize {
+        if byte_offset > self.byte_len() {
+            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
+        }
+
+        // The segments starting before the byte offset, the last of which
+        // is a run of text ending at or after it.
+        let SegmentMetric(segments) =
+            self.tree.convert_measure(ByteMetric(byte_offset));
+
+        let ByteMetric(byte_end) =
+            self.tree.convert_measure(SegmentMetric(segments));
+
+        let DisplayMetric(display_end) =
+            self.tree.convert_measure(SegmentMetric(segments));
+
+        display_end - (byte_end - byte_offset)
+    }
+
+    /// Updates the map after the byte range of the `Rope` it was created
+    /// from was replaced with `text_len` bytes of text, where `rope` is the
+    /// `Rope` after the edit.
+    ///
+    /// The byte range is in the coordinates of the text before the edit,
+    /// exactly like the one passed to [`Rope::replace()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than its end, or if its
+    /// end is greater than the byte length of the text before the edit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{InlayMap, Rope};
+    /// #
+    /// let mut r = Rope::from("let x = 1;");
+    ///
+    /// let mut map = InlayMap::new(&r);
+    /// map.insert(&r, 5, ": u8");
+    ///
+    /// r.insert(5, "yz");
+    /// map.edit(&r, 5..5, 2);
+    /// assert_eq!(map.inlays().collect::<Vec<_>>(), [(7, ": u8")]);
+    ///
+    /// r.delete(4..7);
+    /// map.edit(&r, 4..7, 0);
+    /// assert_eq!(map.inlays().collect::<Vec<_>>(), [(4, ": u8")]);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn edit<R>(&mut self, rope: &Rope, byte_range: R, text_len: usize)
+    where
+        R: RangeBounds<usize>,
+    {
+        let old_len = self.byte_len();
+
+        let (start, end) = range_bounds_to_start_end(byte_range, 0, old_len);
+
+        if start > end {
+            panic::byte_start_after_end(start, end);
+        }
+
+        if end > old_len {
+            panic::byte_offset_out_of_bounds(end, old_len);
+        }
+
+        debug_assert_eq!(rope.byte_len(), old_len - (end - start) + text_len);
+
+        let shift = |offset: usize| offset - (end - start) + text_len;
+
+        // Inlays anchored inside the replaced range are pushed after the new
+        // text, and so are the ones anchored at its start.
+        let map_anchor = |offset: usize| {
+            if offset < start {
+                offset
+            } else if offset >= end {
+                shift(offset)
+            } else {
+                start + text_len
+            }
+        };
+
+        let (segments, byte_range) = self.region(start, end);
+
+        let inlays = self
+            .inlays_in(segments.clone(), byte_range.start)
+            .map(|(anchor, text)| (map_anchor(anchor), String::from(text)))
+            .collect();
+
+        let byte_range = byte_range.start..shift(byte_range.end);
+
+        self.replace_segments(segments, byte_range, inlays);
+    }
+
+    /// Returns an iterator over the inlays and the byte offsets they're
+    /// anchored at, in the order they're displayed in.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{InlayMap, Rope};
+    /// #
+    /// let r = Rope::from("foo(1, 2)");
+    ///
+    /// let mut map = InlayMap::new(&r);
+    /// map.insert(&r, 7, "b: ");
+    /// map.insert(&r, 4, "a: ");
+    ///
+    /// assert_eq!(
+    ///     map.inlays().collect::<Vec<_>>(),
+    ///     [(4, "a: "), (7, "b: ")]
+    /// );
+    /// ```
+    #[inline]
+    pub fn inlays(&self) -> impl Iterator<Item = (usize, &str)> + '_ {
+        let mut offset = 0;
+
+        self.tree.leaves().flat_map(|leaf| leaf.segments.iter()).filter_map(
+            move |segment| match segment {
+                Segment::Text(len) => {
+                    offset += len;
+                    None
+                },
+                Segment::Inlay(text) => Some((offset, text.as_str())),
+            },
+        )
+    }
+
+    /// Inserts an inlay displaying the given text before the char at the
+    /// given byte offset, after all the inlays already anchored there.
+    ///
+    /// Inserting an empty inlay doesn't do anything.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than the
+    /// byte length of the `Rope`) or if it doesn't lie on a char boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{InlayMap, Rope};
+    /// #
+    /// let r = Rope::from("foo(bar)");
+    ///
+    /// let mut map = InlayMap::new(&r);
+    /// map.insert(&r, 4, "a");
+    /// map.insert(&r, 4, ": ");
+    ///
+    /// assert_eq!(map.chunks(&r).collect::<String>(), "foo(a: bar)");
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn insert(&mut self, rope: &Rope, byte_offset: usize, text: &str) {
+        // This panics if the offset is invalid.
+        let _ = rope.byte_slice(byte_offset..byte_offset);
+
+        if text.is_empty() {
+            return;
+        }
+
+        let (segments, byte_range) = self.region(byte_offset, byte_offset);
+
+        let mut inlays = self
+            .inlays_in(segments.clone(), byte_range.start)
+            .map(|(anchor, text)| (anchor, String::from(text)))
+            .collect::<Vec<_>>();
+
+        let idx = inlays.partition_point(|&(anchor, _)| anchor <= byte_offset);
+
+        inlays.insert(idx, (byte_offset, String::from(text)));
+
+        self.replace_segments(segments, byte_range, inlays);
+    }
+
+    /// Creates a new `InlayMap` without any inlays.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{InlayMap, Rope};
+    /// #
+    /// let r = Rope::from("foo\nbar\n");
+    ///
+    /// let map = InlayMap::new(&r);
+    ///
+    /// assert_eq!(map.inlays().count(), 0);
+    /// assert_eq!(map.display_len(), r.byte_len());
+    /// ```
+    #[inline]
+    pub fn new(rope: &Rope) -> Self {
+        let mut map = Self { tree: InlayTree::default() };
+        map.replace_segments(0..0, 0..rope.byte_len(), Vec::new());
+        map
+    }
+
+    /// Removes all the inlays anchored inside the given byte range,
+    /// including the ones anchored at its start and end.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than the end, or if the
+    /// end is out of bounds (i.e. greater than the byte length of the
+    /// `Rope`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{InlayMap, Rope};
+    /// #
+    /// let r = Rope::from("foo(1, 2, 3)");
+    ///
+    /// let mut map = InlayMap::new(&r);
+    /// map.insert(&r, 4, "a: ");
+    /// map.insert(&r, 7, "b: ");
+    /// map.insert(&r, 10, "c: ");
+    ///
+    /// map.remove(&r, 7..7);
+    /// assert_eq!(
+    ///     map.inlays().collect::<Vec<_>>(),
+    ///     [(4, "a: "), (10, "c: ")]
+    /// );
+    ///
+    /// map.remove(&r, ..);
+    /// assert_eq!(map.inlays().count(), 0);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn remove<R>(&mut self, rope: &Rope, byte_range: R)
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) =
+            range_bounds_to_start_end(byte_range, 0, rope.byte_len());
+
+        if start > end {
+            panic::byte_start_after_end(start, end);
+        }
+
+        if end > rope.byte_len() {
+            panic::byte_offset_out_of_bounds(end, rope.byte_len());
+        }
+
+        let (segments, byte_range) = self.region(start, end);
+
+        let inlays = self
+            .inlays_in(segments.clone(), byte_range.start)
+            .filter(|&(anchor, _)| !(start <= anchor && anchor <= end))
+            .map(|(anchor, text)| (anchor, String::from(text)))
+            .collect();
+
+        self.replace_segments(segments, byte_range, inlays);
+    }
+
+    /// Replaces the contents of the `Rope` in the given byte range with the
+    /// given text, updating the inlays accordingly.
+    ///
+    /// This is equivalent to calling [`Rope::replace()`] followed by
+    /// [`edit()`](Self::edit()).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than the end, or if the
+    /// end is out of bounds (i.e. greater than the byte length of the
+    /// `Rope`), or if either of them doesn't lie on a char boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{InlayMap, Rope};
+    /// #
+    /// let mut r = Rope::from("foo(bar)");
+    ///
+    /// let mut map = InlayMap::new(&r);
+    /// map.insert(&r, 4, "x: ");
+    ///
+    /// map.replace(&mut r, 0..3, "quux");
+    ///
+    /// assert_eq!(map.inlays().collect::<Vec<_>>(), [(5, "x: ")]);
+    /// assert_eq!(map.chunks(&r).collect::<String>(), "quux(x: bar)");
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn replace<R, T>(&mut self, rope: &mut Rope, byte_range: R, text: T)
+    where
+        R: RangeBounds<usize>,
+        T: AsRef<str>,
+    {
+        let (start, end) =
+            range_bounds_to_start_end(byte_range, 0, rope.byte_len());
+
+        let text = text.as_ref();
+
+        rope.replace(start..end, text);
+
+        self.edit(rope, start..end, text.len());
+    }
+
+    #[inline]
+    fn byte_len(&self) -> usize {
+        self.tree.summary().bytes
+    }
+
+    /// Returns an iterator over the inlays in the given range of segments,
+    /// the first of which starts at `byte_offset`.
+    #[inline]
+    fn inlays_in(
+        &self,
+        segments: Range<usize>,
+        mut byte_offset: usize,
+    ) -> impl Iterator<Item = (usize, &str)> + '_ {
+        let slice = if segments.is_empty() {
+            None
+        } else {
+            Some(
+                self.tree.slice(
+                    SegmentMetric(segments.start)..SegmentMetric(segments.end),
+                ),
+            )
+        };
+
+        slice
+            .into_iter()
+            .flat_map(|slice| slice.leaves())
+            .flat_map(|leaf| leaf.segments.iter())
+            .filter_map(move |segment| match segment {
+                Segment::Text(len) => {
+                    byte_offset += len;
+                    None
+                },
+                Segment::Inlay(text) => Some((byte_offset, text.as_str())),
+            })
+    }
+
+    /// Returns the range of segments that have to be rebuilt after editing
+    /// the `start..end` byte range, together with the byte range they span.
+    ///
+    /// The range contains all the inlays anchored between `start` and `end`
+    /// (inclusive), and it's surrounded by inlays or by the edges of the
+    /// text, so that the runs of text at its edges can be merged with the
+    /// new ones.
+    #[inline]
+    fn region(
+        &self,
+        start: usize,
+        end: usize,
+    ) -> (Range<usize>, Range<usize>) {
+        let segment_len = self.segment_len();
+
+        if segment_len == 0 {
+            return (0..0, 0..0);
+        }
+
+        // The segment before the ones starting at or after `start` is either
+        // a run of text ending at or after it, or there's none.
+        let SegmentMetric(first) =
+            self.tree.convert_measure(ByteMetric(start));
+
+        let first = first.saturating_sub(1);
+
+        // The segments starting at or before `end`, the last of which is
+        // followed by an inlay or by the end of the text.
+        let last = if end < self.byte_len() {
+            let SegmentMetric(last) =
+                self.tree.convert_measure(ByteMetric(end + 1));
+            last
+        } else {
+            segment_len
+        };
+
+        let ByteMetric(byte_start) =
+            self.tree.convert_measure(SegmentMetric(first));
+
+        let ByteMetric(byte_end) =
+            self.tree.convert_measure(SegmentMetric(last));
+
+        (first..last, byte_start..byte_end)
+    }
+
+    /// Replaces the given range of segments with the ones obtained by
+    /// splicing the given inlays into `byte_range`. The inlays have to be
+    /// sorted by their anchors, which have to be contained in `byte_range`.
+    #[inline]
+    fn replace_segments(
+        &mut self,
+        segments: Range<usize>,
+        byte_range: Range<usize>,
+        inlays: Vec<(usize, String)>,
+    ) {
+        let mut new_segments = Vec::with_capacity(2 * inlays.len() + 1);
+
+        let mut offset = byte_range.start;
+
+        for (anchor, text) in inlays {
+            debug_assert!(offset <= anchor);
+
+            if offset < anchor {
+                new_segments.push(Segment::Text(anchor - offset));
+            }
+
+            new_segments.push(Segment::Inlay(text));
+
+            offset = anchor;
+        }
+
+        debug_assert!(offset <= byte_range.end);
+
+        if offset < byte_range.end {
+            new_segments.push(Segment::Text(byte_range.end - offset));
+        }
+
+        if segments.start == 0 && segments.end == self.segment_len() {
+            self.tree =
+                Tree::from_leaves(InlaySegments::segment(new_segments));
+        } else {
+            self.tree.replace(
+                SegmentMetric(segments.start)..SegmentMetric(segments.end),
+                &new_segments,
+            );
+        }
+    }
+
+    #[inline]
+    fn segment(&self, segment_index: usize) -> &Segment {
+        let (slice, SegmentMetric(segments_before)) =
+            self.tree.leaf_at_measure(SegmentMetric(segment_index + 1));
+
+        &slice.segments[segment_index - segments_before]
+    }
+
+    #[inline]
+    fn segment_len(&self) -> usize {
+        self.tree.summary().segments
+    }
+}
+
+/// An iterator over the chunks of the text displayed by an [`InlayMap`].
+///
+/// This struct is created by [`InlayMap::chunks()`]. See its documentation
+/// for more.
+#[derive(Clone)]
+pub struct InlayChunks<'a> {
+    rope: &'a Rope,
+    leaves: Leaves<'a, { Rope::arity() }, InlaySegments>,
+
+    /// The segments of the current leaf that haven't been yielded yet.
+    segments: &'a [Segment],
+
+    /// The byte offset of the start of the first segment in `segments`.
+    offset: usize,
+
+    /// The chunks of the run of text currently being yielded.
+    chunks: Option<Chunks<'a>>,
+}
+
+impl<'a> Iterator for InlayChunks<'a> {
+    type Item = &'a str;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(chunk) = self.chunks.as_mut().and_then(Iterator::next)
+            {
+                return Some(chunk);
+            }
+
+            self.chunks = None;
+
+            let Some((segment, rest)) = self.segments.split_first() else {
+                self.segments = self.leaves.next()?.segments;
+                continue;
+            };
+
+            self.segments = rest;
+
+            match segment {
+                Segment::Text(len) => {
+                    let start = self.offset;
+                    self.offset += len;
+                    let text = self.rope.byte_slice(start..self.offset);
+                    self.chunks = Some(text.chunks());
+                },
+
+                Segment::Inlay(text) => return Some(text),
+            }
+        }
+    }
+}
+
+impl core::iter::FusedIterator for InlayChunks<'_> {}
+
+/// Either a non-empty run of the `Rope`'s text or a non-empty inlay.
+#[derive(Clone, Debug, PartialEq)]
+enum Segment {
+    /// A run of text with the given byte length.
+    Text(usize),
+
+    /// A piece of virtual text, taking up no space in the `Rope`.
+    Inlay(String),
+}
+
+impl Summarize for Segment {
+    type Summary = InlaySummary;
+
+    #[inline]
+    fn summarize(&self) -> Self::Summary {
+        match self {
+            Segment::Text(len) => {
+                InlaySummary { bytes: *len, display_bytes: *len, segments: 1 }
+            },
+
+            Segment::Inlay(text) => InlaySummary {
+                bytes: 0,
+                display_bytes: text.len(),
+                segments: 1,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+struct InlaySegments {
+    segments: Vec<Segment>,
+}
+
+impl InlaySegments {
+    /// Splits a sequence of segments into leaves containing between
+    /// `MIN_SEGMENTS` and `MAX_SEGMENTS` segments each.
+    ///
+    /// The only exception is if there are at most `MAX_SEGMENTS` segments in
+    /// total, in which case they're all put in a single leaf.
+    #[inline]
+    fn segment(segments: Vec<Segment>) -> alloc::vec::IntoIter<Self> {
+        if segments.len() <= MAX_SEGMENTS {
+            return alloc::vec![Self { segments }].into_iter();
+        }
+
+        let num_leaves = (segments.len() + MAX_SEGMENTS - 1) / MAX_SEGMENTS;
+
+        let min_len = segments.len() / num_leaves;
+
+        let mut longer = segments.len() % num_leaves;
+
+        let mut segments = segments.into_iter();
+
+        let mut leaves = Vec::with_capacity(num_leaves);
+
+        for _ in 0..num_leaves {
+            let len = min_len + (longer > 0) as usize;
+            longer = longer.saturating_sub(1);
+            let segments = segments.by_ref().take(len).collect();
+            leaves.push(Self { segments });
+        }
+
+        debug_assert!(leaves
+            .iter()
+            .all(|leaf| leaf.segments.len() >= MIN_SEGMENTS));
+
+        leaves.into_iter()
+    }
+}
+
+impl Summarize for InlaySegments {
+    type Summary = InlaySummary;
+
+    #[inline]
+    fn summarize(&self) -> Self::Summary {
+        self.as_slice().summarize()
+    }
+}
+
+impl BaseMeasured for InlaySegments {
+    type BaseMetric = SegmentMetric;
+}
+
+impl AsSlice for InlaySegments {
+    type Slice<'a> = InlaySegmentsSlice<'a>;
+
+    #[inline]
+    fn as_slice(&self) -> InlaySegmentsSlice<'_> {
+        InlaySegmentsSlice { segments: &self.segments }
+    }
+}
+
+impl From<InlaySegmentsSlice<'_>> for InlaySegments {
+    #[inline]
+    fn from(slice: InlaySegmentsSlice<'_>) -> Self {
+        Self { segments: slice.segments.to_vec() }
+    }
+}
+
+impl BalancedLeaf for InlaySegments {
+    #[inline]
+    fn is_underfilled(&self, _: &InlaySummary) -> bool {
+        self.segments.len() < MIN_SEGMENTS
+    }
+
+    #[inline]
+    fn balance_leaves(
+        (left, left_summary): (&mut Self, &mut InlaySummary),
+        (right, right_summary): (&mut Self, &mut InlaySummary),
+    ) {
+        // The two leaves can be combined in a single leaf.
+        if left.segments.len() + right.segments.len() <= MAX_SEGMENTS {
+            left.segments.append(&mut right.segments);
+            *left_summary += &*right_summary;
+            *right_summary = InlaySummary::default();
+            return;
+        }
+
+        // The left side is underfilled => take segments from the right side.
+        if left.segments.len() < MIN_SEGMENTS {
+            let moved = MIN_SEGMENTS - left.segments.len();
+            left.segments.extend(right.segments.drain(..moved));
+        }
+        // The right side is underfilled => take segments from the left side.
+        else if right.segments.len() < MIN_SEGMENTS {
+            let moved = MIN_SEGMENTS - right.segments.len();
+            let split = left.segments.len() - moved;
+            right.segments.splice(..0, left.segments.drain(split..));
+        } else {
+            return;
+        }
+
+        *left_summary = left.summarize();
+        *right_summary = right.summarize();
+
+        debug_assert!(left.segments.len() >= MIN_SEGMENTS);
+        debug_assert!(right.segments.len() >= MIN_SEGMENTS);
+    }
+}
+
+impl ReplaceableLeaf<SegmentMetric> for InlaySegments {
+    type Replacement<'a> = &'a [Segment];
+
+    type ExtraLeaves = alloc::vec::IntoIter<Self>;
+
+    #[inline]
+    fn replace<R>(
+        &mut self,
+        summary: &mut InlaySummary,
+        range: R,
+        replacement: &[Segment],
+    ) -> Option<Self::ExtraLeaves>
+    where
+        R: RangeBounds<SegmentMetric>,
+    {
+        let (start, end) =
+            range_bounds_to_start_end(range, 0, summary.segments);
+
+        debug_assert!(start <= end);
+        debug_assert!(end <= summary.segments);
+
+        let new_len = self.segments.len() - (end - start) + replacement.len();
+
+        if new_len <= MAX_SEGMENTS {
+            self.segments.splice(start..end, replacement.iter().cloned());
+            *summary = self.summarize();
+            return None;
+        }
+
+        let mut segments = Vec::with_capacity(new_len);
+
+        segments.extend(self.segments.drain(..start));
+        segments.extend(replacement.iter().cloned());
+        segments.extend(self.segments.drain(end - start..));
+
+        let mut leaves = Self::segment(segments);
+        *self = leaves.next().unwrap();
+        *summary = self.summarize();
+        Some(leaves)
+    }
+
+    #[inline]
+    fn remove_up_to(
+        &mut self,
+        summary: &mut InlaySummary,
+        SegmentMetric(up_to): SegmentMetric,
+    ) {
+        self.segments.drain(..up_to);
+        *summary = self.summarize();
+    }
+}
+
+#[derive(Copy, Clone, Default, Debug)]
+struct InlaySegmentsSlice<'a> {
+    segments: &'a [Segment],
+}
+
+impl InlaySegmentsSlice<'_> {
+    /// Splits the slice right before the first segment for which `stop`
+    /// returns `true` when called with the summary of the segments before
+    /// it, or at the end if there isn't one.
+    #[inline]
+    fn split_where<F>(self, mut stop: F) -> (Self, Self)
+    where
+        F: FnMut(&InlaySummary) -> bool,
+    {
+        let mut summary = InlaySummary::default();
+
+        let mut split = 0;
+
+        for segment in self.segments {
+            if stop(&summary) {
+                break;
+            }
+
+            summary += &segment.summarize();
+
+            split += 1;
+        }
+
+        self.split_at(split)
+    }
+
+    #[inline]
+    fn split_at(self, segment_offset: usize) -> (Self, Self) {
+        let (left, right) = self.segments.split_at(segment_offset);
+        (Self { segments: left }, Self { segments: right })
+    }
+}
+
+impl Summarize for InlaySegmentsSlice<'_> {
+    type Summary = InlaySummary;
+
+    #[inline]
+    fn summarize(&self) -> Self::Summary {
+        self.segments.iter().fold(InlaySummary::default(), |mut summary, seg| {
+            summary += &seg.summarize();
+            summary
+        })
+    }
+}
+
+#[derive(Copy, Clone, Default, Debug, PartialEq)]
+struct InlaySummary {
+    bytes: usize,
+    display_bytes: usize,
+    segments: usize,
+}
+
+impl Add<&Self> for InlaySummary {
+    type Output = Self;
+
+    #[inline]
+    fn add(mut self, rhs: &Self) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+impl Sub<&Self> for InlaySummary {
+    type Output = Self;
+
+    #[inline]
+    fn sub(mut self, rhs: &Self) -> Self {
+        self -= rhs;
+        self
+    }
+}
+
+impl AddAssign<&Self> for InlaySummary {
+    #[inline]
+    fn add_assign(&mut self, rhs: &Self) {
+        self.bytes += rhs.bytes;
+        self.display_bytes += rhs.display_bytes;
+        self.segments += rhs.segments;
+    }
+}
+
+impl SubAssign<&Self> for InlaySummary {
+    #[inline]
+    fn sub_assign(&mut self, rhs: &Self) {
+        self.bytes -= rhs.bytes;
+        self.display_bytes -= rhs.display_bytes;
+        self.segments -= rhs.segments;
+    }
+}
+
+impl Metric<InlaySummary> for ByteMetric {
+    #[inline]
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    #[inline]
+    fn one() -> Self {
+        Self(1)
+    }
+
+    #[inline]
+    fn measure(summary: &InlaySummary) -> Self {
+        Self(summary.bytes)
+    }
+}
+
+/// Slicing up to a byte offset keeps all the segments starting before it,
+/// so the inlays anchored at the offset end up on the right side.
+impl SlicingMetric<InlaySegments> for ByteMetric {
+    #[inline]
+    fn slice_up_to<'a>(
+        slice: InlaySegmentsSlice<'a>,
+        ByteMetric(byte_offset): Self,
+        _: &InlaySummary,
+    ) -> (InlaySegmentsSlice<'a>, InlaySummary)
+    where
+        'a: 'a,
+    {
+        let (left, _) = slice.split_where(|s| s.bytes >= byte_offset);
+        (left, left.summarize())
+    }
+
+    #[inline]
+    fn slice_from<'a>(
+        slice: InlaySegmentsSlice<'a>,
+        ByteMetric(byte_offset): Self,
+        _: &InlaySummary,
+    ) -> (InlaySegmentsSlice<'a>, InlaySummary)
+    where
+        'a: 'a,
+    {
+        let (_, right) = slice.split_where(|s| s.bytes >= byte_offset);
+        (right, right.summarize())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct DisplayMetric(usize);
+
+impl Add for DisplayMetric {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl Sub for DisplayMetric {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+impl AddAssign for DisplayMetric {
+    #[inline]
+    fn add_assign(&mut self, other: Self) {
+        self.0 += other.0
+    }
+}
+
+impl SubAssign for DisplayMetric {
+    #[inline]
+    fn sub_assign(&mut self, other: Self) {
+        self.0 -= other.0
+    }
+}
+
+impl Metric<InlaySummary> for DisplayMetric {
+    #[inline]
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    #[inline]
+    fn one() -> Self {
+        Self(1)
+    }
+
+    #[inline]
+    fn measure(summary: &InlaySummary) -> Self {
+        Self(summary.display_bytes)
+    }
+}
+
+/// Same as the `ByteMetric`, except it measures the displayed text.
+impl SlicingMetric<InlaySegments> for DisplayMetric {
+    #[inline]
+    fn slice_up_to<'a>(
+        slice: InlaySegmentsSlice<'a>,
+        DisplayMetric(display_offset): Self,
+        _: &InlaySummary,
+    ) -> (InlaySegmentsSlice<'a>, InlaySummary)
+    where
+        'a: 'a,
+    {
+        let (left, _) =
+            slice.split_where(|s| s.display_bytes >= display_offset);
+        (left, left.summarize())
+    }
+
+    #[inline]
+    fn slice_from<'a>(
+        slice: InlaySegmentsSlice<'a>,
+        DisplayMetric(display_offset): Self,
+        _: &InlaySummary,
+    ) -> (InlaySegmentsSlice<'a>, InlaySummary)
+    where
+        'a: 'a,
+    {
+        let (_, right) =
+            slice.split_where(|s| s.display_bytes >= display_offset);
+        (right, right.summarize())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct SegmentMetric(usize);
+
+impl Add for SegmentMetric {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl Sub for SegmentMetric {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+impl AddAssign for SegmentMetric {
+    #[inline]
+    fn add_assign(&mut self, other: Self) {
+        self.0 += other.0
+    }
+}
+
+impl SubAssign for SegmentMetric {
+    #[inline]
+    fn sub_assign(&mut self, other: Self) {
+        self.0 -= other.0
+    }
+}
+
+impl Add<usize> for SegmentMetric {
+    type Output = usize;
+
+    #[inline]
+    fn add(self, other: usize) -> usize {
+        self.0 + other
+    }
+}
+
+impl From<SegmentMetric> for usize {
+    #[inline]
+    fn from(SegmentMetric(value): SegmentMetric) -> usize {
+        value
+    }
+}
+
+impl Metric<InlaySummary> for SegmentMetric {
+    #[inline]
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    #[inline]
+    fn one() -> Self {
+        Self(1)
+    }
+
+    #[inline]
+    fn measure(summary: &InlaySummary) -> Self {
+        Self(summary.segments)
+    }
+}
+
+impl SlicingMetric<InlaySegments> for SegmentMetric {
+    #[inline]
+    fn slice_up_to<'a>(
+        slice: InlaySegmentsSlice<'a>,
+        SegmentMetric(segment_offset): Self,
+        _: &InlaySummary,
+    ) -> (InlaySegmentsSlice<'a>, InlaySummary)
+    where
+        'a: 'a,
+    {
+        let (left, _) = slice.split_at(segment_offset);
+        (left, left.summarize())
+    }
+
+    #[inline]
+    fn slice_from<'a>(
+        slice: InlaySegmentsSlice<'a>,
+        SegmentMetric(segment_offset): Self,
+        _: &InlaySummary,
+    ) -> (InlaySegmentsSlice<'a>, InlaySummary)
+    where
+        'a: 'a,
+    {
+        let (_, right) = slice.split_at(segment_offset);
+        (right, right.summarize())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slice_up_to_byte_offset() {
+        let inlay = || Segment::Inlay(String::from("ab"));
+
+        let leaf = InlaySegments {
+            segments: alloc::vec![
+                Segment::Text(3),
+                inlay(),
+                inlay(),
+                Segment::Text(2),
+            ],
+        };
+
+        let summary = leaf.summarize();
+
+        let segments_before = |byte_offset| {
+            let (left, _) =
+                <ByteMetric as SlicingMetric<InlaySegments>>::slice_up_to(
+                    leaf.as_slice(),
+                    ByteMetric(byte_offset),
+                    &summary,
+                );
+            left.segments.len()
+        };
+
+        assert_eq!(segments_before(0), 0);
+        assert_eq!(segments_before(3), 1);
+        assert_eq!(segments_before(4), 4);
+        assert_eq!(segments_before(5), 4);
+    }
+
+    #[test]
+    fn replace_overflowing() {
+        let inlay = || Segment::Inlay(String::from("ab"));
+
+        let mut leaf =
+            InlaySegments { segments: alloc::vec![Segment::Text(1), inlay()] };
+        let mut summary = leaf.summarize();
+
+        let replacement =
+            [Segment::Text(3), inlay(), Segment::Text(5), inlay()];
+
+        let extras = leaf
+            .replace(
+                &mut summary,
+                SegmentMetric(1)..SegmentMetric(2),
+                &replacement,
+            )
+            .unwrap()
+            .collect::<Vec<_>>();
+
+        assert_eq!(summary, leaf.summarize());
+
+        let total =
+            extras.iter().fold(summary, |acc, leaf| acc + &leaf.summarize());
+
+        assert_eq!(
+            total,
+            InlaySummary { bytes: 9, display_bytes: 13, segments: 5 }
+        );
+    }
+}
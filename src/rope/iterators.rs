@@ -1,17 +1,70 @@
+use core::ops::Range;
+
 use super::metrics::{ByteMetric, LineMetric, RawLineMetric};
 use super::rope::RopeChunk;
 use super::{Rope, RopeSlice};
-use crate::tree::{Leaves, Units};
+use crate::tree::{Leaves, TreeSlice, Units};
 
 /// An iterator over the `&str` chunks of `Rope`s and `RopeSlice`s.
 ///
-/// This struct is created by the `chunks` method on [`Rope`](Rope::chunks())
-/// and [`RopeSlice`](RopeSlice::chunks()). See their documentation for more.
+/// This struct is created by the `chunks` and `chunks_at` methods on
+/// [`Rope`](Rope::chunks()) and [`RopeSlice`](RopeSlice::chunks()). See their
+/// documentation for more.
 #[derive(Clone)]
 pub struct Chunks<'a> {
     leaves: Leaves<'a, { Rope::arity() }, RopeChunk>,
     forward_extra_right: Option<&'a str>,
     backward_extra_left: Option<&'a str>,
+
+    /// The leaves before the starting position if this iterator was created
+    /// by one of the `chunks_at` methods, which are yielded by
+    /// [`prev()`](Self::prev()).
+    leaves_before: Option<Leaves<'a, { Rope::arity() }, RopeChunk>>,
+    prev_extra_left: Option<&'a str>,
+}
+
+impl<'a> Chunks<'a> {
+    #[inline]
+    pub(super) fn at(slice: &RopeSlice<'a>, byte_offset: usize) -> Self {
+        let mut chunks = Self::from(&slice.byte_slice(byte_offset..));
+        let before = Self::from(&slice.byte_slice(..byte_offset));
+        chunks.leaves_before = Some(before.leaves);
+        chunks
+    }
+
+    /// Returns the chunk before the ones already yielded by this method,
+    /// starting from the position the iterator was created at by one of the
+    /// `chunks_at` methods, i.e. the first chunk it returns ends exactly at
+    /// that position.
+    ///
+    /// This walks backward independently of [`next()`](Iterator::next()) and
+    /// [`next_back()`](DoubleEndedIterator::next_back()), and it always
+    /// returns `None` if the iterator was created by one of the `chunks`
+    /// methods.
+    #[inline]
+    pub fn prev(&mut self) -> Option<&'a str> {
+        if let Some(extra) = self.prev_extra_left.take() {
+            return Some(extra);
+        }
+
+        let chunk = self.leaves_before.as_mut()?.next_back()?;
+
+        if chunk.right_chunk().is_empty() {
+            #[cfg(feature = "small_chunks")]
+            if chunk.left_chunk().is_empty() {
+                return self.prev();
+            }
+
+            debug_assert!(!chunk.left_chunk().is_empty());
+
+            Some(chunk.left_chunk())
+        } else {
+            if !chunk.left_chunk().is_empty() {
+                self.prev_extra_left = Some(chunk.left_chunk());
+            }
+            Some(chunk.right_chunk())
+        }
+    }
 }
 
 impl<'a> From<&'a Rope> for Chunks<'a> {
@@ -21,7 +74,13 @@ impl<'a> From<&'a Rope> for Chunks<'a> {
         if rope.is_empty() {
             let _ = leaves.next();
         }
-        Self { leaves, forward_extra_right: None, backward_extra_left: None }
+        Self {
+            leaves,
+            forward_extra_right: None,
+            backward_extra_left: None,
+            leaves_before: None,
+            prev_extra_left: None,
+        }
     }
 }
 
@@ -32,7 +91,13 @@ impl<'a> From<&RopeSlice<'a>> for Chunks<'a> {
         if slice.is_empty() {
             let _ = leaves.next();
         }
-        Self { leaves, forward_extra_right: None, backward_extra_left: None }
+        Self {
+            leaves,
+            forward_extra_right: None,
+            backward_extra_left: None,
+            leaves_before: None,
+            prev_extra_left: None,
+        }
     }
 }
 
@@ -106,8 +171,9 @@ impl core::iter::FusedIterator for Chunks<'_> {}
 
 /// An iterator over the bytes of `Rope`s and `RopeSlice`s.
 ///
-/// This struct is created by the `bytes` method on [`Rope`](Rope::bytes())
-/// and [`RopeSlice`](RopeSlice::bytes()). See their documentation for more.
+/// This struct is created by the `bytes` and `bytes_at` methods on
+/// [`Rope`](Rope::bytes()) and [`RopeSlice`](RopeSlice::bytes()). See their
+/// documentation for more.
 #[derive(Clone)]
 pub struct Bytes<'a> {
     chunks: Chunks<'a>,
@@ -129,6 +195,45 @@ pub struct Bytes<'a> {
 
     /// The total number of bytes this iterator will yield.
     bytes_total: usize,
+
+    /// The bytes of the chunk used when calling [`Bytes::prev()`] which are
+    /// yet to be yielded.
+    prev_chunk: &'a [u8],
+}
+
+impl<'a> Bytes<'a> {
+    #[inline]
+    pub(super) fn at(slice: &RopeSlice<'a>, byte_offset: usize) -> Self {
+        Self {
+            chunks: Chunks::at(slice, byte_offset),
+            forward_chunk: &[],
+            forward_byte_idx: 0,
+            backward_chunk: &[],
+            backward_byte_idx: 0,
+            bytes_yielded: 0,
+            bytes_total: slice.byte_len() - byte_offset,
+            prev_chunk: &[],
+        }
+    }
+
+    /// Returns the byte before the ones already yielded by this method,
+    /// starting from the position the iterator was created at by one of the
+    /// `bytes_at` methods.
+    ///
+    /// This walks backward independently of [`next()`](Iterator::next()) and
+    /// [`next_back()`](DoubleEndedIterator::next_back()), and it always
+    /// returns `None` if the iterator was created by one of the `bytes`
+    /// methods.
+    #[inline]
+    pub fn prev(&mut self) -> Option<u8> {
+        if self.prev_chunk.is_empty() {
+            self.prev_chunk = self.chunks.prev()?.as_bytes();
+        }
+
+        let (&byte, rest) = self.prev_chunk.split_last()?;
+        self.prev_chunk = rest;
+        Some(byte)
+    }
 }
 
 impl<'a> From<&'a Rope> for Bytes<'a> {
@@ -142,6 +247,7 @@ impl<'a> From<&'a Rope> for Bytes<'a> {
             backward_byte_idx: 0,
             bytes_yielded: 0,
             bytes_total: rope.byte_len(),
+            prev_chunk: &[],
         }
     }
 }
@@ -157,6 +263,7 @@ impl<'a> From<&RopeSlice<'a>> for Bytes<'a> {
             backward_byte_idx: 0,
             bytes_yielded: 0,
             bytes_total: slice.byte_len(),
+            prev_chunk: &[],
         }
     }
 }
@@ -231,8 +338,9 @@ impl core::iter::FusedIterator for Bytes<'_> {}
 /// An iterator over the code points (i.e. [`char`]s) of `Rope`s and
 /// `RopeSlice`s.
 ///
-/// This struct is created by the `chars` method on [`Rope`](Rope::chars())
-/// and [`RopeSlice`](RopeSlice::chars()). See their documentation for more.
+/// This struct is created by the `chars` and `chars_at` methods on
+/// [`Rope`](Rope::chars()) and [`RopeSlice`](RopeSlice::chars()). See their
+/// documentation for more.
 #[derive(Clone)]
 pub struct Chars<'a> {
     chunks: Chunks<'a>,
@@ -249,6 +357,44 @@ pub struct Chars<'a> {
 
     /// The number of bytes of `backward_chunk` which are yet to be yielded.
     backward_byte_idx: usize,
+
+    /// The part of the chunk used when calling [`Chars::prev()`] which is yet
+    /// to be yielded.
+    prev_chunk: &'a str,
+}
+
+impl<'a> Chars<'a> {
+    #[inline]
+    pub(super) fn at(slice: &RopeSlice<'a>, byte_offset: usize) -> Self {
+        Self {
+            chunks: Chunks::at(slice, byte_offset),
+            forward_chunk: "",
+            forward_byte_idx: 0,
+            backward_chunk: "",
+            backward_byte_idx: 0,
+            prev_chunk: "",
+        }
+    }
+
+    /// Returns the char before the ones already yielded by this method,
+    /// starting from the position the iterator was created at by one of the
+    /// `chars_at` methods.
+    ///
+    /// This walks backward independently of [`next()`](Iterator::next()) and
+    /// [`next_back()`](DoubleEndedIterator::next_back()), and it always
+    /// returns `None` if the iterator was created by one of the `chars`
+    /// methods.
+    #[inline]
+    pub fn prev(&mut self) -> Option<char> {
+        if self.prev_chunk.is_empty() {
+            self.prev_chunk = self.chunks.prev()?;
+        }
+
+        let ch = self.prev_chunk.chars().next_back()?;
+        self.prev_chunk =
+            &self.prev_chunk[..self.prev_chunk.len() - ch.len_utf8()];
+        Some(ch)
+    }
 }
 
 impl<'a> From<&'a Rope> for Chars<'a> {
@@ -260,6 +406,7 @@ impl<'a> From<&'a Rope> for Chars<'a> {
             forward_byte_idx: 0,
             backward_chunk: "",
             backward_byte_idx: 0,
+            prev_chunk: "",
         }
     }
 }
@@ -273,6 +420,7 @@ impl<'a> From<&RopeSlice<'a>> for Chars<'a> {
             forward_byte_idx: 0,
             backward_chunk: "",
             backward_byte_idx: 0,
+            prev_chunk: "",
         }
     }
 }
@@ -440,19 +588,135 @@ impl ExactSizeIterator for RawLines<'_> {
 impl core::iter::FusedIterator for RawLines<'_> {}
 
 /// An iterator over the lines of `Rope`s and `RopeSlice`s, not including the
-/// line terminators (`\n` or `\r\n`).
+/// line terminators (`\n` or `\r\n`, plus the ones enabled by the
+/// `unicode-lines` feature).
 ///
-/// This struct is created by the `lines` method on [`Rope`](Rope::lines()) and
-/// [`RopeSlice`](RopeSlice::lines()). See their documentation for more.
+/// This struct is created by the `lines` and `lines_at` methods on
+/// [`Rope`](Rope::lines()) and [`RopeSlice`](RopeSlice::lines()). See their
+/// documentation for more.
 #[derive(Clone)]
 pub struct Lines<'a> {
     units: Units<'a, { Rope::arity() }, RopeChunk, LineMetric>,
 
+    /// The lines before the starting position if this iterator was created
+    /// by one of the `lines_at` methods, which are yielded by
+    /// [`prev()`](Self::prev()).
+    units_before: Option<Units<'a, { Rope::arity() }, RopeChunk, LineMetric>>,
+
     /// The number of lines that have been yielded so far.
     lines_yielded: usize,
 
     /// The total number of bytes this iterator will yield.
     lines_total: usize,
+
+    /// The slice we're iterating over, used to check which line break
+    /// follows a line ending with a `\r`.
+    #[cfg(feature = "unicode-lines")]
+    slice: RopeSlice<'a>,
+
+    /// The number of bytes (line breaks included) before the start of the
+    /// next line yielded by `next()`.
+    #[cfg(feature = "unicode-lines")]
+    bytes_front: usize,
+
+    /// The number of bytes (line breaks included) after the end of the next
+    /// line yielded by `next_back()`.
+    #[cfg(feature = "unicode-lines")]
+    bytes_back: usize,
+
+    /// The number of bytes (line breaks included) before the end of the next
+    /// line yielded by `prev()`.
+    #[cfg(feature = "unicode-lines")]
+    bytes_prev: usize,
+}
+
+impl<'a> Lines<'a> {
+    #[inline]
+    pub(super) fn at(slice: &RopeSlice<'a>, line_offset: usize) -> Self {
+        let byte_offset = slice.byte_of_line(line_offset);
+
+        let after = slice.byte_slice(byte_offset..);
+        let before = slice.byte_slice(..byte_offset);
+
+        Self {
+            units: after.tree_slice.units::<LineMetric>(),
+            units_before: Some(before.tree_slice.units::<LineMetric>()),
+            lines_yielded: 0,
+            lines_total: slice.line_len() - line_offset,
+            #[cfg(feature = "unicode-lines")]
+            slice: *slice,
+            #[cfg(feature = "unicode-lines")]
+            bytes_front: byte_offset,
+            #[cfg(feature = "unicode-lines")]
+            bytes_back: 0,
+            #[cfg(feature = "unicode-lines")]
+            bytes_prev: byte_offset,
+        }
+    }
+
+    /// Returns the line before the ones already yielded by this method,
+    /// starting from the position the iterator was created at by one of the
+    /// `lines_at` methods.
+    ///
+    /// This walks backward independently of [`next()`](Iterator::next()) and
+    /// [`next_back()`](DoubleEndedIterator::next_back()), and it always
+    /// returns `None` if the iterator was created by one of the `lines`
+    /// methods.
+    #[inline]
+    pub fn prev(&mut self) -> Option<RopeSlice<'a>> {
+        let (tree_slice, ByteMetric(advance)) =
+            self.units_before.as_mut()?.next_back()?;
+
+        #[cfg(feature = "unicode-lines")]
+        let line_start = {
+            self.bytes_prev -= advance;
+            self.bytes_prev
+        };
+
+        #[cfg(not(feature = "unicode-lines"))]
+        let line_start = 0;
+
+        Some(self.line(tree_slice, advance, line_start))
+    }
+
+    /// Turns the tree slice of a line into the `RopeSlice` to yield, where
+    /// `advance` is the byte length of the line including its line break,
+    /// and `line_start` its byte offset in the slice we're iterating over
+    /// (only used with the `unicode-lines` feature).
+    #[inline]
+    fn line(
+        &self,
+        tree_slice: TreeSlice<'a, { Rope::arity() }, RopeChunk>,
+        advance: usize,
+        #[allow(unused_variables)] line_start: usize,
+    ) -> RopeSlice<'a> {
+        let mut slice = RopeSlice {
+            tree_slice,
+            has_trailing_newline: false,
+            #[cfg(feature = "grapheme-metric")]
+            graphemes: None,
+        };
+
+        // This handles CRLF pairs that have been split across chunks. For
+        // example, if we have "aaa\r" and "\nbbb" we should yield "aaa", but
+        // the tree slice currently contains "aaa\r", so we need to remove
+        // the trailing "\r".
+        let is_split_crlf =
+            slice.tree_slice.end_slice().last_chunk().ends_with('\r')
+                && advance - slice.byte_len() == 1;
+
+        // The one byte line break could also be a vertical tab or a form
+        // feed, which don't form a pair with the "\r".
+        #[cfg(feature = "unicode-lines")]
+        let is_split_crlf = is_split_crlf
+            && self.slice.byte(line_start + slice.byte_len()) == b'\n';
+
+        if is_split_crlf {
+            slice.truncate_last_char();
+        }
+
+        slice
+    }
 }
 
 impl<'a> From<&'a Rope> for Lines<'a> {
@@ -460,8 +724,17 @@ impl<'a> From<&'a Rope> for Lines<'a> {
     fn from(rope: &'a Rope) -> Self {
         Self {
             units: rope.tree.units::<LineMetric>(),
+            units_before: None,
             lines_yielded: 0,
             lines_total: rope.line_len(),
+            #[cfg(feature = "unicode-lines")]
+            slice: rope.byte_slice(..),
+            #[cfg(feature = "unicode-lines")]
+            bytes_front: 0,
+            #[cfg(feature = "unicode-lines")]
+            bytes_back: 0,
+            #[cfg(feature = "unicode-lines")]
+            bytes_prev: 0,
         }
     }
 }
@@ -471,8 +744,17 @@ impl<'a> From<&RopeSlice<'a>> for Lines<'a> {
     fn from(slice: &RopeSlice<'a>) -> Self {
         Self {
             units: slice.tree_slice.units::<LineMetric>(),
+            units_before: None,
             lines_yielded: 0,
             lines_total: slice.line_len(),
+            #[cfg(feature = "unicode-lines")]
+            slice: *slice,
+            #[cfg(feature = "unicode-lines")]
+            bytes_front: 0,
+            #[cfg(feature = "unicode-lines")]
+            bytes_back: 0,
+            #[cfg(feature = "unicode-lines")]
+            bytes_prev: 0,
         }
     }
 }
@@ -485,19 +767,16 @@ impl<'a> Iterator for Lines<'a> {
         let (tree_slice, ByteMetric(advance)) = self.units.next()?;
         self.lines_yielded += 1;
 
-        let mut slice = RopeSlice { tree_slice, has_trailing_newline: false };
+        #[cfg(feature = "unicode-lines")]
+        let line_start = {
+            self.bytes_front += advance;
+            self.bytes_front - advance
+        };
 
-        // This handles CRLF pairs that have been split across chunks. For
-        // example, if we have "aaa\r" and "\nbbb" we should yield "aaa", but
-        // the tree slice currently contains "aaa\r", so we need to remove
-        // the trailing "\r".
-        if slice.tree_slice.end_slice().last_chunk().ends_with('\r')
-            && advance - slice.byte_len() == 1
-        {
-            slice.truncate_last_char();
-        }
+        #[cfg(not(feature = "unicode-lines"))]
+        let line_start = 0;
 
-        Some(slice)
+        Some(self.line(tree_slice, advance, line_start))
     }
 
     #[inline]
@@ -513,16 +792,16 @@ impl DoubleEndedIterator for Lines<'_> {
         let (tree_slice, ByteMetric(advance)) = self.units.next_back()?;
         self.lines_yielded += 1;
 
-        let mut slice = RopeSlice { tree_slice, has_trailing_newline: false };
+        #[cfg(feature = "unicode-lines")]
+        let line_start = {
+            self.bytes_back += advance;
+            self.slice.byte_len() - self.bytes_back
+        };
 
-        // Same as above.
-        if slice.tree_slice.end_slice().last_chunk().ends_with('\r')
-            && advance - slice.byte_len() == 1
-        {
-            slice.truncate_last_char();
-        }
+        #[cfg(not(feature = "unicode-lines"))]
+        let line_start = 0;
 
-        Some(slice)
+        Some(self.line(tree_slice, advance, line_start))
     }
 }
 
@@ -535,13 +814,143 @@ impl ExactSizeIterator for Lines<'_> {
 
 impl core::iter::FusedIterator for Lines<'_> {}
 
+/// An iterator over the paragraphs of `Rope`s and `RopeSlice`s, i.e. the runs
+/// of consecutive lines which are not blank.
+///
+/// A line is blank if it only contains whitespace. Each paragraph is yielded
+/// together with its byte range, which starts at the start of its first line
+/// and ends at the end of its last line, not including the line terminator.
+///
+/// This struct is created by the `paragraphs` method on
+/// [`Rope`](Rope::paragraphs()) and [`RopeSlice`](RopeSlice::paragraphs()).
+/// See their documentation for more.
+#[derive(Clone)]
+pub struct Paragraphs<'a> {
+    slice: RopeSlice<'a>,
+
+    /// Used to get the byte length of each line, line terminator included.
+    raw_lines: RawLines<'a>,
+
+    /// Used to get the contents of each line, without the line terminator.
+    lines: Lines<'a>,
+
+    /// The byte offset of the start of the next line yielded by
+    /// `raw_lines.next()`.
+    forward_offset: usize,
+
+    /// The byte offset of the end of the next line yielded by
+    /// `raw_lines.next_back()`.
+    backward_offset: usize,
+}
+
+impl<'a> From<&'a Rope> for Paragraphs<'a> {
+    #[inline]
+    fn from(rope: &'a Rope) -> Self {
+        Self::from(&rope.byte_slice(..))
+    }
+}
+
+impl<'a> From<&RopeSlice<'a>> for Paragraphs<'a> {
+    #[inline]
+    fn from(slice: &RopeSlice<'a>) -> Self {
+        Self {
+            slice: *slice,
+            raw_lines: slice.raw_lines(),
+            lines: slice.lines(),
+            forward_offset: 0,
+            backward_offset: slice.byte_len(),
+        }
+    }
+}
+
+/// Returns whether the line only contains whitespace.
+#[inline]
+fn is_blank(line: RopeSlice<'_>) -> bool {
+    line.chunks().all(|chunk| chunk.chars().all(char::is_whitespace))
+}
+
+impl<'a> Iterator for Paragraphs<'a> {
+    type Item = (RopeSlice<'a>, Range<usize>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut range: Option<Range<usize>> = None;
+
+        for raw_line in self.raw_lines.by_ref() {
+            let line = self.lines.next().unwrap();
+
+            let line_start = self.forward_offset;
+            self.forward_offset += raw_line.byte_len();
+
+            if is_blank(line) {
+                if range.is_some() {
+                    break;
+                }
+                continue;
+            }
+
+            let line_end = line_start + line.byte_len();
+
+            match &mut range {
+                Some(range) => range.end = line_end,
+                None => range = Some(line_start..line_end),
+            }
+        }
+
+        range.map(|range| (self.slice.byte_slice(range.clone()), range))
+    }
+}
+
+impl DoubleEndedIterator for Paragraphs<'_> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let mut range: Option<Range<usize>> = None;
+
+        while let Some(raw_line) = self.raw_lines.next_back() {
+            let line = self.lines.next_back().unwrap();
+
+            self.backward_offset -= raw_line.byte_len();
+            let line_start = self.backward_offset;
+
+            if is_blank(line) {
+                if range.is_some() {
+                    break;
+                }
+                continue;
+            }
+
+            let line_end = line_start + line.byte_len();
+
+            match &mut range {
+                Some(range) => range.start = line_start,
+                None => range = Some(line_start..line_end),
+            }
+        }
+
+        range.map(|range| (self.slice.byte_slice(range.clone()), range))
+    }
+}
+
+impl core::iter::FusedIterator for Paragraphs<'_> {}
+
 #[cfg_attr(docsrs, doc(cfg(feature = "graphemes")))]
 #[cfg(feature = "graphemes")]
 pub use graphemes::Graphemes;
 
+pub use super::fold_map::FoldChunks;
+pub use super::inlay_map::InlayChunks;
+pub use super::search::Matches;
+#[cfg_attr(docsrs, doc(cfg(feature = "sentences")))]
+#[cfg(feature = "sentences")]
+pub use super::sentences::Sentences;
+#[cfg_attr(docsrs, doc(cfg(feature = "words")))]
+#[cfg(feature = "words")]
+pub use super::words::{WordBounds, Words};
+
 #[cfg(feature = "graphemes")]
 mod graphemes {
     use alloc::borrow::Cow;
+    use alloc::string::String;
 
     use unicode_segmentation::{GraphemeCursor, GraphemeIncomplete};
 
@@ -675,7 +1084,7 @@ mod graphemes {
                             },
 
                             Cow::Borrowed(gr) => {
-                                let mut gr = gr.to_owned();
+                                let mut gr = String::from(*gr);
                                 gr.push_str(grapheme_end);
                                 grapheme = Cow::Owned(gr);
                             },
@@ -706,7 +1115,7 @@ mod graphemes {
                             },
 
                             Cow::Borrowed(gr) => {
-                                let mut gr = gr.to_owned();
+                                let mut gr = String::from(*gr);
                                 gr.push_str(self.forward_chunk);
                                 grapheme = Cow::Owned(gr);
                             },
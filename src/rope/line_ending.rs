@@ -0,0 +1,202 @@
+//! Detection of the line endings used in `Rope`s and `RopeSlice`s.
+
+use super::iterators::Chunks;
+
+/// The kinds of line endings recognized by
+/// [`Rope::detect_line_ending()`](crate::Rope::detect_line_ending()) and
+/// [`Rope::normalize_line_endings()`](crate::Rope::normalize_line_endings()).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LineEnding {
+    /// A line feed (`\n`), used on Unix-like systems.
+    Lf,
+
+    /// A carriage return followed by a line feed (`\r\n`), used on Windows.
+    CrLf,
+
+    /// A lone carriage return (`\r`), used on classic Mac OS.
+    Cr,
+}
+
+impl LineEnding {
+    /// Returns the string this line ending is made of.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::LineEnding;
+    /// #
+    /// assert_eq!(LineEnding::Lf.as_str(), "\n");
+    /// assert_eq!(LineEnding::CrLf.as_str(), "\r\n");
+    /// assert_eq!(LineEnding::Cr.as_str(), "\r");
+    /// ```
+    #[inline]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::CrLf => "\r\n",
+            Self::Cr => "\r",
+        }
+    }
+}
+
+/// The line endings used in a [`Rope`](crate::Rope) or
+/// [`RopeSlice`](crate::RopeSlice), as returned by their
+/// `detect_line_ending` method.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DetectedLineEnding {
+    /// The text doesn't contain any line endings.
+    None,
+
+    /// All the line endings are LFs, and there are this many of them.
+    Lf(usize),
+
+    /// All the line endings are CRLFs, and there are this many of them.
+    CrLf(usize),
+
+    /// All the line endings are CRs, and there are this many of them.
+    Cr(usize),
+
+    /// The text contains more than one kind of line ending.
+    Mixed {
+        /// The number of `\n`s not preceded by a `\r`.
+        lf: usize,
+
+        /// The number of `\r\n` pairs.
+        crlf: usize,
+
+        /// The number of `\r`s not followed by a `\n`.
+        cr: usize,
+    },
+}
+
+impl DetectedLineEnding {
+    #[inline]
+    pub(super) fn from_counts(lf: usize, crlf: usize, cr: usize) -> Self {
+        match (lf, crlf, cr) {
+            (0, 0, 0) => Self::None,
+            (lf, 0, 0) => Self::Lf(lf),
+            (0, crlf, 0) => Self::CrLf(crlf),
+            (0, 0, cr) => Self::Cr(cr),
+            (lf, crlf, cr) => Self::Mixed { lf, crlf, cr },
+        }
+    }
+
+    /// Returns the most common line ending, or `None` if there aren't any.
+    ///
+    /// Ties are broken in favor of LF, then CRLF.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{LineEnding, Rope};
+    /// #
+    /// let r = Rope::from("foo\r\nbar\r\nbaz\n");
+    /// assert_eq!(r.detect_line_ending().most_common(), Some(LineEnding::CrLf));
+    ///
+    /// let r = Rope::from("foo");
+    /// assert_eq!(r.detect_line_ending().most_common(), None);
+    /// ```
+    #[inline]
+    pub fn most_common(&self) -> Option<LineEnding> {
+        match *self {
+            Self::None => None,
+            Self::Lf(_) => Some(LineEnding::Lf),
+            Self::CrLf(_) => Some(LineEnding::CrLf),
+            Self::Cr(_) => Some(LineEnding::Cr),
+            Self::Mixed { lf, crlf, cr } => {
+                if lf >= crlf && lf >= cr {
+                    Some(LineEnding::Lf)
+                } else if crlf >= cr {
+                    Some(LineEnding::CrLf)
+                } else {
+                    Some(LineEnding::Cr)
+                }
+            },
+        }
+    }
+}
+
+/// An iterator over the byte offsets and kinds of the line endings in the
+/// text yielded by a [`Chunks`], correctly handling CRLF pairs split across
+/// chunks.
+pub(super) struct LineEndings<'a> {
+    chunks: Chunks<'a>,
+
+    /// The chunk we're currently searching.
+    chunk: &'a str,
+
+    /// The byte offset of [`chunk`](Self::chunk) in the whole text.
+    chunk_offset: usize,
+
+    /// The byte offset in [`chunk`](Self::chunk) to resume searching from.
+    idx: usize,
+}
+
+impl<'a> LineEndings<'a> {
+    #[inline]
+    pub(super) fn new(mut chunks: Chunks<'a>) -> Self {
+        let chunk = chunks.next().unwrap_or_default();
+        Self { chunks, chunk, chunk_offset: 0, idx: 0 }
+    }
+
+    /// Moves to the next chunk, returning `false` if there are no more.
+    #[inline]
+    fn next_chunk(&mut self) -> bool {
+        self.chunk_offset += self.chunk.len();
+        self.idx = 0;
+
+        match self.chunks.next() {
+            Some(chunk) => {
+                self.chunk = chunk;
+                true
+            },
+
+            None => {
+                self.chunk = "";
+                false
+            },
+        }
+    }
+}
+
+impl Iterator for LineEndings<'_> {
+    type Item = (usize, LineEnding);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let bytes = &self.chunk.as_bytes()[self.idx..];
+
+            let Some(pos) =
+                bytes.iter().position(|&byte| byte == b'\n' || byte == b'\r')
+            else {
+                if self.next_chunk() {
+                    continue;
+                } else {
+                    return None;
+                }
+            };
+
+            let offset = self.chunk_offset + self.idx + pos;
+
+            self.idx += pos + 1;
+
+            if bytes[pos] == b'\n' {
+                return Some((offset, LineEnding::Lf));
+            }
+
+            // The "\n" of a CRLF pair could be at the start of the next
+            // chunk.
+            if self.idx == self.chunk.len() {
+                self.next_chunk();
+            }
+
+            if self.chunk.as_bytes().get(self.idx) == Some(&b'\n') {
+                self.idx += 1;
+                return Some((offset, LineEnding::CrLf));
+            } else {
+                return Some((offset, LineEnding::Cr));
+            }
+        }
+    }
+}
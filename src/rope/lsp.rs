@@ -0,0 +1,154 @@
+//! Types used to apply the edits sent by a Language Server Protocol client.
+
+use super::{Point, PositionEncoding, Rope};
+
+/// A range in a text document expressed as a pair of [`Point`]s, like LSP's
+/// `Range`.
+///
+/// Like in the LSP specification the end is exclusive, and a column greater
+/// than the length of its line is clamped back to the end of the line.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Range {
+    /// The start of the range.
+    pub start: Point,
+
+    /// The end of the range.
+    pub end: Point,
+}
+
+impl Range {
+    /// Creates a new `Range` from its start and end points.
+    #[inline]
+    pub const fn new(start: Point, end: Point) -> Self {
+        Self { start, end }
+    }
+}
+
+/// A change sent in a `textDocument/didChange` notification, mirroring LSP's
+/// `TextDocumentContentChangeEvent`.
+///
+/// See [`Rope::apply_content_changes()`] for how a list of them is applied.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ContentChange<'a> {
+    /// The range of the document being replaced, or `None` if `text` is the
+    /// new content of the whole document.
+    pub range: Option<Range>,
+
+    /// The new text for the range or for the whole document.
+    pub text: &'a str,
+}
+
+/// An edit to a text document, mirroring LSP's `TextEdit`.
+///
+/// See [`Rope::apply_text_edits()`] for how a list of them is applied.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TextEdit<'a> {
+    /// The range of the document being replaced.
+    pub range: Range,
+
+    /// The text to replace the range with. Use an empty string to delete
+    /// the range.
+    pub new_text: &'a str,
+}
+
+/// The error returned by [`Rope::apply_content_changes()`] and
+/// [`Rope::apply_text_edits()`] when one of the ranges is invalid.
+///
+/// The `index` of each variant is the position of the offending change or
+/// edit in the list it was given in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LspError {
+    /// The line of one of the range's points was greater than the number of
+    /// lines in the document.
+    LineOutOfBounds {
+        /// The index of the change or edit.
+        index: usize,
+
+        /// The line of the point.
+        line: usize,
+
+        /// The number of lines in the document, as returned by
+        /// [`Rope::line_len()`].
+        line_len: usize,
+    },
+
+    /// The start of a range came after its end.
+    StartAfterEnd {
+        /// The index of the change or edit.
+        index: usize,
+
+        /// The start of the range.
+        start: Point,
+
+        /// The end of the range.
+        end: Point,
+    },
+
+    /// The ranges of two text edits overlapped.
+    OverlappingEdits {
+        /// The index of the edit whose range starts first.
+        first: usize,
+
+        /// The index of the edit whose range starts second.
+        second: usize,
+    },
+}
+
+impl core::fmt::Display for LspError {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::LineOutOfBounds { index, line, line_len } => write!(
+                f,
+                "invalid range at index {index}: line {line} is out of \
+                 bounds, the document only has {line_len} lines"
+            ),
+
+            Self::StartAfterEnd { index, start, end } => write!(
+                f,
+                "invalid range at index {index}: the start ({}:{}) is after \
+                 the end ({}:{})",
+                start.line, start.column, end.line, end.column
+            ),
+
+            Self::OverlappingEdits { first, second } => write!(
+                f,
+                "the ranges of the text edits at index {first} and {second} \
+                 overlap"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LspError {}
+
+/// Converts the LSP range into a byte range in the `Rope`, or returns an
+/// error if the range is invalid.
+#[inline]
+pub(super) fn byte_range(
+    rope: &Rope,
+    range: Range,
+    encoding: PositionEncoding,
+    index: usize,
+) -> Result<core::ops::Range<usize>, LspError> {
+    let Range { start, end } = range;
+
+    for point in [start, end] {
+        if point.line > rope.line_len() {
+            return Err(LspError::LineOutOfBounds {
+                index,
+                line: point.line,
+                line_len: rope.line_len(),
+            });
+        }
+    }
+
+    if start > end {
+        return Err(LspError::StartAfterEnd { index, start, end });
+    }
+
+    // Clamping and rounding down the columns never swaps the two points, so
+    // the byte range is well-formed.
+    Ok(rope.byte_of_point(start, encoding)..rope.byte_of_point(end, encoding))
+}
@@ -2,6 +2,7 @@ use core::ops::{Add, AddAssign, Sub, SubAssign};
 
 use super::gap_buffer::GapBuffer;
 use super::gap_slice::GapSlice;
+use super::utils::{is_line_break, splits_crlf};
 use crate::tree::{DoubleEndedUnitMetric, Metric, SlicingMetric, UnitMetric};
 
 #[derive(Copy, Clone, Default, Debug, PartialEq)]
@@ -9,8 +10,12 @@ use crate::tree::{DoubleEndedUnitMetric, Metric, SlicingMetric, UnitMetric};
 pub struct ChunkSummary {
     bytes: usize,
     line_breaks: usize,
+    #[cfg(feature = "char-metric")]
+    chars: usize,
     #[cfg(feature = "utf16-metric")]
     utf16_code_units: usize,
+    #[cfg(feature = "unicode-width")]
+    display_width: usize,
 }
 
 impl From<&str> for ChunkSummary {
@@ -19,8 +24,12 @@ impl From<&str> for ChunkSummary {
         Self {
             bytes: s.len(),
             line_breaks: count::line_breaks(s),
+            #[cfg(feature = "char-metric")]
+            chars: count::chars(s),
             #[cfg(feature = "utf16-metric")]
             utf16_code_units: count::utf16_code_units(s),
+            #[cfg(feature = "unicode-width")]
+            display_width: count::display_width(s),
         }
     }
 }
@@ -30,19 +39,61 @@ impl From<char> for ChunkSummary {
     fn from(ch: char) -> Self {
         Self {
             bytes: ch.len_utf8(),
-            line_breaks: (ch == '\n') as usize,
+            line_breaks: is_line_break(ch) as usize,
+            #[cfg(feature = "char-metric")]
+            chars: 1,
             #[cfg(feature = "utf16-metric")]
             utf16_code_units: ch.len_utf16(),
+            #[cfg(feature = "unicode-width")]
+            display_width: count::char_display_width(ch),
         }
     }
 }
 
 impl ChunkSummary {
+    /// Summarizes `s` knowing that it's immediately followed by `next`.
+    ///
+    /// This only differs from `ChunkSummary::from(s)` when `s` ends with the
+    /// `\r` of a CRLF pair whose `\n` is at the start of `next`, in which
+    /// case the line break is left to be counted by `next`.
+    #[inline]
+    pub(super) fn from_followed_by(s: &str, next: &str) -> Self {
+        let mut summary = Self::from(s);
+        summary.line_breaks -= splits_crlf(s, next) as usize;
+        summary
+    }
+
+    /// Adjusts the summary of `s`, computed knowing that `s` was followed by
+    /// `old_next`, to the case where it's followed by `new_next`.
+    #[inline]
+    pub(super) fn with_next(
+        mut self,
+        s: &str,
+        old_next: &str,
+        new_next: &str,
+    ) -> Self {
+        self.line_breaks += splits_crlf(s, old_next) as usize;
+        self.line_breaks -= splits_crlf(s, new_next) as usize;
+        self
+    }
+
     #[inline]
     pub fn bytes(&self) -> usize {
         self.bytes
     }
 
+    #[cfg(feature = "char-metric")]
+    #[inline]
+    pub fn chars(&self) -> usize {
+        self.chars
+    }
+
+    #[cfg(feature = "unicode-width")]
+    #[inline]
+    pub fn display_width(&self) -> usize {
+        self.display_width
+    }
+
     #[inline]
     pub fn line_breaks(&self) -> usize {
         self.line_breaks
@@ -106,10 +157,18 @@ impl AddAssign<Self> for ChunkSummary {
     fn add_assign(&mut self, rhs: Self) {
         self.bytes += rhs.bytes;
         self.line_breaks += rhs.line_breaks;
+        #[cfg(feature = "char-metric")]
+        {
+            self.chars += rhs.chars;
+        }
         #[cfg(feature = "utf16-metric")]
         {
             self.utf16_code_units += rhs.utf16_code_units;
         }
+        #[cfg(feature = "unicode-width")]
+        {
+            self.display_width += rhs.display_width;
+        }
     }
 }
 
@@ -118,10 +177,18 @@ impl SubAssign<Self> for ChunkSummary {
     fn sub_assign(&mut self, rhs: Self) {
         self.bytes -= rhs.bytes;
         self.line_breaks -= rhs.line_breaks;
+        #[cfg(feature = "char-metric")]
+        {
+            self.chars -= rhs.chars;
+        }
         #[cfg(feature = "utf16-metric")]
         {
             self.utf16_code_units -= rhs.utf16_code_units;
         }
+        #[cfg(feature = "unicode-width")]
+        {
+            self.display_width -= rhs.display_width;
+        }
     }
 }
 
@@ -237,12 +304,22 @@ impl SummaryUpTo for ByteMetric {
                 str_summary.line_breaks,
             ),
 
+            #[cfg(feature = "char-metric")]
+            chars: count::chars_up_to(in_str, byte_offset, str_summary.chars),
+
             #[cfg(feature = "utf16-metric")]
             utf16_code_units: count::utf16_code_units_up_to(
                 in_str,
                 byte_offset,
                 str_summary.utf16_code_units,
             ),
+
+            #[cfg(feature = "unicode-width")]
+            display_width: count::display_width_up_to(
+                in_str,
+                byte_offset,
+                str_summary.display_width,
+            ),
         }
     }
 }
@@ -343,7 +420,14 @@ impl ToByteOffset for RawLineMetric {
 }
 
 impl SummaryUpTo for RawLineMetric {
-    #[cfg_attr(not(feature = "utf16-metric"), allow(unused_variables))]
+    #[cfg_attr(
+        not(any(
+            feature = "char-metric",
+            feature = "utf16-metric",
+            feature = "unicode-width"
+        )),
+        allow(unused_variables)
+    )]
     #[inline]
     fn up_to(
         in_str: &str,
@@ -356,12 +440,22 @@ impl SummaryUpTo for RawLineMetric {
 
             line_breaks: line_offset,
 
+            #[cfg(feature = "char-metric")]
+            chars: count::chars_up_to(in_str, byte_offset, str_summary.chars),
+
             #[cfg(feature = "utf16-metric")]
             utf16_code_units: count::utf16_code_units_up_to(
                 in_str,
                 byte_offset,
                 str_summary.utf16_code_units,
             ),
+
+            #[cfg(feature = "unicode-width")]
+            display_width: count::display_width_up_to(
+                in_str,
+                byte_offset,
+                str_summary.display_width,
+            ),
         }
     }
 }
@@ -573,6 +667,139 @@ impl<const MAX_BYTES: usize> DoubleEndedUnitMetric<GapBuffer<MAX_BYTES>>
     }
 }
 
+#[cfg(feature = "char-metric")]
+pub use char_metric::CharMetric;
+
+#[cfg(feature = "char-metric")]
+mod char_metric {
+    use super::*;
+
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct CharMetric(pub usize);
+
+    impl Add<Self> for CharMetric {
+        type Output = Self;
+
+        #[inline]
+        fn add(self, other: Self) -> Self {
+            Self(self.0 + other.0)
+        }
+    }
+
+    impl Sub for CharMetric {
+        type Output = Self;
+
+        #[inline]
+        fn sub(self, other: Self) -> Self {
+            Self(self.0 - other.0)
+        }
+    }
+
+    impl AddAssign for CharMetric {
+        #[inline]
+        fn add_assign(&mut self, other: Self) {
+            self.0 += other.0
+        }
+    }
+
+    impl SubAssign for CharMetric {
+        #[inline]
+        fn sub_assign(&mut self, other: Self) {
+            self.0 -= other.0
+        }
+    }
+
+    impl ToByteOffset for CharMetric {
+        #[inline]
+        fn to_byte_offset(&self, in_str: &str) -> usize {
+            convert::byte_of_char(in_str, self.0)
+        }
+    }
+
+    impl SummaryUpTo for CharMetric {
+        #[inline]
+        fn up_to(
+            in_str: &str,
+            str_summary: ChunkSummary,
+            Self(char_offset): Self,
+            byte_offset: usize,
+        ) -> ChunkSummary {
+            ChunkSummary {
+                bytes: byte_offset,
+
+                line_breaks: count::line_breaks_up_to(
+                    in_str,
+                    byte_offset,
+                    str_summary.line_breaks,
+                ),
+
+                chars: char_offset,
+
+                #[cfg(feature = "utf16-metric")]
+                utf16_code_units: count::utf16_code_units_up_to(
+                    in_str,
+                    byte_offset,
+                    str_summary.utf16_code_units,
+                ),
+
+                #[cfg(feature = "unicode-width")]
+                display_width: count::display_width_up_to(
+                    in_str,
+                    byte_offset,
+                    str_summary.display_width,
+                ),
+            }
+        }
+    }
+
+    impl Metric<ChunkSummary> for CharMetric {
+        #[inline]
+        fn zero() -> Self {
+            Self(0)
+        }
+
+        #[inline]
+        fn one() -> Self {
+            Self(1)
+        }
+
+        #[inline]
+        fn measure(summary: &ChunkSummary) -> Self {
+            Self(summary.chars)
+        }
+    }
+
+    impl<const MAX_BYTES: usize> SlicingMetric<GapBuffer<MAX_BYTES>>
+        for CharMetric
+    {
+        #[inline]
+        fn slice_up_to<'a>(
+            chunk: GapSlice<'a>,
+            char_offset: Self,
+            &summary: &ChunkSummary,
+        ) -> (GapSlice<'a>, ChunkSummary)
+        where
+            'a: 'a,
+        {
+            let (left, _) = chunk.split_at_offset(char_offset, summary);
+            left
+        }
+
+        #[inline]
+        fn slice_from<'a>(
+            chunk: GapSlice<'a>,
+            char_offset: Self,
+            &summary: &ChunkSummary,
+        ) -> (GapSlice<'a>, ChunkSummary)
+        where
+            'a: 'a,
+        {
+            let (_, right) = chunk.split_at_offset(char_offset, summary);
+            right
+        }
+    }
+}
+
 #[cfg(feature = "utf16-metric")]
 pub use utf16_metric::Utf16Metric;
 
@@ -643,7 +870,21 @@ mod utf16_metric {
                     str_summary.line_breaks,
                 ),
 
+                #[cfg(feature = "char-metric")]
+                chars: count::chars_up_to(
+                    in_str,
+                    byte_offset,
+                    str_summary.chars,
+                ),
+
                 utf16_code_units: utf16_code_unit_offset,
+
+                #[cfg(feature = "unicode-width")]
+                display_width: count::display_width_up_to(
+                    in_str,
+                    byte_offset,
+                    str_summary.display_width,
+                ),
             }
         }
     }
@@ -700,30 +941,254 @@ mod utf16_metric {
     }
 }
 
+#[cfg(feature = "unicode-width")]
+pub use display_width_metric::DisplayWidthMetric;
+
+#[cfg(feature = "unicode-width")]
+mod display_width_metric {
+    use super::*;
+
+    /// A metric measuring the number of columns a piece of text takes up when
+    /// displayed in a terminal.
+    ///
+    /// Unlike the other metrics, converting a `DisplayWidthMetric` to a byte
+    /// offset doesn't always land exactly on it: a double-width char can
+    /// straddle a given column. In that case the byte offset is the one right
+    /// after the char.
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct DisplayWidthMetric(pub usize);
+
+    impl Add<Self> for DisplayWidthMetric {
+        type Output = Self;
+
+        #[inline]
+        fn add(self, other: Self) -> Self {
+            Self(self.0 + other.0)
+        }
+    }
+
+    impl Sub for DisplayWidthMetric {
+        type Output = Self;
+
+        #[inline]
+        fn sub(self, other: Self) -> Self {
+            Self(self.0 - other.0)
+        }
+    }
+
+    impl AddAssign for DisplayWidthMetric {
+        #[inline]
+        fn add_assign(&mut self, other: Self) {
+            self.0 += other.0
+        }
+    }
+
+    impl SubAssign for DisplayWidthMetric {
+        #[inline]
+        fn sub_assign(&mut self, other: Self) {
+            self.0 -= other.0
+        }
+    }
+
+    impl ToByteOffset for DisplayWidthMetric {
+        #[inline]
+        fn to_byte_offset(&self, in_str: &str) -> usize {
+            convert::byte_of_display_column(in_str, self.0)
+        }
+    }
+
+    impl SummaryUpTo for DisplayWidthMetric {
+        #[inline]
+        fn up_to(
+            in_str: &str,
+            str_summary: ChunkSummary,
+            _: Self,
+            byte_offset: usize,
+        ) -> ChunkSummary {
+            ChunkSummary {
+                bytes: byte_offset,
+
+                line_breaks: count::line_breaks_up_to(
+                    in_str,
+                    byte_offset,
+                    str_summary.line_breaks,
+                ),
+
+                #[cfg(feature = "char-metric")]
+                chars: count::chars_up_to(
+                    in_str,
+                    byte_offset,
+                    str_summary.chars,
+                ),
+
+                #[cfg(feature = "utf16-metric")]
+                utf16_code_units: count::utf16_code_units_up_to(
+                    in_str,
+                    byte_offset,
+                    str_summary.utf16_code_units,
+                ),
+
+                // The byte offset can be past the given column if it falls
+                // inside a double-width char, so we have to count it again.
+                display_width: count::display_width_up_to(
+                    in_str,
+                    byte_offset,
+                    str_summary.display_width,
+                ),
+            }
+        }
+    }
+
+    impl Metric<ChunkSummary> for DisplayWidthMetric {
+        #[inline]
+        fn zero() -> Self {
+            Self(0)
+        }
+
+        #[inline]
+        fn one() -> Self {
+            Self(1)
+        }
+
+        #[inline]
+        fn measure(summary: &ChunkSummary) -> Self {
+            Self(summary.display_width)
+        }
+    }
+
+    impl<const MAX_BYTES: usize> SlicingMetric<GapBuffer<MAX_BYTES>>
+        for DisplayWidthMetric
+    {
+        #[inline]
+        fn slice_up_to<'a>(
+            chunk: GapSlice<'a>,
+            column: Self,
+            &summary: &ChunkSummary,
+        ) -> (GapSlice<'a>, ChunkSummary)
+        where
+            'a: 'a,
+        {
+            let (left, _) = chunk.split_at_offset(column, summary);
+            left
+        }
+
+        #[inline]
+        fn slice_from<'a>(
+            chunk: GapSlice<'a>,
+            column: Self,
+            &summary: &ChunkSummary,
+        ) -> (GapSlice<'a>, ChunkSummary)
+        where
+            'a: 'a,
+        {
+            let (_, right) = chunk.split_at_offset(column, summary);
+            right
+        }
+    }
+}
+
 use str_utils::*;
 
 mod str_utils {
-    #[cfg(not(miri))]
+    #[cfg(all(not(miri), feature = "char-metric"))]
+    use str_indices::chars;
+    #[cfg(all(not(miri), not(feature = "unicode-lines")))]
     use str_indices::lines_lf as lines;
     #[cfg(all(not(miri), feature = "utf16-metric"))]
     use str_indices::utf16;
 
+    /// Line breaks as defined by the `unicode-lines` feature. We can't use
+    /// `str_indices::lines` because it doesn't know about U+000B and U+000C.
+    ///
+    /// A `\r` at the end of the string is always counted, even though it
+    /// could be the first half of a CRLF pair whose `\n` is at the start of
+    /// the following string: it's up to the callers to handle that case.
+    #[cfg(feature = "unicode-lines")]
+    mod lines {
+        /// Returns `true` if the byte at `idx` is the last byte of a line
+        /// break. The `\r` of a CRLF pair is not, since the pair is a single
+        /// line break ending on the `\n`.
+        #[inline(always)]
+        fn is_break_end(bytes: &[u8], idx: usize) -> bool {
+            match bytes[idx] {
+                b'\n' | 0x0B | 0x0C => true,
+
+                b'\r' => bytes.get(idx + 1) != Some(&b'\n'),
+
+                // U+0085 is encoded as `C2 85`.
+                0x85 => idx >= 1 && bytes[idx - 1] == 0xC2,
+
+                // U+2028 and U+2029 are encoded as `E2 80 A8` and `E2 80 A9`.
+                0xA8 | 0xA9 => {
+                    idx >= 2
+                        && bytes[idx - 1] == 0x80
+                        && bytes[idx - 2] == 0xE2
+                },
+
+                _ => false,
+            }
+        }
+
+        #[inline]
+        pub fn count_breaks(s: &str) -> usize {
+            let bytes = s.as_bytes();
+            (0..bytes.len()).filter(|&idx| is_break_end(bytes, idx)).count()
+        }
+
+        #[inline]
+        pub fn to_byte_idx(s: &str, line_idx: usize) -> usize {
+            if line_idx == 0 {
+                return 0;
+            }
+
+            let bytes = s.as_bytes();
+
+            let mut seen = 0;
+
+            for idx in 0..bytes.len() {
+                if is_break_end(bytes, idx) {
+                    seen += 1;
+                    if seen == line_idx {
+                        return idx + 1;
+                    }
+                }
+            }
+
+            s.len()
+        }
+    }
+
     pub mod count {
-        #[cfg(not(miri))]
+        #[cfg(any(not(miri), feature = "unicode-lines"))]
         use super::*;
+        #[cfg(feature = "unicode-lines")]
+        use crate::rope::utils::splits_crlf;
 
         #[inline]
         pub fn line_breaks(s: &str) -> usize {
-            #[cfg(not(miri))]
+            #[cfg(any(not(miri), feature = "unicode-lines"))]
             {
                 lines::count_breaks(s)
             }
-            #[cfg(miri)]
+            #[cfg(all(miri, not(feature = "unicode-lines")))]
             {
                 s.bytes().filter(|&b| b == b'\n').count()
             }
         }
 
+        #[cfg(feature = "char-metric")]
+        #[inline]
+        pub fn chars(s: &str) -> usize {
+            #[cfg(not(miri))]
+            {
+                chars::count(s)
+            }
+            #[cfg(miri)]
+            {
+                s.chars().count()
+            }
+        }
+
         #[cfg(feature = "utf16-metric")]
         #[inline]
         pub fn utf16_code_units(s: &str) -> usize {
@@ -737,15 +1202,54 @@ mod str_utils {
             }
         }
 
+        /// Returns the number of columns the char takes up in a terminal,
+        /// using the East Asian Width rules. Control chars, including tabs and
+        /// line breaks, take up zero columns.
+        #[cfg(feature = "unicode-width")]
+        #[inline]
+        pub fn char_display_width(ch: char) -> usize {
+            unicode_width::UnicodeWidthChar::width(ch).unwrap_or(0)
+        }
+
+        #[cfg(feature = "unicode-width")]
+        #[inline]
+        pub fn display_width(s: &str) -> usize {
+            s.chars().map(char_display_width).sum()
+        }
+
         #[inline(always)]
         pub fn line_breaks_up_to(
             s: &str,
             byte_offset: usize,
             tot_line_breaks: usize,
         ) -> usize {
+            // A trailing `\r` is only included in the total if the string is
+            // not followed by a `\n`, which we can't know from here, so in
+            // that case we always count the prefix directly.
+            #[cfg(feature = "unicode-lines")]
+            {
+                if byte_offset == s.len() {
+                    return tot_line_breaks;
+                } else if byte_offset <= s.len() / 2 || s.ends_with('\r') {
+                    let (prefix, rest) = s.split_at(byte_offset);
+                    return line_breaks(prefix)
+                        - splits_crlf(prefix, rest) as usize;
+                }
+            }
+
             metric_up_to(s, byte_offset, tot_line_breaks, line_breaks)
         }
 
+        #[cfg(feature = "char-metric")]
+        #[inline(always)]
+        pub fn chars_up_to(
+            s: &str,
+            byte_offset: usize,
+            tot_chars: usize,
+        ) -> usize {
+            metric_up_to(s, byte_offset, tot_chars, chars)
+        }
+
         #[cfg(feature = "utf16-metric")]
         #[inline(always)]
         pub fn utf16_code_units_up_to(
@@ -761,6 +1265,16 @@ mod str_utils {
             )
         }
 
+        #[cfg(feature = "unicode-width")]
+        #[inline(always)]
+        pub fn display_width_up_to(
+            s: &str,
+            byte_offset: usize,
+            tot_display_width: usize,
+        ) -> usize {
+            metric_up_to(s, byte_offset, tot_display_width, display_width)
+        }
+
         #[inline(always)]
         fn metric_up_to(
             s: &str,
@@ -783,17 +1297,17 @@ mod str_utils {
     }
 
     pub mod convert {
-        #[cfg(not(miri))]
+        #[cfg(any(not(miri), feature = "unicode-lines"))]
         use super::*;
 
         #[inline]
         pub fn byte_of_line(s: &str, line_offset: usize) -> usize {
-            #[cfg(not(miri))]
+            #[cfg(any(not(miri), feature = "unicode-lines"))]
             {
                 lines::to_byte_idx(s, line_offset)
             }
 
-            #[cfg(miri)]
+            #[cfg(all(miri, not(feature = "unicode-lines")))]
             {
                 if line_offset == 0 {
                     return 0;
@@ -818,6 +1332,23 @@ mod str_utils {
             }
         }
 
+        #[cfg(feature = "char-metric")]
+        #[inline]
+        pub fn byte_of_char(s: &str, char_offset: usize) -> usize {
+            #[cfg(not(miri))]
+            {
+                chars::to_byte_idx(s, char_offset)
+            }
+
+            #[cfg(miri)]
+            {
+                s.char_indices()
+                    .nth(char_offset)
+                    .map(|(byte_offset, _)| byte_offset)
+                    .unwrap_or(s.len())
+            }
+        }
+
         #[cfg(feature = "utf16-metric")]
         #[inline]
         pub fn byte_of_utf16_code_unit(
@@ -841,5 +1372,28 @@ mod str_utils {
                 decoded_utf16.len()
             }
         }
+
+        /// Returns the byte offset right after the first char at which the
+        /// display width of the string reaches `column`, or zero if `column`
+        /// is zero.
+        #[cfg(feature = "unicode-width")]
+        #[inline]
+        pub fn byte_of_display_column(s: &str, column: usize) -> usize {
+            if column == 0 {
+                return 0;
+            }
+
+            let mut width = 0;
+
+            for (byte_offset, ch) in s.char_indices() {
+                width += super::count::char_display_width(ch);
+
+                if width >= column {
+                    return byte_offset + ch.len_utf8();
+                }
+            }
+
+            s.len()
+        }
     }
 }
@@ -1,12 +1,43 @@
+mod cursor;
+#[cfg(feature = "unicode-width")]
+mod display_width;
+mod error;
+mod fold_map;
 pub(crate) mod gap_buffer;
 pub(crate) mod gap_slice;
+#[cfg(feature = "grapheme-metric")]
+mod grapheme_runs;
+mod inlay_map;
 pub(crate) mod iterators;
+mod line_ending;
+#[cfg(feature = "lsp")]
+pub(crate) mod lsp;
 pub mod metrics;
+mod point;
+#[cfg(feature = "regex")]
+pub(crate) mod regex;
 mod rope;
 mod rope_builder;
 mod rope_slice;
+mod search;
+#[cfg(any(feature = "sentences", feature = "words"))]
+mod segmentation;
+#[cfg(feature = "sentences")]
+mod sentences;
 mod utils;
+mod visual_column;
+#[cfg(feature = "words")]
+mod words;
+mod wrap_map;
 
+pub use cursor::RopeCursor;
+pub use error::{RopeError, Unit};
+pub use fold_map::FoldMap;
+pub use inlay_map::InlayMap;
+pub use line_ending::{DetectedLineEnding, LineEnding};
+pub use point::{Point, PositionEncoding};
 pub use rope::Rope;
 pub use rope_builder::RopeBuilder;
 pub use rope_slice::RopeSlice;
+pub use search::Pattern;
+pub use wrap_map::WrapMap;
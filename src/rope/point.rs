@@ -0,0 +1,137 @@
+//! Conversions between byte offsets and `(line, column)` pairs.
+
+use super::RopeSlice;
+
+/// A position in a [`Rope`](crate::Rope) or [`RopeSlice`] expressed as a
+/// zero-based line index and a zero-based column within that line.
+///
+/// The unit the column is measured in is given by the [`PositionEncoding`]
+/// passed to the methods creating or consuming the `Point`.
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub struct Point {
+    /// The line index.
+    pub line: usize,
+
+    /// The offset from the start of the line.
+    pub column: usize,
+}
+
+impl Point {
+    /// Creates a new `Point` from a line index and a column.
+    #[inline]
+    pub const fn new(line: usize, column: usize) -> Self {
+        Self { line, column }
+    }
+}
+
+/// The unit the column of a [`Point`] is measured in.
+///
+/// This mirrors the `PositionEncodingKind` of the Language Server Protocol.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PositionEncoding {
+    /// Columns are measured in UTF-8 bytes.
+    Utf8,
+
+    /// Columns are measured in UTF-16 code units. This is the default
+    /// encoding used by the Language Server Protocol.
+    #[default]
+    Utf16,
+
+    /// Columns are measured in Unicode scalar values, i.e. [`char`]s.
+    Utf32,
+}
+
+/// Returns the length of the slice in the given encoding.
+///
+/// This runs in logarithmic time if the feature flag of the corresponding
+/// metric is enabled, and in linear time otherwise.
+#[inline]
+pub(super) fn len_in(
+    slice: RopeSlice<'_>,
+    encoding: PositionEncoding,
+) -> usize {
+    match encoding {
+        PositionEncoding::Utf8 => slice.byte_len(),
+
+        #[cfg(feature = "utf16-metric")]
+        PositionEncoding::Utf16 => slice.utf16_len(),
+
+        #[cfg(not(feature = "utf16-metric"))]
+        PositionEncoding::Utf16 => slice.chars().map(char::len_utf16).sum(),
+
+        #[cfg(feature = "char-metric")]
+        PositionEncoding::Utf32 => slice.char_len(),
+
+        #[cfg(not(feature = "char-metric"))]
+        PositionEncoding::Utf32 => slice.chars().count(),
+    }
+}
+
+/// Returns the byte offset of the given column in the line.
+///
+/// Columns past the end of the line are clamped to its length, and columns
+/// inside a character (e.g. between the two code units of a surrogate pair)
+/// are rounded down to the start of that character.
+#[inline]
+pub(super) fn byte_of_column(
+    line: RopeSlice<'_>,
+    column: usize,
+    encoding: PositionEncoding,
+) -> usize {
+    match encoding {
+        PositionEncoding::Utf8 => {
+            let mut byte_offset = column.min(line.byte_len());
+
+            while !line.is_char_boundary(byte_offset) {
+                byte_offset -= 1;
+            }
+
+            byte_offset
+        },
+
+        #[cfg(feature = "utf16-metric")]
+        PositionEncoding::Utf16 => {
+            line.byte_of_utf16_code_unit(column.min(line.utf16_len()))
+        },
+
+        #[cfg(not(feature = "utf16-metric"))]
+        PositionEncoding::Utf16 => {
+            byte_of_column_linear(line, column, char::len_utf16)
+        },
+
+        #[cfg(feature = "char-metric")]
+        PositionEncoding::Utf32 => {
+            line.byte_of_char(column.min(line.char_len()))
+        },
+
+        #[cfg(not(feature = "char-metric"))]
+        PositionEncoding::Utf32 => byte_of_column_linear(line, column, |_| 1),
+    }
+}
+
+/// Walks the characters of the line until the given column is reached, where
+/// `len` returns the length of a character in the column's unit.
+#[cfg(any(not(feature = "utf16-metric"), not(feature = "char-metric")))]
+#[inline]
+fn byte_of_column_linear(
+    line: RopeSlice<'_>,
+    column: usize,
+    len: impl Fn(char) -> usize,
+) -> usize {
+    let mut byte_offset = 0;
+    let mut measured = 0;
+
+    for ch in line.chars() {
+        measured += len(ch);
+
+        if measured > column {
+            break;
+        }
+
+        byte_offset += ch.len_utf8();
+    }
+
+    byte_offset
+}
@@ -0,0 +1,871 @@
+//! Regex searches over the chunks of `Rope`s and `RopeSlice`s.
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::ops::Range;
+use core::panic::{RefUnwindSafe, UnwindSafe};
+
+use regex_automata::hybrid::dfa::{Cache as DfaCache, DFA};
+use regex_automata::hybrid::{self, LazyStateID};
+use regex_automata::nfa::thompson;
+use regex_automata::util::captures::Captures as RawCaptures;
+use regex_automata::util::interpolate;
+use regex_automata::util::pool::Pool;
+use regex_automata::util::start;
+use regex_automata::{meta, Anchored, Input};
+
+use super::RopeSlice;
+
+type CachePoolFn =
+    Box<dyn Fn() -> Caches + Send + Sync + UnwindSafe + RefUnwindSafe>;
+
+/// A compiled regular expression that can search
+/// [`Rope`](crate::Rope)s and [`RopeSlice`]s without copying their text into
+/// a contiguous string.
+///
+/// Matches are found by running a lazy DFA over the chunks of the text, first
+/// forward to find where a match ends and then backward to find where it
+/// starts. Resolving capture groups needs a contiguous haystack, so the
+/// [`captures`](Self::captures()) family of methods copies the text of each
+/// match (plus a char on either side of it) before running a second engine
+/// on it.
+///
+/// Every search method takes the haystack as either a `&Rope` or a
+/// `RopeSlice`, and returns byte offsets relative to its start. The syntax is
+/// the same as the one of the [`regex`](https://docs.rs/regex) crate, except
+/// that the Unicode-aware word boundaries `\b` and `\B` aren't supported by
+/// the lazy DFA; their ASCII-only versions `(?-u:\b)` and `(?-u:\B)` are.
+///
+/// # Examples
+///
+/// ```
+/// # use crop::regex::Regex;
+/// # use crop::Rope;
+/// #
+/// let r = Rope::from("foo 42\nbar 1337\n");
+/// let re = Regex::new(r"(?<name>[a-z]+) (?<number>\d+)").unwrap();
+///
+/// assert_eq!(re.find(&r), Some(0..6));
+/// assert_eq!(re.rfind(&r), Some(7..15));
+///
+/// let caps = re.rcaptures(r.byte_slice(..)).unwrap();
+/// assert_eq!(caps.name("number"), Some(11..15));
+/// ```
+pub struct Regex {
+    pattern: Box<str>,
+
+    /// A leftmost-first DFA used to find where a match ends, and to find the
+    /// end of a match given its start.
+    forward: DFA,
+
+    /// A DFA matching the reversed regex with `MatchKind::All` semantics,
+    /// used to find where a match starts given its end.
+    reverse_all: DFA,
+
+    /// A leftmost-first DFA matching the reversed regex, used to find where
+    /// the last match starts.
+    reverse_first: DFA,
+
+    /// Used to resolve the capture groups of a match once its range is known.
+    meta: meta::Regex,
+
+    pool: Pool<Caches, CachePoolFn>,
+}
+
+/// The mutable state of the two lazy DFAs of a [`Regex`].
+struct Caches {
+    forward: DfaCache,
+    reverse_all: DfaCache,
+    reverse_first: DfaCache,
+}
+
+impl Regex {
+    /// Returns the pattern this `Regex` was compiled from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::regex::Regex;
+    /// #
+    /// let re = Regex::new(r"\d+").unwrap();
+    /// assert_eq!(re.as_str(), r"\d+");
+    /// ```
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Returns the capture groups of the first match of the regex in the
+    /// haystack, or `None` if there isn't one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::regex::Regex;
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo=1, bar=2");
+    /// let re = Regex::new(r"(\w+)=(\d)").unwrap();
+    ///
+    /// let caps = re.captures(&r).unwrap();
+    /// assert_eq!(caps.get(0), Some(0..5));
+    /// assert_eq!(caps.get(1), Some(0..3));
+    /// assert_eq!(caps.get(2), Some(4..5));
+    /// ```
+    #[inline]
+    pub fn captures<'h, H>(&self, haystack: H) -> Option<Captures>
+    where
+        H: Into<RopeSlice<'h>>,
+    {
+        self.captures_iter(haystack).next()
+    }
+
+    /// Returns an iterator over the capture groups of the non-overlapping
+    /// matches of the regex in the haystack, from front to back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::regex::Regex;
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo=1, bar=2");
+    /// let re = Regex::new(r"(\w+)=(\d)").unwrap();
+    ///
+    /// let keys = re
+    ///     .captures_iter(&r)
+    ///     .map(|caps| r.byte_slice(caps.get(1).unwrap()).to_string())
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(keys, ["foo", "bar"]);
+    /// ```
+    #[inline]
+    pub fn captures_iter<'r, 'h, H>(
+        &'r self,
+        haystack: H,
+    ) -> CaptureMatches<'r, 'h>
+    where
+        H: Into<RopeSlice<'h>>,
+    {
+        CaptureMatches { matches: self.find_iter(haystack) }
+    }
+
+    /// Returns the byte range of the first match of the regex in the
+    /// haystack, or `None` if there isn't one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::regex::Regex;
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("Hello, World!");
+    /// let re = Regex::new(r"[A-Z]\w*").unwrap();
+    ///
+    /// assert_eq!(re.find(&r), Some(0..5));
+    /// assert_eq!(re.find(r.byte_slice(1..)), Some(6..11));
+    /// ```
+    #[inline]
+    pub fn find<'h, H>(&self, haystack: H) -> Option<Range<usize>>
+    where
+        H: Into<RopeSlice<'h>>,
+    {
+        self.find_iter(haystack).next()
+    }
+
+    /// Returns an iterator over the byte ranges of the non-overlapping matches
+    /// of the regex in the haystack, from front to back.
+    ///
+    /// Like in the `regex` crate, an empty match that immediately follows the
+    /// previous match is skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::regex::Regex;
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("a1b22c333");
+    /// let re = Regex::new(r"\d+").unwrap();
+    ///
+    /// let matches = re.find_iter(&r).collect::<Vec<_>>();
+    /// assert_eq!(matches, [1..2, 3..5, 6..9]);
+    /// ```
+    #[inline]
+    pub fn find_iter<'r, 'h, H>(&'r self, haystack: H) -> Matches<'r, 'h>
+    where
+        H: Into<RopeSlice<'h>>,
+    {
+        Matches::new(self, haystack.into(), false)
+    }
+
+    /// Compiles a new `Regex` from the given pattern.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RegexError::Invalid`] if the pattern is not a valid regex,
+    /// and [`RegexError::Unsupported`] if it can't be searched with a lazy
+    /// DFA, e.g. because it contains a Unicode word boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::regex::{Regex, RegexError};
+    /// #
+    /// assert!(Regex::new(r"\w+").is_ok());
+    /// assert!(matches!(Regex::new(r"(foo"), Err(RegexError::Invalid(_))));
+    /// let err = Regex::new(r"\bfoo").unwrap_err();
+    /// assert!(matches!(err, RegexError::Unsupported(_)));
+    /// assert!(Regex::new(r"(?-u:\b)foo").is_ok());
+    /// ```
+    #[inline]
+    pub fn new(pattern: &str) -> Result<Self, RegexError> {
+        let meta = meta::Regex::new(pattern)
+            .map_err(|err| RegexError::Invalid(Box::new(err)))?;
+
+        let hybrid = hybrid::regex::Regex::new(pattern)
+            .map_err(|err| RegexError::Unsupported(Box::new(err)))?;
+
+        let forward = hybrid.forward().clone();
+        let reverse_all = hybrid.reverse().clone();
+
+        let reverse_first = DFA::builder()
+            .thompson(thompson::Config::new().reverse(true))
+            .build(pattern)
+            .map_err(|err| RegexError::Unsupported(Box::new(err)))?;
+
+        let pool = {
+            let forward = forward.clone();
+            let reverse_all = reverse_all.clone();
+            let reverse_first = reverse_first.clone();
+
+            let create: CachePoolFn = Box::new(move || Caches {
+                forward: DfaCache::new(&forward),
+                reverse_all: DfaCache::new(&reverse_all),
+                reverse_first: DfaCache::new(&reverse_first),
+            });
+
+            Pool::new(create)
+        };
+
+        Ok(Self {
+            pattern: pattern.into(),
+            forward,
+            reverse_all,
+            reverse_first,
+            meta,
+            pool,
+        })
+    }
+
+    /// Returns the capture groups of the last match of the regex in the
+    /// haystack, or `None` if there isn't one.
+    ///
+    /// See [`rfind()`](Self::rfind()) for which match is considered the last.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::regex::Regex;
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo=1, bar=2");
+    /// let re = Regex::new(r"(\w+)=(\d)").unwrap();
+    ///
+    /// let caps = re.rcaptures(&r).unwrap();
+    /// assert_eq!(caps.get(0), Some(7..12));
+    /// assert_eq!(caps.get(1), Some(7..10));
+    /// ```
+    #[inline]
+    pub fn rcaptures<'h, H>(&self, haystack: H) -> Option<Captures>
+    where
+        H: Into<RopeSlice<'h>>,
+    {
+        self.rcaptures_iter(haystack).next()
+    }
+
+    /// Returns an iterator over the capture groups of the non-overlapping
+    /// matches of the regex in the haystack, from back to front.
+    ///
+    /// See [`rfind_iter()`](Self::rfind_iter()) for which matches are
+    /// yielded.
+    #[inline]
+    pub fn rcaptures_iter<'r, 'h, H>(
+        &'r self,
+        haystack: H,
+    ) -> CaptureMatches<'r, 'h>
+    where
+        H: Into<RopeSlice<'h>>,
+    {
+        CaptureMatches { matches: self.rfind_iter(haystack) }
+    }
+
+    /// Returns the byte range of the last match of the regex in the haystack,
+    /// or `None` if there isn't one.
+    ///
+    /// This mirrors [`find()`](Self::find()): the last match is the one
+    /// ending at the greatest byte offset, and where it starts is chosen by
+    /// searching the reversed regex with leftmost-first semantics. Its end is
+    /// then chosen with the same leftmost-first semantics used by `find()`,
+    /// starting from there. If the matches of the regex can overlap, this can
+    /// differ from the last match yielded by
+    /// [`find_iter()`](Self::find_iter()).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::regex::Regex;
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("aaa bb");
+    ///
+    /// let re = Regex::new(r"\w+").unwrap();
+    /// assert_eq!(re.rfind(&r), Some(4..6));
+    ///
+    /// let re = Regex::new(r"aa").unwrap();
+    /// assert_eq!(re.find(&r), Some(0..2));
+    /// assert_eq!(re.rfind(&r), Some(1..3));
+    /// ```
+    #[inline]
+    pub fn rfind<'h, H>(&self, haystack: H) -> Option<Range<usize>>
+    where
+        H: Into<RopeSlice<'h>>,
+    {
+        self.rfind_iter(haystack).next()
+    }
+
+    /// Returns an iterator over the byte ranges of the non-overlapping matches
+    /// of the regex in the haystack, from back to front.
+    ///
+    /// Each match is the last one (as defined by [`rfind()`](Self::rfind()))
+    /// ending at or before the start of the previous one, and an empty match
+    /// that immediately precedes the previous match is skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::regex::Regex;
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("a1b22c333");
+    /// let re = Regex::new(r"\d+").unwrap();
+    ///
+    /// let matches = re.rfind_iter(&r).collect::<Vec<_>>();
+    /// assert_eq!(matches, [6..9, 3..5, 1..2]);
+    /// ```
+    #[inline]
+    pub fn rfind_iter<'r, 'h, H>(&'r self, haystack: H) -> Matches<'r, 'h>
+    where
+        H: Into<RopeSlice<'h>>,
+    {
+        Matches::new(self, haystack.into(), true)
+    }
+
+    /// Returns the capture groups of the match spanning the given byte range,
+    /// which must have been found by one of the lazy DFAs.
+    #[inline]
+    fn captures_of(
+        &self,
+        slice: RopeSlice<'_>,
+        range: Range<usize>,
+    ) -> Captures {
+        // Include a char on either side of the match so that look-around
+        // assertions like `(?m:^)` or `\b` see the same text they'd see on
+        // the whole slice.
+        let context_start = slice
+            .byte_slice(..range.start)
+            .chars()
+            .next_back()
+            .map_or(range.start, |ch| range.start - ch.len_utf8());
+
+        let context_end = slice
+            .byte_slice(range.end..)
+            .chars()
+            .next()
+            .map_or(range.end, |ch| range.end + ch.len_utf8());
+
+        let mut haystack = String::with_capacity(context_end - context_start);
+
+        for chunk in slice.byte_slice(context_start..context_end).chunks() {
+            haystack.push_str(chunk);
+        }
+
+        let input = Input::new(&haystack)
+            .span(range.start - context_start..range.end - context_start)
+            .anchored(Anchored::Yes);
+
+        let mut raw = self.meta.create_captures();
+        self.meta.search_captures(&input, &mut raw);
+
+        debug_assert_eq!(
+            raw.get_match().map(|m| m.range()),
+            Some(range.start - context_start..range.end - context_start)
+        );
+
+        Captures { raw, offset: context_start }
+    }
+
+    /// Returns the byte range of the first match starting at or after
+    /// `from`, which has to be a char boundary.
+    #[inline]
+    fn next_match(
+        &self,
+        caches: &mut Caches,
+        slice: RopeSlice<'_>,
+        from: usize,
+    ) -> Option<Range<usize>> {
+        let span = from..slice.byte_len();
+
+        let end = search_forward(
+            &self.forward,
+            &mut caches.forward,
+            slice,
+            span,
+            Anchored::No,
+        )?;
+
+        // Only empty matches can split a char, and those are skipped by the
+        // iterator.
+        if !slice.is_char_boundary(end) {
+            return Some(end..end);
+        }
+
+        let start = search_reverse(
+            &self.reverse_all,
+            &mut caches.reverse_all,
+            slice,
+            from..end,
+            Anchored::Yes,
+        )
+        .expect("the reverse DFA matches every match of the forward one");
+
+        Some(start..end)
+    }
+
+    /// Returns the byte range of the last match ending at or before `to`,
+    /// which has to be a char boundary.
+    ///
+    /// This mirrors `next_match()`: the reversed regex is searched backward
+    /// with leftmost-first semantics, which finds the start of the match with
+    /// the greatest end, and the end is then found by searching forward from
+    /// that start.
+    #[inline]
+    fn prev_match(
+        &self,
+        caches: &mut Caches,
+        slice: RopeSlice<'_>,
+        to: usize,
+    ) -> Option<Range<usize>> {
+        let start = search_reverse(
+            &self.reverse_first,
+            &mut caches.reverse_first,
+            slice,
+            0..to,
+            Anchored::No,
+        )?;
+
+        if !slice.is_char_boundary(start) {
+            return Some(start..start);
+        }
+
+        let end = search_forward(
+            &self.forward,
+            &mut caches.forward,
+            slice,
+            start..to,
+            Anchored::Yes,
+        )
+        .expect("the forward DFA matches every match of the reverse one");
+
+        Some(start..end)
+    }
+}
+
+impl core::fmt::Debug for Regex {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("Regex").field(&self.pattern).finish()
+    }
+}
+
+/// Runs the DFA forward over the given span of the slice, returning the end
+/// of the match found by the DFA, if any.
+#[inline]
+fn search_forward(
+    dfa: &DFA,
+    cache: &mut DfaCache,
+    slice: RopeSlice<'_>,
+    span: Range<usize>,
+    anchored: Anchored,
+) -> Option<usize> {
+    let look_behind = (span.start > 0).then(|| slice.byte(span.start - 1));
+
+    let config =
+        start::Config::new().anchored(anchored).look_behind(look_behind);
+
+    let mut state = dfa
+        .start_state(cache, &config)
+        .expect("no quit bytes and the cache never gives up");
+
+    let mut end = None;
+    let mut offset = span.start;
+
+    for chunk in slice.byte_slice(span.clone()).chunks() {
+        for &byte in chunk.as_bytes() {
+            state = next_state(dfa, cache, state, Some(byte));
+
+            // Matches are reported one byte late, so this match ends right
+            // before the byte we just fed to the DFA.
+            if state.is_match() {
+                end = Some(offset);
+            } else if state.is_dead() {
+                return end;
+            }
+
+            offset += 1;
+        }
+    }
+
+    // The look-ahead byte, if the span stops before the end of the slice.
+    let look_ahead =
+        (span.end < slice.byte_len()).then(|| slice.byte(span.end));
+
+    if next_state(dfa, cache, state, look_ahead).is_match() {
+        end = Some(span.end);
+    }
+
+    end
+}
+
+/// Runs the DFA backward over the given span of the slice, returning the start
+/// of the match found by the DFA, if any.
+#[inline]
+fn search_reverse(
+    dfa: &DFA,
+    cache: &mut DfaCache,
+    slice: RopeSlice<'_>,
+    span: Range<usize>,
+    anchored: Anchored,
+) -> Option<usize> {
+    let look_behind =
+        (span.end < slice.byte_len()).then(|| slice.byte(span.end));
+
+    let config =
+        start::Config::new().anchored(anchored).look_behind(look_behind);
+
+    let mut state = dfa
+        .start_state(cache, &config)
+        .expect("no quit bytes and the cache never gives up");
+
+    let mut start = None;
+    let mut offset = span.end;
+
+    for chunk in slice.byte_slice(span.clone()).chunks().rev() {
+        for &byte in chunk.as_bytes().iter().rev() {
+            state = next_state(dfa, cache, state, Some(byte));
+
+            // Matches are reported one byte late, so this match starts right
+            // after the byte we just fed to the DFA.
+            if state.is_match() {
+                start = Some(offset);
+            } else if state.is_dead() {
+                return start;
+            }
+
+            offset -= 1;
+        }
+    }
+
+    let look_ahead = (span.start > 0).then(|| slice.byte(span.start - 1));
+
+    if next_state(dfa, cache, state, look_ahead).is_match() {
+        start = Some(span.start);
+    }
+
+    start
+}
+
+/// Feeds the byte to the DFA, or the end-of-input marker if it's `None`.
+#[inline]
+fn next_state(
+    dfa: &DFA,
+    cache: &mut DfaCache,
+    state: LazyStateID,
+    byte: Option<u8>,
+) -> LazyStateID {
+    let next = match byte {
+        Some(byte) => dfa.next_state(cache, state, byte),
+        None => dfa.next_eoi_state(cache, state),
+    };
+
+    next.expect("the cache never gives up")
+}
+
+/// The capture groups of a match of a [`Regex`].
+///
+/// All the ranges are byte ranges relative to the start of the searched
+/// haystack.
+#[derive(Clone, Debug)]
+pub struct Captures {
+    raw: RawCaptures,
+
+    /// The byte offset of the start of the `raw` haystack in the searched
+    /// slice.
+    offset: usize,
+}
+
+impl Captures {
+    /// Expands all the `$name` and `${name}` references in the replacement
+    /// string with the text of the corresponding capture groups, appending
+    /// the result to `dst`.
+    ///
+    /// The haystack has to be the one these capture groups were found in.
+    /// The syntax is the same as the one used by the `regex` crate: names can
+    /// either be indices or the names of named groups, references to groups
+    /// that didn't participate in the match expand to the empty string, and
+    /// `$$` expands to a literal `$`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::regex::Regex;
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("width = 42");
+    /// let re = Regex::new(r"(?<key>\w+) = (\d+)").unwrap();
+    ///
+    /// let caps = re.captures(&r).unwrap();
+    ///
+    /// let mut dst = String::new();
+    /// caps.expand(&r, "$2 is the ${key}, $$", &mut dst);
+    ///
+    /// assert_eq!(dst, "42 is the width, $");
+    /// ```
+    #[inline]
+    pub fn expand<'h, H>(
+        &self,
+        haystack: H,
+        replacement: &str,
+        dst: &mut String,
+    ) where
+        H: Into<RopeSlice<'h>>,
+    {
+        let haystack = haystack.into();
+
+        interpolate::string(
+            replacement,
+            |index, dst| {
+                if let Some(range) = self.get(index) {
+                    for chunk in haystack.byte_slice(range).chunks() {
+                        dst.push_str(chunk);
+                    }
+                }
+            },
+            |name| {
+                let pattern = self.raw.pattern()?;
+                self.raw.group_info().to_index(pattern, name)
+            },
+            dst,
+        );
+    }
+
+    /// Returns the byte range of the capture group at the given index, or
+    /// `None` if the group didn't participate in the match or if the index is
+    /// out of bounds.
+    ///
+    /// The group at index 0 always corresponds to the whole match.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<Range<usize>> {
+        self.raw.get_group(index).map(|span| self.shift(span.range()))
+    }
+
+    /// Returns the number of capture groups of the regex, including the
+    /// implicit one spanning the whole match.
+    #[inline]
+    pub fn group_len(&self) -> usize {
+        self.raw.group_len()
+    }
+
+    /// Returns the byte range of the capture group with the given name, or
+    /// `None` if the group didn't participate in the match or if there's no
+    /// group with that name.
+    #[inline]
+    pub fn name(&self, name: &str) -> Option<Range<usize>> {
+        self.raw.get_group_by_name(name).map(|span| self.shift(span.range()))
+    }
+
+    #[inline]
+    fn shift(&self, range: Range<usize>) -> Range<usize> {
+        range.start + self.offset..range.end + self.offset
+    }
+}
+
+/// An iterator over the byte ranges of the non-overlapping matches of a
+/// [`Regex`] in a `Rope` or `RopeSlice`.
+///
+/// This struct is created by [`Regex::find_iter()`] and
+/// [`Regex::rfind_iter()`]. See their documentation for more.
+#[derive(Clone)]
+pub struct Matches<'r, 'h> {
+    regex: &'r Regex,
+    slice: RopeSlice<'h>,
+
+    /// Whether the matches are yielded from back to front.
+    is_reverse: bool,
+
+    /// The byte offset the next search starts at, or ends at if the
+    /// iterator is reversed. `None` once the iterator is exhausted.
+    offset: Option<usize>,
+
+    /// The end of the last match, or its start if the iterator is reversed.
+    last_boundary: Option<usize>,
+}
+
+impl<'r, 'h> Matches<'r, 'h> {
+    #[inline]
+    fn new(regex: &'r Regex, slice: RopeSlice<'h>, is_reverse: bool) -> Self {
+        Self {
+            regex,
+            is_reverse,
+            offset: Some(if is_reverse { slice.byte_len() } else { 0 }),
+            last_boundary: None,
+            slice,
+        }
+    }
+
+    /// Returns the closest char boundary after (or before, if the iterator
+    /// is reversed) the byte offset, if there is one.
+    #[inline]
+    fn step(&self, byte_offset: usize) -> Option<usize> {
+        if self.is_reverse {
+            let mut offset = byte_offset.checked_sub(1)?;
+            while !self.slice.is_char_boundary(offset) {
+                offset -= 1;
+            }
+            Some(offset)
+        } else {
+            let mut offset = byte_offset + 1;
+            if offset > self.slice.byte_len() {
+                return None;
+            }
+            while !self.slice.is_char_boundary(offset) {
+                offset += 1;
+            }
+            Some(offset)
+        }
+    }
+}
+
+impl Iterator for Matches<'_, '_> {
+    type Item = Range<usize>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let offset = self.offset?;
+
+            let mut caches = self.regex.pool.get();
+
+            let found = if self.is_reverse {
+                self.regex.prev_match(&mut caches, self.slice, offset)
+            } else {
+                self.regex.next_match(&mut caches, self.slice, offset)
+            };
+
+            let Some(range) = found else {
+                self.offset = None;
+                return None;
+            };
+
+            let (near, far) = if self.is_reverse {
+                (range.end, range.start)
+            } else {
+                (range.start, range.end)
+            };
+
+            // Skip empty matches that either touch the previous match or
+            // split a char in two.
+            if range.is_empty()
+                && (Some(near) == self.last_boundary
+                    || !self.slice.is_char_boundary(near))
+            {
+                self.offset = self.step(near);
+                continue;
+            }
+
+            self.offset = Some(far);
+            self.last_boundary = Some(far);
+            return Some(range);
+        }
+    }
+}
+
+impl core::iter::FusedIterator for Matches<'_, '_> {}
+
+impl core::fmt::Debug for Matches<'_, '_> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Matches")
+            .field("regex", &self.regex)
+            .field("is_reverse", &self.is_reverse)
+            .field("offset", &self.offset)
+            .finish()
+    }
+}
+
+/// An iterator over the [`Captures`] of the non-overlapping matches of a
+/// [`Regex`] in a `Rope` or `RopeSlice`.
+///
+/// This struct is created by [`Regex::captures_iter()`] and
+/// [`Regex::rcaptures_iter()`]. See their documentation for more.
+#[derive(Clone, Debug)]
+pub struct CaptureMatches<'r, 'h> {
+    matches: Matches<'r, 'h>,
+}
+
+impl Iterator for CaptureMatches<'_, '_> {
+    type Item = Captures;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let range = self.matches.next()?;
+        Some(self.matches.regex.captures_of(self.matches.slice, range))
+    }
+}
+
+impl core::iter::FusedIterator for CaptureMatches<'_, '_> {}
+
+/// The error returned by [`Regex::new()`].
+#[derive(Clone, Debug)]
+pub enum RegexError {
+    /// The pattern is not a valid regex, or it's too big.
+    Invalid(Box<meta::BuildError>),
+
+    /// The pattern is a valid regex, but it can't be searched with a lazy
+    /// DFA (e.g. because it contains a Unicode word boundary).
+    Unsupported(Box<hybrid::BuildError>),
+}
+
+impl core::fmt::Display for RegexError {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Invalid(err) => write!(f, "invalid regex: {err}"),
+
+            Self::Unsupported(err) => write!(
+                f,
+                "the regex can't be searched over a rope's chunks: {err}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RegexError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Invalid(err) => Some(&**err),
+            Self::Unsupported(err) => Some(&**err),
+        }
+    }
+}
@@ -1,11 +1,34 @@
 use alloc::string::String;
-use core::ops::RangeBounds;
+use alloc::vec::Vec;
+use core::ops::{Range, RangeBounds};
 
+#[cfg(feature = "unicode-width")]
+use super::display_width;
 use super::gap_buffer::GapBuffer;
-use super::iterators::{Bytes, Chars, Chunks, Lines, RawLines};
+#[cfg(feature = "grapheme-metric")]
+use super::grapheme_runs::{GraphemeRuns, GraphemeTree};
+use super::iterators::{Bytes, Chars, Chunks, Lines, Paragraphs, RawLines};
+use super::line_ending::LineEndings;
+#[cfg(feature = "lsp")]
+use super::lsp::{self, ContentChange, LspError, TextEdit};
 use super::metrics::{ByteMetric, RawLineMetric};
+use super::point::{byte_of_column, len_in};
+use super::search::{self, Matches, Needle};
 use super::utils::{panic_messages as panic, *};
-use super::RopeSlice;
+use super::visual_column;
+#[cfg(feature = "words")]
+use super::words;
+use super::{
+    DetectedLineEnding,
+    LineEnding,
+    Pattern,
+    Point,
+    PositionEncoding,
+    RopeCursor,
+    RopeError,
+    RopeSlice,
+    Unit,
+};
 use crate::range_bounds_to_start_end;
 use crate::tree::Tree;
 
@@ -32,15 +55,370 @@ pub(super) type RopeChunk = GapBuffer<CHUNK_MAX_BYTES>;
 pub struct Rope {
     pub(super) tree: Tree<{ Self::arity() }, RopeChunk>,
     pub(super) has_trailing_newline: bool,
+    #[cfg(feature = "grapheme-metric")]
+    pub(super) grapheme_tree: GraphemeTree,
 }
 
 impl Rope {
+    /// Applies the changes of a `textDocument/didChange` notification sent by
+    /// a Language Server Protocol client.
+    ///
+    /// The changes are applied in order, so the range of each change is
+    /// interpreted in the document resulting from all the previous ones. A
+    /// change without a range replaces the whole document. The columns of the
+    /// ranges are measured in the given [`PositionEncoding`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`LspError`] if the range of any change is invalid, in
+    /// which case the `Rope` is left unchanged, even if some of the changes
+    /// before the invalid one were valid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::lsp::{ContentChange, LspError, Range};
+    /// # use crop::{Point, PositionEncoding, Rope};
+    /// #
+    /// let mut r = Rope::from("fn main() {}\n");
+    ///
+    /// let changes = [
+    ///     ContentChange {
+    ///         range: Some(Range::new(Point::new(0, 11), Point::new(0, 11))),
+    ///         text: "\n    println!(\"🦀\");\n",
+    ///     },
+    ///     ContentChange {
+    ///         range: Some(Range::new(Point::new(1, 14), Point::new(1, 16))),
+    ///         text: "🐸",
+    ///     },
+    /// ];
+    ///
+    /// assert_eq!(
+    ///     r.apply_content_changes(changes, PositionEncoding::Utf16),
+    ///     Ok(())
+    /// );
+    /// assert_eq!(r, "fn main() {\n    println!(\"🐸\");\n}\n");
+    ///
+    /// let invalid = ContentChange {
+    ///     range: Some(Range::new(Point::new(5, 0), Point::new(5, 0))),
+    ///     text: "",
+    /// };
+    ///
+    /// assert_eq!(
+    ///     r.apply_content_changes([invalid], PositionEncoding::Utf16),
+    ///     Err(LspError::LineOutOfBounds { index: 0, line: 5, line_len: 3 })
+    /// );
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "lsp")))]
+    #[cfg(feature = "lsp")]
+    #[inline]
+    pub fn apply_content_changes<'a, I>(
+        &mut self,
+        changes: I,
+        encoding: PositionEncoding,
+    ) -> Result<(), LspError>
+    where
+        I: IntoIterator<Item = ContentChange<'a>>,
+    {
+        // Cloning a `Rope` is cheap, and working on the clone means we don't
+        // have to undo the previous changes if one of them is invalid.
+        let mut rope = self.clone();
+
+        for (index, change) in changes.into_iter().enumerate() {
+            match change.range {
+                Some(range) => {
+                    let range =
+                        lsp::byte_range(&rope, range, encoding, index)?;
+                    rope.replace(range, change.text);
+                },
+
+                None => rope = Self::from(change.text),
+            }
+        }
+
+        *self = rope;
+
+        Ok(())
+    }
+
+    /// Applies a batch of non-overlapping edits to the `Rope`, where each edit
+    /// replaces a byte range with a string.
+    ///
+    /// All the ranges are interpreted as byte offsets in the `Rope` *before*
+    /// any of the edits are applied, so there's no need to shift them to
+    /// account for the previous edits. The edits can be given in any order,
+    /// and insertions at the same offset end up in the order they're yielded
+    /// in.
+    ///
+    /// The edits are validated before any of them is applied, so if this
+    /// panics the `Rope` is left unchanged.
+    ///
+    /// The edits falling in the same leaves are applied together, so every
+    /// leaf is rebuilt at most once and the subtrees without edits remain
+    /// shared with any clone of the `Rope`. The cost is still that of one
+    /// [`replace()`](Self::replace()) per group of such edits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any two ranges overlap, or for any range for which
+    /// [`replace()`](Self::replace()) would panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let mut r = Rope::from("foo(bar, baz)");
+    ///
+    /// r.apply_edits([(9..12, "qux"), (0..3, "fun"), (4..7, "quux")]);
+    ///
+    /// assert_eq!(r, "fun(quux, qux)");
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn apply_edits<'a, I>(&mut self, edits: I)
+    where
+        I: IntoIterator<Item = (Range<usize>, &'a str)>,
+    {
+        let mut edits = edits.into_iter().collect::<Vec<_>>();
+
+        for (range, _) in &edits {
+            // This panics if the range is invalid.
+            let _ = self.byte_slice(range.clone());
+        }
+
+        if let Some(idx) = sort_edits(&mut edits) {
+            panic::overlapping_edits(
+                edits[idx - 1].0.clone(),
+                edits[idx].0.clone(),
+            );
+        }
+
+        self.replace_sorted(&edits);
+    }
+
+    /// Replaces a sequence of sorted and non-overlapping byte ranges, all
+    /// interpreted as offsets in the `Rope` before any of them is replaced.
+    ///
+    /// All the edits starting in the same leaves are combined into a single
+    /// [`replace()`](Self::replace()), so every leaf is rebuilt at most once
+    /// and all the subtrees without edits remain shared with any clone of the
+    /// `Rope`. Each group of edits still descends the tree on its own, so
+    /// `k` groups take `O(k log n)` time.
+    #[inline]
+    fn replace_sorted<T>(&mut self, edits: &[(Range<usize>, T)])
+    where
+        T: AsRef<str>,
+    {
+        // Returns the byte offset of the end of the leaf containing the given
+        // byte offset.
+        let leaf_end = |rope: &Self, byte_offset: usize| {
+            let (leaf, ByteMetric(leaf_start)) =
+                rope.tree.leaf_at_measure(ByteMetric(byte_offset));
+
+            leaf_start + leaf.len()
+        };
+
+        let mut buffer = String::new();
+
+        // The number of bytes added and removed by the previous replacements,
+        // used to map the byte offsets of the edits to the current `Rope`.
+        let (mut added, mut removed) = (0, 0);
+
+        let mut edits = edits.iter().peekable();
+
+        while let Some((range, text)) = edits.next() {
+            let start = range.start + added - removed;
+
+            let mut end = range.end + added - removed;
+
+            let mut group_end = leaf_end(self, end);
+
+            buffer.clear();
+            buffer.push_str(text.as_ref());
+
+            while let Some((range, text)) = edits.next_if(|(range, _)| {
+                range.start + added - removed <= group_end
+            }) {
+                buffer.extend(
+                    self.byte_slice(end..range.start + added - removed)
+                        .chunks(),
+                );
+                buffer.push_str(text.as_ref());
+                end = range.end + added - removed;
+                group_end = group_end.max(leaf_end(self, end));
+            }
+
+            self.replace(start..end, &buffer);
+
+            added += buffer.len();
+            removed += end - start;
+        }
+    }
+
+    /// Applies a list of `TextEdit`s sent by a Language Server Protocol
+    /// client or server.
+    ///
+    /// As required by the LSP specification the edits are applied as a
+    /// simultaneous set, i.e. all the ranges refer to the document before any
+    /// of the edits are applied, and insertions at the same position end up in
+    /// the order they're given in. The columns of the ranges are measured in
+    /// the given [`PositionEncoding`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`LspError`] if the range of any edit is invalid or if any
+    /// two ranges overlap, in which case the `Rope` is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::lsp::{LspError, Range, TextEdit};
+    /// # use crop::{Point, PositionEncoding, Rope};
+    /// #
+    /// let mut r = Rope::from("let 🐸 = foo(🐸);");
+    ///
+    /// let rename = |start, end| TextEdit {
+    ///     range: Range::new(Point::new(0, start), Point::new(0, end)),
+    ///     new_text: "frog",
+    /// };
+    ///
+    /// assert_eq!(
+    ///     r.apply_text_edits(
+    ///         [rename(13, 15), rename(4, 6)],
+    ///         PositionEncoding::Utf16
+    ///     ),
+    ///     Ok(())
+    /// );
+    /// assert_eq!(r, "let frog = foo(frog);");
+    ///
+    /// assert_eq!(
+    ///     r.apply_text_edits(
+    ///         [rename(0, 8), rename(4, 10)],
+    ///         PositionEncoding::Utf16
+    ///     ),
+    ///     Err(LspError::OverlappingEdits { first: 0, second: 1 })
+    /// );
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "lsp")))]
+    #[cfg(feature = "lsp")]
+    #[inline]
+    pub fn apply_text_edits<'a, I>(
+        &mut self,
+        edits: I,
+        encoding: PositionEncoding,
+    ) -> Result<(), LspError>
+    where
+        I: IntoIterator<Item = TextEdit<'a>>,
+    {
+        let mut edits = edits
+            .into_iter()
+            .enumerate()
+            .map(|(index, edit)| {
+                lsp::byte_range(self, edit.range, encoding, index)
+                    .map(|range| (range, (index, edit.new_text)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if let Some(idx) = sort_edits(&mut edits) {
+            let (_, (first, _)) = edits[idx - 1];
+            let (_, (second, _)) = edits[idx];
+            return Err(LspError::OverlappingEdits { first, second });
+        }
+
+        // The edits are now valid, so this can't panic.
+        self.apply_edits(
+            edits.into_iter().map(|(range, (_, text))| (range, text)),
+        );
+
+        Ok(())
+    }
+
+    /// Appends another `Rope` to the end of this one.
+    ///
+    /// This runs in logarithmic time, and all the chunks of `other` except
+    /// the ones next to the seam are shared with it instead of being copied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let mut r = Rope::from("Hello ");
+    /// r.append(Rope::from("Earth 🌎!\n"));
+    ///
+    /// assert_eq!(r, "Hello Earth 🌎!\n");
+    /// assert_eq!(r.line_len(), 1);
+    /// ```
+    #[inline]
+    pub fn append(&mut self, other: Self) {
+        if other.is_empty() {
+            return;
+        }
+
+        #[cfg(any(feature = "grapheme-metric", feature = "unicode-lines"))]
+        let seam = self.byte_len();
+
+        self.has_trailing_newline = other.has_trailing_newline;
+
+        self.tree.append(other.tree);
+
+        #[cfg(feature = "unicode-lines")]
+        self.join_split_crlf(seam);
+
+        // The graphemes on the two sides of the seam could be joined into a
+        // single one, e.g. a "\r" followed by a "\n".
+        #[cfg(feature = "grapheme-metric")]
+        {
+            self.grapheme_tree.append(other.grapheme_tree);
+            self.update_grapheme_tree(seam, seam, 0);
+        }
+    }
+
+    /// Checks that the lengths stored in the grapheme tree match the ones
+    /// obtained by segmenting the whole text from scratch.
+    ///
+    /// This runs in linear time in the length of the `Rope`, so tests should
+    /// call it once they're done editing instead of after every edit.
+    #[doc(hidden)]
+    pub fn assert_grapheme_invariants(&self) {
+        self.assert_invariants();
+
+        #[cfg(feature = "grapheme-metric")]
+        {
+            use alloc::string::ToString;
+
+            use unicode_segmentation::UnicodeSegmentation;
+
+            use super::grapheme_runs::grapheme_lens;
+
+            // Not using `self.graphemes()` because it can get sequences of
+            // regional indicators spanning several chunks wrong.
+            let text = self.to_string();
+
+            assert!(grapheme_lens(&self.grapheme_tree)
+                .eq(text.graphemes(true).map(str::len)));
+        }
+    }
+
     #[doc(hidden)]
     pub fn assert_invariants(&self) {
         self.tree.assert_invariants();
 
+        // Checking the grapheme lengths means segmenting the whole text, so
+        // that's left to `assert_grapheme_invariants()`.
+        #[cfg(feature = "grapheme-metric")]
+        {
+            self.grapheme_tree.assert_invariants();
+
+            assert_eq!(
+                self.grapheme_tree.base_measure(),
+                ByteMetric(self.byte_len())
+            );
+        }
+
         if let Some(last) = self.chunks().next_back() {
-            assert_eq!(self.has_trailing_newline, last.ends_with('\n'));
+            assert_eq!(self.has_trailing_newline, ends_with_line_break(last));
         } else {
             return;
         }
@@ -51,7 +429,24 @@ impl Rope {
             return;
         }
 
+        let mut previous_chunk = "";
+
         for chunk in leaves {
+            let first_chunk = if chunk.len_left() > 0 {
+                chunk.left_chunk()
+            } else {
+                chunk.right_chunk()
+            };
+
+            assert!(
+                !splits_crlf(previous_chunk, first_chunk),
+                "A CRLF pair is split between the chunks {:?} and {:?}",
+                previous_chunk,
+                first_chunk
+            );
+
+            previous_chunk = chunk.last_chunk();
+
             assert!(
                 chunk.len() >= RopeChunk::chunk_min(),
                 "The chunk {:?} was supposed to contain at least {} bytes \
@@ -112,6 +507,117 @@ impl Rope {
         self.tree.summary().bytes()
     }
 
+    /// Returns the byte offset corresponding to the given char offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the char offset is out of bounds (i.e. greater than
+    /// [`char_len()`](Self::char_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// // The "🐸" emoji is encoded using four bytes in UTF-8.
+    /// let r = Rope::from("a🐸b");
+    /// assert_eq!(r.byte_of_char(2), 5);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "char-metric")))]
+    #[cfg(feature = "char-metric")]
+    #[track_caller]
+    #[inline]
+    pub fn byte_of_char(&self, char_offset: usize) -> usize {
+        if char_offset > self.char_len() {
+            panic::char_offset_out_of_bounds(char_offset, self.char_len())
+        }
+
+        let ByteMetric(byte_offset) =
+            self.tree.convert_measure(super::metrics::CharMetric(char_offset));
+
+        byte_offset
+    }
+
+    /// Returns the byte offset of the char displayed at the given column of
+    /// the line at `line_index`, where the columns are the ones the line
+    /// takes up in a terminal (see [`display_width()`](Self::display_width())
+    /// for how they're counted).
+    ///
+    /// If the column falls inside a double-width char the offset of the start
+    /// of that char is returned. Zero-width chars are attributed to the char
+    /// before them, and a column equal to the width of the line maps to its
+    /// end.
+    ///
+    /// This runs in logarithmic time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the line index is out of bounds (i.e. greater than or equal
+    /// to [`line_len()`](Self::line_len())) or if the column is greater than
+    /// the line's [`display_width()`](Self::display_width()).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// // "日" takes up two columns and is encoded using three bytes in UTF-8.
+    /// let r = Rope::from("foo\na日b\n");
+    ///
+    /// assert_eq!(r.byte_of_display_column(1, 1), 5);
+    /// assert_eq!(r.byte_of_display_column(1, 2), 5);
+    /// assert_eq!(r.byte_of_display_column(1, 3), 8);
+    /// assert_eq!(r.byte_of_display_column(1, 4), 9);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "unicode-width")))]
+    #[cfg(feature = "unicode-width")]
+    #[track_caller]
+    #[inline]
+    pub fn byte_of_display_column(
+        &self,
+        line_index: usize,
+        column: usize,
+    ) -> usize {
+        self.byte_of_line(line_index)
+            + display_width::byte_of_column(self.line(line_index), column)
+    }
+
+    /// Returns the byte offset corresponding to the given grapheme offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the grapheme offset is out of bounds (i.e. greater than
+    /// [`grapheme_len()`](Self::grapheme_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// // "🇷🇸" is a single grapheme made up of two chars, both encoded using
+    /// // four bytes in UTF-8.
+    /// let r = Rope::from("a🇷🇸b");
+    /// assert_eq!(r.byte_of_grapheme(2), 9);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "grapheme-metric")))]
+    #[cfg(feature = "grapheme-metric")]
+    #[track_caller]
+    #[inline]
+    pub fn byte_of_grapheme(&self, grapheme_offset: usize) -> usize {
+        if grapheme_offset > self.grapheme_len() {
+            panic::grapheme_offset_out_of_bounds(
+                grapheme_offset,
+                self.grapheme_len(),
+            )
+        }
+
+        let ByteMetric(byte_offset) = self.grapheme_tree.convert_measure(
+            super::grapheme_runs::GraphemeMetric(grapheme_offset),
+        );
+
+        byte_offset
+    }
+
     /// Returns the byte offset of the start of the given line.
     ///
     /// # Panics
@@ -148,6 +654,63 @@ impl Rope {
         byte_offset
     }
 
+    /// Returns the byte offset corresponding to the given [`Point`], whose
+    /// column is measured in the given [`PositionEncoding`].
+    ///
+    /// Columns past the end of the line (not counting its line terminator)
+    /// are clamped to the end of the line, and columns inside a character
+    /// (e.g. between the two code units of a surrogate pair) are rounded down
+    /// to the start of that character.
+    ///
+    /// This runs in logarithmic time if the metric of the encoding is
+    /// enabled (`utf16-metric` for UTF-16, `char-metric` for UTF-32), and in
+    /// time linear in the length of the line otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the line of the point is out of bounds (i.e. greater than
+    /// [`line_len()`](Self::line_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Point, PositionEncoding, Rope};
+    /// #
+    /// let r = Rope::from("foo\nb🐸r\n");
+    ///
+    /// let utf8 = PositionEncoding::Utf8;
+    /// let utf16 = PositionEncoding::Utf16;
+    ///
+    /// assert_eq!(r.byte_of_point(Point::new(1, 5), utf8), 9);
+    /// assert_eq!(r.byte_of_point(Point::new(1, 3), utf16), 9);
+    ///
+    /// // Columns past the end of the line are clamped.
+    /// assert_eq!(r.byte_of_point(Point::new(0, 42), utf16), 3);
+    ///
+    /// // Columns inside a character are rounded down.
+    /// assert_eq!(r.byte_of_point(Point::new(1, 2), utf16), 5);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn byte_of_point(
+        &self,
+        point: Point,
+        encoding: PositionEncoding,
+    ) -> usize {
+        if point.line > self.line_len() {
+            panic::line_offset_out_of_bounds(point.line, self.line_len());
+        }
+
+        let line_start = self.byte_of_line(point.line);
+
+        if point.line == self.line_len() {
+            return line_start;
+        }
+
+        line_start
+            + byte_of_column(self.line(point.line), point.column, encoding)
+    }
+
     /// Returns the byte offset corresponding to the given UTF-16 code unit
     /// offset.
     ///
@@ -183,6 +746,52 @@ impl Rope {
         byte_offset
     }
 
+    /// Returns the byte offset of the unit displayed at the given visual
+    /// column of the line at `line_index`, where a tab advances the column to
+    /// the next multiple of `tab_width`.
+    ///
+    /// A unit is a grapheme if the `graphemes` feature is enabled and a char
+    /// otherwise (see
+    /// [`visual_column_of_byte()`](Self::visual_column_of_byte()) for how
+    /// they're counted). If the column falls inside a tab or a wide unit the
+    /// offset of its start is returned. Columns past the end of the line are
+    /// clamped to its length, which is handy when moving the cursor between
+    /// lines.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the line index is out of bounds (i.e. greater than or equal
+    /// to [`line_len()`](Self::line_len())) or if `tab_width` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo\n\tbar\tbaz\n");
+    ///
+    /// assert_eq!(r.byte_of_visual_column(1, 0, 4), 4);
+    /// assert_eq!(r.byte_of_visual_column(1, 2, 4), 4);
+    /// assert_eq!(r.byte_of_visual_column(1, 4, 4), 5);
+    /// assert_eq!(r.byte_of_visual_column(1, 8, 4), 9);
+    /// assert_eq!(r.byte_of_visual_column(1, 100, 4), 12);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn byte_of_visual_column(
+        &self,
+        line_index: usize,
+        column: usize,
+        tab_width: usize,
+    ) -> usize {
+        self.byte_of_line(line_index)
+            + visual_column::byte_of_column(
+                self.line(line_index),
+                column,
+                tab_width,
+            )
+    }
+
     /// Returns an immutable slice of the `Rope` in the specified byte range,
     /// where the start and end of the range are interpreted as offsets.
     ///
@@ -220,7 +829,16 @@ impl Rope {
             panic::byte_offset_out_of_bounds(end, self.byte_len());
         }
 
-        self.tree.slice(ByteMetric(start)..ByteMetric(end)).into()
+        let slice: RopeSlice<'_> =
+            self.tree.slice(ByteMetric(start)..ByteMetric(end)).into();
+
+        #[cfg(feature = "grapheme-metric")]
+        let slice = RopeSlice {
+            graphemes: Some((&self.grapheme_tree, start)),
+            ..slice
+        };
+
+        slice
     }
 
     /// Returns an iterator over the bytes of this `Rope`.
@@ -244,99 +862,239 @@ impl Rope {
         Bytes::from(self)
     }
 
-    /// Returns an iterator over the [`char`]s of this `Rope`.
+    /// Returns an iterator over the bytes of this `Rope` starting at the given
+    /// byte offset.
+    ///
+    /// The iterator yields the bytes after the offset from both of its ends,
+    /// while the ones before it can be yielded in reverse order by calling
+    /// [`Bytes::prev()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())) or if it doesn't lie on a char
+    /// boundary.
     ///
     /// # Examples
     ///
     /// ```
     /// # use crop::Rope;
     /// #
-    /// let r = Rope::from("🐻‍❄️");
+    /// let r = Rope::from("foo\nbar\nbaz\n");
     ///
-    /// let mut chars = r.chars();
+    /// let mut bytes = r.bytes_at(5);
     ///
-    /// assert_eq!(Some('🐻'), chars.next());
-    /// assert_eq!(Some('\u{200d}'), chars.next());
-    /// assert_eq!(Some('❄'), chars.next());
-    /// assert_eq!(Some('\u{fe0f}'), chars.next());
-    /// assert_eq!(None, chars.next());
+    /// assert_eq!(bytes.len(), 7);
+    /// assert_eq!(Some(b'a'), bytes.next());
+    /// assert_eq!(Some(b'\n'), bytes.next_back());
+    /// assert_eq!(Some(b'b'), bytes.prev());
+    /// assert_eq!(Some(b'\n'), bytes.prev());
     /// ```
+    #[track_caller]
     #[inline]
-    pub fn chars(&self) -> Chars<'_> {
-        Chars::from(self)
+    pub fn bytes_at(&self, byte_offset: usize) -> Bytes<'_> {
+        Bytes::at(&self.byte_slice(..), byte_offset)
     }
 
-    /// Returns an iterator over the chunks of this [`Rope`].
+    /// Returns the number of [`char`]s in the `Rope`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// // The "🐸" emoji is a single char but it's encoded using four
+    /// // bytes in UTF-8.
+    /// let r = Rope::from("abc🐸");
+    /// assert_eq!(r.char_len(), 4);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "char-metric")))]
+    #[cfg(feature = "char-metric")]
     #[inline]
-    pub fn chunks(&self) -> Chunks<'_> {
-        Chunks::from(self)
+    pub fn char_len(&self) -> usize {
+        self.tree.summary().chars()
     }
 
-    /// Deletes the contents of the `Rope` within the specified byte range,
-    /// where the start and end of the range are interpreted as offsets.
+    /// Returns the char offset corresponding to the given byte offset.
     ///
     /// # Panics
     ///
-    /// Panics if the start or the end of the byte range don't lie on a code
-    /// point boundary, if the start is greater than the end or if the end is
-    /// out of bounds (i.e. greater than [`byte_len()`](Self::byte_len())).
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())) or if it doesn't lie on a code point
+    /// boundary.
     ///
     /// # Examples
     ///
     /// ```
     /// # use crop::Rope;
     /// #
-    /// let mut r = Rope::from("Hello Earth 🌎!");
+    /// // The "🐸" emoji is encoded using four bytes in UTF-8.
+    /// let r = Rope::from("a🐸b");
+    /// assert_eq!(r.char_of_byte(5), 2);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "char-metric")))]
+    #[cfg(feature = "char-metric")]
+    #[track_caller]
+    #[inline]
+    pub fn char_of_byte(&self, byte_offset: usize) -> usize {
+        if byte_offset > self.byte_len() {
+            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
+        }
+
+        let super::metrics::CharMetric(char_offset) =
+            self.tree.convert_measure(ByteMetric(byte_offset));
+
+        char_offset
+    }
+
+    /// Returns an immutable slice of the `Rope` in the specified char range,
+    /// where the start and end of the range are interpreted as offsets.
     ///
-    /// r.delete(5..16);
-    /// assert_eq!(r, "Hello!");
+    /// # Panics
+    ///
+    /// Panics if the start is greater than the end or if the end is out of
+    /// bounds (i.e. greater than [`char_len()`](Self::char_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("🗻∈🌏");
+    ///
+    /// assert_eq!(r.char_slice(..1), "🗻");
+    /// assert_eq!(r.char_slice(1..2), "∈");
+    /// assert_eq!(r.char_slice(2..), "🌏");
     /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "char-metric")))]
+    #[cfg(feature = "char-metric")]
     #[track_caller]
     #[inline]
-    pub fn delete<R>(&mut self, byte_range: R)
+    pub fn char_slice<R>(&self, char_range: R) -> RopeSlice<'_>
     where
         R: RangeBounds<usize>,
     {
-        self.replace(byte_range, "");
+        use super::metrics::CharMetric;
+
+        let (start, end) =
+            range_bounds_to_start_end(char_range, 0, self.char_len());
+
+        if start > end {
+            panic::char_start_after_end(start, end);
+        }
+
+        if end > self.char_len() {
+            panic::char_offset_out_of_bounds(end, self.char_len());
+        }
+
+        self.tree.slice(CharMetric(start)..CharMetric(end)).into()
     }
 
-    pub(super) const fn arity() -> usize {
-        ARITY
+    /// Returns an iterator over the [`char`]s of this `Rope`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("🐻‍❄️");
+    ///
+    /// let mut chars = r.chars();
+    ///
+    /// assert_eq!(Some('🐻'), chars.next());
+    /// assert_eq!(Some('\u{200d}'), chars.next());
+    /// assert_eq!(Some('❄'), chars.next());
+    /// assert_eq!(Some('\u{fe0f}'), chars.next());
+    /// assert_eq!(None, chars.next());
+    /// ```
+    #[inline]
+    pub fn chars(&self) -> Chars<'_> {
+        Chars::from(self)
     }
 
-    /// Returns an iterator over the extended grapheme clusters of this
-    /// `Rope`.
+    /// Returns an iterator over the [`char`]s of this `Rope` starting at the
+    /// given byte offset.
+    ///
+    /// The iterator yields the chars after the offset from both of its ends,
+    /// while the ones before it can be yielded in reverse order by calling
+    /// [`Chars::prev()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())) or if it doesn't lie on a char
+    /// boundary.
     ///
     /// # Examples
     ///
-    /// ```no_run
+    /// ```
     /// # use crop::Rope;
     /// #
-    /// let r = Rope::from("arg!\r\n🐻‍❄️");
+    /// let r = Rope::from("foo\nbar\nbaz\n");
     ///
-    /// let mut graphemes = r.graphemes();
+    /// let mut chars = r.chars_at(4);
     ///
-    /// assert_eq!(Some("a"), graphemes.next().as_deref());
-    /// assert_eq!(Some("r"), graphemes.next().as_deref());
-    /// assert_eq!(Some("g"), graphemes.next().as_deref());
-    /// assert_eq!(Some("!"), graphemes.next().as_deref());
-    /// assert_eq!(Some("\r\n"), graphemes.next().as_deref());
-    /// assert_eq!(Some("🐻‍❄️"), graphemes.next().as_deref());
-    /// assert_eq!(None, graphemes.next());
+    /// assert_eq!("bar\nbaz\n", chars.clone().collect::<String>());
+    /// assert_eq!("\nzab\nrab", chars.clone().rev().collect::<String>());
+    /// assert_eq!(Some('\n'), chars.prev());
+    /// assert_eq!(Some('o'), chars.prev());
     /// ```
-    #[cfg_attr(docsrs, doc(cfg(feature = "graphemes")))]
-    #[cfg(feature = "graphemes")]
+    #[track_caller]
     #[inline]
-    pub fn graphemes(&self) -> crate::iter::Graphemes<'_> {
-        crate::iter::Graphemes::from(self)
+    pub fn chars_at(&self, byte_offset: usize) -> Chars<'_> {
+        Chars::at(&self.byte_slice(..), byte_offset)
     }
 
-    /// Inserts `text` in the `Rope` at the given byte offset.
+    /// Returns an iterator over the chunks of this [`Rope`].
+    #[inline]
+    pub fn chunks(&self) -> Chunks<'_> {
+        Chunks::from(self)
+    }
+
+    /// Returns an iterator over the chunks of this `Rope` starting at the
+    /// given byte offset.
+    ///
+    /// The iterator yields the chunks after the offset from both of its ends,
+    /// the first one starting exactly at the offset, while the ones before it
+    /// can be yielded in reverse order by calling [`Chunks::prev()`], the
+    /// first one ending exactly at the offset.
     ///
     /// # Panics
     ///
-    /// Panics if the byte offset doesn't lie on a code point boundary or if
-    /// it's out of bounds (i.e. greater than
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())) or if it doesn't lie on a char
+    /// boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo\nbar\nbaz\n");
+    ///
+    /// let mut chunks = r.chunks_at(6);
+    ///
+    /// assert_eq!("r\nbaz\n", chunks.clone().collect::<String>());
+    ///
+    /// let mut before = Vec::new();
+    /// while let Some(chunk) = chunks.prev() {
+    ///     before.insert(0, chunk);
+    /// }
+    /// assert_eq!("foo\nba", before.concat());
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn chunks_at(&self, byte_offset: usize) -> Chunks<'_> {
+        Chunks::at(&self.byte_slice(..), byte_offset)
+    }
+
+    /// Returns a [`RopeCursor`] placed at the given byte offset.
+    ///
+    /// This runs in logarithmic time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than
     /// [`byte_len()`](Self::byte_len())).
     ///
     /// # Examples
@@ -344,72 +1102,234 @@ impl Rope {
     /// ```
     /// # use crop::Rope;
     /// #
-    /// let mut r = Rope::from("Hello Earth!");
+    /// let r = Rope::from("Hello, World!");
+    /// let mut cursor = r.cursor_at(7);
     ///
-    /// r.insert(11, " 🌎");
-    /// assert_eq!(r, "Hello Earth 🌎!");
+    /// assert_eq!(cursor.next_char(), Some('W'));
+    /// assert_eq!(cursor.prev_byte(), Some(b'W'));
+    /// assert_eq!(cursor.prev_byte(), Some(b' '));
     /// ```
     #[track_caller]
     #[inline]
-    pub fn insert<T>(&mut self, byte_offset: usize, text: T)
+    pub fn cursor_at(&self, byte_offset: usize) -> RopeCursor<'_> {
+        RopeCursor::new(self.byte_slice(..), byte_offset)
+    }
+
+    /// Deletes the contents of the `Rope` within the specified byte range,
+    /// where the start and end of the range are interpreted as offsets.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start or the end of the byte range don't lie on a code
+    /// point boundary, if the start is greater than the end or if the end is
+    /// out of bounds (i.e. greater than [`byte_len()`](Self::byte_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let mut r = Rope::from("Hello Earth 🌎!");
+    ///
+    /// r.delete(5..16);
+    /// assert_eq!(r, "Hello!");
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn delete<R>(&mut self, byte_range: R)
     where
-        T: AsRef<str>,
+        R: RangeBounds<usize>,
     {
-        self.replace(byte_offset..byte_offset, text)
+        self.replace(byte_range, "");
     }
 
-    /// Returns `true` if the given byte offset lies on a [`char`] boundary.
+    /// Returns the kinds of line endings used in the `Rope`, together with
+    /// how many of each there are.
+    ///
+    /// A `\r` immediately followed by a `\n` counts as a single CRLF.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{DetectedLineEnding, Rope};
+    /// #
+    /// let r = Rope::from("foo\nbar\n");
+    /// assert_eq!(r.detect_line_ending(), DetectedLineEnding::Lf(2));
+    ///
+    /// let r = Rope::from("foo\r\nbar\rbaz\r\n");
+    /// assert_eq!(
+    ///     r.detect_line_ending(),
+    ///     DetectedLineEnding::Mixed { lf: 0, crlf: 2, cr: 1 }
+    /// );
+    /// ```
+    #[inline]
+    pub fn detect_line_ending(&self) -> DetectedLineEnding {
+        let (mut lf, mut crlf, mut cr) = (0, 0, 0);
+
+        for (_, line_ending) in LineEndings::new(self.chunks()) {
+            match line_ending {
+                LineEnding::Lf => lf += 1,
+                LineEnding::CrLf => crlf += 1,
+                LineEnding::Cr => cr += 1,
+            }
+        }
+
+        DetectedLineEnding::from_counts(lf, crlf, cr)
+    }
+
+    /// Returns the column of the given byte offset within its line, where the
+    /// columns are the ones the text takes up in a terminal (see
+    /// [`display_width()`](Self::display_width()) for how they're counted).
+    ///
+    /// This runs in logarithmic time.
     ///
     /// # Panics
     ///
     /// Panics if the byte offset is out of bounds (i.e. greater than
-    /// [`byte_len()`](Self::byte_len())).
+    /// [`byte_len()`](Self::byte_len())) or if it doesn't lie on a char
+    /// boundary.
     ///
     /// # Examples
     ///
     /// ```
     /// # use crop::Rope;
     /// #
-    /// let r = Rope::from("Löwe 老虎 Léopard");
+    /// // "日" takes up two columns and "\u{301}" zero.
+    /// let r = Rope::from("foo\na日e\u{301}b\n");
     ///
-    /// assert!(r.is_char_boundary(0));
-    /// assert!(r.is_char_boundary(r.byte_len()));
-    /// assert!(r.is_char_boundary(6)); // between ' ' and '老'
-    /// assert!(!r.is_char_boundary(2)); // between the 1st and 2nd byte of 'ö'
+    /// assert_eq!(r.display_column_of_byte(3), 3);
+    /// assert_eq!(r.display_column_of_byte(4), 0);
+    /// assert_eq!(r.display_column_of_byte(8), 3);
+    /// assert_eq!(r.display_column_of_byte(11), 4);
     /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "unicode-width")))]
+    #[cfg(feature = "unicode-width")]
     #[track_caller]
     #[inline]
-    pub fn is_char_boundary(&self, byte_offset: usize) -> bool {
+    pub fn display_column_of_byte(&self, byte_offset: usize) -> usize {
         if byte_offset > self.byte_len() {
             panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
         }
 
-        let (chunk, ByteMetric(chunk_byte_offset)) =
-            self.tree.leaf_at_measure(ByteMetric(byte_offset));
+        display_width::column_of_byte(self.byte_slice(..), byte_offset)
+    }
 
-        chunk.is_char_boundary(byte_offset - chunk_byte_offset)
+    /// Returns the number of columns the line at `line_index` takes up when
+    /// displayed in a terminal, not including its line terminator.
+    ///
+    /// Widths follow the Unicode East Asian Width rules: most CJK characters
+    /// and emoji take up two columns, combining marks and other zero-width
+    /// characters take up none, and ambiguous-width characters are treated as
+    /// narrow. Control characters, including tabs, also take up zero columns,
+    /// so expanding tabs is left to the caller.
+    ///
+    /// This runs in logarithmic time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the line index is out of bounds (i.e. greater than or equal
+    /// to [`line_len()`](Self::line_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo\n日本語\ne\u{301}🐸\n");
+    ///
+    /// assert_eq!(r.display_width(0), 3);
+    /// assert_eq!(r.display_width(1), 6);
+    /// assert_eq!(r.display_width(2), 3);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "unicode-width")))]
+    #[cfg(feature = "unicode-width")]
+    #[track_caller]
+    #[inline]
+    pub fn display_width(&self, line_index: usize) -> usize {
+        display_width::width(self.line(line_index))
     }
 
-    /// Returns `true` if the `Rope`'s byte length is zero.
+    pub(super) const fn arity() -> usize {
+        ARITY
+    }
+
+    /// Returns the byte range of the first match of the pattern in the
+    /// `Rope`, or `None` if there isn't one.
+    ///
+    /// The pattern can be a [`char`] or a string, and matches spanning
+    /// several chunks are found just like the others. An empty pattern
+    /// matches at the start of the `Rope`.
     ///
     /// # Examples
     ///
     /// ```
     /// # use crop::Rope;
     /// #
-    /// let mut r = Rope::new();
-    /// assert!(r.is_empty());
+    /// let r = Rope::from("foo\nbar 🐸\nbaz");
+    /// assert_eq!(r.find("bar"), Some(4..7));
+    /// assert_eq!(r.find('🐸'), Some(8..12));
+    /// assert_eq!(r.find("qux"), None);
+    /// ```
+    #[inline]
+    pub fn find<'p, P: Pattern<'p>>(
+        &self,
+        pattern: P,
+    ) -> Option<Range<usize>> {
+        self.find_iter(pattern).next()
+    }
+
+    /// Returns an iterator over the byte ranges of the non-overlapping
+    /// matches of the pattern in the `Rope`, from front to back.
     ///
-    /// r.insert(0, "hey");
-    /// assert!(!r.is_empty());
+    /// Like [`str::match_indices()`], a match that overlaps a previous one is
+    /// skipped, and an empty pattern matches on every char boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo\nbar 🐸\nbaz");
+    /// let mut matches = r.find_iter('a');
+    ///
+    /// assert_eq!(matches.next(), Some(5..6));
+    /// assert_eq!(matches.next(), Some(14..15));
+    /// assert_eq!(matches.next(), None);
     /// ```
     #[inline]
-    pub fn is_empty(&self) -> bool {
-        self.byte_len() == 0
+    pub fn find_iter<'p, P: Pattern<'p>>(
+        &self,
+        pattern: P,
+    ) -> Matches<'_, 'p> {
+        Matches::new(self.byte_slice(..), Needle::new(pattern))
     }
 
-    /// Returns `true` if the given byte offset lies on a grapheme cluster
-    /// boundary.
+    /// Returns the number of extended grapheme clusters in the `Rope`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// // The "🐻‍❄️" emoji is made up of four chars but it's rendered as a
+    /// // single grapheme, and so is "\r\n".
+    /// let r = Rope::from("a\r\n🐻‍❄️");
+    /// assert_eq!(r.grapheme_len(), 3);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "grapheme-metric")))]
+    #[cfg(feature = "grapheme-metric")]
+    #[inline]
+    pub fn grapheme_len(&self) -> usize {
+        let super::grapheme_runs::GraphemeMetric(grapheme_len) =
+            self.grapheme_tree.measure();
+
+        grapheme_len
+    }
+
+    /// Returns the grapheme offset corresponding to the given byte offset.
+    ///
+    /// If the byte offset doesn't lie on a grapheme boundary this returns the
+    /// offset of the grapheme containing it.
     ///
     /// # Panics
     ///
@@ -421,303 +1341,2209 @@ impl Rope {
     /// ```
     /// # use crop::Rope;
     /// #
-    /// let r = Rope::from("aargh!\r\n🐻‍❄️");
-    ///
-    /// assert!(r.is_grapheme_boundary(0));
-    /// assert!(r.is_grapheme_boundary(r.byte_len()));
-    /// assert!(!r.is_grapheme_boundary(7)); // between '\r' and '\n'
-    /// assert!(r.is_grapheme_boundary(8)); // between '\n' and '🐻‍❄️'
-    /// assert!(!r.is_grapheme_boundary(12)); // between the 1st and 2nd code point of '🐻‍❄️'
+    /// // "🇷🇸" is a single grapheme made up of two chars, both encoded using
+    /// // four bytes in UTF-8.
+    /// let r = Rope::from("a🇷🇸b");
+    /// assert_eq!(r.grapheme_of_byte(9), 2);
+    /// assert_eq!(r.grapheme_of_byte(5), 1);
     /// ```
-    #[cfg_attr(docsrs, doc(cfg(feature = "graphemes")))]
-    #[cfg(feature = "graphemes")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "grapheme-metric")))]
+    #[cfg(feature = "grapheme-metric")]
     #[track_caller]
     #[inline]
-    pub fn is_grapheme_boundary(&self, byte_offset: usize) -> bool {
+    pub fn grapheme_of_byte(&self, byte_offset: usize) -> usize {
         if byte_offset > self.byte_len() {
             panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
         }
 
-        is_grapheme_boundary(self.chunks(), self.byte_len(), byte_offset)
+        let super::grapheme_runs::GraphemeMetric(grapheme_offset) =
+            self.grapheme_tree.convert_measure(ByteMetric(byte_offset));
+
+        grapheme_offset
+    }
+
+    /// Returns an immutable slice of the `Rope` in the specified grapheme
+    /// range, where the start and end of the range are interpreted as
+    /// offsets.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start is greater than the end or if the end is out of
+    /// bounds (i.e. greater than [`grapheme_len()`](Self::grapheme_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("a🇷🇸e\u{301}🐻‍❄️");
+    ///
+    /// assert_eq!(r.grapheme_slice(..2), "a🇷🇸");
+    /// assert_eq!(r.grapheme_slice(2..3), "e\u{301}");
+    /// assert_eq!(r.grapheme_slice(3..), "🐻‍❄️");
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "grapheme-metric")))]
+    #[cfg(feature = "grapheme-metric")]
+    #[track_caller]
+    #[inline]
+    pub fn grapheme_slice<R>(&self, grapheme_range: R) -> RopeSlice<'_>
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) =
+            range_bounds_to_start_end(grapheme_range, 0, self.grapheme_len());
+
+        if start > end {
+            panic::grapheme_start_after_end(start, end);
+        }
+
+        if end > self.grapheme_len() {
+            panic::grapheme_offset_out_of_bounds(end, self.grapheme_len());
+        }
+
+        self.byte_slice(
+            self.byte_of_grapheme(start)..self.byte_of_grapheme(end),
+        )
+    }
+
+    /// Returns an iterator over the extended grapheme clusters of this
+    /// `Rope`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("arg!\r\n🐻‍❄️");
+    ///
+    /// let mut graphemes = r.graphemes();
+    ///
+    /// assert_eq!(Some("a"), graphemes.next().as_deref());
+    /// assert_eq!(Some("r"), graphemes.next().as_deref());
+    /// assert_eq!(Some("g"), graphemes.next().as_deref());
+    /// assert_eq!(Some("!"), graphemes.next().as_deref());
+    /// assert_eq!(Some("\r\n"), graphemes.next().as_deref());
+    /// assert_eq!(Some("🐻‍❄️"), graphemes.next().as_deref());
+    /// assert_eq!(None, graphemes.next());
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "graphemes")))]
+    #[cfg(feature = "graphemes")]
+    #[inline]
+    pub fn graphemes(&self) -> crate::iter::Graphemes<'_> {
+        crate::iter::Graphemes::from(self)
+    }
+
+    /// Inserts `text` in the `Rope` at the given byte offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset doesn't lie on a code point boundary or if
+    /// it's out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let mut r = Rope::from("Hello Earth!");
+    ///
+    /// r.insert(11, " 🌎");
+    /// assert_eq!(r, "Hello Earth 🌎!");
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn insert<T>(&mut self, byte_offset: usize, text: T)
+    where
+        T: AsRef<str>,
+    {
+        self.replace(byte_offset..byte_offset, text)
+    }
+
+    /// Inserts a [`RopeSlice`] in the `Rope` at the given byte offset.
+    ///
+    /// Unlike calling [`insert()`](Self::insert()) with the slice's contents,
+    /// this shares the chunks fully covered by the slice instead of copying
+    /// them, so it runs in logarithmic time (in linear time in the length of
+    /// the slice if the `grapheme-metric` feature is enabled, since the
+    /// slice's graphemes have to be segmented).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset doesn't lie on a code point boundary or if
+    /// it's out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let mut r = Rope::from("foo\nbar\n");
+    ///
+    /// // Cloning a `Rope` is cheap, and it lets us insert a slice of the
+    /// // original text in `r`.
+    /// let original = r.clone();
+    ///
+    /// r.insert_slice(4, original.line_slice(1..2));
+    /// assert_eq!(r, "foo\nbar\nbar\n");
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn insert_slice(&mut self, byte_offset: usize, slice: RopeSlice<'_>) {
+        self.replace_with_slice(byte_offset..byte_offset, slice)
+    }
+
+    /// Returns `true` if the given byte offset lies on a [`char`] boundary.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("Löwe 老虎 Léopard");
+    ///
+    /// assert!(r.is_char_boundary(0));
+    /// assert!(r.is_char_boundary(r.byte_len()));
+    /// assert!(r.is_char_boundary(6)); // between ' ' and '老'
+    /// assert!(!r.is_char_boundary(2)); // between the 1st and 2nd byte of 'ö'
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn is_char_boundary(&self, byte_offset: usize) -> bool {
+        if byte_offset > self.byte_len() {
+            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
+        }
+
+        let (chunk, ByteMetric(chunk_byte_offset)) =
+            self.tree.leaf_at_measure(ByteMetric(byte_offset));
+
+        chunk.is_char_boundary(byte_offset - chunk_byte_offset)
+    }
+
+    /// Returns `true` if the `Rope`'s byte length is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let mut r = Rope::new();
+    /// assert!(r.is_empty());
+    ///
+    /// r.insert(0, "hey");
+    /// assert!(!r.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.byte_len() == 0
+    }
+
+    /// Returns `true` if the given byte offset lies on a grapheme cluster
+    /// boundary.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("aargh!\r\n🐻‍❄️");
+    ///
+    /// assert!(r.is_grapheme_boundary(0));
+    /// assert!(r.is_grapheme_boundary(r.byte_len()));
+    /// assert!(!r.is_grapheme_boundary(7)); // between '\r' and '\n'
+    /// assert!(r.is_grapheme_boundary(8)); // between '\n' and '🐻‍❄️'
+    /// assert!(!r.is_grapheme_boundary(12)); // between the 1st and 2nd code point of '🐻‍❄️'
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "graphemes")))]
+    #[cfg(feature = "graphemes")]
+    #[track_caller]
+    #[inline]
+    pub fn is_grapheme_boundary(&self, byte_offset: usize) -> bool {
+        if byte_offset > self.byte_len() {
+            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
+        }
+
+        is_grapheme_boundary(self.byte_slice(..), byte_offset)
+    }
+
+    /// Returns the line at `line_index`, without its line terminator.
+    ///
+    /// If you want to include the line break consider taking a
+    /// [`line_slice()`](Self::line_slice()) in the
+    /// `line_index..line_index + 1` range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the line index is out of bounds (i.e. greater than or equal
+    /// to [`line_len()`](Self::byte_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo\nbar\r\nbaz");
+    ///
+    /// assert_eq!(r.line(0), "foo");
+    /// assert_eq!(r.line(1), "bar");
+    /// assert_eq!(r.line(2), "baz");
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn line(&self, line_index: usize) -> RopeSlice<'_> {
+        if line_index >= self.line_len() {
+            panic::line_index_out_of_bounds(line_index, self.line_len());
+        }
+
+        let tree_slice = self
+            .tree
+            .slice(RawLineMetric(line_index)..RawLineMetric(line_index + 1));
+
+        let mut line = RopeSlice {
+            tree_slice,
+            has_trailing_newline: false,
+            #[cfg(feature = "grapheme-metric")]
+            graphemes: None,
+        };
+
+        if line.tree_slice.summary().line_breaks() == 1 {
+            line.truncate_trailing_line_break();
+        }
+
+        line
+    }
+
+    /// Returns the number of lines in the `Rope`.
+    ///
+    /// The final line break is optional and doesn't count as a separate empty
+    /// line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let mut r = Rope::new();
+    ///
+    /// assert_eq!(r.line_len(), 0);
+    ///
+    /// r.insert(0, "a");
+    /// assert_eq!(r.line_len(), 1);
+    ///
+    /// r.insert(1, "\n");
+    /// assert_eq!(r.line_len(), 1);
+    ///
+    /// r.insert(2, "b");
+    /// assert_eq!(r.line_len(), 2);
+    ///
+    /// r.insert(3, "\r\n");
+    /// assert_eq!(r.line_len(), 2);
+    /// ```
+    #[inline]
+    pub fn line_len(&self) -> usize {
+        self.tree.summary().line_breaks() + 1
+            - (self.has_trailing_newline as usize)
+            - (self.is_empty() as usize)
+    }
+
+    /// Returns the line offset of the given byte.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo\nbar\r\nbaz");
+    ///
+    /// assert_eq!(r.line_of_byte(0), 0);
+    /// assert_eq!(r.line_of_byte(3), 0);
+    /// assert_eq!(r.line_of_byte(4), 1);
+    /// assert_eq!(r.line_of_byte(8), 1); // between the '\r' and the '\n'
+    /// assert_eq!(r.line_of_byte(r.byte_len()), 2);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn line_of_byte(&self, byte_offset: usize) -> usize {
+        if byte_offset > self.byte_len() {
+            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
+        }
+
+        let RawLineMetric(line_offset) =
+            self.tree.convert_measure(ByteMetric(byte_offset));
+
+        line_offset
+    }
+
+    /// Returns an immutable slice of the `Rope` in the specified line range,
+    /// where the start and end of the range are interpreted as offsets.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start is greater than the end or if the end is out of
+    /// bounds (i.e. greater than [`line_len()`](Self::line_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo\nbar\r\nbaz\nfoobar\n");
+    ///
+    /// assert_eq!(r.line_slice(..1), "foo\n");
+    /// assert_eq!(r.line_slice(1..3), "bar\r\nbaz\n");
+    /// assert_eq!(r.line_slice(3..4), "foobar\n");
+    /// assert_eq!(r.line_slice(4..), "");
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn line_slice<R>(&self, line_range: R) -> RopeSlice<'_>
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) =
+            range_bounds_to_start_end(line_range, 0, self.line_len());
+
+        if start > end {
+            panic::line_start_after_end(start, end);
+        }
+
+        if end > self.line_len() {
+            panic::line_offset_out_of_bounds(end, self.line_len());
+        }
+
+        let slice: RopeSlice<'_> =
+            self.tree.slice(RawLineMetric(start)..RawLineMetric(end)).into();
+
+        #[cfg(feature = "grapheme-metric")]
+        let slice = RopeSlice {
+            graphemes: Some((&self.grapheme_tree, self.byte_of_line(start))),
+            ..slice
+        };
+
+        slice
+    }
+
+    /// Returns an iterator over the lines of this `Rope`, not including the
+    /// line terminators.
+    ///
+    /// The final line break is optional and doesn't cause the iterator to
+    /// return a final empty line.
+    ///
+    /// If you want to include the line breaks consider using the
+    /// [`raw_lines()`](Self::raw_lines()) method instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo\nbar\r\nbaz\n");
+    ///
+    /// let mut lines = r.lines();
+    ///
+    /// assert_eq!("foo", lines.next().unwrap());
+    /// assert_eq!("bar", lines.next().unwrap());
+    /// assert_eq!("baz", lines.next().unwrap());
+    /// assert_eq!(None, lines.next());
+    /// ```
+    #[inline]
+    pub fn lines(&self) -> Lines<'_> {
+        Lines::from(self)
+    }
+
+    /// Returns an iterator over the lines of this `Rope` starting at the given
+    /// line offset, not including the line breaks.
+    ///
+    /// The iterator yields the lines from the `line_offset`-th one onwards
+    /// from both of its ends, while the ones before it can be yielded in
+    /// reverse order by calling [`Lines::prev()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the line offset is out of bounds (i.e. greater than
+    /// [`line_len()`](Self::line_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo\nbar\nbaz\n");
+    ///
+    /// let mut lines = r.lines_at(1);
+    ///
+    /// assert_eq!(lines.len(), 2);
+    /// assert_eq!("bar", lines.next().unwrap());
+    /// assert_eq!("baz", lines.next_back().unwrap());
+    /// assert_eq!(None, lines.next());
+    /// assert_eq!("foo", lines.prev().unwrap());
+    /// assert_eq!(None, lines.prev());
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn lines_at(&self, line_offset: usize) -> Lines<'_> {
+        Lines::at(&self.byte_slice(..), line_offset)
+    }
+
+    /// Returns the byte offset of the closest grapheme cluster boundary
+    /// strictly after the given byte offset, or the byte offset itself if
+    /// it's equal to [`byte_len()`](Self::byte_len()).
+    ///
+    /// This runs in logarithmic time plus the size of the grapheme clusters
+    /// around the offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let s = Rope::from("a\r\n🐻‍❄️");
+    /// assert_eq!(s.next_grapheme_boundary(0), 1);
+    /// assert_eq!(s.next_grapheme_boundary(1), 3); // skips the "\r\n"
+    /// assert_eq!(s.next_grapheme_boundary(4), s.byte_len()); // inside '🐻‍❄️'
+    /// assert_eq!(s.next_grapheme_boundary(s.byte_len()), s.byte_len());
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "graphemes")))]
+    #[cfg(feature = "graphemes")]
+    #[track_caller]
+    #[inline]
+    pub fn next_grapheme_boundary(&self, byte_offset: usize) -> usize {
+        if byte_offset > self.byte_len() {
+            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
+        }
+
+        next_grapheme_boundary(self.byte_slice(..), byte_offset)
+    }
+
+    /// Returns the byte offset of the closest Unicode word boundary strictly
+    /// after the given byte offset, or the byte offset itself if it's equal to
+    /// [`byte_len()`](Self::byte_len()).
+    ///
+    /// This can be used to implement word-wise cursor movements. It runs in
+    /// logarithmic time plus the length of the words around the offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("Hello, world!\nfoo_bar");
+    /// assert_eq!(r.next_word_boundary(0), 5);
+    /// assert_eq!(r.next_word_boundary(5), 6);
+    /// assert_eq!(r.next_word_boundary(9), 12); // inside "world"
+    /// assert_eq!(r.next_word_boundary(14), r.byte_len());
+    /// assert_eq!(r.next_word_boundary(r.byte_len()), r.byte_len());
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "words")))]
+    #[cfg(feature = "words")]
+    #[track_caller]
+    #[inline]
+    pub fn next_word_boundary(&self, byte_offset: usize) -> usize {
+        if byte_offset > self.byte_len() {
+            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
+        }
+
+        words::next_word_boundary(self.byte_slice(..), byte_offset)
+    }
+
+    /// Replaces every line ending (i.e. every LF, CRLF and lone CR) with the
+    /// given one.
+    ///
+    /// Like with [`apply_edits()`](Self::apply_edits()), only the leaves
+    /// containing line endings that need to be changed are rebuilt, and all
+    /// the other subtrees remain shared with the `Rope`'s clones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{LineEnding, Rope};
+    /// #
+    /// let mut r = Rope::from("foo\r\nbar\rbaz\n");
+    ///
+    /// r.normalize_line_endings(LineEnding::Lf);
+    /// assert_eq!(r, "foo\nbar\nbaz\n");
+    ///
+    /// r.normalize_line_endings(LineEnding::CrLf);
+    /// assert_eq!(r, "foo\r\nbar\r\nbaz\r\n");
+    /// ```
+    #[inline]
+    pub fn normalize_line_endings(&mut self, line_ending: LineEnding) {
+        let new = line_ending.as_str();
+
+        let edits = LineEndings::new(self.chunks())
+            .filter(|&(_, old)| old != line_ending)
+            .map(|(offset, old)| (offset..offset + old.as_str().len(), new))
+            .collect::<Vec<_>>();
+
+        // The line endings are yielded in order and never overlap, and the
+        // edits falling in the same leaves are applied together, so only the
+        // leaves containing line endings to be changed are rebuilt.
+        self.replace_sorted(&edits);
+    }
+
+    /// Returns an iterator over the paragraphs of this `Rope`, i.e. the runs of
+    /// consecutive lines which are not blank, together with their byte
+    /// ranges.
+    ///
+    /// A line is blank if it only contains whitespace. The range of a
+    /// paragraph goes from the start of its first line to the end of its last
+    /// line, not including the line terminator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo\nbar\n\n  \nbaz\n");
+    ///
+    /// let mut paragraphs = r.paragraphs();
+    ///
+    /// let (foobar, range) = paragraphs.next().unwrap();
+    /// assert_eq!(foobar, "foo\nbar");
+    /// assert_eq!(range, 0..7);
+    ///
+    /// let (baz, range) = paragraphs.next().unwrap();
+    /// assert_eq!(baz, "baz");
+    /// assert_eq!(range, 12..15);
+    ///
+    /// assert_eq!(paragraphs.next(), None);
+    /// ```
+    #[inline]
+    pub fn paragraphs(&self) -> Paragraphs<'_> {
+        Paragraphs::from(self)
+    }
+
+    /// Returns the [`Point`] corresponding to the given byte offset, with its
+    /// column measured in the given [`PositionEncoding`].
+    ///
+    /// This runs in logarithmic time if the metric of the encoding is
+    /// enabled (`utf16-metric` for UTF-16, `char-metric` for UTF-32), and in
+    /// time linear in the length of the line otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())) or if it doesn't lie on a code point
+    /// boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Point, PositionEncoding, Rope};
+    /// #
+    /// let r = Rope::from("foo\nb🐸r\n");
+    ///
+    /// assert_eq!(r.point_of_byte(9, PositionEncoding::Utf8), Point::new(1, 5));
+    /// assert_eq!(r.point_of_byte(9, PositionEncoding::Utf16), Point::new(1, 3));
+    /// assert_eq!(r.point_of_byte(9, PositionEncoding::Utf32), Point::new(1, 2));
+    ///
+    /// // The offset right after the trailing line break is on a new line.
+    /// assert_eq!(r.point_of_byte(11, PositionEncoding::Utf8), Point::new(2, 0));
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn point_of_byte(
+        &self,
+        byte_offset: usize,
+        encoding: PositionEncoding,
+    ) -> Point {
+        if byte_offset > self.byte_len() {
+            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
+        }
+
+        let line = self.line_of_byte(byte_offset);
+
+        let line_start = self.byte_of_line(line);
+
+        let column =
+            len_in(self.byte_slice(line_start..byte_offset), encoding);
+
+        Point::new(line, column)
+    }
+
+    /// Returns the byte offset of the closest grapheme cluster boundary
+    /// strictly before the given byte offset, or zero if the byte offset is
+    /// zero.
+    ///
+    /// This runs in logarithmic time plus the size of the grapheme clusters
+    /// around the offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let s = Rope::from("a\r\n🐻‍❄️");
+    /// assert_eq!(s.prev_grapheme_boundary(s.byte_len()), 3);
+    /// assert_eq!(s.prev_grapheme_boundary(3), 1); // skips the "\r\n"
+    /// assert_eq!(s.prev_grapheme_boundary(2), 1); // between '\r' and '\n'
+    /// assert_eq!(s.prev_grapheme_boundary(0), 0);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "graphemes")))]
+    #[cfg(feature = "graphemes")]
+    #[track_caller]
+    #[inline]
+    pub fn prev_grapheme_boundary(&self, byte_offset: usize) -> usize {
+        if byte_offset > self.byte_len() {
+            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
+        }
+
+        prev_grapheme_boundary(self.byte_slice(..), byte_offset)
+    }
+
+    /// Returns the byte offset of the closest Unicode word boundary strictly
+    /// before the given byte offset, or zero if the byte offset is zero.
+    ///
+    /// This can be used to implement word-wise cursor movements. It runs in
+    /// logarithmic time plus the length of the words around the offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("Hello, world!\nfoo_bar");
+    /// assert_eq!(r.prev_word_boundary(r.byte_len()), 14);
+    /// assert_eq!(r.prev_word_boundary(12), 7);
+    /// assert_eq!(r.prev_word_boundary(9), 7); // inside "world"
+    /// assert_eq!(r.prev_word_boundary(7), 6);
+    /// assert_eq!(r.prev_word_boundary(0), 0);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "words")))]
+    #[cfg(feature = "words")]
+    #[track_caller]
+    #[inline]
+    pub fn prev_word_boundary(&self, byte_offset: usize) -> usize {
+        if byte_offset > self.byte_len() {
+            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
+        }
+
+        words::prev_word_boundary(self.byte_slice(..), byte_offset)
+    }
+
+    /// Returns an iterator over the lines of this `Rope`, including the
+    /// line terminators.
+    ///
+    /// The final line break is optional and doesn't cause the iterator to
+    /// return a final empty line.
+    ///
+    /// If you don't want to include the line breaks consider using the
+    /// [`lines()`](Self::lines()) method instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let mut r = Rope::from("foo\nbar\r\nbaz");
+    ///
+    /// let mut raw_lines = r.raw_lines();
+    ///
+    /// assert_eq!("foo\n", raw_lines.next().unwrap());
+    /// assert_eq!("bar\r\n", raw_lines.next().unwrap());
+    /// assert_eq!("baz", raw_lines.next().unwrap());
+    /// assert_eq!(None, raw_lines.next());
+    ///
+    /// r.insert(r.byte_len(), "\n");
+    ///
+    /// let mut raw_lines = r.raw_lines();
+    ///
+    /// assert_eq!("foo\n", raw_lines.next().unwrap());
+    /// assert_eq!("bar\r\n", raw_lines.next().unwrap());
+    /// assert_eq!("baz\n", raw_lines.next().unwrap());
+    /// assert_eq!(None, raw_lines.next());
+    /// ```
+    #[inline]
+    pub fn raw_lines(&self) -> RawLines<'_> {
+        RawLines::from(self)
+    }
+
+    /// Returns a new empty [`Rope`].
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Deletes the text in the specified byte range, returning it as a new
+    /// `Rope`.
+    ///
+    /// The returned `Rope` shares all the chunks fully covered by the range
+    /// with the one it was removed from, so this runs in logarithmic time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start or the end of the byte range don't lie on a code
+    /// point boundary, if the start is greater than the end or if the end is
+    /// out of bounds (i.e. greater than [`byte_len()`](Self::byte_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let mut r = Rope::from("Hello Earth 🌎!");
+    ///
+    /// let removed = r.remove(5..16);
+    ///
+    /// assert_eq!(r, "Hello!");
+    /// assert_eq!(removed, " Earth 🌎");
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn remove<R>(&mut self, byte_range: R) -> Self
+    where
+        R: RangeBounds<usize>,
+    {
+        self.splice(byte_range, "")
+    }
+
+    /// Replaces the contents of the `Rope` within the specified byte range
+    /// with the given string, where the start and end of the range are
+    /// interpreted as byte offsets.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start or the end of the byte range don't lie on a code
+    /// point boundary, if the start is greater than the end or if the end is
+    /// out of bounds (i.e. greater than [`byte_len()`](Self::byte_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let mut r = Rope::from("Hello Earth 🌎!");
+    ///
+    /// r.replace(6..16, "Saturn 🪐");
+    /// assert_eq!(r, "Hello Saturn 🪐!");
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn replace<R, T>(&mut self, byte_range: R, text: T)
+    where
+        R: RangeBounds<usize>,
+        T: AsRef<str>,
+    {
+        let (start, end) =
+            range_bounds_to_start_end(byte_range, 0, self.byte_len());
+
+        self.replace_range_with(
+            start..end,
+            text.as_ref(),
+            |tree, range, text| tree.replace(range, text),
+        );
+    }
+
+    /// Replaces the `start..end` byte range with the given text, using
+    /// `replace_tree` to edit the tree of chunks, and returns its result.
+    #[track_caller]
+    #[inline]
+    fn replace_range_with<F, T>(
+        &mut self,
+        Range { start, end }: Range<usize>,
+        text: &str,
+        replace_tree: F,
+    ) -> T
+    where
+        F: FnOnce(
+            &mut Tree<{ Self::arity() }, RopeChunk>,
+            Range<ByteMetric>,
+            &str,
+        ) -> T,
+    {
+        if start > end {
+            panic::byte_start_after_end(start, end);
+        }
+
+        if end > self.byte_len() {
+            panic::byte_offset_out_of_bounds(end, self.line_len());
+        }
+
+        let mut update_trailing = false;
+
+        if end == self.byte_len() {
+            if !text.is_empty() {
+                self.has_trailing_newline = ends_with_line_break(text);
+            } else if start == 0 {
+                self.has_trailing_newline = false;
+            } else {
+                update_trailing = true;
+            }
+        }
+
+        let replaced = replace_tree(
+            &mut self.tree,
+            ByteMetric(start)..ByteMetric(end),
+            text,
+        );
+
+        #[cfg(feature = "unicode-lines")]
+        {
+            self.join_split_crlf(start);
+            self.join_split_crlf(start + text.len());
+        }
+
+        if update_trailing {
+            self.has_trailing_newline =
+                ends_with_line_break(self.chunks().next_back().unwrap());
+        }
+
+        #[cfg(feature = "grapheme-metric")]
+        self.update_grapheme_tree(start, end, text.len());
+
+        replaced
+    }
+
+    /// Moves the two halves of a CRLF pair into the same leaf if the given
+    /// byte offset lies between them and on a leaf boundary.
+    ///
+    /// A leaf can only count the line break of a CRLF pair if it contains
+    /// both of its halves, which editing the text around a leaf boundary
+    /// doesn't guarantee.
+    #[cfg(feature = "unicode-lines")]
+    #[inline]
+    fn join_split_crlf(&mut self, byte_offset: usize) {
+        if byte_offset == 0
+            || byte_offset >= self.byte_len()
+            || self.byte(byte_offset - 1) != b'\r'
+            || self.byte(byte_offset) != b'\n'
+        {
+            return;
+        }
+
+        let (leaf, ByteMetric(leaf_start)) =
+            self.tree.leaf_at_measure(ByteMetric(byte_offset));
+
+        if leaf_start == byte_offset || leaf_start + leaf.len() == byte_offset
+        {
+            self.tree.replace(
+                ByteMetric(byte_offset - 1)..ByteMetric(byte_offset + 1),
+                "\r\n",
+            );
+        }
+    }
+
+    /// Updates the grapheme tree after the `start..end` byte range of the
+    /// `Rope` has been replaced with `new_len` bytes of text.
+    ///
+    /// Only the graphemes around the edited range are re-segmented, going
+    /// from the last grapheme boundary before `start` to the first one after
+    /// `end` that's still a boundary in the new text.
+    #[cfg(feature = "grapheme-metric")]
+    #[inline]
+    fn update_grapheme_tree(
+        &mut self,
+        start: usize,
+        end: usize,
+        new_len: usize,
+    ) {
+        use alloc::vec::Vec;
+        use core::ops::ControlFlow;
+
+        use super::grapheme_runs::{
+            for_each_grapheme_len,
+            runs,
+            GraphemeMetric,
+        };
+
+        let tree = &self.grapheme_tree;
+
+        let ByteMetric(old_len) = tree.base_measure();
+
+        // Returns the byte offset of the start of the grapheme containing
+        // the given byte offset in the old text.
+        let grapheme_start = |byte_offset: usize| {
+            let GraphemeMetric(grapheme_offset) =
+                tree.convert_measure(ByteMetric(byte_offset));
+
+            let ByteMetric(byte_offset) =
+                tree.convert_measure(GraphemeMetric(grapheme_offset));
+
+            byte_offset
+        };
+
+        // Returns the byte offset of the first grapheme boundary strictly
+        // after the given byte offset in the old text.
+        let next_boundary = |byte_offset: usize| {
+            if byte_offset == old_len {
+                return old_len;
+            }
+
+            let GraphemeMetric(grapheme_offset) =
+                tree.convert_measure(ByteMetric(byte_offset));
+
+            let ByteMetric(byte_offset) =
+                tree.convert_measure(GraphemeMetric(grapheme_offset + 1));
+
+            byte_offset
+        };
+
+        let region_start =
+            if start == 0 { 0 } else { grapheme_start(start - 1) };
+
+        let mut old_region_end = next_boundary(end);
+
+        let mut new_region_end = region_start;
+
+        let mut grapheme_lens = Vec::new();
+
+        // The edit can turn boundaries after it into non-boundaries, e.g. by
+        // changing the parity of a sequence of regional indicators, so we
+        // keep going until we find a boundary that's in both the old and the
+        // new text.
+        for_each_grapheme_len(
+            self.byte_slice(region_start..).chunks(),
+            |len| {
+                grapheme_lens.push(len);
+
+                new_region_end += len;
+
+                let mut old_in_new = old_region_end - end + start + new_len;
+
+                while old_in_new < new_region_end {
+                    old_region_end = next_boundary(old_region_end);
+                    old_in_new = old_region_end - end + start + new_len;
+                }
+
+                if old_in_new == new_region_end {
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                }
+            },
+        );
+
+        let runs = runs(grapheme_lens);
+
+        self.grapheme_tree.replace(
+            ByteMetric(region_start)..ByteMetric(old_region_end),
+            &runs,
+        );
+    }
+
+    /// Replaces all the non-overlapping matches of the pattern with the given
+    /// string, returning the number of replacements.
+    ///
+    /// The matches falling in the same leaves are replaced together, so only
+    /// the leaves containing matches are rebuilt and all the subtrees without
+    /// matches remain shared with any clone of the `Rope`. The cost is that of
+    /// one [`replace()`](Self::replace()) per group of such matches.
+    ///
+    /// If the pattern is a [`Regex`](crate::regex::Regex), the `$name` and
+    /// `${name}` references in the replacement are expanded with the text of
+    /// the corresponding capture groups (see
+    /// [`Captures::expand()`](crate::regex::Captures::expand())). Other
+    /// patterns insert the replacement verbatim.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let mut r = Rope::from("foo bar foo baz");
+    ///
+    /// assert_eq!(r.replace_all("foo", "qux"), 2);
+    /// assert_eq!(r, "qux bar qux baz");
+    ///
+    /// assert_eq!(r.replace_all('🐸', "frog"), 0);
+    /// assert_eq!(r, "qux bar qux baz");
+    /// ```
+    #[inline]
+    pub fn replace_all<'p, P: Pattern<'p>>(
+        &mut self,
+        pattern: P,
+        replacement: &str,
+    ) -> usize {
+        let edits = search::replacements(
+            self.byte_slice(..),
+            Needle::new(pattern),
+            replacement,
+        );
+
+        // The matches are already sorted and don't overlap.
+        self.replace_sorted(&edits);
+
+        edits.len()
+    }
+
+    /// Replaces the text in the specified byte range with a [`RopeSlice`],
+    /// where the start and end of the range are interpreted as byte offsets.
+    ///
+    /// Unlike calling [`replace()`](Self::replace()) with the slice's
+    /// contents, this shares the chunks fully covered by the slice instead of
+    /// copying them, so it runs in logarithmic time. If the `grapheme-metric`
+    /// feature is enabled the same goes for the slice's graphemes, as long as
+    /// the slice was obtained by slicing a `Rope` by bytes or lines; otherwise
+    /// they have to be segmented, which takes linear time in its length.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start or the end of the byte range don't lie on a code
+    /// point boundary, if the start is greater than the end or if the end is
+    /// out of bounds (i.e. greater than [`byte_len()`](Self::byte_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let mut r = Rope::from("Hello Earth 🌎!");
+    /// let saturn = Rope::from("Hi Saturn 🪐!");
+    ///
+    /// r.replace_with_slice(6..16, saturn.byte_slice(3..14));
+    /// assert_eq!(r, "Hello Saturn 🪐!");
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn replace_with_slice<R>(
+        &mut self,
+        byte_range: R,
+        slice: RopeSlice<'_>,
+    ) where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) =
+            range_bounds_to_start_end(byte_range, 0, self.byte_len());
+
+        // This panics if the range is invalid, which we want to happen before
+        // splitting `self`.
+        let _ = self.byte_slice(start..end);
+
+        let mut chunks = slice.chunks();
+
+        // If the slice is contained in a single chunk there are no subtrees to
+        // share, and it's faster to just copy its text.
+        if let (first, None) = (chunks.next(), chunks.next()) {
+            self.replace(start..end, first.unwrap_or_default());
+            return;
+        }
+
+        let rest = self.split_off(end);
+        let _ = self.split_off(start);
+        self.append(Rope::from(slice));
+        self.append(rest);
+    }
+
+    /// Returns the byte range of the last match of the pattern in the
+    /// `Rope`, or `None` if there isn't one.
+    ///
+    /// An empty pattern matches at the end of the `Rope`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo\nbar 🐸\nbaz");
+    /// assert_eq!(r.rfind('a'), Some(14..15));
+    /// assert_eq!(r.rfind("🐸"), Some(8..12));
+    /// assert_eq!(r.rfind("qux"), None);
+    /// ```
+    #[inline]
+    pub fn rfind<'p, P: Pattern<'p>>(
+        &self,
+        pattern: P,
+    ) -> Option<Range<usize>> {
+        search::rfind(self.byte_slice(..), Needle::new(pattern))
+    }
+
+    /// Returns an iterator over the Unicode sentences of this `Rope`.
+    ///
+    /// Every sentence includes the whitespace and line break following it, so
+    /// concatenating all the items gives back the whole text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("Hello there. How are you?\nFine, thanks!");
+    ///
+    /// assert_eq!(
+    ///     r.sentences().collect::<Vec<_>>(),
+    ///     ["Hello there. ", "How are you?\n", "Fine, thanks!"]
+    /// );
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "sentences")))]
+    #[cfg(feature = "sentences")]
+    #[inline]
+    pub fn sentences(&self) -> crate::iter::Sentences<'_> {
+        crate::iter::Sentences::new(self.byte_slice(..))
+    }
+
+    /// Splits the `Rope` in two at the given byte offset, returning
+    /// everything after it and leaving everything before it in `self`.
+    ///
+    /// This runs in logarithmic time, and the two halves share all their
+    /// chunks except the one containing the split point.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())) or if it doesn't lie on a code point
+    /// boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let mut r = Rope::from("Hello\nEarth 🌎!");
+    ///
+    /// let rest = r.split_off(6);
+    ///
+    /// assert_eq!(r, "Hello\n");
+    /// assert_eq!(rest, "Earth 🌎!");
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn split_off(&mut self, byte_offset: usize) -> Self {
+        if byte_offset > self.byte_len() {
+            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
+        }
+
+        let tree = self.tree.split_off(ByteMetric(byte_offset));
+
+        let has_trailing_newline =
+            self.has_trailing_newline && tree.summary().bytes() != 0;
+
+        self.has_trailing_newline =
+            self.chunks().next_back().map_or(false, ends_with_line_break);
+
+        #[cfg(feature = "grapheme-metric")]
+        {
+            use super::grapheme_runs::GraphemeMetric;
+
+            // The grapheme tree can only be split on a grapheme boundary, so
+            // we split it at the start of the grapheme containing the offset,
+            // and then re-segment the text around the split on either side.
+            let grapheme_offset: GraphemeMetric =
+                self.grapheme_tree.convert_measure(ByteMetric(byte_offset));
+
+            let grapheme_tree = self.grapheme_tree.split_off(grapheme_offset);
+
+            let ByteMetric(grapheme_start) = self.grapheme_tree.base_measure();
+
+            let missing = byte_offset - grapheme_start;
+
+            self.update_grapheme_tree(grapheme_start, grapheme_start, missing);
+
+            let mut rest = Self { tree, has_trailing_newline, grapheme_tree };
+
+            rest.update_grapheme_tree(0, missing, 0);
+
+            rest
+        }
+
+        #[cfg(not(feature = "grapheme-metric"))]
+        Self { tree, has_trailing_newline }
+    }
+
+    /// Replaces the text in the specified byte range with the given string,
+    /// returning the replaced text as a new `Rope`.
+    ///
+    /// This is equivalent to calling [`replace()`](Self::replace()) after
+    /// converting the [`byte_slice()`](Self::byte_slice()) of the range into
+    /// a `Rope`, except that the replaced text is collected while replacing
+    /// it, and the returned `Rope` shares all the chunks fully covered by the
+    /// range with the one it was removed from instead of copying them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start or the end of the byte range don't lie on a code
+    /// point boundary, if the start is greater than the end or if the end is
+    /// out of bounds (i.e. greater than [`byte_len()`](Self::byte_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let mut r = Rope::from("Hello Earth 🌎!");
+    ///
+    /// let replaced = r.splice(6..16, "Saturn 🪐");
+    ///
+    /// assert_eq!(r, "Hello Saturn 🪐!");
+    /// assert_eq!(replaced, "Earth 🌎");
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn splice<R, T>(&mut self, byte_range: R, text: T) -> Self
+    where
+        R: RangeBounds<usize>,
+        T: AsRef<str>,
+    {
+        let (start, end) =
+            range_bounds_to_start_end(byte_range, 0, self.byte_len());
+
+        // Cloning the grapheme tree is cheap, and it's the only way to slice
+        // it once the text has been replaced.
+        #[cfg(feature = "grapheme-metric")]
+        let grapheme_tree = self.grapheme_tree.clone();
+
+        let tree = self.replace_range_with(
+            start..end,
+            text.as_ref(),
+            |tree, range, text| tree.splice(range, text),
+        );
+
+        let mut removed = Self {
+            tree,
+            has_trailing_newline: false,
+            #[cfg(feature = "grapheme-metric")]
+            grapheme_tree: GraphemeTree::default(),
+        };
+
+        removed.has_trailing_newline =
+            removed.chunks().next_back().map_or(false, ends_with_line_break);
+
+        #[cfg(feature = "grapheme-metric")]
+        {
+            removed.grapheme_tree = super::grapheme_runs::slice_tree(
+                &grapheme_tree,
+                start..end,
+                removed.chunks(),
+            );
+        }
+
+        removed
+    }
+
+    /// Returns the [`InputEdit`](tree_sitter::InputEdit) describing the edit
+    /// that [`replace()`](Self::replace()) would make with the same
+    /// arguments, which can be passed to
+    /// [`Tree::edit()`](tree_sitter::Tree::edit()) before reparsing.
+    ///
+    /// This has to be called *before* replacing the text, since the start and
+    /// old end positions are computed on the current content of the `Rope`.
+    /// Like in tree-sitter the columns are measured in bytes and the rows
+    /// are only delimited by `\n`s. With the `unicode-lines` feature these
+    /// can differ from the `Rope`'s lines, and the positions are computed by
+    /// scanning the text before the end of the range, which takes linear
+    /// time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start is greater than the end or if the end is out of
+    /// bounds (i.e. greater than [`byte_len()`](Self::byte_len())), or if
+    /// either one doesn't lie on a char boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// # use tree_sitter::Point;
+    /// #
+    /// let mut r = Rope::from("fn foo() {}\n");
+    ///
+    /// let edit = r.tree_sitter_edit(9..11, "{\n    bar();\n}");
+    /// r.replace(9..11, "{\n    bar();\n}");
+    ///
+    /// assert_eq!(edit.start_byte, 9);
+    /// assert_eq!(edit.old_end_byte, 11);
+    /// assert_eq!(edit.new_end_byte, 23);
+    /// assert_eq!(edit.start_position, Point::new(0, 9));
+    /// assert_eq!(edit.old_end_position, Point::new(0, 11));
+    /// assert_eq!(edit.new_end_position, Point::new(2, 1));
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "tree-sitter")))]
+    #[cfg(feature = "tree-sitter")]
+    #[track_caller]
+    #[inline]
+    pub fn tree_sitter_edit<R, T>(
+        &self,
+        byte_range: R,
+        text: T,
+    ) -> tree_sitter::InputEdit
+    where
+        R: RangeBounds<usize>,
+        T: AsRef<str>,
+    {
+        let (start, end) =
+            range_bounds_to_start_end(byte_range, 0, self.byte_len());
+
+        // This panics if the range is invalid.
+        let _ = self.byte_slice(start..end);
+
+        let text = text.as_ref();
+
+        #[cfg(not(feature = "unicode-lines"))]
+        let (start_position, old_end_position) = {
+            let to_ts_point = |point: Point| {
+                tree_sitter::Point::new(point.line, point.column)
+            };
+
+            (
+                to_ts_point(self.point_of_byte(start, PositionEncoding::Utf8)),
+                to_ts_point(self.point_of_byte(end, PositionEncoding::Utf8)),
+            )
+        };
+
+        // The `Rope`'s lines can also be delimited by other line breaks, so
+        // we have to count the `\n`s ourselves.
+        #[cfg(feature = "unicode-lines")]
+        let (start_position, old_end_position) = {
+            // Returns the point of `to`, given the point of `from`.
+            let advance = |mut point: tree_sitter::Point, from, to| {
+                for chunk in self.byte_slice(from..to).chunks() {
+                    let line_breaks = str_indices::lines_lf::count_breaks(chunk);
+
+                    if line_breaks > 0 {
+                        let last_line_start =
+                            chunk.rfind('\n').map_or(0, |idx| idx + 1);
+
+                        point.row += line_breaks;
+                        point.column = chunk.len() - last_line_start;
+                    } else {
+                        point.column += chunk.len();
+                    }
+                }
+
+                point
+            };
+
+            let start_position =
+                advance(tree_sitter::Point::new(0, 0), 0, start);
+
+            (start_position, advance(start_position, start, end))
+        };
+
+        let mut text_lines = text.split('\n');
+
+        // `split()` always yields at least one item, even on an empty string.
+        let last_line = text_lines.next_back().unwrap_or_default();
+
+        let new_end_position = match text_lines.count() {
+            0 => tree_sitter::Point::new(
+                start_position.row,
+                start_position.column + last_line.len(),
+            ),
+
+            line_breaks => tree_sitter::Point::new(
+                start_position.row + line_breaks,
+                last_line.len(),
+            ),
+        };
+
+        tree_sitter::InputEdit {
+            start_byte: start,
+            old_end_byte: end,
+            new_end_byte: start + text.len(),
+            start_position,
+            old_end_position,
+            new_end_position,
+        }
+    }
+
+    /// Parses the `Rope` with the given tree-sitter [`Parser`](tree_sitter::Parser)
+    /// by feeding it the `Rope`'s chunks, without copying the text into a
+    /// contiguous buffer.
+    ///
+    /// If the `Rope` has been edited since `old_tree` was produced, the tree
+    /// has to be updated with the edits returned by
+    /// [`tree_sitter_edit()`](Self::tree_sitter_edit()) first, and parsing
+    /// will reuse its unchanged parts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// # use tree_sitter::Parser;
+    /// #
+    /// let mut parser = Parser::new();
+    /// parser.set_language(tree_sitter_rust::language()).unwrap();
+    ///
+    /// let mut r = Rope::from("fn foo() {}\n");
+    /// let mut tree = r.tree_sitter_parse(&mut parser, None).unwrap();
+    ///
+    /// let edit = r.tree_sitter_edit(3..6, "bar");
+    /// r.replace(3..6, "bar");
+    /// tree.edit(&edit);
+    ///
+    /// let tree = r.tree_sitter_parse(&mut parser, Some(&tree)).unwrap();
+    ///
+    /// let name = tree.root_node().child(0).unwrap().child(1).unwrap();
+    /// assert_eq!(r.byte_slice(name.byte_range()), "bar");
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "tree-sitter")))]
+    #[cfg(feature = "tree-sitter")]
+    #[inline]
+    pub fn tree_sitter_parse(
+        &self,
+        parser: &mut tree_sitter::Parser,
+        old_tree: Option<&tree_sitter::Tree>,
+    ) -> Option<tree_sitter::Tree> {
+        parser.parse_with(&mut self.tree_sitter_reader(), old_tree)
+    }
+
+    /// Returns a callback to be passed to tree-sitter's
+    /// [`Parser::parse_with()`](tree_sitter::Parser::parse_with()) and
+    /// similar APIs, which returns the bytes from the requested offset to the
+    /// end of the chunk containing it.
+    ///
+    /// Each call runs in logarithmic time, and an empty slice is returned
+    /// for offsets at or past the end of the `Rope`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// # use tree_sitter::Point;
+    /// #
+    /// let r = Rope::from("Hello, World!");
+    ///
+    /// let mut read = r.tree_sitter_reader();
+    ///
+    /// assert_eq!(read(7, Point::new(0, 7)), b"World!");
+    /// assert_eq!(read(13, Point::new(0, 13)), b"");
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "tree-sitter")))]
+    #[cfg(feature = "tree-sitter")]
+    #[inline]
+    pub fn tree_sitter_reader<'a>(
+        &'a self,
+    ) -> impl FnMut(usize, tree_sitter::Point) -> &'a [u8] + 'a {
+        move |byte_offset, _| {
+            if byte_offset >= self.byte_len() {
+                return &[];
+            }
+
+            let (chunk, ByteMetric(chunk_byte_offset)) =
+                self.tree.leaf_at_measure(ByteMetric(byte_offset + 1));
+
+            let offset = byte_offset - chunk_byte_offset;
+
+            if offset < chunk.len_left() {
+                &chunk.left_chunk().as_bytes()[offset..]
+            } else {
+                &chunk.right_chunk().as_bytes()[offset - chunk.len_left()..]
+            }
+        }
+    }
+
+    /// Non-panicking version of [`byte()`](Self::byte()).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RopeError::IndexOutOfBounds`] if the index is out of bounds
+    /// (i.e. greater than or equal to [`byte_len()`](Self::byte_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, RopeError, Unit};
+    /// #
+    /// let r = Rope::from("bar");
+    ///
+    /// assert_eq!(r.try_byte(1), Ok(b'a'));
+    ///
+    /// assert_eq!(
+    ///     r.try_byte(3),
+    ///     Err(RopeError::IndexOutOfBounds {
+    ///         unit: Unit::Byte,
+    ///         index: 3,
+    ///         len: 3
+    ///     })
+    /// );
+    /// ```
+    #[inline]
+    pub fn try_byte(&self, byte_index: usize) -> Result<u8, RopeError> {
+        check_index(Unit::Byte, byte_index, self.byte_len())?;
+        Ok(self.byte(byte_index))
+    }
+
+    /// Non-panicking version of [`byte_of_char()`](Self::byte_of_char()).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RopeError::OffsetOutOfBounds`] if the char offset is out of
+    /// bounds (i.e. greater than [`char_len()`](Self::char_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, RopeError, Unit};
+    /// #
+    /// let r = Rope::from("a🐸b");
+    ///
+    /// assert_eq!(r.try_byte_of_char(2), Ok(5));
+    ///
+    /// assert_eq!(
+    ///     r.try_byte_of_char(4),
+    ///     Err(RopeError::OffsetOutOfBounds {
+    ///         unit: Unit::Char,
+    ///         offset: 4,
+    ///         len: 3
+    ///     })
+    /// );
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "char-metric")))]
+    #[cfg(feature = "char-metric")]
+    #[inline]
+    pub fn try_byte_of_char(
+        &self,
+        char_offset: usize,
+    ) -> Result<usize, RopeError> {
+        check_offset(Unit::Char, char_offset, self.char_len())?;
+        Ok(self.byte_of_char(char_offset))
+    }
+
+    /// Non-panicking version of
+    /// [`byte_of_grapheme()`](Self::byte_of_grapheme()).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RopeError::OffsetOutOfBounds`] if the grapheme offset is out
+    /// of bounds (i.e. greater than [`grapheme_len()`](Self::grapheme_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, RopeError, Unit};
+    /// #
+    /// let r = Rope::from("🇷🇸🇮🇴");
+    ///
+    /// assert_eq!(r.try_byte_of_grapheme(1), Ok(8));
+    ///
+    /// assert_eq!(
+    ///     r.try_byte_of_grapheme(3),
+    ///     Err(RopeError::OffsetOutOfBounds {
+    ///         unit: Unit::Grapheme,
+    ///         offset: 3,
+    ///         len: 2
+    ///     })
+    /// );
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "grapheme-metric")))]
+    #[cfg(feature = "grapheme-metric")]
+    #[inline]
+    pub fn try_byte_of_grapheme(
+        &self,
+        grapheme_offset: usize,
+    ) -> Result<usize, RopeError> {
+        check_offset(Unit::Grapheme, grapheme_offset, self.grapheme_len())?;
+        Ok(self.byte_of_grapheme(grapheme_offset))
+    }
+
+    /// Non-panicking version of [`byte_of_line()`](Self::byte_of_line()).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RopeError::OffsetOutOfBounds`] if the line offset is out of
+    /// bounds (i.e. greater than [`line_len()`](Self::line_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, RopeError, Unit};
+    /// #
+    /// let r = Rope::from("foo\nbar\r\nbaz");
+    ///
+    /// assert_eq!(r.try_byte_of_line(2), Ok(9));
+    ///
+    /// assert_eq!(
+    ///     r.try_byte_of_line(4),
+    ///     Err(RopeError::OffsetOutOfBounds {
+    ///         unit: Unit::Line,
+    ///         offset: 4,
+    ///         len: 3
+    ///     })
+    /// );
+    /// ```
+    #[inline]
+    pub fn try_byte_of_line(
+        &self,
+        line_offset: usize,
+    ) -> Result<usize, RopeError> {
+        check_offset(Unit::Line, line_offset, self.line_len())?;
+        Ok(self.byte_of_line(line_offset))
+    }
+
+    /// Non-panicking version of
+    /// [`byte_of_utf16_code_unit()`](Self::byte_of_utf16_code_unit()).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RopeError::OffsetOutOfBounds`] if the UTF-16 offset is out of
+    /// bounds (i.e. greater than [`utf16_len()`](Self::utf16_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, RopeError, Unit};
+    /// #
+    /// let r = Rope::from("a🐸b");
+    ///
+    /// assert_eq!(r.try_byte_of_utf16_code_unit(3), Ok(5));
+    ///
+    /// assert_eq!(
+    ///     r.try_byte_of_utf16_code_unit(5),
+    ///     Err(RopeError::OffsetOutOfBounds {
+    ///         unit: Unit::Utf16CodeUnit,
+    ///         offset: 5,
+    ///         len: 4
+    ///     })
+    /// );
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "utf16-metric")))]
+    #[cfg(feature = "utf16-metric")]
+    #[inline]
+    pub fn try_byte_of_utf16_code_unit(
+        &self,
+        utf16_offset: usize,
+    ) -> Result<usize, RopeError> {
+        check_offset(Unit::Utf16CodeUnit, utf16_offset, self.utf16_len())?;
+        Ok(self.byte_of_utf16_code_unit(utf16_offset))
+    }
+
+    /// Non-panicking version of [`byte_slice()`](Self::byte_slice()).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RopeError::StartAfterEnd`] if the start of the byte range is
+    /// greater than its end, [`RopeError::OffsetOutOfBounds`] if the end is
+    /// greater than [`byte_len()`](Self::byte_len()) and
+    /// [`RopeError::NotCharBoundary`] if the start or the end don't lie on a
+    /// code point boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, RopeError, Unit};
+    /// #
+    /// let r = Rope::from("🗻∈🌏");
+    ///
+    /// assert_eq!(r.try_byte_slice(4..7).unwrap(), "∈");
+    ///
+    /// assert_eq!(
+    ///     r.try_byte_slice(7..4),
+    ///     Err(RopeError::StartAfterEnd { unit: Unit::Byte, start: 7, end: 4 })
+    /// );
+    ///
+    /// assert_eq!(
+    ///     r.try_byte_slice(..2),
+    ///     Err(RopeError::NotCharBoundary { byte_offset: 2 })
+    /// );
+    /// ```
+    #[inline]
+    pub fn try_byte_slice<R>(
+        &self,
+        byte_range: R,
+    ) -> Result<RopeSlice<'_>, RopeError>
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) =
+            range_bounds_to_start_end(byte_range, 0, self.byte_len());
+
+        check_byte_range(self.byte_slice(..), start, end)?;
+
+        Ok(self.byte_slice(start..end))
+    }
+
+    /// Non-panicking version of [`char_of_byte()`](Self::char_of_byte()).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RopeError::OffsetOutOfBounds`] if the byte offset is out of
+    /// bounds (i.e. greater than [`byte_len()`](Self::byte_len())) and
+    /// [`RopeError::NotCharBoundary`] if it doesn't lie on a code point
+    /// boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, RopeError, Unit};
+    /// #
+    /// let r = Rope::from("a🐸b");
+    ///
+    /// assert_eq!(r.try_char_of_byte(5), Ok(2));
+    ///
+    /// assert_eq!(
+    ///     r.try_char_of_byte(3),
+    ///     Err(RopeError::NotCharBoundary { byte_offset: 3 })
+    /// );
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "char-metric")))]
+    #[cfg(feature = "char-metric")]
+    #[inline]
+    pub fn try_char_of_byte(
+        &self,
+        byte_offset: usize,
+    ) -> Result<usize, RopeError> {
+        check_byte_offset(self.byte_slice(..), byte_offset)?;
+        Ok(self.char_of_byte(byte_offset))
+    }
+
+    /// Non-panicking version of [`char_slice()`](Self::char_slice()).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RopeError::StartAfterEnd`] if the start of the char range is
+    /// greater than its end and [`RopeError::OffsetOutOfBounds`] if the end is
+    /// greater than [`char_len()`](Self::char_len()).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, RopeError, Unit};
+    /// #
+    /// let r = Rope::from("🗻∈🌏");
+    ///
+    /// assert_eq!(r.try_char_slice(1..2).unwrap(), "∈");
+    ///
+    /// assert_eq!(
+    ///     r.try_char_slice(..4),
+    ///     Err(RopeError::OffsetOutOfBounds {
+    ///         unit: Unit::Char,
+    ///         offset: 4,
+    ///         len: 3
+    ///     })
+    /// );
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "char-metric")))]
+    #[cfg(feature = "char-metric")]
+    #[inline]
+    pub fn try_char_slice<R>(
+        &self,
+        char_range: R,
+    ) -> Result<RopeSlice<'_>, RopeError>
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) =
+            range_bounds_to_start_end(char_range, 0, self.char_len());
+
+        check_range(Unit::Char, start, end, self.char_len())?;
+
+        Ok(self.char_slice(start..end))
+    }
+
+    /// Non-panicking version of [`delete()`](Self::delete()).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in the same cases as
+    /// [`try_replace()`](Self::try_replace()), in which case the `Rope` is
+    /// left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, RopeError, Unit};
+    /// #
+    /// let mut r = Rope::from("Hello Earth 🌎!");
+    ///
+    /// assert_eq!(
+    ///     r.try_delete(5..42),
+    ///     Err(RopeError::OffsetOutOfBounds {
+    ///         unit: Unit::Byte,
+    ///         offset: 42,
+    ///         len: 17
+    ///     })
+    /// );
+    ///
+    /// assert_eq!(r.try_delete(5..16), Ok(()));
+    /// assert_eq!(r, "Hello!");
+    /// ```
+    #[inline]
+    pub fn try_delete<R>(&mut self, byte_range: R) -> Result<(), RopeError>
+    where
+        R: RangeBounds<usize>,
+    {
+        self.try_replace(byte_range, "")
+    }
+
+    /// Non-panicking version of
+    /// [`grapheme_of_byte()`](Self::grapheme_of_byte()).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RopeError::OffsetOutOfBounds`] if the byte offset is out of
+    /// bounds (i.e. greater than [`byte_len()`](Self::byte_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, RopeError, Unit};
+    /// #
+    /// let r = Rope::from("🇷🇸🇮🇴");
+    ///
+    /// assert_eq!(r.try_grapheme_of_byte(8), Ok(1));
+    ///
+    /// assert_eq!(
+    ///     r.try_grapheme_of_byte(17),
+    ///     Err(RopeError::OffsetOutOfBounds {
+    ///         unit: Unit::Byte,
+    ///         offset: 17,
+    ///         len: 16
+    ///     })
+    /// );
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "grapheme-metric")))]
+    #[cfg(feature = "grapheme-metric")]
+    #[inline]
+    pub fn try_grapheme_of_byte(
+        &self,
+        byte_offset: usize,
+    ) -> Result<usize, RopeError> {
+        check_offset(Unit::Byte, byte_offset, self.byte_len())?;
+        Ok(self.grapheme_of_byte(byte_offset))
+    }
+
+    /// Non-panicking version of [`grapheme_slice()`](Self::grapheme_slice()).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RopeError::StartAfterEnd`] if the start of the grapheme range
+    /// is greater than its end and [`RopeError::OffsetOutOfBounds`] if the end
+    /// is greater than [`grapheme_len()`](Self::grapheme_len()).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, RopeError, Unit};
+    /// #
+    /// let r = Rope::from("🇷🇸🇮🇴");
+    ///
+    /// assert_eq!(r.try_grapheme_slice(1..).unwrap(), "🇮🇴");
+    ///
+    /// assert_eq!(
+    ///     r.try_grapheme_slice(2..1),
+    ///     Err(RopeError::StartAfterEnd {
+    ///         unit: Unit::Grapheme,
+    ///         start: 2,
+    ///         end: 1
+    ///     })
+    /// );
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "grapheme-metric")))]
+    #[cfg(feature = "grapheme-metric")]
+    #[inline]
+    pub fn try_grapheme_slice<R>(
+        &self,
+        grapheme_range: R,
+    ) -> Result<RopeSlice<'_>, RopeError>
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) =
+            range_bounds_to_start_end(grapheme_range, 0, self.grapheme_len());
+
+        check_range(Unit::Grapheme, start, end, self.grapheme_len())?;
+
+        Ok(self.grapheme_slice(start..end))
     }
 
-    /// Returns the line at `line_index`, without its line terminator.
-    ///
-    /// If you want to include the line break consider taking a
-    /// [`line_slice()`](Self::line_slice()) in the
-    /// `line_index..line_index + 1` range.
+    /// Non-panicking version of [`insert()`](Self::insert()).
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// Panics if the line index is out of bounds (i.e. greater than or equal
-    /// to [`line_len()`](Self::byte_len())).
+    /// Returns [`RopeError::OffsetOutOfBounds`] if the byte offset is out of
+    /// bounds (i.e. greater than [`byte_len()`](Self::byte_len())) and
+    /// [`RopeError::NotCharBoundary`] if it doesn't lie on a code point
+    /// boundary. In both cases the `Rope` is left unchanged.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use crop::Rope;
+    /// # use crop::{Rope, RopeError, Unit};
     /// #
-    /// let r = Rope::from("foo\nbar\r\nbaz");
+    /// let mut r = Rope::from("Hello 🌎!");
     ///
-    /// assert_eq!(r.line(0), "foo");
-    /// assert_eq!(r.line(1), "bar");
-    /// assert_eq!(r.line(2), "baz");
+    /// assert_eq!(
+    ///     r.try_insert(7, "Earth "),
+    ///     Err(RopeError::NotCharBoundary { byte_offset: 7 })
+    /// );
+    ///
+    /// assert_eq!(r.try_insert(6, "Earth "), Ok(()));
+    /// assert_eq!(r, "Hello Earth 🌎!");
     /// ```
-    #[track_caller]
     #[inline]
-    pub fn line(&self, line_index: usize) -> RopeSlice<'_> {
-        if line_index >= self.line_len() {
-            panic::line_index_out_of_bounds(line_index, self.line_len());
-        }
-
-        let tree_slice = self
-            .tree
-            .slice(RawLineMetric(line_index)..RawLineMetric(line_index + 1));
-
-        let mut line = RopeSlice { tree_slice, has_trailing_newline: false };
-
-        if line.tree_slice.summary().line_breaks() == 1 {
-            line.truncate_trailing_line_break();
-        }
-
-        line
+    pub fn try_insert<T>(
+        &mut self,
+        byte_offset: usize,
+        text: T,
+    ) -> Result<(), RopeError>
+    where
+        T: AsRef<str>,
+    {
+        check_byte_offset(self.byte_slice(..), byte_offset)?;
+        self.insert(byte_offset, text);
+        Ok(())
     }
 
-    /// Returns the number of lines in the `Rope`.
+    /// Non-panicking version of [`line()`](Self::line()).
     ///
-    /// The final line break is optional and doesn't count as a separate empty
-    /// line.
+    /// # Errors
+    ///
+    /// Returns [`RopeError::IndexOutOfBounds`] if the line index is out of
+    /// bounds (i.e. greater than or equal to
+    /// [`line_len()`](Self::line_len())).
     ///
     /// # Examples
     ///
     /// ```
-    /// # use crop::Rope;
+    /// # use crop::{Rope, RopeError, Unit};
     /// #
-    /// let mut r = Rope::new();
-    ///
-    /// assert_eq!(r.line_len(), 0);
-    ///
-    /// r.insert(0, "a");
-    /// assert_eq!(r.line_len(), 1);
-    ///
-    /// r.insert(1, "\n");
-    /// assert_eq!(r.line_len(), 1);
+    /// let r = Rope::from("foo\nbar\r\nbaz\n");
     ///
-    /// r.insert(2, "b");
-    /// assert_eq!(r.line_len(), 2);
+    /// assert_eq!(r.try_line(1).unwrap(), "bar");
     ///
-    /// r.insert(3, "\r\n");
-    /// assert_eq!(r.line_len(), 2);
+    /// assert_eq!(
+    ///     r.try_line(3),
+    ///     Err(RopeError::IndexOutOfBounds {
+    ///         unit: Unit::Line,
+    ///         index: 3,
+    ///         len: 3
+    ///     })
+    /// );
     /// ```
     #[inline]
-    pub fn line_len(&self) -> usize {
-        self.tree.summary().line_breaks() + 1
-            - (self.has_trailing_newline as usize)
-            - (self.is_empty() as usize)
+    pub fn try_line(
+        &self,
+        line_index: usize,
+    ) -> Result<RopeSlice<'_>, RopeError> {
+        check_index(Unit::Line, line_index, self.line_len())?;
+        Ok(self.line(line_index))
     }
 
-    /// Returns the line offset of the given byte.
+    /// Non-panicking version of [`line_of_byte()`](Self::line_of_byte()).
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// Panics if the byte offset is out of bounds (i.e. greater than
-    /// [`byte_len()`](Self::byte_len())).
+    /// Returns [`RopeError::OffsetOutOfBounds`] if the byte offset is out of
+    /// bounds (i.e. greater than [`byte_len()`](Self::byte_len())) and
+    /// [`RopeError::NotCharBoundary`] if it doesn't lie on a code point
+    /// boundary.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use crop::Rope;
+    /// # use crop::{Rope, RopeError, Unit};
     /// #
     /// let r = Rope::from("foo\nbar\r\nbaz");
     ///
-    /// assert_eq!(r.line_of_byte(0), 0);
-    /// assert_eq!(r.line_of_byte(3), 0);
-    /// assert_eq!(r.line_of_byte(4), 1);
-    /// assert_eq!(r.line_of_byte(8), 1); // between the '\r' and the '\n'
-    /// assert_eq!(r.line_of_byte(r.byte_len()), 2);
+    /// assert_eq!(r.try_line_of_byte(9), Ok(2));
+    ///
+    /// assert_eq!(
+    ///     r.try_line_of_byte(13),
+    ///     Err(RopeError::OffsetOutOfBounds {
+    ///         unit: Unit::Byte,
+    ///         offset: 13,
+    ///         len: 12
+    ///     })
+    /// );
     /// ```
-    #[track_caller]
     #[inline]
-    pub fn line_of_byte(&self, byte_offset: usize) -> usize {
-        if byte_offset > self.byte_len() {
-            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
-        }
-
-        let RawLineMetric(line_offset) =
-            self.tree.convert_measure(ByteMetric(byte_offset));
-
-        line_offset
+    pub fn try_line_of_byte(
+        &self,
+        byte_offset: usize,
+    ) -> Result<usize, RopeError> {
+        check_byte_offset(self.byte_slice(..), byte_offset)?;
+        Ok(self.line_of_byte(byte_offset))
     }
 
-    /// Returns an immutable slice of the `Rope` in the specified line range,
-    /// where the start and end of the range are interpreted as offsets.
+    /// Non-panicking version of [`line_slice()`](Self::line_slice()).
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// Panics if the start is greater than the end or if the end is out of
-    /// bounds (i.e. greater than [`line_len()`](Self::line_len())).
+    /// Returns [`RopeError::StartAfterEnd`] if the start of the line range is
+    /// greater than its end and [`RopeError::OffsetOutOfBounds`] if the end is
+    /// greater than [`line_len()`](Self::line_len()).
     ///
     /// # Examples
     ///
     /// ```
-    /// # use crop::Rope;
+    /// # use crop::{Rope, RopeError, Unit};
     /// #
-    /// let r = Rope::from("foo\nbar\r\nbaz\nfoobar\n");
+    /// let r = Rope::from("foo\nbar\r\nbaz\n");
     ///
-    /// assert_eq!(r.line_slice(..1), "foo\n");
-    /// assert_eq!(r.line_slice(1..3), "bar\r\nbaz\n");
-    /// assert_eq!(r.line_slice(3..4), "foobar\n");
-    /// assert_eq!(r.line_slice(4..), "");
+    /// assert_eq!(r.try_line_slice(1..).unwrap(), "bar\r\nbaz\n");
+    ///
+    /// assert_eq!(
+    ///     r.try_line_slice(..4),
+    ///     Err(RopeError::OffsetOutOfBounds {
+    ///         unit: Unit::Line,
+    ///         offset: 4,
+    ///         len: 3
+    ///     })
+    /// );
     /// ```
-    #[track_caller]
     #[inline]
-    pub fn line_slice<R>(&self, line_range: R) -> RopeSlice<'_>
+    pub fn try_line_slice<R>(
+        &self,
+        line_range: R,
+    ) -> Result<RopeSlice<'_>, RopeError>
     where
         R: RangeBounds<usize>,
     {
         let (start, end) =
             range_bounds_to_start_end(line_range, 0, self.line_len());
 
-        if start > end {
-            panic::line_start_after_end(start, end);
-        }
-
-        if end > self.line_len() {
-            panic::line_offset_out_of_bounds(end, self.line_len());
-        }
+        check_range(Unit::Line, start, end, self.line_len())?;
 
-        self.tree.slice(RawLineMetric(start)..RawLineMetric(end)).into()
+        Ok(self.line_slice(start..end))
     }
 
-    /// Returns an iterator over the lines of this `Rope`, not including the
-    /// line terminators.
+    /// Non-panicking version of [`replace()`](Self::replace()).
     ///
-    /// The final line break is optional and doesn't cause the iterator to
-    /// return a final empty line.
+    /// # Errors
     ///
-    /// If you want to include the line breaks consider using the
-    /// [`raw_lines()`](Self::raw_lines()) method instead.
+    /// Returns [`RopeError::StartAfterEnd`] if the start of the byte range is
+    /// greater than its end, [`RopeError::OffsetOutOfBounds`] if the end is
+    /// greater than [`byte_len()`](Self::byte_len()) and
+    /// [`RopeError::NotCharBoundary`] if the start or the end don't lie on a
+    /// code point boundary. In all cases the `Rope` is left unchanged.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use crop::Rope;
+    /// # use crop::{Rope, RopeError, Unit};
     /// #
-    /// let r = Rope::from("foo\nbar\r\nbaz\n");
+    /// let mut r = Rope::from("Hello Earth 🌎!");
     ///
-    /// let mut lines = r.lines();
+    /// assert_eq!(
+    ///     r.try_replace(6..14, "Saturn 🪐"),
+    ///     Err(RopeError::NotCharBoundary { byte_offset: 14 })
+    /// );
     ///
-    /// assert_eq!("foo", lines.next().unwrap());
-    /// assert_eq!("bar", lines.next().unwrap());
-    /// assert_eq!("baz", lines.next().unwrap());
-    /// assert_eq!(None, lines.next());
+    /// assert_eq!(r.try_replace(6..16, "Saturn 🪐"), Ok(()));
+    /// assert_eq!(r, "Hello Saturn 🪐!");
     /// ```
     #[inline]
-    pub fn lines(&self) -> Lines<'_> {
-        Lines::from(self)
+    pub fn try_replace<R, T>(
+        &mut self,
+        byte_range: R,
+        text: T,
+    ) -> Result<(), RopeError>
+    where
+        R: RangeBounds<usize>,
+        T: AsRef<str>,
+    {
+        let (start, end) =
+            range_bounds_to_start_end(byte_range, 0, self.byte_len());
+
+        check_byte_range(self.byte_slice(..), start, end)?;
+
+        self.replace(start..end, text);
+
+        Ok(())
     }
 
-    /// Returns an iterator over the lines of this `Rope`, including the
-    /// line terminators.
+    /// Non-panicking version of
+    /// [`utf16_code_unit_of_byte()`](Self::utf16_code_unit_of_byte()).
     ///
-    /// The final line break is optional and doesn't cause the iterator to
-    /// return a final empty line.
+    /// # Errors
     ///
-    /// If you don't want to include the line breaks consider using the
-    /// [`lines()`](Self::lines()) method instead.
+    /// Returns [`RopeError::OffsetOutOfBounds`] if the byte offset is out of
+    /// bounds (i.e. greater than [`byte_len()`](Self::byte_len())) and
+    /// [`RopeError::NotCharBoundary`] if it doesn't lie on a code point
+    /// boundary.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use crop::Rope;
+    /// # use crop::{Rope, RopeError, Unit};
     /// #
-    /// let mut r = Rope::from("foo\nbar\r\nbaz");
-    ///
-    /// let mut raw_lines = r.raw_lines();
-    ///
-    /// assert_eq!("foo\n", raw_lines.next().unwrap());
-    /// assert_eq!("bar\r\n", raw_lines.next().unwrap());
-    /// assert_eq!("baz", raw_lines.next().unwrap());
-    /// assert_eq!(None, raw_lines.next());
-    ///
-    /// r.insert(r.byte_len(), "\n");
+    /// let r = Rope::from("a🐸b");
     ///
-    /// let mut raw_lines = r.raw_lines();
+    /// assert_eq!(r.try_utf16_code_unit_of_byte(5), Ok(3));
     ///
-    /// assert_eq!("foo\n", raw_lines.next().unwrap());
-    /// assert_eq!("bar\r\n", raw_lines.next().unwrap());
-    /// assert_eq!("baz\n", raw_lines.next().unwrap());
-    /// assert_eq!(None, raw_lines.next());
+    /// assert_eq!(
+    ///     r.try_utf16_code_unit_of_byte(2),
+    ///     Err(RopeError::NotCharBoundary { byte_offset: 2 })
+    /// );
     /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "utf16-metric")))]
+    #[cfg(feature = "utf16-metric")]
     #[inline]
-    pub fn raw_lines(&self) -> RawLines<'_> {
-        RawLines::from(self)
-    }
-
-    /// Returns a new empty [`Rope`].
-    #[inline]
-    pub fn new() -> Self {
-        Self::default()
+    pub fn try_utf16_code_unit_of_byte(
+        &self,
+        byte_offset: usize,
+    ) -> Result<usize, RopeError> {
+        check_byte_offset(self.byte_slice(..), byte_offset)?;
+        Ok(self.utf16_code_unit_of_byte(byte_offset))
     }
 
-    /// Replaces the contents of the `Rope` within the specified byte range
-    /// with the given string, where the start and end of the range are
-    /// interpreted as byte offsets.
+    /// Non-panicking version of [`utf16_slice()`](Self::utf16_slice()).
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// Panics if the start or the end of the byte range don't lie on a code
-    /// point boundary, if the start is greater than the end or if the end is
-    /// out of bounds (i.e. greater than [`byte_len()`](Self::byte_len())).
+    /// Returns [`RopeError::StartAfterEnd`] if the start of the UTF-16 range
+    /// is greater than its end and [`RopeError::OffsetOutOfBounds`] if the
+    /// end is greater than [`utf16_len()`](Self::utf16_len()).
     ///
     /// # Examples
     ///
     /// ```
-    /// # use crop::Rope;
+    /// # use crop::{Rope, RopeError, Unit};
     /// #
-    /// let mut r = Rope::from("Hello Earth 🌎!");
-    ///
-    /// r.replace(6..16, "Saturn 🪐");
-    /// assert_eq!(r, "Hello Saturn 🪐!");
+    /// let r = Rope::from("a🐸b");
+    ///
+    /// assert_eq!(r.try_utf16_slice(1..3).unwrap(), "🐸");
+    ///
+    /// assert_eq!(
+    ///     r.try_utf16_slice(3..2),
+    ///     Err(RopeError::StartAfterEnd {
+    ///         unit: Unit::Utf16CodeUnit,
+    ///         start: 3,
+    ///         end: 2
+    ///     })
+    /// );
     /// ```
-    #[track_caller]
+    #[cfg_attr(docsrs, doc(cfg(feature = "utf16-metric")))]
+    #[cfg(feature = "utf16-metric")]
     #[inline]
-    pub fn replace<R, T>(&mut self, byte_range: R, text: T)
+    pub fn try_utf16_slice<R>(
+        &self,
+        utf16_range: R,
+    ) -> Result<RopeSlice<'_>, RopeError>
     where
         R: RangeBounds<usize>,
-        T: AsRef<str>,
     {
         let (start, end) =
-            range_bounds_to_start_end(byte_range, 0, self.byte_len());
-
-        if start > end {
-            panic::byte_start_after_end(start, end);
-        }
-
-        if end > self.byte_len() {
-            panic::byte_offset_out_of_bounds(end, self.line_len());
-        }
-
-        let text = text.as_ref();
-
-        let mut update_trailing = false;
-
-        if end == self.byte_len() {
-            if !text.is_empty() {
-                self.has_trailing_newline = text.ends_with('\n');
-            } else if start == 0 {
-                self.has_trailing_newline = false;
-            } else {
-                update_trailing = true;
-            }
-        }
+            range_bounds_to_start_end(utf16_range, 0, self.utf16_len());
 
-        self.tree.replace(ByteMetric(start)..ByteMetric(end), text);
+        check_range(Unit::Utf16CodeUnit, start, end, self.utf16_len())?;
 
-        if update_trailing {
-            self.has_trailing_newline =
-                self.chunks().next_back().unwrap().ends_with('\n');
-        }
+        Ok(self.utf16_slice(start..end))
     }
 
     /// Returns the number of UTF-16 code units the `Rope` would have if it
@@ -816,14 +3642,144 @@ impl Rope {
 
         self.tree.slice(Utf16Metric(start)..Utf16Metric(end)).into()
     }
+
+    /// Returns the visual column of the given byte offset within its line,
+    /// where a tab advances the column to the next multiple of `tab_width`.
+    ///
+    /// The rest of the line is counted in units, which are graphemes if the
+    /// `graphemes` feature is enabled and chars otherwise, so `"e\u{301}"`
+    /// is one unit with the feature and two without it. Every unit other
+    /// than a tab takes up a single column, or as many columns as its
+    /// [`display_width()`](Self::display_width()) if the `unicode-width`
+    /// feature is enabled. If the offset is inside a unit the column of its
+    /// start is returned.
+    ///
+    /// This walks the line from its start, so it runs in time linear in the
+    /// length of the line.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())), if it doesn't lie on a char
+    /// boundary or if `tab_width` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo\n\tbar\tbaz\n");
+    ///
+    /// assert_eq!(r.visual_column_of_byte(2, 4), 2);
+    /// assert_eq!(r.visual_column_of_byte(5, 4), 4);
+    /// assert_eq!(r.visual_column_of_byte(5, 8), 8);
+    /// assert_eq!(r.visual_column_of_byte(8, 4), 7);
+    /// assert_eq!(r.visual_column_of_byte(9, 4), 8);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn visual_column_of_byte(
+        &self,
+        byte_offset: usize,
+        tab_width: usize,
+    ) -> usize {
+        if byte_offset > self.byte_len() {
+            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
+        }
+
+        let line_index = self.line_of_byte(byte_offset);
+
+        // The offset is after the last line break, so it's on an empty line.
+        let line = if line_index == self.line_len() {
+            self.byte_slice(byte_offset..)
+        } else {
+            self.line_slice(line_index..line_index + 1)
+        };
+
+        visual_column::column_of_byte(
+            line,
+            byte_offset - self.byte_of_line(line_index),
+            tab_width,
+        )
+    }
+
+    /// Returns an iterator over the Unicode word boundaries of this `Rope`,
+    /// yielding the text between each pair of consecutive boundaries.
+    ///
+    /// Unlike [`words()`](Self::words()), this also yields the whitespace and
+    /// punctuation between the words, so concatenating all the items gives
+    /// back the whole text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("Hello, world!");
+    ///
+    /// let mut bounds = r.word_bounds();
+    ///
+    /// assert_eq!(Some("Hello"), bounds.next().as_deref());
+    /// assert_eq!(Some(","), bounds.next().as_deref());
+    /// assert_eq!(Some("!"), bounds.next_back().as_deref());
+    /// assert_eq!(Some(" "), bounds.next().as_deref());
+    /// assert_eq!(Some("world"), bounds.next().as_deref());
+    /// assert_eq!(None, bounds.next());
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "words")))]
+    #[cfg(feature = "words")]
+    #[inline]
+    pub fn word_bounds(&self) -> crate::iter::WordBounds<'_> {
+        crate::iter::WordBounds::new(self.byte_slice(..))
+    }
+
+    /// Returns an iterator over the Unicode words of this `Rope`, i.e. the
+    /// segments between its word boundaries containing at least one
+    /// alphanumeric character.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("The quick (\"brown\") fox can't jump 32.3 feet");
+    ///
+    /// assert_eq!(
+    ///     r.words().collect::<Vec<_>>(),
+    ///     ["The", "quick", "brown", "fox", "can't", "jump", "32.3", "feet"]
+    /// );
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "words")))]
+    #[cfg(feature = "words")]
+    #[inline]
+    pub fn words(&self) -> crate::iter::Words<'_> {
+        crate::iter::Words::new(self.byte_slice(..))
+    }
 }
 
 impl From<RopeSlice<'_>> for Rope {
     #[inline]
     fn from(rope_slice: RopeSlice<'_>) -> Rope {
+        // A slice ending between the two halves of a CRLF pair doesn't
+        // count its trailing `\r` as a line break, but the `Rope` does.
+        let has_trailing_newline = rope_slice.has_trailing_newline
+            || rope_slice
+                .chunks()
+                .next_back()
+                .map_or(false, ends_with_line_break);
+
         Self {
-            has_trailing_newline: rope_slice.has_trailing_newline,
+            has_trailing_newline,
             tree: Tree::from(rope_slice.tree_slice),
+            #[cfg(feature = "grapheme-metric")]
+            grapheme_tree: match rope_slice.graphemes {
+                Some((graphemes, start)) => super::grapheme_runs::slice_tree(
+                    graphemes,
+                    start..start + rope_slice.byte_len(),
+                    rope_slice.chunks(),
+                ),
+                None => GraphemeRuns::tree(rope_slice.chunks()),
+            },
         }
     }
 }
@@ -851,10 +3807,12 @@ impl From<&str> for Rope {
     #[inline]
     fn from(s: &str) -> Self {
         Rope {
-            has_trailing_newline: s.ends_with('\n'),
+            has_trailing_newline: ends_with_line_break(s),
             tree: Tree::from_leaves(
                 RopeChunk::segmenter(s).map(RopeChunk::from),
             ),
+            #[cfg(feature = "grapheme-metric")]
+            grapheme_tree: GraphemeRuns::tree(core::iter::once(s)),
         }
     }
 }
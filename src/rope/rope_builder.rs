@@ -1,7 +1,7 @@
 use super::gap_buffer::GapBuffer;
 use super::metrics::ChunkSummary;
 use super::rope::RopeChunk;
-use super::utils::split_adjusted;
+use super::utils::{split_adjusted, splits_crlf};
 use super::Rope;
 use crate::tree::TreeBuilder;
 
@@ -62,6 +62,14 @@ impl RopeBuilder {
             &mut self.buffer_len_left,
             text,
         ) {
+            // The two halves of a CRLF pair have to be in the same chunk, so
+            // a trailing `\r` is moved to the next one.
+            let carry_cr = splits_crlf(self.buffer_left_chunk(), rest);
+
+            if carry_cr {
+                self.buffer_len_left -= 1;
+            }
+
             self.buffer.left_summary =
                 ChunkSummary::from(self.buffer_left_chunk());
 
@@ -69,6 +77,11 @@ impl RopeBuilder {
 
             self.buffer_len_left = 0;
 
+            if carry_cr {
+                self.buffer.bytes[0] = b'\r';
+                self.buffer_len_left = 1;
+            }
+
             text = rest;
         }
 
@@ -116,10 +129,22 @@ impl RopeBuilder {
             self.tree_builder.append(self.buffer);
         }
 
-        Rope {
+        let rope = Rope {
             tree: self.tree_builder.build(),
             has_trailing_newline: self.rope_has_trailing_newline,
-        }
+            #[cfg(feature = "grapheme-metric")]
+            grapheme_tree: Default::default(),
+        };
+
+        #[cfg(feature = "grapheme-metric")]
+        let rope = Rope {
+            grapheme_tree: super::grapheme_runs::GraphemeRuns::tree(
+                rope.chunks(),
+            ),
+            ..rope
+        };
+
+        rope
     }
 
     /// Creates a new `RopeBuilder`.
@@ -1,11 +1,31 @@
 use alloc::string::String;
-use core::ops::RangeBounds;
-
-use super::iterators::{Bytes, Chars, Chunks, Lines, RawLines};
+use core::ops::{Range, RangeBounds};
+
+#[cfg(feature = "unicode-width")]
+use super::display_width;
+#[cfg(feature = "grapheme-metric")]
+use super::grapheme_runs::GraphemeTree;
+use super::iterators::{Bytes, Chars, Chunks, Lines, Paragraphs, RawLines};
+use super::line_ending::LineEndings;
 use super::metrics::{ByteMetric, RawLineMetric};
+use super::point::{byte_of_column, len_in};
 use super::rope::RopeChunk;
+use super::search::{self, Matches, Needle};
 use super::utils::{panic_messages as panic, *};
-use super::Rope;
+use super::visual_column;
+#[cfg(feature = "words")]
+use super::words;
+use super::{
+    DetectedLineEnding,
+    LineEnding,
+    Pattern,
+    Point,
+    PositionEncoding,
+    Rope,
+    RopeCursor,
+    RopeError,
+    Unit,
+};
 use crate::range_bounds_to_start_end;
 use crate::tree::TreeSlice;
 
@@ -14,6 +34,13 @@ use crate::tree::TreeSlice;
 pub struct RopeSlice<'a> {
     pub(super) tree_slice: TreeSlice<'a, { Rope::arity() }, RopeChunk>,
     pub(super) has_trailing_newline: bool,
+
+    /// The grapheme tree of the `Rope` this slice was taken from, together
+    /// with the byte offset of the slice's start in that `Rope`. It's only
+    /// known for slices created by slicing a `Rope` by bytes or lines, and
+    /// it's used to turn the slice into a `Rope` without segmenting it.
+    #[cfg(feature = "grapheme-metric")]
+    pub(super) graphemes: Option<(&'a GraphemeTree, usize)>,
 }
 
 impl<'a> RopeSlice<'a> {
@@ -82,6 +109,83 @@ impl<'a> RopeSlice<'a> {
         self.tree_slice.summary().bytes()
     }
 
+    /// Returns the byte offset corresponding to the given char offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the char offset is out of bounds (i.e. greater than
+    /// [`char_len()`](Self::char_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// // The "🐸" emoji is encoded using four bytes in UTF-8.
+    /// let r = Rope::from("a🐸b");
+    /// let s = r.byte_slice(1..);
+    /// assert_eq!(s.byte_of_char(1), 4);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "char-metric")))]
+    #[cfg(feature = "char-metric")]
+    #[track_caller]
+    #[inline]
+    pub fn byte_of_char(&self, char_offset: usize) -> usize {
+        if char_offset > self.char_len() {
+            panic::char_offset_out_of_bounds(char_offset, self.char_len())
+        }
+
+        let ByteMetric(byte_offset) = self
+            .tree_slice
+            .convert_measure(super::metrics::CharMetric(char_offset));
+
+        byte_offset
+    }
+
+    /// Returns the byte offset of the char displayed at the given column of
+    /// the line at `line_index`, where the columns are the ones the line
+    /// takes up in a terminal (see [`display_width()`](Self::display_width())
+    /// for how they're counted).
+    ///
+    /// If the column falls inside a double-width char the offset of the start
+    /// of that char is returned. Zero-width chars are attributed to the char
+    /// before them, and a column equal to the width of the line maps to its
+    /// end.
+    ///
+    /// This runs in logarithmic time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the line index is out of bounds (i.e. greater than or equal
+    /// to [`line_len()`](Self::line_len())) or if the column is greater than
+    /// the line's [`display_width()`](Self::display_width()).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// // "日" takes up two columns and is encoded using three bytes in UTF-8.
+    /// let r = Rope::from("foo\na日b\n");
+    /// let s = r.byte_slice(2..);
+    ///
+    /// assert_eq!(s.byte_of_display_column(0, 1), 1);
+    /// assert_eq!(s.byte_of_display_column(1, 2), 3);
+    /// assert_eq!(s.byte_of_display_column(1, 3), 6);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "unicode-width")))]
+    #[cfg(feature = "unicode-width")]
+    #[track_caller]
+    #[inline]
+    pub fn byte_of_display_column(
+        &self,
+        line_index: usize,
+        column: usize,
+    ) -> usize {
+        self.byte_of_line(line_index)
+            + display_width::byte_of_column(self.line(line_index), column)
+    }
+
     /// Returns the byte offset of the start of the given line.
     ///
     /// # Panics
@@ -121,6 +225,64 @@ impl<'a> RopeSlice<'a> {
         byte_offset
     }
 
+    /// Returns the byte offset corresponding to the given [`Point`], whose
+    /// column is measured in the given [`PositionEncoding`].
+    ///
+    /// Columns past the end of the line (not counting its line terminator)
+    /// are clamped to the end of the line, and columns inside a character
+    /// (e.g. between the two code units of a surrogate pair) are rounded down
+    /// to the start of that character.
+    ///
+    /// This runs in logarithmic time if the metric of the encoding is
+    /// enabled (`utf16-metric` for UTF-16, `char-metric` for UTF-32), and in
+    /// time linear in the length of the line otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the line of the point is out of bounds (i.e. greater than
+    /// [`line_len()`](Self::line_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Point, PositionEncoding, Rope};
+    /// #
+    /// let r = Rope::from("foo\nb🐸r\n");
+    /// let s = r.byte_slice(..);
+    ///
+    /// let utf8 = PositionEncoding::Utf8;
+    /// let utf16 = PositionEncoding::Utf16;
+    ///
+    /// assert_eq!(s.byte_of_point(Point::new(1, 5), utf8), 9);
+    /// assert_eq!(s.byte_of_point(Point::new(1, 3), utf16), 9);
+    ///
+    /// // Columns past the end of the line are clamped.
+    /// assert_eq!(s.byte_of_point(Point::new(0, 42), utf16), 3);
+    ///
+    /// // Columns inside a character are rounded down.
+    /// assert_eq!(s.byte_of_point(Point::new(1, 2), utf16), 5);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn byte_of_point(
+        &self,
+        point: Point,
+        encoding: PositionEncoding,
+    ) -> usize {
+        if point.line > self.line_len() {
+            panic::line_offset_out_of_bounds(point.line, self.line_len());
+        }
+
+        let line_start = self.byte_of_line(point.line);
+
+        if point.line == self.line_len() {
+            return line_start;
+        }
+
+        line_start
+            + byte_of_column(self.line(point.line), point.column, encoding)
+    }
+
     /// Returns the byte offset corresponding to the given UTF-16 code unit
     /// offset.
     ///
@@ -157,6 +319,51 @@ impl<'a> RopeSlice<'a> {
         byte_offset
     }
 
+    /// Returns the byte offset of the unit displayed at the given visual
+    /// column of the line at `line_index`, where a tab advances the column to
+    /// the next multiple of `tab_width`.
+    ///
+    /// A unit is a grapheme if the `graphemes` feature is enabled and a char
+    /// otherwise (see
+    /// [`visual_column_of_byte()`](Self::visual_column_of_byte()) for how
+    /// they're counted). If the column falls inside a tab or a wide unit the
+    /// offset of its start is returned. Columns past the end of the line are
+    /// clamped to its length, which is handy when moving the cursor between
+    /// lines.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the line index is out of bounds (i.e. greater than or equal
+    /// to [`line_len()`](Self::line_len())) or if `tab_width` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo\n\tbar\tbaz\n");
+    /// let s = r.byte_slice(5..);
+    ///
+    /// assert_eq!(s.byte_of_visual_column(0, 1, 4), 1);
+    /// assert_eq!(s.byte_of_visual_column(0, 3, 4), 3);
+    /// assert_eq!(s.byte_of_visual_column(0, 4, 4), 4);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn byte_of_visual_column(
+        &self,
+        line_index: usize,
+        column: usize,
+        tab_width: usize,
+    ) -> usize {
+        self.byte_of_line(line_index)
+            + visual_column::byte_of_column(
+                self.line(line_index),
+                column,
+                tab_width,
+            )
+    }
+
     /// Returns a sub-slice of this `RopeSlice` in the specified byte range,
     /// where the start and end of the range are interpreted as offsets.
     ///
@@ -194,7 +401,18 @@ impl<'a> RopeSlice<'a> {
             panic::byte_offset_out_of_bounds(end, self.byte_len());
         }
 
-        self.tree_slice.slice(ByteMetric(start)..ByteMetric(end)).into()
+        let slice: RopeSlice<'a> =
+            self.tree_slice.slice(ByteMetric(start)..ByteMetric(end)).into();
+
+        #[cfg(feature = "grapheme-metric")]
+        let slice = RopeSlice {
+            graphemes: self
+                .graphemes
+                .map(|(graphemes, offset)| (graphemes, offset + start)),
+            ..slice
+        };
+
+        slice
     }
 
     /// Returns an iterator over the bytes of this `RopeSlice`.
@@ -220,416 +438,1609 @@ impl<'a> RopeSlice<'a> {
         Bytes::from(self)
     }
 
-    /// Returns an iterator over the [`char`]s of this `RopeSlice`.
+    /// Returns an iterator over the bytes of this `RopeSlice` starting at the
+    /// given byte offset.
+    ///
+    /// The iterator yields the bytes after the offset from both of its ends,
+    /// while the ones before it can be yielded in reverse order by calling
+    /// [`Bytes::prev()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())) or if it doesn't lie on a char
+    /// boundary.
     ///
     /// # Examples
     ///
     /// ```
     /// # use crop::Rope;
     /// #
-    /// let r = Rope::from("🐻‍❄️");
-    /// let s = r.byte_slice(4..);
+    /// let r = Rope::from("foo\nbar\nbaz\n");
+    /// let s = r.byte_slice(..);
     ///
-    /// let mut chars = s.chars();
+    /// let mut bytes = s.bytes_at(5);
     ///
-    /// assert_eq!(Some('\u{200d}'), chars.next());
-    /// assert_eq!(Some('❄'), chars.next());
-    /// assert_eq!(Some('\u{fe0f}'), chars.next());
-    /// assert_eq!(None, chars.next());
+    /// assert_eq!(bytes.len(), 7);
+    /// assert_eq!(Some(b'a'), bytes.next());
+    /// assert_eq!(Some(b'\n'), bytes.next_back());
+    /// assert_eq!(Some(b'b'), bytes.prev());
+    /// assert_eq!(Some(b'\n'), bytes.prev());
     /// ```
+    #[track_caller]
     #[inline]
-    pub fn chars(&self) -> Chars<'a> {
-        Chars::from(self)
+    pub fn bytes_at(&self, byte_offset: usize) -> Bytes<'a> {
+        Bytes::at(self, byte_offset)
     }
 
-    /// Returns an iterator over the chunks of this `RopeSlice`.
+    /// Returns the number of [`char`]s in this `RopeSlice`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// // The "🐸" emoji is a single char but it's encoded using four
+    /// // bytes in UTF-8.
+    /// let r = Rope::from("abc🐸");
+    /// let s = r.byte_slice(2..);
+    /// assert_eq!(s.char_len(), 2);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "char-metric")))]
+    #[cfg(feature = "char-metric")]
     #[inline]
-    pub fn chunks(&self) -> Chunks<'a> {
-        Chunks::from(self)
+    pub fn char_len(&self) -> usize {
+        self.tree_slice.summary().chars()
     }
 
-    /// Returns an iterator over the extended grapheme clusters of this
-    /// `RopeSlice`.
+    /// Returns the char offset corresponding to the given byte offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())) or if it doesn't lie on a code point
+    /// boundary.
     ///
     /// # Examples
     ///
-    /// ```no_run
+    /// ```
     /// # use crop::Rope;
     /// #
-    /// let r = Rope::from("arg!\r\n🐻‍❄️");
-    /// let s = r.byte_slice(3..);
-    ///
-    /// let mut graphemes = s.graphemes();
-    ///
-    /// assert_eq!(Some("!"), graphemes.next().as_deref());
-    /// assert_eq!(Some("\r\n"), graphemes.next().as_deref());
-    /// assert_eq!(Some("🐻‍❄️"), graphemes.next().as_deref());
-    /// assert_eq!(None, graphemes.next());
+    /// // The "🐸" emoji is encoded using four bytes in UTF-8.
+    /// let r = Rope::from("a🐸b");
+    /// let s = r.byte_slice(1..);
+    /// assert_eq!(s.char_of_byte(4), 1);
     /// ```
-    #[cfg_attr(docsrs, doc(cfg(feature = "graphemes")))]
-    #[cfg(feature = "graphemes")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "char-metric")))]
+    #[cfg(feature = "char-metric")]
+    #[track_caller]
     #[inline]
-    pub fn graphemes(&self) -> crate::iter::Graphemes<'a> {
-        crate::iter::Graphemes::from(self)
+    pub fn char_of_byte(&self, byte_offset: usize) -> usize {
+        if byte_offset > self.byte_len() {
+            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
+        }
+
+        let super::metrics::CharMetric(char_offset) =
+            self.tree_slice.convert_measure(ByteMetric(byte_offset));
+
+        char_offset
     }
 
-    /// Returns `true` if the given byte offset lies on a [`char`] boundary.
+    /// Returns a sub-slice of this `RopeSlice` in the specified char range,
+    /// where the start and end of the range are interpreted as offsets.
     ///
     /// # Panics
     ///
-    /// Panics if the byte offset is out of bounds (i.e. greater than
-    /// [`byte_len()`](Self::byte_len())).
+    /// Panics if the start is greater than the end or if the end is out of
+    /// bounds (i.e. greater than [`char_len()`](Self::char_len())).
     ///
     /// # Examples
     ///
     /// ```
     /// # use crop::Rope;
     /// #
-    /// let r = Rope::from("Löwe 老虎 Léopard");
-    /// let s = r.byte_slice(3..);
+    /// let r = Rope::from("🗻∈🌏");
+    /// let s = r.byte_slice(4..);
     ///
-    /// assert!(s.is_char_boundary(0));
-    /// assert!(s.is_char_boundary(s.byte_len()));
-    /// assert!(s.is_char_boundary(6)); // between '老' and '虎'
-    /// assert!(!s.is_char_boundary(12)); // between the 1st and 2nd byte of 'é'
+    /// assert_eq!(s.char_slice(..1), "∈");
+    /// assert_eq!(s.char_slice(1..), "🌏");
     /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "char-metric")))]
+    #[cfg(feature = "char-metric")]
     #[track_caller]
     #[inline]
-    pub fn is_char_boundary(&self, byte_offset: usize) -> bool {
-        if byte_offset > self.byte_len() {
-            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
+    pub fn char_slice<R>(self, char_range: R) -> RopeSlice<'a>
+    where
+        R: RangeBounds<usize>,
+    {
+        use super::metrics::CharMetric;
+
+        let (start, end) =
+            range_bounds_to_start_end(char_range, 0, self.char_len());
+
+        if start > end {
+            panic::char_start_after_end(start, end);
         }
 
-        let (chunk, ByteMetric(chunk_byte_offset)) =
-            self.tree_slice.leaf_at_measure(ByteMetric(byte_offset));
+        if end > self.char_len() {
+            panic::char_offset_out_of_bounds(end, self.char_len());
+        }
 
-        chunk.is_char_boundary(byte_offset - chunk_byte_offset)
+        self.tree_slice.slice(CharMetric(start)..CharMetric(end)).into()
     }
 
-    /// Returns `true` if the `RopeSlice`'s byte length is zero.
+    /// Returns an iterator over the [`char`]s of this `RopeSlice`.
     ///
     /// # Examples
     ///
     /// ```
     /// # use crop::Rope;
     /// #
-    /// let r = Rope::from("");
-    /// assert!(r.byte_slice(..).is_empty());
+    /// let r = Rope::from("🐻‍❄️");
+    /// let s = r.byte_slice(4..);
     ///
-    /// let r = Rope::from("foo");
-    /// assert!(!r.line_slice(..).is_empty());
+    /// let mut chars = s.chars();
+    ///
+    /// assert_eq!(Some('\u{200d}'), chars.next());
+    /// assert_eq!(Some('❄'), chars.next());
+    /// assert_eq!(Some('\u{fe0f}'), chars.next());
+    /// assert_eq!(None, chars.next());
     /// ```
     #[inline]
-    pub fn is_empty(&self) -> bool {
-        self.byte_len() == 0
+    pub fn chars(&self) -> Chars<'a> {
+        Chars::from(self)
     }
 
-    /// Returns `true` if the given byte offset lies on a grapheme cluster
-    /// boundary.
+    /// Returns an iterator over the [`char`]s of this `RopeSlice` starting at
+    /// the given byte offset.
+    ///
+    /// The iterator yields the chars after the offset from both of its ends,
+    /// while the ones before it can be yielded in reverse order by calling
+    /// [`Chars::prev()`].
     ///
     /// # Panics
     ///
     /// Panics if the byte offset is out of bounds (i.e. greater than
-    /// [`byte_len()`](Self::byte_len())).
+    /// [`byte_len()`](Self::byte_len())) or if it doesn't lie on a char
+    /// boundary.
     ///
     /// # Examples
     ///
     /// ```
     /// # use crop::Rope;
     /// #
-    /// let r = Rope::from("aargh!\r\n🐻‍❄️");
-    /// let s = r.line_slice(1..);
+    /// let r = Rope::from("foo\nbar\nbaz\n");
+    /// let s = r.byte_slice(..);
     ///
-    /// assert!(s.is_grapheme_boundary(0));
-    /// assert!(s.is_grapheme_boundary(s.byte_len()));
-    /// assert!(!s.is_grapheme_boundary(4)); // between the 1st and 2nd code point of '🐻‍❄️'
+    /// let mut chars = s.chars_at(4);
+    ///
+    /// assert_eq!("bar\nbaz\n", chars.clone().collect::<String>());
+    /// assert_eq!("\nzab\nrab", chars.clone().rev().collect::<String>());
+    /// assert_eq!(Some('\n'), chars.prev());
+    /// assert_eq!(Some('o'), chars.prev());
     /// ```
-    #[cfg_attr(docsrs, doc(cfg(feature = "graphemes")))]
-    #[cfg(feature = "graphemes")]
     #[track_caller]
     #[inline]
-    pub fn is_grapheme_boundary(&self, byte_offset: usize) -> bool {
-        if byte_offset > self.byte_len() {
-            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
-        }
+    pub fn chars_at(&self, byte_offset: usize) -> Chars<'a> {
+        Chars::at(self, byte_offset)
+    }
 
-        is_grapheme_boundary(self.chunks(), self.byte_len(), byte_offset)
+    /// Returns an iterator over the chunks of this `RopeSlice`.
+    #[inline]
+    pub fn chunks(&self) -> Chunks<'a> {
+        Chunks::from(self)
     }
 
-    /// Returns the line at `line_index`, without its line terminator.
+    /// Returns an iterator over the chunks of this `RopeSlice` starting at the
+    /// given byte offset.
     ///
-    /// If you want to include the line break consider taking a
-    /// [`line_slice()`](Self::line_slice()) in the
-    /// `line_index..line_index + 1` range.
+    /// The iterator yields the chunks after the offset from both of its ends,
+    /// the first one starting exactly at the offset, while the ones before it
+    /// can be yielded in reverse order by calling [`Chunks::prev()`], the
+    /// first one ending exactly at the offset.
     ///
     /// # Panics
     ///
-    /// Panics if the line index is out of bounds (i.e. greater than or equal
-    /// to [`line_len()`](Self::byte_len())).
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())) or if it doesn't lie on a char
+    /// boundary.
     ///
     /// # Examples
     ///
     /// ```
     /// # use crop::Rope;
     /// #
-    /// let r = Rope::from("foo\nbar\r\nbaz");
-    /// let s = r.line_slice(..2);
+    /// let r = Rope::from("foo\nbar\nbaz\n");
+    /// let s = r.byte_slice(..);
     ///
-    /// assert_eq!(s.line(0), "foo");
-    /// assert_eq!(s.line(1), "bar");
+    /// let mut chunks = s.chunks_at(6);
+    ///
+    /// assert_eq!("r\nbaz\n", chunks.clone().collect::<String>());
+    ///
+    /// let mut before = Vec::new();
+    /// while let Some(chunk) = chunks.prev() {
+    ///     before.insert(0, chunk);
+    /// }
+    /// assert_eq!("foo\nba", before.concat());
     /// ```
     #[track_caller]
     #[inline]
-    pub fn line(self, line_index: usize) -> RopeSlice<'a> {
-        if line_index >= self.line_len() {
-            panic::line_offset_out_of_bounds(line_index, self.line_len());
-        }
-
-        let tree_slice = self
-            .tree_slice
-            .slice(RawLineMetric(line_index)..RawLineMetric(line_index + 1));
-
-        let mut line = Self { tree_slice, has_trailing_newline: false };
-
-        if line.tree_slice.summary().line_breaks() == 1 {
-            line.truncate_trailing_line_break();
-        }
-
-        line
+    pub fn chunks_at(&self, byte_offset: usize) -> Chunks<'a> {
+        Chunks::at(self, byte_offset)
     }
 
-    /// Returns the number of lines in the `RopeSlice`.
+    /// Returns a [`RopeCursor`] placed at the given byte offset.
     ///
-    /// The final line break is optional and doesn't count as a separate empty
-    /// line.
+    /// This runs in logarithmic time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())).
     ///
     /// # Examples
     ///
     /// ```
     /// # use crop::Rope;
     /// #
-    /// let r = Rope::from("a\nb\r\n");
+    /// let r = Rope::from("Hello, World!");
+    /// let s = r.byte_slice(7..);
+    /// let mut cursor = s.cursor_at(0);
     ///
-    /// let s = r.byte_slice(..);
+    /// assert_eq!(cursor.next_char(), Some('W'));
+    /// assert_eq!(cursor.prev_char(), Some('W'));
+    /// assert_eq!(cursor.prev_char(), None);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn cursor_at(&self, byte_offset: usize) -> RopeCursor<'a> {
+        RopeCursor::new(*self, byte_offset)
+    }
+
+    /// Returns the kinds of line endings used in the `RopeSlice`, together with
+    /// how many of each there are.
+    ///
+    /// A `\r` immediately followed by a `\n` counts as a single CRLF.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{DetectedLineEnding, Rope};
+    /// #
+    /// let r = Rope::from("foo\nbar\r\nbaz\r\n");
+    /// assert_eq!(
+    ///     r.byte_slice(..4).detect_line_ending(),
+    ///     DetectedLineEnding::Lf(1)
+    /// );
+    /// assert_eq!(
+    ///     r.byte_slice(4..).detect_line_ending(),
+    ///     DetectedLineEnding::CrLf(2)
+    /// );
+    /// ```
+    #[inline]
+    pub fn detect_line_ending(&self) -> DetectedLineEnding {
+        let (mut lf, mut crlf, mut cr) = (0, 0, 0);
+
+        for (_, line_ending) in LineEndings::new(self.chunks()) {
+            match line_ending {
+                LineEnding::Lf => lf += 1,
+                LineEnding::CrLf => crlf += 1,
+                LineEnding::Cr => cr += 1,
+            }
+        }
+
+        DetectedLineEnding::from_counts(lf, crlf, cr)
+    }
+
+    /// Returns the column of the given byte offset within its line, where the
+    /// columns are the ones the text takes up in a terminal (see
+    /// [`display_width()`](Self::display_width()) for how they're counted).
+    ///
+    /// This runs in logarithmic time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())) or if it doesn't lie on a char
+    /// boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// // "日" takes up two columns and "\u{301}" zero.
+    /// let r = Rope::from("foo\na日e\u{301}b\n");
+    /// let s = r.byte_slice(5..);
+    ///
+    /// assert_eq!(s.display_column_of_byte(0), 0);
+    /// assert_eq!(s.display_column_of_byte(3), 2);
+    /// assert_eq!(s.display_column_of_byte(6), 3);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "unicode-width")))]
+    #[cfg(feature = "unicode-width")]
+    #[track_caller]
+    #[inline]
+    pub fn display_column_of_byte(&self, byte_offset: usize) -> usize {
+        if byte_offset > self.byte_len() {
+            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
+        }
+
+        display_width::column_of_byte(*self, byte_offset)
+    }
+
+    /// Returns the number of columns the line at `line_index` takes up when
+    /// displayed in a terminal, not including its line terminator.
+    ///
+    /// Widths follow the Unicode East Asian Width rules: most CJK characters
+    /// and emoji take up two columns, combining marks and other zero-width
+    /// characters take up none, and ambiguous-width characters are treated as
+    /// narrow. Control characters, including tabs, also take up zero columns,
+    /// so expanding tabs is left to the caller.
+    ///
+    /// This runs in logarithmic time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the line index is out of bounds (i.e. greater than or equal
+    /// to [`line_len()`](Self::line_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo\n日本語\ne\u{301}🐸\n");
+    /// let s = r.byte_slice(7..);
+    ///
+    /// assert_eq!(s.display_width(0), 4);
+    /// assert_eq!(s.display_width(1), 3);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "unicode-width")))]
+    #[cfg(feature = "unicode-width")]
+    #[track_caller]
+    #[inline]
+    pub fn display_width(&self, line_index: usize) -> usize {
+        display_width::width(self.line(line_index))
+    }
+
+    /// Returns the byte range of the first match of the pattern in the
+    /// `RopeSlice`, or `None` if there isn't one.
+    ///
+    /// The pattern can be a [`char`] or a string, and matches spanning
+    /// several chunks are found just like the others. An empty pattern
+    /// matches at the start of the `RopeSlice`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("Hello, foo\nbar 🐸\nbaz");
+    /// let s = r.byte_slice(7..);
+    /// assert_eq!(s.find("bar"), Some(4..7));
+    /// assert_eq!(s.find('🐸'), Some(8..12));
+    /// assert_eq!(s.find("qux"), None);
+    /// ```
+    #[inline]
+    pub fn find<'p, P: Pattern<'p>>(
+        &self,
+        pattern: P,
+    ) -> Option<Range<usize>> {
+        self.find_iter(pattern).next()
+    }
+
+    /// Returns an iterator over the byte ranges of the non-overlapping
+    /// matches of the pattern in the `RopeSlice`, from front to back.
+    ///
+    /// Like [`str::match_indices()`], a match that overlaps a previous one is
+    /// skipped, and an empty pattern matches on every char boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("Hello, foo\nbar 🐸\nbaz");
+    /// let s = r.byte_slice(7..);
+    /// let mut matches = s.find_iter('a');
+    ///
+    /// assert_eq!(matches.next(), Some(5..6));
+    /// assert_eq!(matches.next(), Some(14..15));
+    /// assert_eq!(matches.next(), None);
+    /// ```
+    #[inline]
+    pub fn find_iter<'p, P: Pattern<'p>>(
+        &self,
+        pattern: P,
+    ) -> Matches<'a, 'p> {
+        Matches::new(*self, Needle::new(pattern))
+    }
+
+    /// Returns an iterator over the extended grapheme clusters of this
+    /// `RopeSlice`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("arg!\r\n🐻‍❄️");
+    /// let s = r.byte_slice(3..);
+    ///
+    /// let mut graphemes = s.graphemes();
+    ///
+    /// assert_eq!(Some("!"), graphemes.next().as_deref());
+    /// assert_eq!(Some("\r\n"), graphemes.next().as_deref());
+    /// assert_eq!(Some("🐻‍❄️"), graphemes.next().as_deref());
+    /// assert_eq!(None, graphemes.next());
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "graphemes")))]
+    #[cfg(feature = "graphemes")]
+    #[inline]
+    pub fn graphemes(&self) -> crate::iter::Graphemes<'a> {
+        crate::iter::Graphemes::from(self)
+    }
+
+    /// Returns `true` if the given byte offset lies on a [`char`] boundary.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("Löwe 老虎 Léopard");
+    /// let s = r.byte_slice(3..);
+    ///
+    /// assert!(s.is_char_boundary(0));
+    /// assert!(s.is_char_boundary(s.byte_len()));
+    /// assert!(s.is_char_boundary(6)); // between '老' and '虎'
+    /// assert!(!s.is_char_boundary(12)); // between the 1st and 2nd byte of 'é'
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn is_char_boundary(&self, byte_offset: usize) -> bool {
+        if byte_offset > self.byte_len() {
+            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
+        }
+
+        let (chunk, ByteMetric(chunk_byte_offset)) =
+            self.tree_slice.leaf_at_measure(ByteMetric(byte_offset));
+
+        chunk.is_char_boundary(byte_offset - chunk_byte_offset)
+    }
+
+    /// Returns `true` if the `RopeSlice`'s byte length is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("");
+    /// assert!(r.byte_slice(..).is_empty());
+    ///
+    /// let r = Rope::from("foo");
+    /// assert!(!r.line_slice(..).is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.byte_len() == 0
+    }
+
+    /// Returns `true` if the given byte offset lies on a grapheme cluster
+    /// boundary.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("aargh!\r\n🐻‍❄️");
+    /// let s = r.line_slice(1..);
+    ///
+    /// assert!(s.is_grapheme_boundary(0));
+    /// assert!(s.is_grapheme_boundary(s.byte_len()));
+    /// assert!(!s.is_grapheme_boundary(4)); // between the 1st and 2nd code point of '🐻‍❄️'
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "graphemes")))]
+    #[cfg(feature = "graphemes")]
+    #[track_caller]
+    #[inline]
+    pub fn is_grapheme_boundary(&self, byte_offset: usize) -> bool {
+        if byte_offset > self.byte_len() {
+            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
+        }
+
+        is_grapheme_boundary(*self, byte_offset)
+    }
+
+    /// Returns the line at `line_index`, without its line terminator.
+    ///
+    /// If you want to include the line break consider taking a
+    /// [`line_slice()`](Self::line_slice()) in the
+    /// `line_index..line_index + 1` range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the line index is out of bounds (i.e. greater than or equal
+    /// to [`line_len()`](Self::byte_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo\nbar\r\nbaz");
+    /// let s = r.line_slice(..2);
+    ///
+    /// assert_eq!(s.line(0), "foo");
+    /// assert_eq!(s.line(1), "bar");
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn line(self, line_index: usize) -> RopeSlice<'a> {
+        if line_index >= self.line_len() {
+            panic::line_offset_out_of_bounds(line_index, self.line_len());
+        }
+
+        let tree_slice = self
+            .tree_slice
+            .slice(RawLineMetric(line_index)..RawLineMetric(line_index + 1));
+
+        let mut line = Self {
+            tree_slice,
+            has_trailing_newline: false,
+            #[cfg(feature = "grapheme-metric")]
+            graphemes: None,
+        };
+
+        if line.tree_slice.summary().line_breaks() == 1 {
+            line.truncate_trailing_line_break();
+        }
+
+        line
+    }
+
+    /// Returns the number of lines in the `RopeSlice`.
+    ///
+    /// The final line break is optional and doesn't count as a separate empty
+    /// line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("a\nb\r\n");
+    ///
+    /// let s = r.byte_slice(..);
     /// assert_eq!(s.line_len(), 2);
     ///
     /// let s = r.byte_slice(..3);
     /// assert_eq!(s.line_len(), 2);
     ///
-    /// let s = r.byte_slice(..2);
-    /// assert_eq!(s.line_len(), 1);
+    /// let s = r.byte_slice(..2);
+    /// assert_eq!(s.line_len(), 1);
+    ///
+    /// let s = r.byte_slice(..1);
+    /// assert_eq!(s.line_len(), 1);
+    ///
+    /// let s = r.byte_slice(..0);
+    /// assert_eq!(s.line_len(), 0);
+    /// ```
+    #[inline]
+    pub fn line_len(&self) -> usize {
+        self.tree_slice.summary().line_breaks() + 1
+            - (self.has_trailing_newline as usize)
+            - (self.is_empty() as usize)
+    }
+
+    /// Returns the line offset of the given byte.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo\nbar\r\nbaz");
+    ///
+    /// let s = r.byte_slice(..);
+    /// assert_eq!(s.line_of_byte(0), 0);
+    /// assert_eq!(s.line_of_byte(4), 1);
+    ///
+    /// let s = r.line_slice(1..);
+    /// assert_eq!(s.line_of_byte(0), 0);
+    /// assert_eq!(s.line_of_byte(4), 0); // between the '\r' and the '\n'
+    /// assert_eq!(s.line_of_byte(s.byte_len()), 1);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn line_of_byte(&self, byte_offset: usize) -> usize {
+        if byte_offset > self.byte_len() {
+            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
+        }
+
+        let RawLineMetric(line_offset) =
+            self.tree_slice.convert_measure(ByteMetric(byte_offset));
+
+        line_offset
+    }
+
+    /// Returns a sub-slice of this `RopeSlice` in the specified line range,
+    /// where the start and end of the range are interpreted as offsets.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start is greater than the end or if the end is out of
+    /// bounds (i.e. greater than [`line_len()`](Self::line_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo\nbar\r\nbaz\nfoobar\n");
+    /// let s = r.byte_slice(2..17);
+    ///
+    /// assert_eq!(s.line_slice(..1), "o\n");
+    /// assert_eq!(s.line_slice(1..3), "bar\r\nbaz\n");
+    /// assert_eq!(s.line_slice(3..4), "foob");
+    /// assert_eq!(s.line_slice(4..), "");
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn line_slice<R>(self, line_range: R) -> RopeSlice<'a>
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) =
+            range_bounds_to_start_end(line_range, 0, self.line_len());
+
+        if start > end {
+            panic::line_start_after_end(start, end);
+        }
+
+        if end > self.line_len() {
+            panic::line_offset_out_of_bounds(end, self.line_len());
+        }
+
+        let slice: RopeSlice<'a> = self
+            .tree_slice
+            .slice(RawLineMetric(start)..RawLineMetric(end))
+            .into();
+
+        #[cfg(feature = "grapheme-metric")]
+        let slice = RopeSlice {
+            graphemes: self.graphemes.map(|(graphemes, offset)| {
+                (graphemes, offset + self.byte_of_line(start))
+            }),
+            ..slice
+        };
+
+        slice
+    }
+
+    /// Returns an iterator over the lines of this `RopeSlice`, not including
+    /// the line terminators.
+    ///
+    /// The final line break is optional and doesn't cause the iterator to
+    /// return a final empty line.
+    ///
+    /// If you want to include the line breaks consider using the
+    /// [`raw_lines()`](Self::raw_lines()) method instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo\nbar\r\nbaz\n");
+    /// let s = r.line_slice(1..);
+    ///
+    /// let mut lines = s.lines();
+    ///
+    /// assert_eq!("bar", lines.next().unwrap());
+    /// assert_eq!("baz", lines.next().unwrap());
+    /// assert_eq!(None, lines.next());
+    /// ```
+    #[inline]
+    pub fn lines(&self) -> Lines<'a> {
+        Lines::from(self)
+    }
+
+    /// Returns an iterator over the lines of this `RopeSlice` starting at the
+    /// given line offset, not including the line breaks.
+    ///
+    /// The iterator yields the lines from the `line_offset`-th one onwards
+    /// from both of its ends, while the ones before it can be yielded in
+    /// reverse order by calling [`Lines::prev()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the line offset is out of bounds (i.e. greater than
+    /// [`line_len()`](Self::line_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo\nbar\nbaz\n");
+    /// let s = r.byte_slice(..);
+    ///
+    /// let mut lines = s.lines_at(1);
+    ///
+    /// assert_eq!(lines.len(), 2);
+    /// assert_eq!("bar", lines.next().unwrap());
+    /// assert_eq!("baz", lines.next_back().unwrap());
+    /// assert_eq!(None, lines.next());
+    /// assert_eq!("foo", lines.prev().unwrap());
+    /// assert_eq!(None, lines.prev());
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn lines_at(&self, line_offset: usize) -> Lines<'a> {
+        Lines::at(self, line_offset)
+    }
+
+    /// Returns the byte offset of the closest grapheme cluster boundary
+    /// strictly after the given byte offset, or the byte offset itself if
+    /// it's equal to [`byte_len()`](Self::byte_len()).
+    ///
+    /// This runs in logarithmic time plus the size of the grapheme clusters
+    /// around the offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("aargh!\r\n🐻‍❄️");
+    /// let s = r.byte_slice(5..);
+    /// assert_eq!(s.next_grapheme_boundary(0), 1);
+    /// assert_eq!(s.next_grapheme_boundary(1), 3); // skips the "\r\n"
+    /// assert_eq!(s.next_grapheme_boundary(4), s.byte_len()); // inside '🐻‍❄️'
+    /// assert_eq!(s.next_grapheme_boundary(s.byte_len()), s.byte_len());
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "graphemes")))]
+    #[cfg(feature = "graphemes")]
+    #[track_caller]
+    #[inline]
+    pub fn next_grapheme_boundary(&self, byte_offset: usize) -> usize {
+        if byte_offset > self.byte_len() {
+            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
+        }
+
+        next_grapheme_boundary(*self, byte_offset)
+    }
+
+    /// Returns the byte offset of the closest Unicode word boundary strictly
+    /// after the given byte offset, or the byte offset itself if it's equal to
+    /// [`byte_len()`](Self::byte_len()).
+    ///
+    /// This can be used to implement word-wise cursor movements. It runs in
+    /// logarithmic time plus the length of the words around the offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("Hello, world!\nfoo_bar");
+    /// let s = r.byte_slice(7..);
+    /// assert_eq!(s.next_word_boundary(0), 5);
+    /// assert_eq!(s.next_word_boundary(2), 5); // inside "world"
+    /// assert_eq!(s.next_word_boundary(7), s.byte_len());
+    /// assert_eq!(s.next_word_boundary(s.byte_len()), s.byte_len());
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "words")))]
+    #[cfg(feature = "words")]
+    #[track_caller]
+    #[inline]
+    pub fn next_word_boundary(&self, byte_offset: usize) -> usize {
+        if byte_offset > self.byte_len() {
+            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
+        }
+
+        words::next_word_boundary(*self, byte_offset)
+    }
+
+    /// Returns an iterator over the paragraphs of this `RopeSlice`, i.e. the
+    /// runs of consecutive lines which are not blank, together with their
+    /// byte ranges.
+    ///
+    /// A line is blank if it only contains whitespace. The range of a
+    /// paragraph goes from the start of its first line to the end of its last
+    /// line, not including the line terminator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo\nbar\n\n  \nbaz\n");
+    /// let s = r.byte_slice(4..);
+    ///
+    /// let mut paragraphs = s.paragraphs();
+    ///
+    /// let (baz, range) = paragraphs.next_back().unwrap();
+    /// assert_eq!(baz, "baz");
+    /// assert_eq!(range, 8..11);
+    ///
+    /// let (bar, range) = paragraphs.next_back().unwrap();
+    /// assert_eq!(bar, "bar");
+    /// assert_eq!(range, 0..3);
+    ///
+    /// assert_eq!(paragraphs.next(), None);
+    /// ```
+    #[inline]
+    pub fn paragraphs(&self) -> Paragraphs<'a> {
+        Paragraphs::from(self)
+    }
+
+    /// Returns the [`Point`] corresponding to the given byte offset, with its
+    /// column measured in the given [`PositionEncoding`].
+    ///
+    /// This runs in logarithmic time if the metric of the encoding is
+    /// enabled (`utf16-metric` for UTF-16, `char-metric` for UTF-32), and in
+    /// time linear in the length of the line otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())) or if it doesn't lie on a code point
+    /// boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Point, PositionEncoding, Rope};
+    /// #
+    /// let r = Rope::from("foo\nb🐸r\n");
+    /// let s = r.byte_slice(..);
+    ///
+    /// assert_eq!(s.point_of_byte(9, PositionEncoding::Utf8), Point::new(1, 5));
+    /// assert_eq!(s.point_of_byte(9, PositionEncoding::Utf16), Point::new(1, 3));
+    /// assert_eq!(s.point_of_byte(9, PositionEncoding::Utf32), Point::new(1, 2));
+    ///
+    /// // The offset right after the trailing line break is on a new line.
+    /// assert_eq!(s.point_of_byte(11, PositionEncoding::Utf8), Point::new(2, 0));
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn point_of_byte(
+        &self,
+        byte_offset: usize,
+        encoding: PositionEncoding,
+    ) -> Point {
+        if byte_offset > self.byte_len() {
+            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
+        }
+
+        let line = self.line_of_byte(byte_offset);
+
+        let line_start = self.byte_of_line(line);
+
+        let column =
+            len_in(self.byte_slice(line_start..byte_offset), encoding);
+
+        Point::new(line, column)
+    }
+
+    /// Returns the byte offset of the closest grapheme cluster boundary
+    /// strictly before the given byte offset, or zero if the byte offset is
+    /// zero.
+    ///
+    /// This runs in logarithmic time plus the size of the grapheme clusters
+    /// around the offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("aargh!\r\n🐻‍❄️");
+    /// let s = r.byte_slice(5..);
+    /// assert_eq!(s.prev_grapheme_boundary(s.byte_len()), 3);
+    /// assert_eq!(s.prev_grapheme_boundary(3), 1); // skips the "\r\n"
+    /// assert_eq!(s.prev_grapheme_boundary(2), 1); // between '\r' and '\n'
+    /// assert_eq!(s.prev_grapheme_boundary(0), 0);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "graphemes")))]
+    #[cfg(feature = "graphemes")]
+    #[track_caller]
+    #[inline]
+    pub fn prev_grapheme_boundary(&self, byte_offset: usize) -> usize {
+        if byte_offset > self.byte_len() {
+            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
+        }
+
+        prev_grapheme_boundary(*self, byte_offset)
+    }
+
+    /// Returns the byte offset of the closest Unicode word boundary strictly
+    /// before the given byte offset, or zero if the byte offset is zero.
+    ///
+    /// This can be used to implement word-wise cursor movements. It runs in
+    /// logarithmic time plus the length of the words around the offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("Hello, world!\nfoo_bar");
+    /// let s = r.byte_slice(7..);
+    /// assert_eq!(s.prev_word_boundary(s.byte_len()), 7);
+    /// assert_eq!(s.prev_word_boundary(7), 6);
+    /// assert_eq!(s.prev_word_boundary(2), 0); // inside "world"
+    /// assert_eq!(s.prev_word_boundary(0), 0);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "words")))]
+    #[cfg(feature = "words")]
+    #[track_caller]
+    #[inline]
+    pub fn prev_word_boundary(&self, byte_offset: usize) -> usize {
+        if byte_offset > self.byte_len() {
+            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
+        }
+
+        words::prev_word_boundary(*self, byte_offset)
+    }
+
+    /// Returns an iterator over the lines of this `RopeSlice`, including the
+    /// line terminators.
+    ///
+    /// The final line break is optional and doesn't cause the iterator to
+    /// return a final empty line.
+    ///
+    /// If you don't  want to include the line breaks consider using the
+    /// [`raw_lines()`](Self::raw_lines()) method instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo\nbar\r\nbaz\n");
+    /// let s = r.byte_slice(..r.byte_len() - 1);
+    ///
+    /// let mut raw_lines = s.raw_lines();
+    ///
+    /// assert_eq!("foo\n", raw_lines.next().unwrap());
+    /// assert_eq!("bar\r\n", raw_lines.next().unwrap());
+    /// assert_eq!("baz", raw_lines.next().unwrap());
+    /// assert_eq!(None, raw_lines.next());
+    ///
+    /// let s = r.byte_slice(..);
+    ///
+    /// let mut raw_lines = s.raw_lines();
+    ///
+    /// assert_eq!("foo\n", raw_lines.next().unwrap());
+    /// assert_eq!("bar\r\n", raw_lines.next().unwrap());
+    /// assert_eq!("baz\n", raw_lines.next().unwrap());
+    /// assert_eq!(None, raw_lines.next());
+    /// ```
+    #[inline]
+    pub fn raw_lines(&self) -> RawLines<'a> {
+        RawLines::from(self)
+    }
+
+    /// Removes the last char from the range spanned by this slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice is empty or if the relative offset is not on a char
+    /// boundary.
+    #[inline]
+    pub(super) fn truncate_last_char(&mut self) {
+        debug_assert!(!self.is_empty());
+
+        let slice = &mut self.tree_slice;
+
+        let last_char_len = slice
+            .end_slice
+            .last_chunk()
+            .chars()
+            .next_back()
+            .expect("the end slice is never empty")
+            .len_utf8();
+
+        // The last slice only contains the last char so we have to re-slice.
+        if slice.end_summary.bytes() == last_char_len {
+            *self = self.byte_slice(..self.byte_len() - last_char_len);
+        }
+        // The last slice contains other chars before the last one so we can
+        // just mutate in place.
+        else {
+            let last = &mut slice.end_slice;
+
+            slice.summary -= slice.end_summary;
+
+            let new_end_summary = last.truncate_last_char(slice.end_summary);
+
+            slice.end_summary = new_end_summary;
+
+            slice.summary += slice.end_summary;
+
+            if slice.leaf_count() == 1 {
+                slice.start_slice = slice.end_slice;
+                slice.start_summary = slice.end_summary;
+            }
+        }
+    }
+
+    /// Removes the trailing line break (including the `\r` of a CRLF pair)
+    /// from the range spanned by this slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this slice doesn't have a trailing line break.
+    #[inline]
+    pub(super) fn truncate_trailing_line_break(&mut self) {
+        let last_chunk = self.tree_slice.end_slice().last_chunk();
+
+        debug_assert!(ends_with_line_break(last_chunk));
+
+        let is_lf = last_chunk.ends_with('\n');
+
+        self.truncate_last_char();
+
+        if is_lf && self.tree_slice.end_slice().last_chunk().ends_with('\r') {
+            self.truncate_last_char();
+        }
+    }
+
+    /// Returns the byte range of the last match of the pattern in the
+    /// `RopeSlice`, or `None` if there isn't one.
+    ///
+    /// An empty pattern matches at the end of the `RopeSlice`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("Hello, foo\nbar 🐸\nbaz");
+    /// let s = r.byte_slice(7..);
+    /// assert_eq!(s.rfind('a'), Some(14..15));
+    /// assert_eq!(s.rfind("🐸"), Some(8..12));
+    /// assert_eq!(s.rfind("qux"), None);
+    /// ```
+    #[inline]
+    pub fn rfind<'p, P: Pattern<'p>>(
+        &self,
+        pattern: P,
+    ) -> Option<Range<usize>> {
+        search::rfind(*self, Needle::new(pattern))
+    }
+
+    /// Returns an iterator over the Unicode sentences of this `RopeSlice`.
+    ///
+    /// Every sentence includes the whitespace and line break following it, so
+    /// concatenating all the items gives back the whole text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("Hello there. How are you?\nFine, thanks!");
+    /// let s = r.byte_slice(6..);
+    ///
+    /// let mut sentences = s.sentences();
+    ///
+    /// assert_eq!(Some("there. "), sentences.next().as_deref());
+    /// assert_eq!(Some("Fine, thanks!"), sentences.next_back().as_deref());
+    /// assert_eq!(Some("How are you?\n"), sentences.next().as_deref());
+    /// assert_eq!(None, sentences.next());
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "sentences")))]
+    #[cfg(feature = "sentences")]
+    #[inline]
+    pub fn sentences(&self) -> crate::iter::Sentences<'a> {
+        crate::iter::Sentences::new(*self)
+    }
+
+    /// Non-panicking version of [`byte()`](Self::byte()).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RopeError::IndexOutOfBounds`] if the index is out of bounds
+    /// (i.e. greater than or equal to [`byte_len()`](Self::byte_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, RopeError, Unit};
+    /// #
+    /// let r = Rope::from("bar");
+    /// let s = r.byte_slice(..);
+    ///
+    /// assert_eq!(s.try_byte(1), Ok(b'a'));
+    ///
+    /// assert_eq!(
+    ///     s.try_byte(3),
+    ///     Err(RopeError::IndexOutOfBounds {
+    ///         unit: Unit::Byte,
+    ///         index: 3,
+    ///         len: 3
+    ///     })
+    /// );
+    /// ```
+    #[inline]
+    pub fn try_byte(&self, byte_index: usize) -> Result<u8, RopeError> {
+        check_index(Unit::Byte, byte_index, self.byte_len())?;
+        Ok(self.byte(byte_index))
+    }
+
+    /// Non-panicking version of [`byte_of_char()`](Self::byte_of_char()).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RopeError::OffsetOutOfBounds`] if the char offset is out of
+    /// bounds (i.e. greater than [`char_len()`](Self::char_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, RopeError, Unit};
+    /// #
+    /// let r = Rope::from("a🐸b");
+    /// let s = r.byte_slice(..);
+    ///
+    /// assert_eq!(s.try_byte_of_char(2), Ok(5));
+    ///
+    /// assert_eq!(
+    ///     s.try_byte_of_char(4),
+    ///     Err(RopeError::OffsetOutOfBounds {
+    ///         unit: Unit::Char,
+    ///         offset: 4,
+    ///         len: 3
+    ///     })
+    /// );
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "char-metric")))]
+    #[cfg(feature = "char-metric")]
+    #[inline]
+    pub fn try_byte_of_char(
+        &self,
+        char_offset: usize,
+    ) -> Result<usize, RopeError> {
+        check_offset(Unit::Char, char_offset, self.char_len())?;
+        Ok(self.byte_of_char(char_offset))
+    }
+
+    /// Non-panicking version of [`byte_of_line()`](Self::byte_of_line()).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RopeError::OffsetOutOfBounds`] if the line offset is out of
+    /// bounds (i.e. greater than [`line_len()`](Self::line_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, RopeError, Unit};
+    /// #
+    /// let r = Rope::from("foo\nbar\r\nbaz");
+    /// let s = r.byte_slice(..);
+    ///
+    /// assert_eq!(s.try_byte_of_line(2), Ok(9));
+    ///
+    /// assert_eq!(
+    ///     s.try_byte_of_line(4),
+    ///     Err(RopeError::OffsetOutOfBounds {
+    ///         unit: Unit::Line,
+    ///         offset: 4,
+    ///         len: 3
+    ///     })
+    /// );
+    /// ```
+    #[inline]
+    pub fn try_byte_of_line(
+        &self,
+        line_offset: usize,
+    ) -> Result<usize, RopeError> {
+        check_offset(Unit::Line, line_offset, self.line_len())?;
+        Ok(self.byte_of_line(line_offset))
+    }
+
+    /// Non-panicking version of
+    /// [`byte_of_utf16_code_unit()`](Self::byte_of_utf16_code_unit()).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RopeError::OffsetOutOfBounds`] if the UTF-16 offset is out of
+    /// bounds (i.e. greater than [`utf16_len()`](Self::utf16_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, RopeError, Unit};
+    /// #
+    /// let r = Rope::from("a🐸b");
+    /// let s = r.byte_slice(..);
     ///
-    /// let s = r.byte_slice(..1);
-    /// assert_eq!(s.line_len(), 1);
+    /// assert_eq!(s.try_byte_of_utf16_code_unit(3), Ok(5));
     ///
-    /// let s = r.byte_slice(..0);
-    /// assert_eq!(s.line_len(), 0);
+    /// assert_eq!(
+    ///     s.try_byte_of_utf16_code_unit(5),
+    ///     Err(RopeError::OffsetOutOfBounds {
+    ///         unit: Unit::Utf16CodeUnit,
+    ///         offset: 5,
+    ///         len: 4
+    ///     })
+    /// );
     /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "utf16-metric")))]
+    #[cfg(feature = "utf16-metric")]
     #[inline]
-    pub fn line_len(&self) -> usize {
-        self.tree_slice.summary().line_breaks() + 1
-            - (self.has_trailing_newline as usize)
-            - (self.is_empty() as usize)
+    pub fn try_byte_of_utf16_code_unit(
+        &self,
+        utf16_offset: usize,
+    ) -> Result<usize, RopeError> {
+        check_offset(Unit::Utf16CodeUnit, utf16_offset, self.utf16_len())?;
+        Ok(self.byte_of_utf16_code_unit(utf16_offset))
     }
 
-    /// Returns the line offset of the given byte.
+    /// Non-panicking version of [`byte_slice()`](Self::byte_slice()).
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// Panics if the byte offset is out of bounds (i.e. greater than
-    /// [`byte_len()`](Self::byte_len())).
+    /// Returns [`RopeError::StartAfterEnd`] if the start of the byte range is
+    /// greater than its end, [`RopeError::OffsetOutOfBounds`] if the end is
+    /// greater than [`byte_len()`](Self::byte_len()) and
+    /// [`RopeError::NotCharBoundary`] if the start or the end don't lie on a
+    /// code point boundary.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use crop::Rope;
+    /// # use crop::{Rope, RopeError, Unit};
     /// #
-    /// let r = Rope::from("foo\nbar\r\nbaz");
-    ///
+    /// let r = Rope::from("🗻∈🌏");
     /// let s = r.byte_slice(..);
-    /// assert_eq!(s.line_of_byte(0), 0);
-    /// assert_eq!(s.line_of_byte(4), 1);
     ///
-    /// let s = r.line_slice(1..);
-    /// assert_eq!(s.line_of_byte(0), 0);
-    /// assert_eq!(s.line_of_byte(4), 0); // between the '\r' and the '\n'
-    /// assert_eq!(s.line_of_byte(s.byte_len()), 1);
+    /// assert_eq!(s.try_byte_slice(4..7).unwrap(), "∈");
+    ///
+    /// assert_eq!(
+    ///     s.try_byte_slice(7..4),
+    ///     Err(RopeError::StartAfterEnd { unit: Unit::Byte, start: 7, end: 4 })
+    /// );
+    ///
+    /// assert_eq!(
+    ///     s.try_byte_slice(..2),
+    ///     Err(RopeError::NotCharBoundary { byte_offset: 2 })
+    /// );
     /// ```
-    #[track_caller]
     #[inline]
-    pub fn line_of_byte(&self, byte_offset: usize) -> usize {
-        if byte_offset > self.byte_len() {
-            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
-        }
+    pub fn try_byte_slice<R>(
+        self,
+        byte_range: R,
+    ) -> Result<RopeSlice<'a>, RopeError>
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) =
+            range_bounds_to_start_end(byte_range, 0, self.byte_len());
 
-        let RawLineMetric(line_offset) =
-            self.tree_slice.convert_measure(ByteMetric(byte_offset));
+        check_byte_range(self, start, end)?;
 
-        line_offset
+        Ok(self.byte_slice(start..end))
     }
 
-    /// Returns a sub-slice of this `RopeSlice` in the specified line range,
-    /// where the start and end of the range are interpreted as offsets.
+    /// Non-panicking version of [`char_of_byte()`](Self::char_of_byte()).
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// Panics if the start is greater than the end or if the end is out of
-    /// bounds (i.e. greater than [`line_len()`](Self::line_len())).
+    /// Returns [`RopeError::OffsetOutOfBounds`] if the byte offset is out of
+    /// bounds (i.e. greater than [`byte_len()`](Self::byte_len())) and
+    /// [`RopeError::NotCharBoundary`] if it doesn't lie on a code point
+    /// boundary.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use crop::Rope;
+    /// # use crop::{Rope, RopeError, Unit};
     /// #
-    /// let r = Rope::from("foo\nbar\r\nbaz\nfoobar\n");
-    /// let s = r.byte_slice(2..17);
+    /// let r = Rope::from("a🐸b");
+    /// let s = r.byte_slice(..);
     ///
-    /// assert_eq!(s.line_slice(..1), "o\n");
-    /// assert_eq!(s.line_slice(1..3), "bar\r\nbaz\n");
-    /// assert_eq!(s.line_slice(3..4), "foob");
-    /// assert_eq!(s.line_slice(4..), "");
+    /// assert_eq!(s.try_char_of_byte(5), Ok(2));
+    ///
+    /// assert_eq!(
+    ///     s.try_char_of_byte(3),
+    ///     Err(RopeError::NotCharBoundary { byte_offset: 3 })
+    /// );
     /// ```
-    #[track_caller]
+    #[cfg_attr(docsrs, doc(cfg(feature = "char-metric")))]
+    #[cfg(feature = "char-metric")]
     #[inline]
-    pub fn line_slice<R>(self, line_range: R) -> RopeSlice<'a>
+    pub fn try_char_of_byte(
+        &self,
+        byte_offset: usize,
+    ) -> Result<usize, RopeError> {
+        check_byte_offset(*self, byte_offset)?;
+        Ok(self.char_of_byte(byte_offset))
+    }
+
+    /// Non-panicking version of [`char_slice()`](Self::char_slice()).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RopeError::StartAfterEnd`] if the start of the char range is
+    /// greater than its end and [`RopeError::OffsetOutOfBounds`] if the end is
+    /// greater than [`char_len()`](Self::char_len()).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, RopeError, Unit};
+    /// #
+    /// let r = Rope::from("🗻∈🌏");
+    /// let s = r.byte_slice(..);
+    ///
+    /// assert_eq!(s.try_char_slice(1..2).unwrap(), "∈");
+    ///
+    /// assert_eq!(
+    ///     s.try_char_slice(..4),
+    ///     Err(RopeError::OffsetOutOfBounds {
+    ///         unit: Unit::Char,
+    ///         offset: 4,
+    ///         len: 3
+    ///     })
+    /// );
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "char-metric")))]
+    #[cfg(feature = "char-metric")]
+    #[inline]
+    pub fn try_char_slice<R>(
+        self,
+        char_range: R,
+    ) -> Result<RopeSlice<'a>, RopeError>
     where
         R: RangeBounds<usize>,
     {
         let (start, end) =
-            range_bounds_to_start_end(line_range, 0, self.line_len());
-
-        if start > end {
-            panic::line_start_after_end(start, end);
-        }
+            range_bounds_to_start_end(char_range, 0, self.char_len());
 
-        if end > self.line_len() {
-            panic::line_offset_out_of_bounds(end, self.line_len());
-        }
+        check_range(Unit::Char, start, end, self.char_len())?;
 
-        self.tree_slice.slice(RawLineMetric(start)..RawLineMetric(end)).into()
+        Ok(self.char_slice(start..end))
     }
 
-    /// Returns an iterator over the lines of this `RopeSlice`, not including
-    /// the line terminators.
+    /// Non-panicking version of [`line()`](Self::line()).
     ///
-    /// The final line break is optional and doesn't cause the iterator to
-    /// return a final empty line.
+    /// # Errors
     ///
-    /// If you want to include the line breaks consider using the
-    /// [`raw_lines()`](Self::raw_lines()) method instead.
+    /// Returns [`RopeError::IndexOutOfBounds`] if the line index is out of
+    /// bounds (i.e. greater than or equal to
+    /// [`line_len()`](Self::line_len())).
     ///
     /// # Examples
     ///
     /// ```
-    /// # use crop::Rope;
+    /// # use crop::{Rope, RopeError, Unit};
     /// #
     /// let r = Rope::from("foo\nbar\r\nbaz\n");
-    /// let s = r.line_slice(1..);
+    /// let s = r.byte_slice(..);
     ///
-    /// let mut lines = s.lines();
+    /// assert_eq!(s.try_line(1).unwrap(), "bar");
     ///
-    /// assert_eq!("bar", lines.next().unwrap());
-    /// assert_eq!("baz", lines.next().unwrap());
-    /// assert_eq!(None, lines.next());
+    /// assert_eq!(
+    ///     s.try_line(3),
+    ///     Err(RopeError::IndexOutOfBounds {
+    ///         unit: Unit::Line,
+    ///         index: 3,
+    ///         len: 3
+    ///     })
+    /// );
     /// ```
     #[inline]
-    pub fn lines(&self) -> Lines<'a> {
-        Lines::from(self)
+    pub fn try_line(
+        self,
+        line_index: usize,
+    ) -> Result<RopeSlice<'a>, RopeError> {
+        check_index(Unit::Line, line_index, self.line_len())?;
+        Ok(self.line(line_index))
     }
 
-    /// Returns an iterator over the lines of this `RopeSlice`, including the
-    /// line terminators.
+    /// Non-panicking version of [`line_of_byte()`](Self::line_of_byte()).
     ///
-    /// The final line break is optional and doesn't cause the iterator to
-    /// return a final empty line.
+    /// # Errors
     ///
-    /// If you don't  want to include the line breaks consider using the
-    /// [`raw_lines()`](Self::raw_lines()) method instead.
+    /// Returns [`RopeError::OffsetOutOfBounds`] if the byte offset is out of
+    /// bounds (i.e. greater than [`byte_len()`](Self::byte_len())) and
+    /// [`RopeError::NotCharBoundary`] if it doesn't lie on a code point
+    /// boundary.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use crop::Rope;
+    /// # use crop::{Rope, RopeError, Unit};
     /// #
-    /// let r = Rope::from("foo\nbar\r\nbaz\n");
-    /// let s = r.byte_slice(..r.byte_len() - 1);
+    /// let r = Rope::from("foo\nbar\r\nbaz");
+    /// let s = r.byte_slice(..);
     ///
-    /// let mut raw_lines = s.raw_lines();
+    /// assert_eq!(s.try_line_of_byte(9), Ok(2));
     ///
-    /// assert_eq!("foo\n", raw_lines.next().unwrap());
-    /// assert_eq!("bar\r\n", raw_lines.next().unwrap());
-    /// assert_eq!("baz", raw_lines.next().unwrap());
-    /// assert_eq!(None, raw_lines.next());
+    /// assert_eq!(
+    ///     s.try_line_of_byte(13),
+    ///     Err(RopeError::OffsetOutOfBounds {
+    ///         unit: Unit::Byte,
+    ///         offset: 13,
+    ///         len: 12
+    ///     })
+    /// );
+    /// ```
+    #[inline]
+    pub fn try_line_of_byte(
+        &self,
+        byte_offset: usize,
+    ) -> Result<usize, RopeError> {
+        check_byte_offset(*self, byte_offset)?;
+        Ok(self.line_of_byte(byte_offset))
+    }
+
+    /// Non-panicking version of [`line_slice()`](Self::line_slice()).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RopeError::StartAfterEnd`] if the start of the line range is
+    /// greater than its end and [`RopeError::OffsetOutOfBounds`] if the end is
+    /// greater than [`line_len()`](Self::line_len()).
+    ///
+    /// # Examples
     ///
+    /// ```
+    /// # use crop::{Rope, RopeError, Unit};
+    /// #
+    /// let r = Rope::from("foo\nbar\r\nbaz\n");
     /// let s = r.byte_slice(..);
     ///
-    /// let mut raw_lines = s.raw_lines();
+    /// assert_eq!(s.try_line_slice(1..).unwrap(), "bar\r\nbaz\n");
     ///
-    /// assert_eq!("foo\n", raw_lines.next().unwrap());
-    /// assert_eq!("bar\r\n", raw_lines.next().unwrap());
-    /// assert_eq!("baz\n", raw_lines.next().unwrap());
-    /// assert_eq!(None, raw_lines.next());
+    /// assert_eq!(
+    ///     s.try_line_slice(..4),
+    ///     Err(RopeError::OffsetOutOfBounds {
+    ///         unit: Unit::Line,
+    ///         offset: 4,
+    ///         len: 3
+    ///     })
+    /// );
     /// ```
     #[inline]
-    pub fn raw_lines(&self) -> RawLines<'a> {
-        RawLines::from(self)
+    pub fn try_line_slice<R>(
+        self,
+        line_range: R,
+    ) -> Result<RopeSlice<'a>, RopeError>
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) =
+            range_bounds_to_start_end(line_range, 0, self.line_len());
+
+        check_range(Unit::Line, start, end, self.line_len())?;
+
+        Ok(self.line_slice(start..end))
     }
 
-    /// Removes the last char from the range spanned by this slice.
+    /// Non-panicking version of
+    /// [`utf16_code_unit_of_byte()`](Self::utf16_code_unit_of_byte()).
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// Panics if the slice is empty or if the relative offset is not on a char
+    /// Returns [`RopeError::OffsetOutOfBounds`] if the byte offset is out of
+    /// bounds (i.e. greater than [`byte_len()`](Self::byte_len())) and
+    /// [`RopeError::NotCharBoundary`] if it doesn't lie on a code point
     /// boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, RopeError, Unit};
+    /// #
+    /// let r = Rope::from("a🐸b");
+    /// let s = r.byte_slice(..);
+    ///
+    /// assert_eq!(s.try_utf16_code_unit_of_byte(5), Ok(3));
+    ///
+    /// assert_eq!(
+    ///     s.try_utf16_code_unit_of_byte(2),
+    ///     Err(RopeError::NotCharBoundary { byte_offset: 2 })
+    /// );
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "utf16-metric")))]
+    #[cfg(feature = "utf16-metric")]
     #[inline]
-    pub(super) fn truncate_last_char(&mut self) {
-        debug_assert!(!self.is_empty());
-
-        let slice = &mut self.tree_slice;
-
-        // The last slice only contains one byte so we have to re-slice.
-        if slice.end_summary.bytes() == 1 {
-            *self = self.byte_slice(..self.byte_len() - 1);
-        }
-        // The last slice contains more than 2 bytes so we can just mutate
-        // in place.
-        else {
-            let last = &mut slice.end_slice;
-
-            slice.summary -= slice.end_summary;
-
-            let new_end_summary = last.truncate_last_char(slice.end_summary);
-
-            slice.end_summary = new_end_summary;
-
-            slice.summary += slice.end_summary;
-
-            if slice.leaf_count() == 1 {
-                slice.start_slice = slice.end_slice;
-                slice.start_summary = slice.end_summary;
-            }
-        }
+    pub fn try_utf16_code_unit_of_byte(
+        &self,
+        byte_offset: usize,
+    ) -> Result<usize, RopeError> {
+        check_byte_offset(*self, byte_offset)?;
+        Ok(self.utf16_code_unit_of_byte(byte_offset))
     }
 
-    /// Removes the trailing line break (either LF or CRLF) from the range
-    /// spanned by this slice.
+    /// Non-panicking version of [`utf16_slice()`](Self::utf16_slice()).
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// Panics if this slice doesn't have a trailing line break.
+    /// Returns [`RopeError::StartAfterEnd`] if the start of the UTF-16 range
+    /// is greater than its end and [`RopeError::OffsetOutOfBounds`] if the
+    /// end is greater than [`utf16_len()`](Self::utf16_len()).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, RopeError, Unit};
+    /// #
+    /// let r = Rope::from("a🐸b");
+    /// let s = r.byte_slice(..);
+    ///
+    /// assert_eq!(s.try_utf16_slice(1..3).unwrap(), "🐸");
+    ///
+    /// assert_eq!(
+    ///     s.try_utf16_slice(3..2),
+    ///     Err(RopeError::StartAfterEnd {
+    ///         unit: Unit::Utf16CodeUnit,
+    ///         start: 3,
+    ///         end: 2
+    ///     })
+    /// );
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "utf16-metric")))]
+    #[cfg(feature = "utf16-metric")]
     #[inline]
-    pub(super) fn truncate_trailing_line_break(&mut self) {
-        debug_assert!(self
-            .tree_slice
-            .end_slice()
-            .last_chunk()
-            .ends_with('\n'));
+    pub fn try_utf16_slice<R>(
+        self,
+        utf16_range: R,
+    ) -> Result<RopeSlice<'a>, RopeError>
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) =
+            range_bounds_to_start_end(utf16_range, 0, self.utf16_len());
 
-        self.truncate_last_char();
+        check_range(Unit::Utf16CodeUnit, start, end, self.utf16_len())?;
 
-        if self.tree_slice.end_slice().last_chunk().ends_with('\r') {
-            self.truncate_last_char();
-        }
+        Ok(self.utf16_slice(start..end))
     }
 
     /// Returns the number of UTF-16 code units this `RopeSlice` would span if
@@ -731,6 +2142,129 @@ impl<'a> RopeSlice<'a> {
 
         self.tree_slice.slice(Utf16Metric(start)..Utf16Metric(end)).into()
     }
+
+    /// Returns the visual column of the given byte offset within its line,
+    /// where a tab advances the column to the next multiple of `tab_width`.
+    ///
+    /// The rest of the line is counted in units, which are graphemes if the
+    /// `graphemes` feature is enabled and chars otherwise, so `"e\u{301}"`
+    /// is one unit with the feature and two without it. Every unit other
+    /// than a tab takes up a single column, or as many columns as its
+    /// [`display_width()`](Self::display_width()) if the `unicode-width`
+    /// feature is enabled. If the offset is inside a unit the column of its
+    /// start is returned.
+    ///
+    /// This walks the line from its start, so it runs in time linear in the
+    /// length of the line.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than
+    /// [`byte_len()`](Self::byte_len())), if it doesn't lie on a char
+    /// boundary or if `tab_width` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("foo\n\tbar\tbaz\n");
+    /// let s = r.byte_slice(5..);
+    ///
+    /// assert_eq!(s.visual_column_of_byte(0, 4), 0);
+    /// assert_eq!(s.visual_column_of_byte(3, 4), 3);
+    /// assert_eq!(s.visual_column_of_byte(4, 4), 4);
+    /// assert_eq!(s.visual_column_of_byte(4, 3), 6);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn visual_column_of_byte(
+        &self,
+        byte_offset: usize,
+        tab_width: usize,
+    ) -> usize {
+        if byte_offset > self.byte_len() {
+            panic::byte_offset_out_of_bounds(byte_offset, self.byte_len());
+        }
+
+        let line_index = self.line_of_byte(byte_offset);
+
+        // The offset is after the last line break, so it's on an empty line.
+        let line = if line_index == self.line_len() {
+            self.byte_slice(byte_offset..)
+        } else {
+            self.line_slice(line_index..line_index + 1)
+        };
+
+        visual_column::column_of_byte(
+            line,
+            byte_offset - self.byte_of_line(line_index),
+            tab_width,
+        )
+    }
+
+    /// Returns an iterator over the Unicode word boundaries of this
+    /// `RopeSlice`, yielding the text between each pair of consecutive
+    /// boundaries.
+    ///
+    /// Unlike [`words()`](Self::words()), this also yields the whitespace and
+    /// punctuation between the words, so concatenating all the items gives
+    /// back the whole text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("Hello, world!");
+    /// let s = r.byte_slice(3..);
+    ///
+    /// let mut bounds = s.word_bounds();
+    ///
+    /// assert_eq!(Some("lo"), bounds.next().as_deref());
+    /// assert_eq!(Some(","), bounds.next().as_deref());
+    /// assert_eq!(Some("!"), bounds.next_back().as_deref());
+    /// assert_eq!(Some(" "), bounds.next().as_deref());
+    /// assert_eq!(Some("world"), bounds.next().as_deref());
+    /// assert_eq!(None, bounds.next());
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "words")))]
+    #[cfg(feature = "words")]
+    #[inline]
+    pub fn word_bounds(&self) -> crate::iter::WordBounds<'a> {
+        crate::iter::WordBounds::new(*self)
+    }
+
+    /// Returns an iterator over the Unicode words of this `RopeSlice`, i.e. the
+    /// segments between its word boundaries containing at least one
+    /// alphanumeric character.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::Rope;
+    /// #
+    /// let r = Rope::from("The quick (\"brown\") fox can't jump 32.3 feet");
+    /// let s = r.byte_slice(5..);
+    ///
+    /// assert_eq!(
+    ///     s.words().collect::<Vec<_>>(),
+    ///     ["uick", "brown", "fox", "can't", "jump", "32.3", "feet"]
+    /// );
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "words")))]
+    #[cfg(feature = "words")]
+    #[inline]
+    pub fn words(&self) -> crate::iter::Words<'a> {
+        crate::iter::Words::new(*self)
+    }
+}
+
+impl<'a> From<&'a Rope> for RopeSlice<'a> {
+    #[inline]
+    fn from(rope: &'a Rope) -> Self {
+        rope.byte_slice(..)
+    }
 }
 
 impl<'a> From<TreeSlice<'a, { Rope::arity() }, RopeChunk>> for RopeSlice<'a> {
@@ -742,6 +2276,9 @@ impl<'a> From<TreeSlice<'a, { Rope::arity() }, RopeChunk>> for RopeSlice<'a> {
                 .has_trailing_newline(),
 
             tree_slice,
+
+            #[cfg(feature = "grapheme-metric")]
+            graphemes: None,
         }
     }
 }
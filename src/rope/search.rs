@@ -0,0 +1,378 @@
+//! Substring search over the chunks of `Rope`s and `RopeSlice`s.
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use super::iterators::Chunks;
+#[cfg(feature = "regex")]
+use super::regex::{self, Regex};
+use super::RopeSlice;
+
+/// A pattern that can be searched for in a [`Rope`](crate::Rope) or a
+/// [`RopeSlice`].
+///
+/// This trait is sealed, and it's implemented for [`char`]s, `&str`s and
+/// `&String`s, plus [`&Regex`](crate::regex::Regex)s if the `regex` feature
+/// is enabled.
+pub trait Pattern<'p>: sealed::Sealed<'p> {}
+
+mod sealed {
+    pub trait Sealed<'p> {
+        fn into_needle(self) -> super::Needle<'p>;
+    }
+}
+
+impl Pattern<'_> for char {}
+
+impl sealed::Sealed<'_> for char {
+    #[inline]
+    fn into_needle(self) -> Needle<'static> {
+        let mut bytes = [0; 4];
+        let len = self.encode_utf8(&mut bytes).len();
+        Needle::Char { bytes, len: len as u8 }
+    }
+}
+
+impl<'p> Pattern<'p> for &'p str {}
+
+impl<'p> sealed::Sealed<'p> for &'p str {
+    #[inline]
+    fn into_needle(self) -> Needle<'p> {
+        Needle::Str(self)
+    }
+}
+
+impl<'p> Pattern<'p> for &'p String {}
+
+impl<'p> sealed::Sealed<'p> for &'p String {
+    #[inline]
+    fn into_needle(self) -> Needle<'p> {
+        Needle::Str(self.as_str())
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "regex")))]
+#[cfg(feature = "regex")]
+impl<'p> Pattern<'p> for &'p Regex {}
+
+#[cfg(feature = "regex")]
+impl<'p> sealed::Sealed<'p> for &'p Regex {
+    #[inline]
+    fn into_needle(self) -> Needle<'p> {
+        Needle::Regex(self)
+    }
+}
+
+/// What a [`Pattern`] is converted to before searching for it.
+#[derive(Copy, Clone)]
+pub enum Needle<'p> {
+    Str(&'p str),
+    Char {
+        bytes: [u8; 4],
+        len: u8,
+    },
+    #[cfg(feature = "regex")]
+    Regex(&'p Regex),
+}
+
+impl<'p> Needle<'p> {
+    #[inline]
+    pub(super) fn new<P: Pattern<'p>>(pattern: P) -> Self {
+        sealed::Sealed::into_needle(pattern)
+    }
+
+    /// Returns the string to search for, or `None` if the needle is a regex.
+    #[inline]
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Str(s) => Some(s),
+
+            // SAFETY: the bytes were written by `char::encode_utf8()`.
+            Self::Char { bytes, len } => Some(unsafe {
+                core::str::from_utf8_unchecked(&bytes[..*len as usize])
+            }),
+
+            #[cfg(feature = "regex")]
+            Self::Regex(_) => None,
+        }
+    }
+}
+
+/// An iterator over the byte ranges of the non-overlapping matches of a
+/// [`Pattern`] in `Rope`s and `RopeSlice`s.
+///
+/// This struct is created by the `find_iter` method on
+/// [`Rope`](crate::Rope::find_iter()) and
+/// [`RopeSlice`](RopeSlice::find_iter()). See their documentation for more.
+#[derive(Clone)]
+pub struct Matches<'a, 'p> {
+    inner: MatchesInner<'a, 'p>,
+}
+
+// Literal patterns are the common case, so we don't want to box them just to
+// save space when searching for a regex.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone)]
+enum MatchesInner<'a, 'p> {
+    Str(StrMatches<'a, 'p>),
+    #[cfg(feature = "regex")]
+    Regex(regex::Matches<'p, 'a>),
+}
+
+impl<'a, 'p> Matches<'a, 'p> {
+    #[inline]
+    pub(super) fn new(slice: RopeSlice<'a>, needle: Needle<'p>) -> Self {
+        let inner = match needle {
+            #[cfg(feature = "regex")]
+            Needle::Regex(regex) => {
+                MatchesInner::Regex(regex.find_iter(slice))
+            },
+
+            _ => MatchesInner::Str(StrMatches::new(slice, needle)),
+        };
+
+        Self { inner }
+    }
+}
+
+impl Iterator for Matches<'_, '_> {
+    type Item = Range<usize>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            MatchesInner::Str(matches) => matches.next(),
+
+            #[cfg(feature = "regex")]
+            MatchesInner::Regex(matches) => matches.next(),
+        }
+    }
+}
+
+impl core::iter::FusedIterator for Matches<'_, '_> {}
+
+/// The [`Matches`] of a `&str` or `char` pattern.
+#[derive(Clone)]
+struct StrMatches<'a, 'p> {
+    slice: RopeSlice<'a>,
+    chunks: Chunks<'a>,
+    needle: Needle<'p>,
+
+    /// The chunk currently being searched.
+    chunk: &'a str,
+
+    /// The byte offset of `chunk` in `slice`.
+    chunk_byte_offset: usize,
+
+    /// The byte offset the next match can start at.
+    search_from: usize,
+
+    /// Whether the empty match at the end of the slice has been yielded.
+    /// Only used when searching for an empty pattern.
+    yielded_end: bool,
+}
+
+impl<'a, 'p> StrMatches<'a, 'p> {
+    #[inline]
+    fn new(slice: RopeSlice<'a>, needle: Needle<'p>) -> Self {
+        Self {
+            chunks: slice.chunks(),
+            slice,
+            needle,
+            chunk: "",
+            chunk_byte_offset: 0,
+            search_from: 0,
+            yielded_end: false,
+        }
+    }
+
+    /// Returns the start of the first match that starts in the current chunk
+    /// at or after `search_from` and ends in one of the following chunks, if
+    /// any.
+    #[inline]
+    fn match_across_chunks(&self, needle: &[u8]) -> Option<usize> {
+        if needle.len() < 2 {
+            return None;
+        }
+
+        let chunk = self.chunk.as_bytes();
+        let chunk_end = self.chunk_byte_offset + chunk.len();
+
+        let first_candidate = (self.search_from - self.chunk_byte_offset)
+            .max(chunk.len().saturating_sub(needle.len() - 1));
+
+        for idx in first_candidate..chunk.len() {
+            let (head, tail) = needle.split_at(chunk.len() - idx);
+
+            if head != &chunk[idx..] {
+                continue;
+            }
+
+            // Every other candidate needs even more bytes after the chunk.
+            if chunk_end + tail.len() > self.slice.byte_len() {
+                return None;
+            }
+
+            // The match starts with the first byte of the needle, so it
+            // starts on a char boundary.
+            if self
+                .slice
+                .bytes_at(chunk_end)
+                .take(tail.len())
+                .eq(tail.iter().copied())
+            {
+                return Some(self.chunk_byte_offset + idx);
+            }
+        }
+
+        None
+    }
+}
+
+impl Iterator for StrMatches<'_, '_> {
+    type Item = Range<usize>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let needle = self.needle;
+        let needle = needle.as_str().expect("not a regex");
+
+        loop {
+            let chunk_end = self.chunk_byte_offset + self.chunk.len();
+
+            if self.search_from >= chunk_end {
+                let Some(chunk) = self.chunks.next() else {
+                    // The empty pattern also matches at the very end.
+                    if needle.is_empty() && !self.yielded_end {
+                        self.yielded_end = true;
+                        let end = self.slice.byte_len();
+                        return Some(end..end);
+                    }
+                    return None;
+                };
+
+                self.chunk = chunk;
+                self.chunk_byte_offset = chunk_end;
+                continue;
+            }
+
+            let local_from = self.search_from - self.chunk_byte_offset;
+
+            if let Some(idx) = self.chunk[local_from..].find(needle) {
+                let start = self.search_from + idx;
+
+                self.search_from = if needle.is_empty() {
+                    // Empty matches are yielded on every char boundary, so
+                    // we skip to the next one.
+                    let ch = self.chunk[local_from + idx..].chars().next();
+                    start + ch.map_or(0, char::len_utf8)
+                } else {
+                    start + needle.len()
+                };
+
+                return Some(start..start + needle.len());
+            }
+
+            if let Some(start) = self.match_across_chunks(needle.as_bytes()) {
+                self.search_from = start + needle.len();
+                return Some(start..start + needle.len());
+            }
+
+            self.search_from = chunk_end;
+        }
+    }
+}
+
+/// Returns the byte range of the last match of the needle in the slice, if
+/// any.
+#[inline]
+pub(super) fn rfind(
+    slice: RopeSlice<'_>,
+    needle: Needle<'_>,
+) -> Option<Range<usize>> {
+    let Some(needle) = needle.as_str() else {
+        #[cfg(feature = "regex")]
+        if let Needle::Regex(regex) = needle {
+            return regex.rfind(slice);
+        }
+        unreachable!();
+    };
+
+    if needle.is_empty() {
+        return Some(slice.byte_len()..slice.byte_len());
+    }
+
+    let mut chunks = slice.chunks();
+    let mut chunk_end = slice.byte_len();
+
+    while let Some(chunk) = chunks.next_back() {
+        let chunk_byte_offset = chunk_end - chunk.len();
+
+        if let Some(idx) = chunk.rfind(needle) {
+            let start = chunk_byte_offset + idx;
+            return Some(start..start + needle.len());
+        }
+
+        // Look for matches that end in this chunk and start in one of the
+        // previous ones, from the one starting last.
+        let max_in_chunk = chunk.len().min(needle.len() - 1);
+
+        for in_chunk in (1..=max_in_chunk).rev() {
+            let (head, tail) =
+                needle.as_bytes().split_at(needle.len() - in_chunk);
+
+            if head.len() > chunk_byte_offset {
+                break;
+            }
+
+            if tail != &chunk.as_bytes()[..in_chunk] {
+                continue;
+            }
+
+            let mut bytes = slice.bytes_at(chunk_byte_offset);
+
+            if core::iter::from_fn(|| bytes.prev())
+                .take(head.len())
+                .eq(head.iter().rev().copied())
+            {
+                let start = chunk_byte_offset - head.len();
+                return Some(start..start + needle.len());
+            }
+        }
+
+        chunk_end = chunk_byte_offset;
+    }
+
+    None
+}
+
+/// Returns the byte ranges of all the non-overlapping matches of the needle in
+/// the slice, together with the text each of them should be replaced with.
+///
+/// The `$name` and `${name}` references in the replacement are expanded with
+/// the text of the corresponding capture groups if the needle is a regex, and
+/// left untouched otherwise.
+#[inline]
+pub(super) fn replacements<'r>(
+    slice: RopeSlice<'_>,
+    needle: Needle<'_>,
+    replacement: &'r str,
+) -> Vec<(Range<usize>, Cow<'r, str>)> {
+    #[cfg(feature = "regex")]
+    if let Needle::Regex(regex) = needle {
+        return regex
+            .captures_iter(slice)
+            .map(|captures| {
+                let mut text = String::new();
+                captures.expand(slice, replacement, &mut text);
+                (captures.get(0).unwrap(), Cow::Owned(text))
+            })
+            .collect();
+    }
+
+    Matches::new(slice, needle)
+        .map(|range| (range, Cow::Borrowed(replacement)))
+        .collect()
+}
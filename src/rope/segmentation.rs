@@ -0,0 +1,337 @@
+//! Unicode text segmentation (as defined by [UAX #29][uax29]) over the chunks
+//! of `Rope`s and `RopeSlice`s.
+//!
+//! The boundaries of a string can depend on context arbitrarily far away from
+//! them, so we can't just segment every chunk on its own. However, for every
+//! kind of segmentation there are positions whose left and right sides are
+//! always segmented independently of each other, e.g. right after a `\n`. We
+//! call these positions "safe points", and to segment a region of the text we
+//! only have to look at the text between the safe points around it.
+//!
+//! [uax29]: https://www.unicode.org/reports/tr29
+
+use alloc::borrow::Cow;
+use alloc::collections::VecDeque;
+use alloc::string::String;
+use core::marker::PhantomData;
+use core::ops::Range;
+
+use super::RopeSlice;
+
+/// A kind of text segmentation.
+pub(super) trait Segmenter {
+    /// Returns whether the position between two bytes is a safe point.
+    fn is_safe_point(prev: u8, next: u8) -> bool;
+
+    /// Pushes the byte ranges of all the segments of the text to the back of
+    /// `segments`, shifted by `offset`.
+    fn split(text: &str, offset: usize, segments: &mut VecDeque<Range<usize>>);
+}
+
+/// An iterator over the segments of a `RopeSlice`, used to implement the
+/// public segment iterators.
+pub(super) struct Segments<'a, S> {
+    slice: RopeSlice<'a>,
+
+    /// The window used when calling [`Segments::next()`].
+    forward: Window<'a>,
+
+    /// The byte offset of the start of the next segment yielded by
+    /// [`Segments::next()`].
+    forward_offset: usize,
+
+    /// The window used when calling [`Segments::next_back()`].
+    backward: Window<'a>,
+
+    /// The byte offset of the end of the next segment yielded by
+    /// [`Segments::next_back()`].
+    backward_offset: usize,
+
+    segmenter: PhantomData<S>,
+}
+
+impl<S> Clone for Segments<'_, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            slice: self.slice,
+            forward: self.forward.clone(),
+            forward_offset: self.forward_offset,
+            backward: self.backward.clone(),
+            backward_offset: self.backward_offset,
+            segmenter: PhantomData,
+        }
+    }
+}
+
+impl<'a, S: Segmenter> Segments<'a, S> {
+    #[inline]
+    pub(super) fn new(slice: RopeSlice<'a>) -> Self {
+        Self {
+            slice,
+            forward: Window::default(),
+            forward_offset: 0,
+            backward: Window::default(),
+            backward_offset: slice.byte_len(),
+            segmenter: PhantomData,
+        }
+    }
+}
+
+impl<'a, S: Segmenter> Iterator for Segments<'a, S> {
+    type Item = Cow<'a, str>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.forward_offset == self.backward_offset {
+            return None;
+        }
+
+        if self.forward.segments.is_empty() {
+            let start = self.forward_offset;
+            let end = next_safe_point::<S>(self.slice, start);
+            self.forward = Window::new::<S>(self.slice, start..end);
+        }
+
+        let segment = self.forward.segments.pop_front()?;
+        self.forward_offset = segment.end;
+        Some(self.forward.text_of(segment))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let hi = self.backward_offset - self.forward_offset;
+        let lo = (hi != 0) as usize;
+        (lo, Some(hi))
+    }
+}
+
+impl<S: Segmenter> DoubleEndedIterator for Segments<'_, S> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.forward_offset == self.backward_offset {
+            return None;
+        }
+
+        if self.backward.segments.is_empty() {
+            let end = self.backward_offset;
+            let start = prev_safe_point::<S>(self.slice, end);
+            self.backward = Window::new::<S>(self.slice, start..end);
+        }
+
+        let segment = self.backward.segments.pop_back()?;
+        self.backward_offset = segment.start;
+        Some(self.backward.text_of(segment))
+    }
+}
+
+/// The text between two consecutive safe points, together with the byte
+/// ranges of its segments that haven't been yielded yet.
+#[derive(Clone, Default)]
+struct Window<'a> {
+    /// The text of the window. It's only owned if the window spans more than
+    /// one chunk.
+    text: Cow<'a, str>,
+
+    /// The byte offset of the start of the window in the slice.
+    offset: usize,
+
+    /// The byte ranges of the segments in the slice.
+    segments: VecDeque<Range<usize>>,
+}
+
+impl<'a> Window<'a> {
+    #[inline]
+    fn new<S: Segmenter>(slice: RopeSlice<'a>, range: Range<usize>) -> Self {
+        let offset = range.start;
+
+        let mut chunks = slice.byte_slice(range).chunks();
+
+        let text = match (chunks.next(), chunks.next()) {
+            (Some(first), None) => Cow::Borrowed(first),
+            (None, _) => Cow::Borrowed(""),
+            (Some(first), Some(second)) => {
+                let mut text = String::from(first);
+                text.push_str(second);
+                chunks.for_each(|chunk| text.push_str(chunk));
+                Cow::Owned(text)
+            },
+        };
+
+        let mut segments = VecDeque::new();
+
+        S::split(&text, offset, &mut segments);
+
+        Self { text, offset, segments }
+    }
+
+    #[inline]
+    fn text_of(&self, segment: Range<usize>) -> Cow<'a, str> {
+        let range = segment.start - self.offset..segment.end - self.offset;
+
+        match &self.text {
+            Cow::Borrowed(text) => Cow::Borrowed(&text[range]),
+            Cow::Owned(text) => Cow::Owned(String::from(&text[range])),
+        }
+    }
+}
+
+/// Returns the byte offset of the first safe point strictly after
+/// `byte_offset`, or the length of the slice if there isn't one.
+///
+/// The offset has to lie on a char boundary.
+#[inline]
+fn next_safe_point<S: Segmenter>(
+    slice: RopeSlice<'_>,
+    byte_offset: usize,
+) -> usize {
+    let mut offset = byte_offset;
+
+    // The byte before `offset`, or `None` if `offset` is `byte_offset`.
+    let mut prev_byte: Option<u8> = None;
+
+    for chunk in slice.chunks_at(byte_offset) {
+        for &byte in chunk.as_bytes() {
+            if let Some(prev) = prev_byte {
+                if S::is_safe_point(prev, byte) {
+                    return offset;
+                }
+            }
+
+            offset += 1;
+
+            prev_byte = Some(byte);
+        }
+    }
+
+    slice.byte_len()
+}
+
+/// Returns the byte offset of the last safe point strictly before
+/// `byte_offset`, or zero if there isn't one.
+///
+/// The offset has to lie on a char boundary.
+#[inline]
+fn prev_safe_point<S: Segmenter>(
+    slice: RopeSlice<'_>,
+    byte_offset: usize,
+) -> usize {
+    let mut offset = byte_offset;
+
+    // The byte at `offset`, or `None` if `offset` is `byte_offset`.
+    let mut next_byte: Option<u8> = None;
+
+    let mut chunks = slice.chunks_at(byte_offset);
+
+    while let Some(chunk) = chunks.prev() {
+        for &byte in chunk.as_bytes().iter().rev() {
+            if let Some(next) = next_byte {
+                if S::is_safe_point(byte, next) {
+                    return offset;
+                }
+            }
+
+            offset -= 1;
+
+            next_byte = Some(byte);
+        }
+    }
+
+    0
+}
+
+/// Returns whether the given byte offset is a safe point.
+#[cfg(feature = "words")]
+#[inline]
+fn is_safe_point<S: Segmenter>(
+    slice: RopeSlice<'_>,
+    byte_offset: usize,
+) -> bool {
+    byte_offset == 0
+        || byte_offset == slice.byte_len()
+        || S::is_safe_point(
+            slice.byte(byte_offset - 1),
+            slice.byte(byte_offset),
+        )
+}
+
+/// Returns the byte offset of the closest boundary strictly after
+/// `byte_offset` in the given slice, or the length of the slice if
+/// `byte_offset` is already at the end.
+///
+/// This only segments the text between the safe points around `byte_offset`.
+#[cfg(feature = "words")]
+#[inline]
+pub(super) fn next_boundary<S: Segmenter>(
+    slice: RopeSlice<'_>,
+    byte_offset: usize,
+) -> usize {
+    debug_assert!(byte_offset <= slice.byte_len());
+
+    if byte_offset == slice.byte_len() {
+        return byte_offset;
+    }
+
+    let mut char_start = byte_offset;
+
+    while !slice.is_char_boundary(char_start) {
+        char_start -= 1;
+    }
+
+    // The window has to start at or before the offset.
+    let start = if is_safe_point::<S>(slice, char_start) {
+        char_start
+    } else {
+        prev_safe_point::<S>(slice, char_start)
+    };
+
+    let end = next_safe_point::<S>(slice, char_start);
+
+    Window::new::<S>(slice, start..end)
+        .segments
+        .into_iter()
+        .map(|segment| segment.end)
+        .find(|&end| end > byte_offset)
+        .unwrap_or(end)
+}
+
+/// Returns the byte offset of the closest boundary strictly before
+/// `byte_offset` in the given slice, or zero if `byte_offset` is already at
+/// the start.
+///
+/// This only segments the text between the safe points around `byte_offset`.
+#[cfg(feature = "words")]
+#[inline]
+pub(super) fn prev_boundary<S: Segmenter>(
+    slice: RopeSlice<'_>,
+    byte_offset: usize,
+) -> usize {
+    debug_assert!(byte_offset <= slice.byte_len());
+
+    if byte_offset == 0 {
+        return 0;
+    }
+
+    let mut char_end = byte_offset;
+
+    while !slice.is_char_boundary(char_end) {
+        char_end += 1;
+    }
+
+    // The window has to end at or after the offset.
+    let end = if is_safe_point::<S>(slice, char_end) {
+        char_end
+    } else {
+        next_safe_point::<S>(slice, char_end)
+    };
+
+    let start = prev_safe_point::<S>(slice, char_end);
+
+    Window::new::<S>(slice, start..end)
+        .segments
+        .into_iter()
+        .rev()
+        .map(|segment| segment.start)
+        .find(|&start| start < byte_offset)
+        .unwrap_or(start)
+}
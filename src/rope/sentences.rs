@@ -0,0 +1,80 @@
+//! Unicode sentence segmentation over the chunks of `Rope`s and `RopeSlice`s.
+
+use alloc::borrow::Cow;
+use alloc::collections::VecDeque;
+use core::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::segmentation::{Segmenter, Segments};
+use super::RopeSlice;
+
+/// Segments text into sentences as defined by [UAX #29][uax29].
+///
+/// A `\n` is a paragraph separator, which always ends a sentence and stops
+/// every rule looking past it, so the position right after it is a safe
+/// point.
+///
+/// [uax29]: https://www.unicode.org/reports/tr29/#Sentence_Boundaries
+pub(super) struct SentenceSegmenter;
+
+impl Segmenter for SentenceSegmenter {
+    #[inline]
+    fn is_safe_point(prev: u8, _next: u8) -> bool {
+        prev == b'\n'
+    }
+
+    #[inline]
+    fn split(
+        text: &str,
+        offset: usize,
+        segments: &mut VecDeque<Range<usize>>,
+    ) {
+        segments.extend(text.split_sentence_bound_indices().map(
+            |(start, segment)| offset + start..offset + start + segment.len(),
+        ));
+    }
+}
+
+/// An iterator over the sentences of `Rope`s and `RopeSlice`s.
+///
+/// Every sentence includes the whitespace and line break following it, so
+/// concatenating all the items yields back the original text.
+///
+/// This struct is created by the `sentences` method on
+/// [`Rope`](crate::Rope::sentences()) and
+/// [`RopeSlice`](RopeSlice::sentences()). See their documentation for more.
+#[derive(Clone)]
+pub struct Sentences<'a> {
+    segments: Segments<'a, SentenceSegmenter>,
+}
+
+impl<'a> Sentences<'a> {
+    #[inline]
+    pub(super) fn new(slice: RopeSlice<'a>) -> Self {
+        Self { segments: Segments::new(slice) }
+    }
+}
+
+impl<'a> Iterator for Sentences<'a> {
+    type Item = Cow<'a, str>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.segments.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.segments.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Sentences<'_> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.segments.next_back()
+    }
+}
+
+impl core::iter::FusedIterator for Sentences<'_> {}
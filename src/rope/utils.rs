@@ -2,6 +2,7 @@
 //! between `Rope`s and `RopeSlice`s, `RopeChunk`s and `ChunkSlice`s.
 
 use super::iterators::Chunks;
+use super::{RopeError, RopeSlice, Unit};
 
 /// Adjusts the candidate byte offset to make sure it's a char boundary for
 /// `s`. Offsets past the end of the string will be clipped to the length of
@@ -11,6 +12,10 @@ use super::iterators::Chunks;
 /// right until it is. The direction is chosen based on the value of
 /// `WITH_RIGHT_BIAS`: true => go right, false => go left.
 ///
+/// With the `unicode-lines` feature the offset is also moved in the same
+/// direction if it would split a CRLF pair, since the two halves of a CRLF
+/// can never end up in different chunks.
+///
 /// In every case the adjusted split point will be within ± 3 bytes from the
 /// initial candidate.
 #[inline]
@@ -34,9 +39,90 @@ pub(super) fn adjust_split_point<const WITH_RIGHT_BIAS: bool>(
         }
     }
 
+    if splits_crlf(&s[..offset], &s[offset..]) {
+        if WITH_RIGHT_BIAS {
+            offset += 1;
+        } else {
+            offset -= 1;
+        }
+    }
+
     offset
 }
 
+/// Returns an error if the byte offset is out of bounds or if it doesn't lie
+/// on a char boundary of the slice.
+#[inline]
+pub(super) fn check_byte_offset(
+    slice: RopeSlice<'_>,
+    byte_offset: usize,
+) -> Result<(), RopeError> {
+    check_offset(Unit::Byte, byte_offset, slice.byte_len())?;
+
+    if !slice.is_char_boundary(byte_offset) {
+        return Err(RopeError::NotCharBoundary { byte_offset });
+    }
+
+    Ok(())
+}
+
+/// Returns an error in all the cases where slicing or editing the slice in
+/// the `start..end` byte range would panic.
+#[inline]
+pub(super) fn check_byte_range(
+    slice: RopeSlice<'_>,
+    start: usize,
+    end: usize,
+) -> Result<(), RopeError> {
+    check_range(Unit::Byte, start, end, slice.byte_len())?;
+    check_byte_offset(slice, start)?;
+    check_byte_offset(slice, end)
+}
+
+/// Returns an error if the index is greater than or equal to the length.
+#[inline]
+pub(super) fn check_index(
+    unit: Unit,
+    index: usize,
+    len: usize,
+) -> Result<(), RopeError> {
+    if index >= len {
+        Err(RopeError::IndexOutOfBounds { unit, index, len })
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns an error if the offset is greater than the length.
+#[inline]
+pub(super) fn check_offset(
+    unit: Unit,
+    offset: usize,
+    len: usize,
+) -> Result<(), RopeError> {
+    if offset > len {
+        Err(RopeError::OffsetOutOfBounds { unit, offset, len })
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns an error if the `start..end` range is inverted or if its end is
+/// greater than the length.
+#[inline]
+pub(super) fn check_range(
+    unit: Unit,
+    start: usize,
+    end: usize,
+    len: usize,
+) -> Result<(), RopeError> {
+    if start > end {
+        return Err(RopeError::StartAfterEnd { unit, start, end });
+    }
+
+    check_offset(unit, end, len)
+}
+
 /// Checks equality between the chunks yielded by iterating over two
 /// [`Chunks`].
 ///
@@ -145,52 +231,152 @@ pub(super) fn debug_no_quotes(
     f.write_str(&s[written..])
 }
 
-/// Returns whether `byte_offset` is a grapheme boundary in the string
-/// constructed by concatenating the chunks yielded by `chunks`.
+/// Returns `true` if the given char is a line break.
+///
+/// Only `\n` is a line break by default, while the `unicode-lines` feature
+/// adds all the other single-char line terminators defined by Unicode,
+/// including `\r`. Note that a `\r` followed by a `\n` is still a single line
+/// break, which is counted on the `\n`.
+#[inline]
+pub(super) fn is_line_break(ch: char) -> bool {
+    #[cfg(not(feature = "unicode-lines"))]
+    {
+        ch == '\n'
+    }
+
+    #[cfg(feature = "unicode-lines")]
+    {
+        matches!(
+            ch,
+            '\n' | '\r'
+                | '\u{000B}'
+                | '\u{000C}'
+                | '\u{0085}'
+                | '\u{2028}'
+                | '\u{2029}'
+        )
+    }
+}
+
+/// Returns `true` if `left` ends with a `\r` and `right` starts with a `\n`,
+/// i.e. if the boundary between the two strings splits a CRLF pair.
+///
+/// This is always `false` without the `unicode-lines` feature, where a `\r`
+/// is never a line break and so splitting a CRLF doesn't change the line
+/// count of either side.
+#[inline]
+pub(super) fn splits_crlf(left: &str, right: &str) -> bool {
+    cfg!(feature = "unicode-lines")
+        && left.ends_with('\r')
+        && right.starts_with('\n')
+}
+
+/// Returns `true` if the given string ends with a line break.
+#[inline]
+pub(super) fn ends_with_line_break(s: &str) -> bool {
+    s.ends_with(is_line_break)
+}
+
+/// Returns whether `byte_offset` is a grapheme boundary in the given slice.
+///
+/// Only the chunks around `byte_offset` are looked at, so this runs in
+/// logarithmic time plus the size of the grapheme containing the offset.
 #[cfg(feature = "graphemes")]
 #[inline]
 pub(super) fn is_grapheme_boundary(
-    mut chunks: Chunks<'_>,
-    byte_len: usize,
+    slice: super::RopeSlice<'_>,
     byte_offset: usize,
 ) -> bool {
     use unicode_segmentation::{GraphemeCursor, GraphemeIncomplete};
 
-    debug_assert!(byte_offset <= byte_len);
-
-    let mut cursor = GraphemeCursor::new(0, byte_len, true);
-    cursor.set_cursor(byte_offset);
+    debug_assert!(byte_offset <= slice.byte_len());
 
-    let mut bytes_left = byte_len;
-
-    // Iterate from the back until we reach the chunk containing the given byte
-    // index.
-    //
-    // TODO: we need something like `Rope{Slice}::chunks_{from,up_to}_byte()`
-    // if we want to make this fast.
-    let chunk = loop {
-        let chunk = chunks.next_back().unwrap();
-        bytes_left -= chunk.len();
-        if bytes_left <= byte_offset {
-            break chunk;
-        }
-    };
+    if byte_offset == 0 || byte_offset == slice.byte_len() {
+        return true;
+    }
 
-    if !chunk.is_char_boundary(byte_offset - bytes_left) {
+    if !slice.is_char_boundary(byte_offset) {
         return false;
     }
 
-    let chunk_start = bytes_left;
+    let chunk = slice.byte_slice(byte_offset..).chunks().next().unwrap();
+
+    let mut prev_chunks = slice.byte_slice(..byte_offset).chunks();
+
+    let mut context_start = byte_offset;
+
+    let mut cursor = GraphemeCursor::new(byte_offset, slice.byte_len(), true);
 
     loop {
-        match cursor.is_boundary(chunk, chunk_start) {
+        match cursor.is_boundary(chunk, byte_offset) {
             Ok(is_boundary) => return is_boundary,
 
             Err(GraphemeIncomplete::PreContext(offset)) => {
-                debug_assert_eq!(offset, bytes_left);
-                let prev = chunks.next_back().unwrap();
-                bytes_left -= prev.len();
-                cursor.provide_context(prev, bytes_left);
+                debug_assert_eq!(offset, context_start);
+                let prev = prev_chunks.next_back().unwrap();
+                context_start -= prev.len();
+                cursor.provide_context(prev, context_start);
+            },
+
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Returns the byte offset of the closest grapheme boundary after
+/// `byte_offset` in the given slice, or the length of the slice if
+/// `byte_offset` is already at the end.
+///
+/// Like [`is_grapheme_boundary`], this only looks at the chunks making up the
+/// graphemes around `byte_offset`.
+#[cfg(feature = "graphemes")]
+#[inline]
+pub(super) fn next_grapheme_boundary(
+    slice: super::RopeSlice<'_>,
+    byte_offset: usize,
+) -> usize {
+    use unicode_segmentation::{GraphemeCursor, GraphemeIncomplete};
+
+    debug_assert!(byte_offset <= slice.byte_len());
+
+    if byte_offset == slice.byte_len() {
+        return byte_offset;
+    }
+
+    // Start from the beginning of the char containing the offset, which can't
+    // be followed by a grapheme boundary before the end of that char.
+    let mut offset = byte_offset;
+
+    while !slice.is_char_boundary(offset) {
+        offset -= 1;
+    }
+
+    let mut chunk_start = offset;
+
+    let mut chunk = slice.byte_slice(offset..).chunks().next().unwrap();
+
+    let mut cursor = GraphemeCursor::new(offset, slice.byte_len(), true);
+
+    loop {
+        match cursor.next_boundary(chunk, chunk_start) {
+            Ok(Some(boundary)) => return boundary,
+
+            Ok(None) => return slice.byte_len(),
+
+            Err(GraphemeIncomplete::NextChunk) => {
+                chunk_start += chunk.len();
+                chunk =
+                    slice.byte_slice(chunk_start..).chunks().next().unwrap();
+            },
+
+            Err(GraphemeIncomplete::PreContext(context_end)) => {
+                let context = slice
+                    .byte_slice(..context_end)
+                    .chunks()
+                    .next_back()
+                    .unwrap();
+
+                cursor.provide_context(context, context_end - context.len());
             },
 
             _ => unreachable!(),
@@ -198,6 +384,88 @@ pub(super) fn is_grapheme_boundary(
     }
 }
 
+/// Returns the byte offset of the closest grapheme boundary before
+/// `byte_offset` in the given slice, or zero if `byte_offset` is already at
+/// the start.
+///
+/// Like [`is_grapheme_boundary`], this only looks at the chunks making up the
+/// graphemes around `byte_offset`.
+#[cfg(feature = "graphemes")]
+#[inline]
+pub(super) fn prev_grapheme_boundary(
+    slice: super::RopeSlice<'_>,
+    byte_offset: usize,
+) -> usize {
+    use unicode_segmentation::{GraphemeCursor, GraphemeIncomplete};
+
+    debug_assert!(byte_offset <= slice.byte_len());
+
+    if byte_offset == 0 {
+        return 0;
+    }
+
+    // Start from the end of the char containing the offset, which can't be
+    // preceded by a grapheme boundary after the start of that char.
+    let mut offset = byte_offset;
+
+    while !slice.is_char_boundary(offset) {
+        offset += 1;
+    }
+
+    let mut chunk = slice.byte_slice(..offset).chunks().next_back().unwrap();
+
+    let mut chunk_start = offset - chunk.len();
+
+    let mut cursor = GraphemeCursor::new(offset, slice.byte_len(), true);
+
+    loop {
+        match cursor.prev_boundary(chunk, chunk_start) {
+            Ok(Some(boundary)) => return boundary,
+
+            Ok(None) => return 0,
+
+            Err(GraphemeIncomplete::PrevChunk) => {
+                chunk = slice
+                    .byte_slice(..chunk_start)
+                    .chunks()
+                    .next_back()
+                    .unwrap();
+                chunk_start -= chunk.len();
+            },
+
+            Err(GraphemeIncomplete::PreContext(context_end)) => {
+                let context = slice
+                    .byte_slice(..context_end)
+                    .chunks()
+                    .next_back()
+                    .unwrap();
+
+                cursor.provide_context(context, context_end - context.len());
+            },
+
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Sorts a batch of edits by their byte ranges, returning the index `i` of
+/// the first edit whose range overlaps with the one of the edit at `i - 1`,
+/// if there's one.
+///
+/// This is a stable sort, so insertions at the same offset keep their
+/// relative order.
+#[inline]
+pub(super) fn sort_edits<T>(
+    edits: &mut [(core::ops::Range<usize>, T)],
+) -> Option<usize> {
+    edits.sort_by_key(|(range, _)| (range.start, range.end));
+
+    edits
+        .windows(2)
+        .position(|pair| pair[1].0.start < pair[0].0.end)
+        .map(|idx| idx + 1)
+}
+
 #[inline]
 pub(super) fn split_adjusted<const WITH_RIGHT_BIAS: bool>(
     s: &str,
@@ -284,6 +552,93 @@ pub mod panic_messages {
         );
     }
 
+    #[cfg(feature = "char-metric")]
+    #[track_caller]
+    #[cold]
+    #[inline(never)]
+    pub(crate) fn char_offset_out_of_bounds(
+        char_offset: usize,
+        char_len: usize,
+    ) -> ! {
+        debug_assert!(char_offset > char_len);
+
+        panic!(
+            "char offset out of bounds: the offset is {char_offset} but the \
+             length is {char_len}"
+        );
+    }
+
+    #[cfg(feature = "char-metric")]
+    #[track_caller]
+    #[cold]
+    #[inline(never)]
+    pub(crate) fn char_start_after_end(
+        char_start: usize,
+        char_end: usize,
+    ) -> ! {
+        debug_assert!(char_start > char_end);
+
+        panic!(
+            "char start after end: the start is {char_start} but the end is \
+             {char_end}"
+        );
+    }
+
+    #[cfg(feature = "unicode-width")]
+    #[track_caller]
+    #[cold]
+    #[inline(never)]
+    pub(crate) fn display_column_out_of_bounds(
+        column: usize,
+        display_width: usize,
+    ) -> ! {
+        debug_assert!(column > display_width);
+
+        panic!(
+            "display column out of bounds: the column is {column} but the \
+             line's width is {display_width}"
+        );
+    }
+
+    #[track_caller]
+    #[cold]
+    #[inline(never)]
+    pub(crate) fn empty_fold_placeholder() -> ! {
+        panic!("the placeholder of the folds must not be empty");
+    }
+
+    #[cfg(feature = "grapheme-metric")]
+    #[track_caller]
+    #[cold]
+    #[inline(never)]
+    pub(crate) fn grapheme_offset_out_of_bounds(
+        grapheme_offset: usize,
+        grapheme_len: usize,
+    ) -> ! {
+        debug_assert!(grapheme_offset > grapheme_len);
+
+        panic!(
+            "grapheme offset out of bounds: the offset is {grapheme_offset} \
+             but the length is {grapheme_len}"
+        );
+    }
+
+    #[cfg(feature = "grapheme-metric")]
+    #[track_caller]
+    #[cold]
+    #[inline(never)]
+    pub(crate) fn grapheme_start_after_end(
+        grapheme_start: usize,
+        grapheme_end: usize,
+    ) -> ! {
+        debug_assert!(grapheme_start > grapheme_end);
+
+        panic!(
+            "grapheme start after end: the start is {grapheme_start} but the \
+             end is {grapheme_end}"
+        );
+    }
+
     #[track_caller]
     #[cold]
     #[inline(never)]
@@ -329,6 +684,36 @@ pub mod panic_messages {
         );
     }
 
+    #[track_caller]
+    #[cold]
+    #[inline(never)]
+    pub(crate) fn overlapping_edits(
+        first: core::ops::Range<usize>,
+        second: core::ops::Range<usize>,
+    ) -> ! {
+        debug_assert!(first.end > second.start);
+
+        panic!(
+            "overlapping edits: the byte range {first:?} overlaps with \
+             {second:?}"
+        );
+    }
+
+    #[track_caller]
+    #[cold]
+    #[inline(never)]
+    pub(crate) fn row_index_out_of_bounds(
+        row_index: usize,
+        row_len: usize,
+    ) -> ! {
+        debug_assert!(row_index >= row_len);
+
+        panic!(
+            "row index out of bounds: the index is {row_index} but the \
+             length is {row_len}"
+        );
+    }
+
     #[cfg(feature = "utf16-metric")]
     #[track_caller]
     #[cold]
@@ -360,4 +745,18 @@ pub mod panic_messages {
              the end is {utf16_end}"
         );
     }
+
+    #[track_caller]
+    #[cold]
+    #[inline(never)]
+    pub(crate) fn zero_tab_width() -> ! {
+        panic!("tab width must be greater than zero");
+    }
+
+    #[track_caller]
+    #[cold]
+    #[inline(never)]
+    pub(crate) fn zero_wrap_width() -> ! {
+        panic!("wrap width must be greater than zero");
+    }
 }
@@ -0,0 +1,131 @@
+//! Conversions between byte offsets and the columns text is displayed at when
+//! tabs jump to the next multiple of a tab width.
+//!
+//! Every grapheme (or every char if the `graphemes` feature is disabled) other
+//! than a tab takes up a single column, or as many columns as its display
+//! width if the `unicode-width` feature is enabled.
+
+use super::utils::panic_messages as panic;
+use super::RopeSlice;
+
+/// Returns the visual column of the given byte offset, where `line` starts at
+/// the beginning of the line containing it.
+///
+/// If the offset is inside a grapheme the column of the start of that grapheme
+/// is returned.
+#[track_caller]
+#[inline]
+pub(super) fn column_of_byte(
+    line: RopeSlice<'_>,
+    byte_offset: usize,
+    tab_width: usize,
+) -> usize {
+    if tab_width == 0 {
+        panic::zero_tab_width();
+    }
+
+    let mut column = 0;
+    let mut offset = 0;
+
+    for (len, width) in units(line) {
+        offset += len;
+
+        if offset > byte_offset {
+            break;
+        }
+
+        column = advance(column, width, tab_width);
+    }
+
+    column
+}
+
+/// Returns the byte offset of the unit displayed at the given visual column of
+/// the line.
+///
+/// If the column is inside a tab or a wide grapheme the offset of its start is
+/// returned, and columns past the end of the line are clamped to its length.
+#[track_caller]
+#[inline]
+pub(super) fn byte_of_column(
+    line: RopeSlice<'_>,
+    column: usize,
+    tab_width: usize,
+) -> usize {
+    if tab_width == 0 {
+        panic::zero_tab_width();
+    }
+
+    let mut current_column = 0;
+    let mut offset = 0;
+
+    for (len, width) in units(line) {
+        current_column = advance(current_column, width, tab_width);
+
+        if current_column > column {
+            break;
+        }
+
+        offset += len;
+    }
+
+    offset
+}
+
+/// Returns the column right after a unit of the given width starting at
+/// `column`, where a `None` width stands for a tab.
+#[inline]
+pub(super) fn advance(
+    column: usize,
+    width: Option<usize>,
+    tab_width: usize,
+) -> usize {
+    match width {
+        Some(width) => column + width,
+        None => (column / tab_width + 1) * tab_width,
+    }
+}
+
+/// Returns an iterator over the byte lengths and widths of the units of the
+/// slice, where the width of a tab is `None`.
+#[inline]
+fn units(
+    slice: RopeSlice<'_>,
+) -> impl Iterator<Item = (usize, Option<usize>)> + '_ {
+    #[cfg(feature = "graphemes")]
+    {
+        slice.graphemes().map(|grapheme| (grapheme.len(), width(&grapheme)))
+    }
+
+    #[cfg(not(feature = "graphemes"))]
+    {
+        slice.chars().map(|ch| {
+            let mut buf = [0; 4];
+            (ch.len_utf8(), width(ch.encode_utf8(&mut buf)))
+        })
+    }
+}
+
+/// Returns the number of columns the unit takes up, or `None` if it's a tab.
+#[inline]
+pub(super) fn width(unit: &str) -> Option<usize> {
+    if unit == "\t" {
+        return None;
+    }
+
+    #[cfg(feature = "unicode-width")]
+    {
+        Some(
+            unit.chars()
+                .map(|ch| {
+                    unicode_width::UnicodeWidthChar::width(ch).unwrap_or(0)
+                })
+                .sum(),
+        )
+    }
+
+    #[cfg(not(feature = "unicode-width"))]
+    {
+        Some(1)
+    }
+}
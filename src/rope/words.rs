@@ -0,0 +1,152 @@
+//! Unicode word segmentation over the chunks of `Rope`s and `RopeSlice`s.
+
+use alloc::borrow::Cow;
+use alloc::collections::VecDeque;
+use core::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::segmentation::{self, Segmenter, Segments};
+use super::RopeSlice;
+
+/// Segments text into words as defined by [UAX #29][uax29].
+///
+/// Both the position right after a `\n` and the one between a space and an
+/// ASCII character that isn't a space are always word boundaries, and no rule
+/// looks past them, so they're safe points.
+///
+/// [uax29]: https://www.unicode.org/reports/tr29/#Word_Boundaries
+pub(super) struct WordSegmenter;
+
+impl Segmenter for WordSegmenter {
+    #[inline]
+    fn is_safe_point(prev: u8, next: u8) -> bool {
+        prev == b'\n' || (prev == b' ' && next != b' ' && next.is_ascii())
+    }
+
+    #[inline]
+    fn split(
+        text: &str,
+        offset: usize,
+        segments: &mut VecDeque<Range<usize>>,
+    ) {
+        segments.extend(text.split_word_bound_indices().map(
+            |(start, segment)| offset + start..offset + start + segment.len(),
+        ));
+    }
+}
+
+/// An iterator over the word boundaries of `Rope`s and `RopeSlice`s, yielding
+/// the text between each pair of consecutive boundaries.
+///
+/// Concatenating all the items yields back the original text. See
+/// [`Words`] for an iterator yielding only the words.
+///
+/// This struct is created by the `word_bounds` method on
+/// [`Rope`](crate::Rope::word_bounds()) and
+/// [`RopeSlice`](RopeSlice::word_bounds()). See their documentation for more.
+#[derive(Clone)]
+pub struct WordBounds<'a> {
+    segments: Segments<'a, WordSegmenter>,
+}
+
+impl<'a> WordBounds<'a> {
+    #[inline]
+    pub(super) fn new(slice: RopeSlice<'a>) -> Self {
+        Self { segments: Segments::new(slice) }
+    }
+}
+
+impl<'a> Iterator for WordBounds<'a> {
+    type Item = Cow<'a, str>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.segments.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.segments.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for WordBounds<'_> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.segments.next_back()
+    }
+}
+
+impl core::iter::FusedIterator for WordBounds<'_> {}
+
+/// An iterator over the words of `Rope`s and `RopeSlice`s.
+///
+/// The words are the segments yielded by [`WordBounds`] which contain at least
+/// one alphanumeric character, so whitespace and punctuation are skipped.
+///
+/// This struct is created by the `words` method on
+/// [`Rope`](crate::Rope::words()) and [`RopeSlice`](RopeSlice::words()). See
+/// their documentation for more.
+#[derive(Clone)]
+pub struct Words<'a> {
+    bounds: WordBounds<'a>,
+}
+
+impl<'a> Words<'a> {
+    #[inline]
+    pub(super) fn new(slice: RopeSlice<'a>) -> Self {
+        Self { bounds: WordBounds::new(slice) }
+    }
+}
+
+impl<'a> Iterator for Words<'a> {
+    type Item = Cow<'a, str>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.bounds.by_ref().find(|segment| is_word(segment))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.bounds.size_hint().1)
+    }
+}
+
+impl DoubleEndedIterator for Words<'_> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.bounds.by_ref().rfind(|segment| is_word(segment))
+    }
+}
+
+impl core::iter::FusedIterator for Words<'_> {}
+
+/// Returns whether a segment yielded by [`WordBounds`] is a word.
+#[inline]
+fn is_word(segment: &str) -> bool {
+    segment.chars().any(char::is_alphanumeric)
+}
+
+/// Returns the byte offset of the closest word boundary strictly after
+/// `byte_offset` in the given slice, or the length of the slice if
+/// `byte_offset` is already at the end.
+#[inline]
+pub(super) fn next_word_boundary(
+    slice: RopeSlice<'_>,
+    byte_offset: usize,
+) -> usize {
+    segmentation::next_boundary::<WordSegmenter>(slice, byte_offset)
+}
+
+/// Returns the byte offset of the closest word boundary strictly before
+/// `byte_offset` in the given slice, or zero if `byte_offset` is already at
+/// the start.
+#[inline]
+pub(super) fn prev_word_boundary(
+    slice: RopeSlice<'_>,
+    byte_offset: usize,
+) -> usize {
+    segmentation::prev_boundary::<WordSegmenter>(slice, byte_offset)
+}
@@ -0,0 +1,1183 @@
+//! This module contains the [`WrapMap`], which soft-wraps the lines of a
+//! `Rope` into visual rows.
+//!
+//! The map is a `Tree` whose leaves store, for every line of the `Rope`, its
+//! byte length and the byte offsets at which it's broken into rows. Since the
+//! rows of a line only depend on the text of that line, an edit only needs to
+//! rewrap the lines it touches, and the summaries of the leaves can be used
+//! to convert between lines, rows and byte offsets in logarithmic time.
+
+use alloc::vec::Vec;
+use core::ops::{Add, AddAssign, RangeBounds, Sub, SubAssign};
+
+use super::metrics::ByteMetric;
+use super::utils::panic_messages as panic;
+use super::visual_column::{advance, width};
+use super::{Rope, RopeSlice};
+use crate::range_bounds_to_start_end;
+use crate::tree::{
+    AsSlice,
+    BalancedLeaf,
+    BaseMeasured,
+    Metric,
+    ReplaceableLeaf,
+    SlicingMetric,
+    Summarize,
+    Tree,
+};
+
+#[cfg(any(test, feature = "small_chunks"))]
+const MAX_LINES: usize = 4;
+
+#[cfg(not(any(test, feature = "small_chunks")))]
+const MAX_LINES: usize = 64;
+
+const MIN_LINES: usize = MAX_LINES / 4;
+
+type WrapTree = Tree<{ Rope::arity() }, WrappedLines>;
+
+/// An index of the visual rows the lines of a [`Rope`] are soft-wrapped into
+/// when displayed in a view `wrap_width` columns wide.
+///
+/// Lines are broken after runs of whitespace so that words are kept on a
+/// single row, and only words that don't fit in a row by themselves are
+/// broken in the middle. Whitespace at the end of a row is allowed to
+/// overflow it, like in most text editors.
+///
+/// Every grapheme (or every char if the `graphemes` feature is disabled)
+/// takes up a single column, or as many columns as its display width if the
+/// `unicode-width` feature is enabled, while tabs advance to the next
+/// multiple of `tab_width` columns in their row.
+///
+/// The map doesn't hold on to the `Rope`, so it has to be kept up to date
+/// by calling [`edit()`](Self::edit()) after every edit, or by editing the
+/// `Rope` via [`replace()`](Self::replace()). Only the lines touched by an
+/// edit are rewrapped, and all the conversions between lines, rows and byte
+/// offsets run in logarithmic time.
+///
+/// The lines of the map are the same as the ones of the `Rope`, plus an
+/// empty line taking up a single row if the `Rope` is empty or ends with a
+/// line break, which is where a cursor placed at the end of the text would
+/// be displayed.
+#[derive(Clone, Debug)]
+pub struct WrapMap {
+    tree: WrapTree,
+    wrap_width: usize,
+    tab_width: usize,
+}
+
+impl WrapMap {
+    /// Returns the byte offset of the start of the given row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the row index is out of bounds (i.e. greater than or equal
+    /// to [`row_len()`](Self::row_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, WrapMap};
+    /// #
+    /// let r = Rope::from("foo bar baz\nqux");
+    ///
+    /// let map = WrapMap::new(&r, 8, 4);
+    ///
+    /// assert_eq!(map.byte_of_row(0), 0);
+    /// assert_eq!(map.byte_of_row(1), 8);
+    /// assert_eq!(map.byte_of_row(2), 12);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn byte_of_row(&self, row_index: usize) -> usize {
+        let line_index = self.line_of_row(row_index);
+
+        let ByteMetric(line_start) =
+            self.tree.convert_measure(LineMetric(line_index));
+
+        let RowMetric(first_row) =
+            self.tree.convert_measure(LineMetric(line_index));
+
+        match row_index - first_row {
+            0 => line_start,
+            row => line_start + self.line(line_index).breaks[row - 1],
+        }
+    }
+
+    /// Updates the map after the byte range of the `Rope` it was created
+    /// from was replaced with `text_len` bytes of text, where `rope` is the
+    /// `Rope` after the edit.
+    ///
+    /// The byte range is in the coordinates of the text before the edit,
+    /// exactly like the one passed to [`Rope::replace()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than its end, or if its
+    /// end is greater than the byte length of the text before the edit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, WrapMap};
+    /// #
+    /// let mut r = Rope::from("foo\nbar");
+    ///
+    /// let mut map = WrapMap::new(&r, 8, 4);
+    ///
+    /// assert_eq!(map.row_len(), 2);
+    ///
+    /// r.replace(4..7, "bar baz qux");
+    /// map.edit(&r, 4..7, "bar baz qux".len());
+    ///
+    /// assert_eq!(map.row_len(), 3);
+    /// assert_eq!(map.byte_of_row(2), 12);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn edit<R>(&mut self, rope: &Rope, byte_range: R, text_len: usize)
+    where
+        R: RangeBounds<usize>,
+    {
+        let ByteMetric(old_len) = self.tree.measure::<ByteMetric>();
+
+        let (start, end) = range_bounds_to_start_end(byte_range, 0, old_len);
+
+        if start > end {
+            panic::byte_start_after_end(start, end);
+        }
+
+        if end > old_len {
+            panic::byte_offset_out_of_bounds(end, old_len);
+        }
+
+        debug_assert_eq!(rope.byte_len(), old_len - (end - start) + text_len);
+
+        // The rows of a line only depend on its own text, so we only need to
+        // rewrap the lines overlapping the edited range.
+        let first_line = self.line_of_byte(start);
+
+        let last_line = self.line_of_byte(end);
+
+        // If the edit reaches the last line the new lines go up to the end
+        // of the `Rope`, otherwise they go up to the start of the line after
+        // the last one.
+        let new_last_line = if last_line + 1 == self.tree.summary().lines {
+            rope.tree.summary().line_breaks()
+        } else {
+            let ByteMetric(old_region_end) =
+                self.tree.convert_measure(LineMetric(last_line + 1));
+
+            rope.line_of_byte(old_region_end - (end - start) + text_len) - 1
+        };
+
+        let lines = (first_line..=new_last_line)
+            .map(|line_index| self.wrap_line(rope, line_index))
+            .collect::<Vec<_>>();
+
+        self.tree
+            .replace(LineMetric(first_line)..LineMetric(last_line + 1), &lines);
+    }
+
+    /// Returns the line of the `Rope` that contains the given row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the row index is out of bounds (i.e. greater than or equal
+    /// to [`row_len()`](Self::row_len())).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, WrapMap};
+    /// #
+    /// let r = Rope::from("foo bar baz\nqux");
+    ///
+    /// let map = WrapMap::new(&r, 8, 4);
+    ///
+    /// assert_eq!(map.line_of_row(0), 0);
+    /// assert_eq!(map.line_of_row(1), 0);
+    /// assert_eq!(map.line_of_row(2), 1);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn line_of_row(&self, row_index: usize) -> usize {
+        if row_index >= self.row_len() {
+            panic::row_index_out_of_bounds(row_index, self.row_len());
+        }
+
+        let LineMetric(line_index) =
+            self.tree.convert_measure(RowMetric(row_index));
+
+        line_index
+    }
+
+    /// Creates a new `WrapMap` soft-wrapping the lines of the `Rope` at
+    /// `wrap_width` columns.
+    ///
+    /// This has to wrap every line of the `Rope`, so it runs in time linear
+    /// in its length.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either `wrap_width` or `tab_width` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, WrapMap};
+    /// #
+    /// let r = Rope::from("Hello there, General Kenobi\n");
+    ///
+    /// let map = WrapMap::new(&r, 16, 4);
+    ///
+    /// // "Hello there, " / "General Kenobi" / "".
+    /// assert_eq!(map.row_len(), 3);
+    /// assert_eq!(map.rows_of_line(0), 2);
+    /// assert_eq!(map.byte_of_row(1), 13);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn new(rope: &Rope, wrap_width: usize, tab_width: usize) -> Self {
+        if wrap_width == 0 {
+            panic::zero_wrap_width();
+        }
+
+        if tab_width == 0 {
+            panic::zero_tab_width();
+        }
+
+        let mut map = Self { tree: WrapTree::default(), wrap_width, tab_width };
+
+        map.tree = map.wrap_all(rope);
+
+        map
+    }
+
+    /// Replaces the contents of the `Rope` in the given byte range with the
+    /// given text, updating the map accordingly.
+    ///
+    /// This is equivalent to calling [`Rope::replace()`] followed by
+    /// [`edit()`](Self::edit()).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than the end, or if the
+    /// end is out of bounds (i.e. greater than the byte length of the
+    /// `Rope`), or if either of them doesn't lie on a char boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, WrapMap};
+    /// #
+    /// let mut r = Rope::from("foo bar baz");
+    ///
+    /// let mut map = WrapMap::new(&r, 8, 4);
+    ///
+    /// assert_eq!(map.row_len(), 2);
+    ///
+    /// map.replace(&mut r, 3..4, "\n");
+    ///
+    /// assert_eq!(r, "foo\nbar baz");
+    /// assert_eq!(map.row_len(), 2);
+    /// assert_eq!(map.rows_of_line(1), 1);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn replace<R, T>(&mut self, rope: &mut Rope, byte_range: R, text: T)
+    where
+        R: RangeBounds<usize>,
+        T: AsRef<str>,
+    {
+        let (start, end) =
+            range_bounds_to_start_end(byte_range, 0, rope.byte_len());
+
+        let text = text.as_ref();
+
+        rope.replace(start..end, text);
+
+        self.edit(rope, start..end, text.len());
+    }
+
+    /// Returns the number of visual rows the lines of the `Rope` are wrapped
+    /// into.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, WrapMap};
+    /// #
+    /// let r = Rope::new();
+    /// assert_eq!(WrapMap::new(&r, 8, 4).row_len(), 1);
+    ///
+    /// let r = Rope::from("foo bar baz\n");
+    /// assert_eq!(WrapMap::new(&r, 8, 4).row_len(), 3);
+    /// ```
+    #[inline]
+    pub fn row_len(&self) -> usize {
+        self.tree.summary().rows
+    }
+
+    /// Returns the row containing the given byte offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte offset is out of bounds (i.e. greater than the
+    /// byte length of the `Rope`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, WrapMap};
+    /// #
+    /// let r = Rope::from("foo bar baz\nqux");
+    ///
+    /// let map = WrapMap::new(&r, 8, 4);
+    ///
+    /// assert_eq!(map.row_of_byte(7), 0);
+    /// assert_eq!(map.row_of_byte(8), 1);
+    /// assert_eq!(map.row_of_byte(11), 1);
+    /// assert_eq!(map.row_of_byte(12), 2);
+    /// assert_eq!(map.row_of_byte(r.byte_len()), 2);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn row_of_byte(&self, byte_offset: usize) -> usize {
+        let ByteMetric(byte_len) = self.tree.measure::<ByteMetric>();
+
+        if byte_offset > byte_len {
+            panic::byte_offset_out_of_bounds(byte_offset, byte_len);
+        }
+
+        let line_index = self.line_of_byte(byte_offset);
+
+        let ByteMetric(line_start) =
+            self.tree.convert_measure(LineMetric(line_index));
+
+        let offset = byte_offset - line_start;
+
+        self.row_of_line(line_index)
+            + self
+                .line(line_index)
+                .breaks
+                .iter()
+                .take_while(|&&row_start| row_start <= offset)
+                .count()
+    }
+
+    /// Returns the first row of the given line.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the line index is out of bounds, i.e. greater than the
+    /// number of line breaks in the `Rope`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, WrapMap};
+    /// #
+    /// let r = Rope::from("foo bar baz\nqux\n");
+    ///
+    /// let map = WrapMap::new(&r, 8, 4);
+    ///
+    /// assert_eq!(map.row_of_line(0), 0);
+    /// assert_eq!(map.row_of_line(1), 2);
+    /// assert_eq!(map.row_of_line(2), 3);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn row_of_line(&self, line_index: usize) -> usize {
+        self.check_line_index(line_index);
+
+        let RowMetric(row_index) =
+            self.tree.convert_measure(LineMetric(line_index));
+
+        row_index
+    }
+
+    /// Returns the number of visual rows the given line is wrapped into,
+    /// which is always at least one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the line index is out of bounds, i.e. greater than the
+    /// number of line breaks in the `Rope`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, WrapMap};
+    /// #
+    /// let r = Rope::from("foo bar baz\n\nabcdefghijklmnopq");
+    ///
+    /// let map = WrapMap::new(&r, 8, 4);
+    ///
+    /// assert_eq!(map.rows_of_line(0), 2);
+    /// assert_eq!(map.rows_of_line(1), 1);
+    /// assert_eq!(map.rows_of_line(2), 3);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn rows_of_line(&self, line_index: usize) -> usize {
+        self.check_line_index(line_index);
+        self.line(line_index).rows()
+    }
+
+    /// Rewraps all the lines of the `Rope` at a new width.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `wrap_width` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crop::{Rope, WrapMap};
+    /// #
+    /// let r = Rope::from("foo bar baz");
+    ///
+    /// let mut map = WrapMap::new(&r, 8, 4);
+    /// assert_eq!(map.row_len(), 2);
+    ///
+    /// map.set_wrap_width(&r, 4);
+    /// assert_eq!(map.row_len(), 3);
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn set_wrap_width(&mut self, rope: &Rope, wrap_width: usize) {
+        if wrap_width == 0 {
+            panic::zero_wrap_width();
+        }
+
+        self.wrap_width = wrap_width;
+        self.tree = self.wrap_all(rope);
+    }
+
+    /// Returns the number of columns a tab advances to the next multiple of.
+    #[inline]
+    pub fn tab_width(&self) -> usize {
+        self.tab_width
+    }
+
+    /// Returns the number of columns the lines are wrapped at.
+    #[inline]
+    pub fn wrap_width(&self) -> usize {
+        self.wrap_width
+    }
+
+    #[track_caller]
+    #[inline]
+    fn check_line_index(&self, line_index: usize) {
+        let line_len = self.tree.summary().lines;
+
+        if line_index >= line_len {
+            panic::line_index_out_of_bounds(line_index, line_len);
+        }
+    }
+
+    #[inline]
+    fn line(&self, line_index: usize) -> &WrappedLine {
+        let (slice, LineMetric(lines_before)) =
+            self.tree.leaf_at_measure(LineMetric(line_index + 1));
+
+        &slice.lines[line_index - lines_before]
+    }
+
+    /// Returns the index of the line containing the given byte offset.
+    #[inline]
+    fn line_of_byte(&self, byte_offset: usize) -> usize {
+        let LineMetric(line_index) =
+            self.tree.convert_measure(ByteMetric(byte_offset));
+
+        // The byte length of the `Rope` is counted as being part of its last
+        // line, even when that line is empty.
+        line_index.min(self.tree.summary().lines - 1)
+    }
+
+    #[inline]
+    fn wrap_all(&self, rope: &Rope) -> WrapTree {
+        let line_len = rope.tree.summary().line_breaks() + 1;
+
+        let lines = (0..line_len)
+            .map(|line_index| self.wrap_line(rope, line_index))
+            .collect::<Vec<_>>();
+
+        Tree::from_leaves(WrappedLines::segment(lines))
+    }
+
+    #[inline]
+    fn wrap_line(&self, rope: &Rope, line_index: usize) -> WrappedLine {
+        let start = rope.byte_of_line(line_index);
+
+        // The empty line after a trailing line break isn't one of the
+        // `Rope`'s lines.
+        if line_index == rope.line_len() {
+            debug_assert_eq!(start, rope.byte_len());
+            return WrappedLine { len: 0, breaks: Vec::new() };
+        }
+
+        let end = if line_index < rope.tree.summary().line_breaks() {
+            rope.byte_of_line(line_index + 1)
+        } else {
+            rope.byte_len()
+        };
+
+        WrappedLine {
+            len: end - start,
+            breaks: wrap(rope.line(line_index), self.wrap_width, self.tab_width),
+        }
+    }
+}
+
+/// Returns whether a line can be broken after the given char.
+///
+/// No-break spaces are whitespace but, as their name suggests, they're not
+/// break opportunities.
+#[inline]
+fn is_break_opportunity(ch: char) -> bool {
+    ch.is_whitespace() && !matches!(ch, '\u{a0}' | '\u{2007}' | '\u{202f}')
+}
+
+/// Returns an iterator over the byte lengths and widths of the units of the
+/// line, together with whether the line can be broken after them.
+#[inline]
+fn units(
+    line: RopeSlice<'_>,
+) -> impl Iterator<Item = (usize, Option<usize>, bool)> + '_ {
+    #[cfg(feature = "graphemes")]
+    {
+        line.graphemes().map(|grapheme| {
+            let is_break = grapheme.chars().all(is_break_opportunity);
+            (grapheme.len(), width(&grapheme), is_break)
+        })
+    }
+
+    #[cfg(not(feature = "graphemes"))]
+    {
+        line.chars().map(|ch| {
+            let mut buf = [0; 4];
+            let width = width(ch.encode_utf8(&mut buf));
+            (ch.len_utf8(), width, is_break_opportunity(ch))
+        })
+    }
+}
+
+/// Returns the byte offsets at which the line (without its line break) has
+/// to be broken for none of its rows to be wider than `wrap_width` columns.
+///
+/// A row is broken after its last run of whitespace, unless there isn't one,
+/// in which case it's broken right before the first unit that doesn't fit.
+/// Whitespace at the end of a row is allowed to overflow it, and a unit
+/// wider than `wrap_width` gets a row to itself.
+#[inline]
+fn wrap(line: RopeSlice<'_>, wrap_width: usize, tab_width: usize) -> Vec<usize> {
+    let mut breaks = Vec::new();
+
+    let mut offset = 0;
+
+    let mut column = 0;
+
+    let mut row_start = 0;
+
+    // The byte offset of the start of the word the current unit belongs to,
+    // together with the widths of the units of that word before it.
+    let mut word_start = 0;
+
+    let mut word = Vec::new();
+
+    for (len, unit_width, is_break) in units(line) {
+        let mut next_column = advance(column, unit_width, tab_width);
+
+        while !is_break && next_column > wrap_width && offset > row_start {
+            if word_start > row_start {
+                // Move the current word to the next row.
+                row_start = word_start;
+                column =
+                    word.iter().fold(0, |col, &w| advance(col, w, tab_width));
+            } else {
+                row_start = offset;
+                column = 0;
+                word.clear();
+            }
+
+            breaks.push(row_start);
+
+            next_column = advance(column, unit_width, tab_width);
+        }
+
+        column = next_column;
+
+        offset += len;
+
+        if is_break {
+            word_start = offset;
+            word.clear();
+        } else {
+            word.push(unit_width);
+        }
+    }
+
+    breaks
+}
+
+/// A line of the `Rope`, together with the offsets at which it's broken into
+/// rows.
+#[derive(Clone, Debug, PartialEq)]
+struct WrappedLine {
+    /// The byte length of the line, including its line break.
+    len: usize,
+
+    /// The byte offsets of the starts of all the rows of the line except the
+    /// first one, relative to the start of the line.
+    breaks: Vec<usize>,
+}
+
+impl WrappedLine {
+    #[inline]
+    fn rows(&self) -> usize {
+        self.breaks.len() + 1
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+struct WrappedLines {
+    lines: Vec<WrappedLine>,
+}
+
+impl WrappedLines {
+    /// Splits a sequence of lines into leaves containing between `MIN_LINES`
+    /// and `MAX_LINES` lines each.
+    ///
+    /// The only exception is if there are at most `MAX_LINES` lines in total,
+    /// in which case they're all put in a single leaf.
+    #[inline]
+    fn segment(lines: Vec<WrappedLine>) -> alloc::vec::IntoIter<Self> {
+        if lines.len() <= MAX_LINES {
+            return alloc::vec![Self { lines }].into_iter();
+        }
+
+        let num_leaves = (lines.len() + MAX_LINES - 1) / MAX_LINES;
+
+        let min_len = lines.len() / num_leaves;
+
+        let mut longer = lines.len() % num_leaves;
+
+        let mut lines = lines.into_iter();
+
+        let mut leaves = Vec::with_capacity(num_leaves);
+
+        for _ in 0..num_leaves {
+            let len = min_len + (longer > 0) as usize;
+            longer = longer.saturating_sub(1);
+            leaves.push(Self { lines: lines.by_ref().take(len).collect() });
+        }
+
+        debug_assert!(leaves
+            .iter()
+            .all(|leaf| leaf.lines.len() >= MIN_LINES));
+
+        leaves.into_iter()
+    }
+}
+
+impl Summarize for WrappedLines {
+    type Summary = WrapSummary;
+
+    #[inline]
+    fn summarize(&self) -> Self::Summary {
+        self.as_slice().summarize()
+    }
+}
+
+impl BaseMeasured for WrappedLines {
+    type BaseMetric = LineMetric;
+}
+
+impl AsSlice for WrappedLines {
+    type Slice<'a> = WrappedLinesSlice<'a>;
+
+    #[inline]
+    fn as_slice(&self) -> WrappedLinesSlice<'_> {
+        WrappedLinesSlice { lines: &self.lines }
+    }
+}
+
+impl From<WrappedLinesSlice<'_>> for WrappedLines {
+    #[inline]
+    fn from(slice: WrappedLinesSlice<'_>) -> Self {
+        Self { lines: slice.lines.to_vec() }
+    }
+}
+
+impl BalancedLeaf for WrappedLines {
+    #[inline]
+    fn is_underfilled(&self, _: &WrapSummary) -> bool {
+        self.lines.len() < MIN_LINES
+    }
+
+    #[inline]
+    fn balance_leaves(
+        (left, left_summary): (&mut Self, &mut WrapSummary),
+        (right, right_summary): (&mut Self, &mut WrapSummary),
+    ) {
+        // The two leaves can be combined in a single leaf.
+        if left.lines.len() + right.lines.len() <= MAX_LINES {
+            left.lines.append(&mut right.lines);
+            *left_summary += &*right_summary;
+            *right_summary = WrapSummary::default();
+            return;
+        }
+
+        // The left side is underfilled => take lines from the right side.
+        if left.lines.len() < MIN_LINES {
+            let moved = MIN_LINES - left.lines.len();
+            left.lines.extend(right.lines.drain(..moved));
+        }
+        // The right side is underfilled => take lines from the left side.
+        else if right.lines.len() < MIN_LINES {
+            let moved = MIN_LINES - right.lines.len();
+            let split = left.lines.len() - moved;
+            right.lines.splice(..0, left.lines.drain(split..));
+        } else {
+            return;
+        }
+
+        *left_summary = left.summarize();
+        *right_summary = right.summarize();
+
+        debug_assert!(left.lines.len() >= MIN_LINES);
+        debug_assert!(right.lines.len() >= MIN_LINES);
+    }
+}
+
+impl ReplaceableLeaf<LineMetric> for WrappedLines {
+    type Replacement<'a> = &'a [WrappedLine];
+
+    type ExtraLeaves = alloc::vec::IntoIter<Self>;
+
+    #[inline]
+    fn replace<R>(
+        &mut self,
+        summary: &mut WrapSummary,
+        range: R,
+        replacement: &[WrappedLine],
+    ) -> Option<Self::ExtraLeaves>
+    where
+        R: RangeBounds<LineMetric>,
+    {
+        let (start, end) = range_bounds_to_start_end(range, 0, summary.lines);
+
+        debug_assert!(start <= end);
+        debug_assert!(end <= summary.lines);
+
+        if self.lines.len() - (end - start) + replacement.len() <= MAX_LINES {
+            self.lines.splice(start..end, replacement.iter().cloned());
+            *summary = self.summarize();
+            return None;
+        }
+
+        let mut lines = Vec::with_capacity(
+            self.lines.len() - (end - start) + replacement.len(),
+        );
+
+        lines.extend(self.lines.drain(..start));
+        lines.extend(replacement.iter().cloned());
+        lines.extend(self.lines.drain(end - start..));
+
+        let mut leaves = Self::segment(lines);
+        *self = leaves.next().unwrap();
+        *summary = self.summarize();
+        Some(leaves)
+    }
+
+    #[inline]
+    fn remove_up_to(
+        &mut self,
+        summary: &mut WrapSummary,
+        LineMetric(up_to): LineMetric,
+    ) {
+        self.lines.drain(..up_to);
+        *summary = self.summarize();
+    }
+}
+
+#[derive(Copy, Clone, Default, Debug)]
+struct WrappedLinesSlice<'a> {
+    lines: &'a [WrappedLine],
+}
+
+impl<'a> WrappedLinesSlice<'a> {
+    /// Splits the slice after the first line for which `stop` returns `true`
+    /// when called with the summary of the lines up to and including it,
+    /// or at the end if there isn't one.
+    #[inline]
+    fn split_where<F>(self, mut stop: F) -> (Self, Self)
+    where
+        F: FnMut(&WrapSummary) -> bool,
+    {
+        let mut summary = WrapSummary::default();
+
+        let mut split = 0;
+
+        for line in self.lines {
+            summary += &line.summarize();
+
+            if stop(&summary) {
+                break;
+            }
+
+            split += 1;
+        }
+
+        let (left, right) = self.lines.split_at(split);
+
+        (Self { lines: left }, Self { lines: right })
+    }
+}
+
+impl Summarize for WrappedLine {
+    type Summary = WrapSummary;
+
+    #[inline]
+    fn summarize(&self) -> Self::Summary {
+        WrapSummary { bytes: self.len, lines: 1, rows: self.rows() }
+    }
+}
+
+impl Summarize for WrappedLinesSlice<'_> {
+    type Summary = WrapSummary;
+
+    #[inline]
+    fn summarize(&self) -> Self::Summary {
+        self.lines.iter().fold(WrapSummary::default(), |mut summary, line| {
+            summary += &line.summarize();
+            summary
+        })
+    }
+}
+
+#[derive(Copy, Clone, Default, Debug, PartialEq)]
+struct WrapSummary {
+    bytes: usize,
+    lines: usize,
+    rows: usize,
+}
+
+impl Add<&Self> for WrapSummary {
+    type Output = Self;
+
+    #[inline]
+    fn add(mut self, rhs: &Self) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+impl Sub<&Self> for WrapSummary {
+    type Output = Self;
+
+    #[inline]
+    fn sub(mut self, rhs: &Self) -> Self {
+        self -= rhs;
+        self
+    }
+}
+
+impl AddAssign<&Self> for WrapSummary {
+    #[inline]
+    fn add_assign(&mut self, rhs: &Self) {
+        self.bytes += rhs.bytes;
+        self.lines += rhs.lines;
+        self.rows += rhs.rows;
+    }
+}
+
+impl SubAssign<&Self> for WrapSummary {
+    #[inline]
+    fn sub_assign(&mut self, rhs: &Self) {
+        self.bytes -= rhs.bytes;
+        self.lines -= rhs.lines;
+        self.rows -= rhs.rows;
+    }
+}
+
+impl Metric<WrapSummary> for ByteMetric {
+    #[inline]
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    #[inline]
+    fn one() -> Self {
+        Self(1)
+    }
+
+    #[inline]
+    fn measure(summary: &WrapSummary) -> Self {
+        Self(summary.bytes)
+    }
+}
+
+/// Slicing up to a byte offset keeps all the lines ending at or before it,
+/// so the left side contains as many lines as the index of the line
+/// containing the offset.
+impl SlicingMetric<WrappedLines> for ByteMetric {
+    #[inline]
+    fn slice_up_to<'a>(
+        slice: WrappedLinesSlice<'a>,
+        ByteMetric(byte_offset): Self,
+        _: &WrapSummary,
+    ) -> (WrappedLinesSlice<'a>, WrapSummary)
+    where
+        'a: 'a,
+    {
+        let (left, _) = slice.split_where(|s| s.bytes > byte_offset);
+        (left, left.summarize())
+    }
+
+    #[inline]
+    fn slice_from<'a>(
+        slice: WrappedLinesSlice<'a>,
+        ByteMetric(byte_offset): Self,
+        _: &WrapSummary,
+    ) -> (WrappedLinesSlice<'a>, WrapSummary)
+    where
+        'a: 'a,
+    {
+        let (_, right) = slice.split_where(|s| s.bytes > byte_offset);
+        (right, right.summarize())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct LineMetric(usize);
+
+impl Add for LineMetric {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl Sub for LineMetric {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+impl AddAssign for LineMetric {
+    #[inline]
+    fn add_assign(&mut self, other: Self) {
+        self.0 += other.0
+    }
+}
+
+impl SubAssign for LineMetric {
+    #[inline]
+    fn sub_assign(&mut self, other: Self) {
+        self.0 -= other.0
+    }
+}
+
+impl Add<usize> for LineMetric {
+    type Output = usize;
+
+    #[inline]
+    fn add(self, other: usize) -> usize {
+        self.0 + other
+    }
+}
+
+impl From<LineMetric> for usize {
+    #[inline]
+    fn from(LineMetric(value): LineMetric) -> usize {
+        value
+    }
+}
+
+impl Metric<WrapSummary> for LineMetric {
+    #[inline]
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    #[inline]
+    fn one() -> Self {
+        Self(1)
+    }
+
+    #[inline]
+    fn measure(summary: &WrapSummary) -> Self {
+        Self(summary.lines)
+    }
+}
+
+impl SlicingMetric<WrappedLines> for LineMetric {
+    #[inline]
+    fn slice_up_to<'a>(
+        slice: WrappedLinesSlice<'a>,
+        LineMetric(line_offset): Self,
+        _: &WrapSummary,
+    ) -> (WrappedLinesSlice<'a>, WrapSummary)
+    where
+        'a: 'a,
+    {
+        let left = WrappedLinesSlice { lines: &slice.lines[..line_offset] };
+        (left, left.summarize())
+    }
+
+    #[inline]
+    fn slice_from<'a>(
+        slice: WrappedLinesSlice<'a>,
+        LineMetric(line_offset): Self,
+        _: &WrapSummary,
+    ) -> (WrappedLinesSlice<'a>, WrapSummary)
+    where
+        'a: 'a,
+    {
+        let right = WrappedLinesSlice { lines: &slice.lines[line_offset..] };
+        (right, right.summarize())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct RowMetric(usize);
+
+impl Add for RowMetric {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl Sub for RowMetric {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+impl AddAssign for RowMetric {
+    #[inline]
+    fn add_assign(&mut self, other: Self) {
+        self.0 += other.0
+    }
+}
+
+impl SubAssign for RowMetric {
+    #[inline]
+    fn sub_assign(&mut self, other: Self) {
+        self.0 -= other.0
+    }
+}
+
+impl Metric<WrapSummary> for RowMetric {
+    #[inline]
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    #[inline]
+    fn one() -> Self {
+        Self(1)
+    }
+
+    #[inline]
+    fn measure(summary: &WrapSummary) -> Self {
+        Self(summary.rows)
+    }
+}
+
+/// Slicing up to a row keeps all the lines whose rows all come before it, so
+/// the left side contains as many lines as the index of the line containing
+/// the row.
+impl SlicingMetric<WrappedLines> for RowMetric {
+    #[inline]
+    fn slice_up_to<'a>(
+        slice: WrappedLinesSlice<'a>,
+        RowMetric(row_offset): Self,
+        _: &WrapSummary,
+    ) -> (WrappedLinesSlice<'a>, WrapSummary)
+    where
+        'a: 'a,
+    {
+        let (left, _) = slice.split_where(|s| s.rows > row_offset);
+        (left, left.summarize())
+    }
+
+    #[inline]
+    fn slice_from<'a>(
+        slice: WrappedLinesSlice<'a>,
+        RowMetric(row_offset): Self,
+        _: &WrapSummary,
+    ) -> (WrappedLinesSlice<'a>, WrapSummary)
+    where
+        'a: 'a,
+    {
+        let (_, right) = slice.split_where(|s| s.rows > row_offset);
+        (right, right.summarize())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wrap_str(s: &str, wrap_width: usize) -> Vec<usize> {
+        let r = Rope::from(s);
+        wrap(r.byte_slice(..), wrap_width, 4)
+    }
+
+    #[test]
+    fn wrap_at_whitespace() {
+        assert_eq!(wrap_str("foo bar baz", 8), [8]);
+        assert_eq!(wrap_str("foo bar baz", 7), [8]);
+        assert_eq!(wrap_str("foo bar baz", 4), [4, 8]);
+        assert_eq!(wrap_str("foo   bar", 4), [6]);
+    }
+
+    #[test]
+    fn wrap_long_words() {
+        assert_eq!(wrap_str("abcdefghij", 4), [4, 8]);
+        assert_eq!(wrap_str("a abcdefghij", 4), [2, 6, 10]);
+    }
+
+    #[test]
+    fn wrap_tabs() {
+        assert_eq!(wrap_str("\t\tfoo", 8), [2]);
+        assert_eq!(wrap_str("a\tb", 4), [2]);
+    }
+
+    #[test]
+    fn replace_overflowing() {
+        let line = |len| WrappedLine { len, breaks: Vec::new() };
+
+        let mut leaf = WrappedLines { lines: alloc::vec![line(1), line(2)] };
+        let mut summary = leaf.summarize();
+
+        let replacement = [line(3), line(4), line(5), line(6)];
+
+        let extras = leaf
+            .replace(&mut summary, LineMetric(1)..LineMetric(2), &replacement)
+            .unwrap()
+            .collect::<Vec<_>>();
+
+        assert_eq!(summary, leaf.summarize());
+
+        let total =
+            extras.iter().fold(summary, |acc, leaf| acc + &leaf.summarize());
+
+        assert_eq!(total, WrapSummary { bytes: 19, lines: 5, rows: 5 });
+    }
+}
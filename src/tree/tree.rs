@@ -40,10 +40,7 @@ impl<const ARITY: usize, L: BalancedLeaf + Clone> From<TreeSlice<'_, ARITY, L>>
         } else if slice.leaf_count() == 1 {
             debug_assert!(slice.root().is_leaf());
 
-            Arc::new(Node::Leaf(Lnode::new(
-                slice.start_slice.into(),
-                slice.summary,
-            )))
+            Arc::new(Node::Leaf(Lnode::from(L::from(slice.start_slice))))
         } else if slice.leaf_count() == 2 {
             let mut first = Lnode::from(L::from(slice.start_slice));
 
@@ -69,6 +66,75 @@ impl<const ARITY: usize, L: BalancedLeaf + Clone> From<TreeSlice<'_, ARITY, L>>
 }
 
 impl<const ARITY: usize, L: Leaf> Tree<ARITY, L> {
+    /// Appends another `Tree` to the end of this one.
+    ///
+    /// The root of the shallower tree is attached at the right depth of the
+    /// deeper one, so this runs in `O(log n)` and all the nodes of `other`
+    /// that don't lie on the seam between the two trees are reused.
+    #[inline]
+    pub fn append(&mut self, mut other: Self)
+    where
+        L: BalancedLeaf + Clone,
+    {
+        if other.root.is_empty() {
+            return;
+        } else if self.root.is_empty() {
+            *self = other;
+            return;
+        }
+
+        use core::cmp::Ordering;
+
+        let (left_depth, right_depth) =
+            (self.root.depth(), other.root.depth());
+
+        match left_depth.cmp(&right_depth) {
+            Ordering::Greater => {
+                let extra = Arc::make_mut(&mut self.root)
+                    .get_internal_mut()
+                    .append_at_depth(other.root);
+
+                if let Some(extra) = extra {
+                    self.root =
+                        Arc::new(Node::Internal(Inode::from_children([
+                            Arc::clone(&self.root),
+                            Arc::new(Node::Internal(extra)),
+                        ])));
+                }
+            },
+
+            Ordering::Less => {
+                let extra = Arc::make_mut(&mut other.root)
+                    .get_internal_mut()
+                    .prepend_at_depth(Arc::clone(&self.root));
+
+                self.root = match extra {
+                    Some(extra) => {
+                        Arc::new(Node::Internal(Inode::from_children([
+                            Arc::new(Node::Internal(extra)),
+                            other.root,
+                        ])))
+                    },
+
+                    None => other.root,
+                };
+            },
+
+            Ordering::Equal => {
+                Arc::make_mut(&mut self.root)
+                    .balance(Arc::make_mut(&mut other.root));
+
+                if !other.root.is_empty() {
+                    self.root =
+                        Arc::new(Node::Internal(Inode::from_children([
+                            Arc::clone(&self.root),
+                            other.root,
+                        ])));
+                }
+            },
+        }
+    }
+
     #[doc(hidden)]
     pub fn assert_invariants(&self) {
         match &*self.root {
@@ -178,9 +244,24 @@ impl<const ARITY: usize, L: Leaf> Tree<ARITY, L> {
     ) where
         M: Metric<L::Summary>,
         L: ReplaceableLeaf<M> + Clone,
+    {
+        self.replace_collecting(range, replace_with, &mut ());
+    }
+
+    #[track_caller]
+    #[inline]
+    fn replace_collecting<M, R>(
+        &mut self,
+        range: Range<M>,
+        replace_with: L::Replacement<'_>,
+        removed: &mut R,
+    ) where
+        M: Metric<L::Summary>,
+        L: ReplaceableLeaf<M> + Clone,
+        R: tree_replace::Removed<ARITY, L, M>,
     {
         if let Some(extras) =
-            tree_replace::replace(&mut self.root, range, replace_with)
+            tree_replace::replace(&mut self.root, range, replace_with, removed)
         {
             debug_assert!(extras
                 .iter()
@@ -213,6 +294,59 @@ impl<const ARITY: usize, L: Leaf> Tree<ARITY, L> {
         TreeSlice::from_range_in_root(&self.root, range)
     }
 
+    /// Replaces a range of the `Tree` with the given replacement, returning
+    /// the contents of the range as a new `Tree`.
+    ///
+    /// The nodes fully contained in the range are collected while descending
+    /// to it, so they're shared between the two trees and only the leaves at
+    /// the edges of the range are copied.
+    #[track_caller]
+    #[inline]
+    pub fn splice<M>(
+        &mut self,
+        range: Range<M>,
+        replace_with: L::Replacement<'_>,
+    ) -> Self
+    where
+        M: SlicingMetric<L>,
+        L: ReplaceableLeaf<M> + Clone + Default,
+    {
+        let mut removed = Vec::new();
+
+        self.replace_collecting(range, replace_with, &mut removed);
+
+        let mut removed = removed.into_iter().map(|root| Self { root });
+
+        let mut tree = removed.next().unwrap_or_default();
+
+        for other in removed {
+            tree.append(other);
+        }
+
+        tree
+    }
+
+    /// Splits the `Tree` in two at the given measure, returning everything
+    /// after it and leaving everything before it in `self`.
+    ///
+    /// Like converting a [`TreeSlice`] into a `Tree`, this runs in `O(log n)`
+    /// and reuses all the nodes that don't lie on the split point.
+    #[track_caller]
+    #[inline]
+    pub fn split_off<M>(&mut self, at: M) -> Self
+    where
+        M: SlicingMetric<L>,
+        L: BalancedLeaf + Clone,
+        L::BaseMetric: SlicingMetric<L>,
+        for<'d> L::Slice<'d>: Default,
+    {
+        debug_assert!(at <= self.measure::<M>());
+
+        let right = Self::from(self.slice(at..self.measure::<M>()));
+        *self = Self::from(self.slice(M::zero()..at));
+        right
+    }
+
     #[inline]
     pub fn summary(&self) -> &L::Summary {
         self.root.summary()
@@ -353,7 +487,6 @@ mod from_treeslice {
                         child,
                         end - offset,
                         slice.end_slice,
-                        slice.end_summary.clone(),
                         &mut invalid_last,
                     );
 
@@ -445,7 +578,6 @@ mod from_treeslice {
         node: &Arc<Node<N, L>>,
         take_up_to: L::BaseMetric,
         end_slice: L::Slice<'_>,
-        end_summary: L::Summary,
         invalid_nodes: &mut usize,
     ) -> Arc<Node<N, L>> {
         match &**node {
@@ -462,7 +594,6 @@ mod from_treeslice {
                             child,
                             take_up_to - offset,
                             end_slice,
-                            end_summary,
                             invalid_nodes,
                         );
 
@@ -490,7 +621,9 @@ mod from_treeslice {
             },
 
             Node::Leaf(_) => {
-                let lnode = Lnode::new(end_slice.into(), end_summary);
+                // The summary of the end slice is recomputed since it can
+                // depend on the text that followed it in its leaf.
+                let lnode = Lnode::from(L::from(end_slice));
 
                 if lnode.is_underfilled() {
                     *invalid_nodes = 1;
@@ -503,10 +636,61 @@ mod from_treeslice {
 }
 
 mod tree_replace {
-    //! This module contains the logic used to implement [`Tree::replace()`].
+    //! This module contains the logic used to implement [`Tree::replace()`]
+    //! and [`Tree::splice()`].
 
     use super::*;
 
+    /// Collects the nodes removed by a replacement, in the order in which
+    /// they appear in the tree.
+    ///
+    /// [`Tree::replace()`] uses `()`, which discards them, while
+    /// [`Tree::splice()`] uses a `Vec` of nodes, which it then concatenates
+    /// into the `Tree` it returns.
+    pub(super) trait Removed<const N: usize, L: Leaf, M> {
+        /// Called with a node that's about to be removed from the tree.
+        fn push_node(&mut self, node: &Arc<Node<N, L>>);
+
+        /// Called with a leaf whose contents in `range` are about to be
+        /// removed.
+        fn push_leaf_range(&mut self, leaf: &Lnode<L>, range: Range<M>);
+    }
+
+    impl<const N: usize, L: Leaf, M> Removed<N, L, M> for () {
+        #[inline]
+        fn push_node(&mut self, _: &Arc<Node<N, L>>) {}
+
+        #[inline]
+        fn push_leaf_range(&mut self, _: &Lnode<L>, _: Range<M>) {}
+    }
+
+    impl<const N: usize, L, M> Removed<N, L, M> for Vec<Arc<Node<N, L>>>
+    where
+        L: BalancedLeaf,
+        M: SlicingMetric<L>,
+    {
+        #[inline]
+        fn push_node(&mut self, node: &Arc<Node<N, L>>) {
+            self.push(Arc::clone(node));
+        }
+
+        #[inline]
+        fn push_leaf_range(&mut self, leaf: &Lnode<L>, range: Range<M>) {
+            let (slice, summary) =
+                M::slice_up_to(leaf.as_slice(), range.end, leaf.summary());
+
+            let (slice, _) = M::slice_from(slice, range.start, &summary);
+
+            // The summary is recomputed since the one of the slice can
+            // depend on the text that followed it in the leaf.
+            let lnode = Lnode::from(L::from(slice));
+
+            if !lnode.is_empty() {
+                self.push(Arc::new(Node::Leaf(lnode)));
+            }
+        }
+    }
+
     /// Recursively calls itself until it finds the deepest node that fully
     /// contains `range`, calling [`replace_range_in_deepest`] if it's an
     /// internal node.
@@ -520,19 +704,23 @@ mod tree_replace {
     /// it was before calling this function.
     #[track_caller]
     #[inline]
-    pub(super) fn replace<const N: usize, M, L>(
+    pub(super) fn replace<const N: usize, M, L, R>(
         node: &mut Arc<Node<N, L>>,
         mut range: Range<M>,
         replace_with: L::Replacement<'_>,
+        removed: &mut R,
     ) -> Option<Vec<Arc<Node<N, L>>>>
     where
         M: Metric<L::Summary>,
         L: ReplaceableLeaf<M> + Clone,
+        R: Removed<N, L, M>,
     {
         let inode = match Arc::make_mut(node) {
             Node::Internal(inode) => inode,
 
             Node::Leaf(leaf) => {
+                removed.push_leaf_range(leaf, range.clone());
+
                 return leaf.replace(range, replace_with).map(|extras| {
                     extras.map(Node::Leaf).map(Arc::new).collect()
                 });
@@ -542,7 +730,8 @@ mod tree_replace {
         let Some((child_idx, offset)) =
             inode.child_containing_range(range.clone())
         else {
-            let extras = replace_range_in_deepest(inode, range, replace_with);
+            let extras =
+                replace_range_in_deepest(inode, range, replace_with, removed);
 
             Node::replace_with_single_child(node);
 
@@ -553,7 +742,7 @@ mod tree_replace {
         range.end -= offset;
 
         let extras = inode.with_child_mut(child_idx, |child| {
-            replace(child, range, replace_with)
+            replace(child, range, replace_with, removed)
         });
 
         let child = inode.child(child_idx);
@@ -603,20 +792,23 @@ mod tree_replace {
     /// even contain a single child) if it was deletion-heavy.
     #[track_caller]
     #[inline]
-    fn replace_range_in_deepest<const N: usize, M, L>(
+    fn replace_range_in_deepest<const N: usize, M, L, R>(
         inode: &mut Inode<N, L>,
         range: Range<M>,
         replace_with: L::Replacement<'_>,
+        removed: &mut R,
     ) -> Option<Vec<Arc<Node<N, L>>>>
     where
         M: Metric<L::Summary>,
         L: ReplaceableLeaf<M> + Clone,
+        R: Removed<N, L, M>,
     {
         let (start_idx, end_idx, extra_leaves) =
             inode_replace_nodes_in_start_and_end_subtrees(
                 inode,
                 range,
                 replace_with,
+                removed,
             );
 
         let mut extra_leaves = extra_leaves?;
@@ -711,14 +903,16 @@ mod tree_replace {
     /// insertion-heavy.
     #[track_caller]
     #[inline]
-    fn inode_replace_nodes_in_start_and_end_subtrees<const N: usize, M, L>(
+    fn inode_replace_nodes_in_start_and_end_subtrees<const N: usize, M, L, R>(
         inode: &mut Inode<N, L>,
         range: Range<M>,
         replace_with: L::Replacement<'_>,
+        removed: &mut R,
     ) -> (usize, usize, Option<Vec<Arc<Node<N, L>>>>)
     where
         M: Metric<L::Summary>,
         L: ReplaceableLeaf<M> + Clone,
+        R: Removed<N, L, M>,
     {
         let mut start_idx = 0;
         let mut end_idx = 0;
@@ -744,6 +938,7 @@ mod tree_replace {
                         range.start + child_measure - offset,
                         replace_with,
                         &mut start_should_rebalance,
+                        removed,
                     )
                 });
 
@@ -766,12 +961,17 @@ mod tree_replace {
             if offset >= range.end {
                 end_idx = idx;
 
+                for child in &inode.children()[start_idx + 1..end_idx] {
+                    removed.push_node(child);
+                }
+
                 inode.with_child_mut(end_idx, |child| {
                     replace_nodes_in_end_subtree(
                         Arc::make_mut(child),
                         range.end + child_measure - offset,
                         &mut extra_leaves,
                         &mut end_should_rebalance,
+                        removed,
                     )
                 });
 
@@ -804,20 +1004,24 @@ mod tree_replace {
     /// removes them if there are no extra leaves.
     #[track_caller]
     #[inline]
-    fn replace_nodes_in_start_subtree<const N: usize, M, L>(
+    fn replace_nodes_in_start_subtree<const N: usize, M, L, R>(
         node: &mut Node<N, L>,
         replace_from: M,
         replace_with: L::Replacement<'_>,
         should_rebalance: &mut bool,
+        removed: &mut R,
     ) -> Option<impl ExactSizeIterator<Item = Arc<Node<N, L>>>>
     where
         M: Metric<L::Summary>,
         L: ReplaceableLeaf<M> + Clone,
+        R: Removed<N, L, M>,
     {
         let inode = match node {
             Node::Internal(inode) => inode,
 
             Node::Leaf(leaf) => {
+                removed.push_leaf_range(leaf, replace_from..leaf.measure());
+
                 if let Some(extra_leaves) =
                     leaf.replace(replace_from.., replace_with)
                 {
@@ -837,9 +1041,14 @@ mod tree_replace {
                 replace_from - offset,
                 replace_with,
                 should_rebalance,
+                removed,
             )
         });
 
+        for child in &inode.children()[start_idx + 1..] {
+            removed.push_node(child);
+        }
+
         let extra_leaves = if let Some(mut extra_leaves) = extra_leaves {
             replace_child_range_with_leaves(
                 inode,
@@ -863,19 +1072,23 @@ mod tree_replace {
     /// nodes before that leaf, or removes them if there are no extra leaves.
     #[track_caller]
     #[inline]
-    fn replace_nodes_in_end_subtree<const N: usize, M, L>(
+    fn replace_nodes_in_end_subtree<const N: usize, M, L, R>(
         node: &mut Node<N, L>,
         replace_up_to: M,
         extra_leaves: &mut Option<Vec<Arc<Node<N, L>>>>,
         should_rebalance: &mut bool,
+        removed: &mut R,
     ) where
         M: Metric<L::Summary>,
         L: ReplaceableLeaf<M> + Clone,
+        R: Removed<N, L, M>,
     {
         let inode = match node {
             Node::Internal(inode) => inode,
 
             Node::Leaf(leaf) => {
+                removed.push_leaf_range(leaf, M::zero()..replace_up_to);
+
                 leaf.remove_up_to(replace_up_to);
 
                 if leaf.is_underfilled() {
@@ -916,12 +1129,17 @@ mod tree_replace {
             if offset < replace_up_to {
                 end_idx = idx;
 
+                for child in &inode.children()[..end_idx] {
+                    removed.push_node(child);
+                }
+
                 inode.with_child_mut(end_idx, |child| {
                     replace_nodes_in_end_subtree(
                         Arc::make_mut(child),
                         replace_up_to - offset,
                         extra_leaves,
                         should_rebalance,
+                        removed,
                     )
                 });
 
@@ -121,17 +121,22 @@ impl<'a, const ARITY: usize, L: Leaf, M: UnitMetric<L>> Iterator
 
                 iter.base_yielded = iter.base_total;
 
-                return Some((remainder, L::BaseMetric::measure(&advance)));
+                let advance = L::BaseMetric::measure(&advance);
+                self.remaining -= advance;
+                return Some((remainder, advance));
             } else {
                 return None;
             };
 
         debug_assert_eq!(M::measure(&advance), M::one());
 
-        iter.base_yielded += L::BaseMetric::measure(&advance);
+        let advance = L::BaseMetric::measure(&advance);
+
+        iter.base_yielded += advance;
         iter.units_yielded += M::one();
+        self.remaining -= advance;
 
-        Some((tree_slice, L::BaseMetric::measure(&advance)))
+        Some((tree_slice, advance))
     }
 }
 
@@ -153,12 +158,10 @@ impl<const ARITY: usize, L: Leaf, M: DoubleEndedUnitMetric<L>>
                 if let Some((remainder, advance)) = iter.remainder() {
                     debug_assert_eq!(M::measure(&advance), M::zero());
 
-                    iter.base_remaining -= L::BaseMetric::measure(&advance);
-
-                    return Some((
-                        remainder,
-                        L::BaseMetric::measure(&advance),
-                    ));
+                    let advance = L::BaseMetric::measure(&advance);
+                    iter.base_remaining -= advance;
+                    self.remaining -= advance;
+                    return Some((remainder, advance));
                 }
             }
         }
@@ -177,10 +180,13 @@ impl<const ARITY: usize, L: Leaf, M: DoubleEndedUnitMetric<L>>
 
         debug_assert_eq!(M::measure(&advance), M::one());
 
-        iter.base_remaining -= L::BaseMetric::measure(&advance);
+        let advance = L::BaseMetric::measure(&advance);
+
+        iter.base_remaining -= advance;
         iter.units_remaining -= M::one();
+        self.remaining -= advance;
 
-        Some((tree_slice, L::BaseMetric::measure(&advance)))
+        Some((tree_slice, advance))
     }
 }
 
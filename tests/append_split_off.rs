@@ -0,0 +1,214 @@
+use crop::Rope;
+use rand::{thread_rng, Rng};
+
+mod common;
+
+use common::{random_char_boundary, CURSED_LIPSUM, LARGE, MEDIUM, SMALL, TINY};
+
+#[test]
+fn append_empty() {
+    let mut r = Rope::new();
+    r.append(Rope::new());
+    r.assert_invariants();
+    assert_eq!(r, "");
+
+    r.append(Rope::from("foo\n"));
+    r.assert_invariants();
+    assert_eq!(r, "foo\n");
+    assert_eq!(r.line_len(), 1);
+
+    r.append(Rope::new());
+    r.assert_invariants();
+    assert_eq!(r, "foo\n");
+    assert_eq!(r.line_len(), 1);
+}
+
+#[test]
+fn append_trailing_newline() {
+    let mut r = Rope::from("foo\n");
+    r.append(Rope::from("bar"));
+    r.assert_invariants();
+    assert_eq!(r.line_len(), 2);
+
+    r.append(Rope::from("\n"));
+    r.assert_invariants();
+    assert_eq!(r.line_len(), 2);
+    assert_eq!(r.line(1), "bar");
+}
+
+#[test]
+fn append_split_crlf() {
+    let mut r = Rope::from("foo\r");
+    r.append(Rope::from("\nbar"));
+    r.assert_invariants();
+    assert_eq!(r, "foo\r\nbar");
+    assert_eq!(r.line(0), "foo");
+    assert_eq!(r.line(1), "bar");
+    assert!(r.lines().eq(["foo", "bar"]));
+    assert!(r.lines().rev().eq(["bar", "foo"]));
+}
+
+#[test]
+fn append_different_depths() {
+    for (left, right) in [(TINY, LARGE), (LARGE, TINY), (SMALL, MEDIUM)] {
+        let mut r = Rope::from(left);
+        r.append(Rope::from(right));
+        r.assert_invariants();
+        assert_eq!(r, format!("{left}{right}"));
+    }
+}
+
+#[test]
+fn append_keeps_other_clones() {
+    let mut r = Rope::from(MEDIUM);
+    let other = Rope::from(LARGE);
+    let clone = other.clone();
+
+    r.append(other);
+    r.assert_invariants();
+
+    clone.assert_invariants();
+    assert_eq!(clone, LARGE);
+    assert_eq!(r, format!("{MEDIUM}{LARGE}"));
+}
+
+#[test]
+fn split_off_start_and_end() {
+    let mut r = Rope::from(MEDIUM);
+    let rest = r.split_off(r.byte_len());
+    r.assert_invariants();
+    rest.assert_invariants();
+    assert_eq!(r, MEDIUM);
+    assert_eq!(rest, "");
+
+    let rest = r.split_off(0);
+    r.assert_invariants();
+    rest.assert_invariants();
+    assert_eq!(r, "");
+    assert_eq!(rest, MEDIUM);
+}
+
+#[test]
+fn split_off_trailing_newline() {
+    let mut r = Rope::from("foo\nbar\n");
+
+    let rest = r.split_off(4);
+    r.assert_invariants();
+    rest.assert_invariants();
+    assert_eq!(r.line_len(), 1);
+    assert_eq!(rest.line_len(), 1);
+
+    let mut r = Rope::from("foo\nbar");
+    let rest = r.split_off(3);
+    assert_eq!(r.line_len(), 1);
+    assert_eq!(rest.line_len(), 2);
+    assert!(rest.lines().eq(["", "bar"]));
+}
+
+#[test]
+fn split_off_keeps_clones() {
+    let mut r = Rope::from(LARGE);
+    let clone = r.clone();
+
+    let rest = r.split_off(LARGE.len() / 2);
+    r.assert_invariants();
+    rest.assert_invariants();
+
+    assert_eq!(clone, LARGE);
+    assert_eq!(r, LARGE[..LARGE.len() / 2]);
+    assert_eq!(rest, LARGE[LARGE.len() / 2..]);
+}
+
+#[test]
+#[should_panic]
+fn split_off_out_of_bounds() {
+    let mut r = Rope::from("foo");
+    let _ = r.split_off(4);
+}
+
+#[test]
+#[should_panic]
+fn split_off_not_char_boundary() {
+    let mut r = Rope::from("🐸");
+    let _ = r.split_off(2);
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn split_off_then_append_random() {
+    let mut rng = thread_rng();
+
+    for s in [TINY, SMALL, MEDIUM, LARGE, CURSED_LIPSUM] {
+        for _ in 0..10 {
+            let at = random_char_boundary(&mut rng, s);
+
+            let mut r = Rope::from(s);
+
+            let rest = r.split_off(at);
+            r.assert_invariants();
+            rest.assert_invariants();
+            assert_eq!(r, s[..at]);
+            assert_eq!(rest, s[at..]);
+            assert_eq!(r.line_len(), Rope::from(&s[..at]).line_len());
+            assert_eq!(rest.line_len(), Rope::from(&s[at..]).line_len());
+
+            r.append(rest);
+            r.assert_invariants();
+            assert_eq!(r, s);
+            assert_eq!(r.line_len(), Rope::from(s).line_len());
+        }
+    }
+}
+
+/// Tests splitting off a `Rope` between the "\r" and the "\n" of a CRLF
+/// grapheme, including when that grapheme starts a leaf of the grapheme
+/// tree.
+#[cfg_attr(miri, ignore)]
+#[test]
+fn split_off_inside_grapheme() {
+    let s = "\r\na".repeat(5000);
+
+    for at in (1..1000).filter(|&at| s[..at].ends_with('\r')) {
+        let mut r = Rope::from(s.as_str());
+
+        let rest = r.split_off(at);
+        r.assert_grapheme_invariants();
+        rest.assert_grapheme_invariants();
+        assert_eq!(r, s[..at]);
+        assert_eq!(rest, s[at..]);
+
+        r.append(rest);
+        r.assert_grapheme_invariants();
+        assert_eq!(r, s);
+    }
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn append_random() {
+    let mut rng = thread_rng();
+
+    let mut r = Rope::new();
+    let mut s = String::new();
+
+    for _ in 0..100 {
+        let start = rng.gen_range(0..CURSED_LIPSUM.len());
+        let end = rng.gen_range(start..=CURSED_LIPSUM.len().min(start + 2000));
+
+        if !CURSED_LIPSUM.is_char_boundary(start)
+            || !CURSED_LIPSUM.is_char_boundary(end)
+        {
+            continue;
+        }
+
+        let text = &CURSED_LIPSUM[start..end];
+
+        r.append(Rope::from(text));
+        s.push_str(text);
+
+        r.assert_invariants();
+        assert_eq!(r, s);
+    }
+
+    r.assert_grapheme_invariants();
+}
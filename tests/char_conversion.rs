@@ -0,0 +1,150 @@
+mod common;
+
+#[cfg(feature = "char-metric")]
+mod tests {
+    use crop::Rope;
+
+    use crate::common::{CURSED_LIPSUM, LARGE, TEXT, TEXT_EMOJI};
+
+    #[test]
+    fn char_len_0() {
+        let r = Rope::from(TEXT);
+        assert_eq!(r.char_len(), 103);
+
+        let s = r.byte_slice(..);
+        assert_eq!(s.char_len(), 103);
+    }
+
+    #[test]
+    fn char_len_1() {
+        let r = Rope::from(TEXT_EMOJI);
+        assert_eq!(r.char_len(), 107);
+
+        let s = r.byte_slice(..);
+        assert_eq!(s.char_len(), 107);
+    }
+
+    #[test]
+    fn char_len_2() {
+        let r = Rope::new();
+        assert_eq!(r.char_len(), 0);
+    }
+
+    #[test]
+    fn char_len_3() {
+        let r = Rope::from("🐸");
+        assert_eq!(r.char_len(), 1);
+
+        let r = Rope::from(TEXT_EMOJI);
+        let s = r.byte_slice(16..39);
+        assert_eq!(s.char_len(), 20);
+    }
+
+    #[test]
+    fn byte_to_char_0() {
+        let r = Rope::new();
+        assert_eq!(r.char_of_byte(0), 0);
+
+        let s = r.byte_slice(..);
+        assert_eq!(s.char_of_byte(0), 0);
+    }
+
+    #[should_panic]
+    #[test]
+    fn byte_to_char_1() {
+        let r = Rope::new();
+        let _ = r.char_of_byte(1);
+    }
+
+    #[test]
+    fn byte_to_char_2() {
+        let r = Rope::from(TEXT_EMOJI);
+
+        assert_eq!(0, r.char_of_byte(0));
+
+        assert_eq!(12, r.char_of_byte(12));
+        assert_eq!(13, r.char_of_byte(16));
+
+        assert_eq!(32, r.char_of_byte(35));
+        assert_eq!(33, r.char_of_byte(39));
+
+        assert_eq!(107, r.char_of_byte(143));
+    }
+
+    #[should_panic]
+    #[test]
+    fn byte_to_char_3() {
+        let r = Rope::from(TEXT_EMOJI);
+        let _ = r.char_of_byte(13);
+    }
+
+    #[test]
+    fn char_to_byte_0() {
+        let r = Rope::new();
+        assert_eq!(r.byte_of_char(0), 0);
+
+        let s = r.byte_slice(..);
+        assert_eq!(s.byte_of_char(0), 0);
+    }
+
+    #[should_panic]
+    #[test]
+    fn char_to_byte_1() {
+        let r = Rope::new();
+        let _ = r.byte_of_char(1);
+    }
+
+    #[test]
+    fn char_to_byte_2() {
+        let r = Rope::from(TEXT_EMOJI);
+        let s = r.byte_slice(..);
+
+        assert_eq!(0, s.byte_of_char(0));
+
+        assert_eq!(12, s.byte_of_char(12));
+        assert_eq!(16, s.byte_of_char(13));
+
+        assert_eq!(35, s.byte_of_char(32));
+        assert_eq!(39, s.byte_of_char(33));
+
+        assert_eq!(143, s.byte_of_char(107));
+    }
+
+    #[test]
+    fn char_slice_0() {
+        let r = Rope::from(CURSED_LIPSUM);
+
+        for (char_idx, (byte_idx, ch)) in
+            CURSED_LIPSUM.char_indices().enumerate()
+        {
+            let end = byte_idx + ch.len_utf8();
+            assert_eq!(
+                r.char_slice(char_idx..char_idx + 1),
+                &CURSED_LIPSUM[byte_idx..end]
+            );
+            assert_eq!(r.byte_of_char(char_idx), byte_idx);
+            assert_eq!(r.char_of_byte(byte_idx), char_idx);
+        }
+    }
+
+    #[test]
+    fn char_conversion_large() {
+        let r = Rope::from(LARGE);
+        let s = r.byte_slice(1000..LARGE.len() - 1000);
+        let str = &LARGE[1000..LARGE.len() - 1000];
+
+        assert_eq!(s.char_len(), str.chars().count());
+
+        for (char_offset, (byte_offset, ch)) in
+            str.char_indices().enumerate().step_by(97)
+        {
+            let end = byte_offset + ch.len_utf8();
+            assert_eq!(s.byte_of_char(char_offset), byte_offset);
+            assert_eq!(s.char_of_byte(byte_offset), char_offset);
+            assert_eq!(
+                s.char_slice(char_offset..char_offset + 1),
+                &str[byte_offset..end]
+            );
+        }
+    }
+}
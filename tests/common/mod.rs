@@ -1,5 +1,10 @@
 #![allow(dead_code)]
 
+use std::ops::Range;
+
+use crop::Rope;
+use rand::Rng;
+
 pub const TINY: &str = include_str!("tiny.txt");
 pub const SMALL: &str = include_str!("small.txt");
 pub const MEDIUM: &str = include_str!("medium.txt");
@@ -30,3 +35,65 @@ pub const TEXT_LINES: &str = "Hello there!  How're you doing?\nIt's a fine \
 pub const TEXT_EMOJI: &str = "Hello there!🐸  How're you doing?🐸  It's a \
                               fine day, isn't it?🐸  Aren't you glad we're \
                               alive?🐸  こんにちは、みんなさん！";
+
+/// A text whose char boundaries can be queried, i.e. a `str` or a `Rope`.
+pub trait Text {
+    fn byte_len(&self) -> usize;
+
+    fn is_char_boundary(&self, byte_offset: usize) -> bool;
+}
+
+impl Text for str {
+    fn byte_len(&self) -> usize {
+        self.len()
+    }
+
+    fn is_char_boundary(&self, byte_offset: usize) -> bool {
+        str::is_char_boundary(self, byte_offset)
+    }
+}
+
+impl Text for Rope {
+    fn byte_len(&self) -> usize {
+        Rope::byte_len(self)
+    }
+
+    fn is_char_boundary(&self, byte_offset: usize) -> bool {
+        Rope::is_char_boundary(self, byte_offset)
+    }
+}
+
+/// Returns the closest char boundary at or before the given byte offset.
+pub fn floor_char_boundary<T>(text: &T, mut byte_offset: usize) -> usize
+where
+    T: Text + ?Sized,
+{
+    while !text.is_char_boundary(byte_offset) {
+        byte_offset -= 1;
+    }
+
+    byte_offset
+}
+
+/// Returns a random char boundary of the text.
+pub fn random_char_boundary<T>(rng: &mut impl Rng, text: &T) -> usize
+where
+    T: Text + ?Sized,
+{
+    floor_char_boundary(text, rng.gen_range(0..=text.byte_len()))
+}
+
+/// Returns a random byte range of the text at most `max_len` bytes long,
+/// whose start and end both lie on char boundaries.
+pub fn random_byte_range<T>(
+    rng: &mut impl Rng,
+    text: &T,
+    max_len: usize,
+) -> Range<usize>
+where
+    T: Text + ?Sized,
+{
+    let start = random_char_boundary(rng, text);
+    let end = (start + rng.gen_range(0..=max_len)).min(text.byte_len());
+    start..floor_char_boundary(text, end)
+}
@@ -0,0 +1,257 @@
+use crop::Rope;
+use rand::{thread_rng, Rng};
+
+mod common;
+
+use common::{random_char_boundary, CURSED_LIPSUM, LARGE, MEDIUM, SMALL, TINY};
+
+/// Returns the byte offset of the start of the line containing the byte
+/// offset in the string, or of the previous line if it's already at the
+/// start of a line.
+fn prev_line_start(s: &str, byte_offset: usize) -> usize {
+    let before = &s[..byte_offset];
+    let before = before.strip_suffix('\n').unwrap_or(before);
+    before.rfind('\n').map_or(0, |idx| idx + 1)
+}
+
+#[test]
+fn cursor_empty() {
+    let r = Rope::new();
+    let mut cursor = r.cursor_at(0);
+
+    assert_eq!(cursor.byte_offset(), 0);
+    assert_eq!(cursor.line_offset(), 0);
+    assert_eq!(cursor.next_byte(), None);
+    assert_eq!(cursor.prev_byte(), None);
+    assert_eq!(cursor.next_char(), None);
+    assert_eq!(cursor.prev_char(), None);
+    assert_eq!(cursor.next_line(), None);
+    assert_eq!(cursor.prev_line(), None);
+}
+
+#[test]
+#[should_panic]
+fn cursor_at_out_of_bounds() {
+    let r = Rope::from("foo");
+    let _ = r.cursor_at(4);
+}
+
+#[test]
+fn cursor_bytes_forward_and_backward() {
+    for s in [TINY, SMALL, MEDIUM, LARGE, CURSED_LIPSUM] {
+        let r = Rope::from(s);
+
+        let mut cursor = r.cursor_at(0);
+        let mut bytes = Vec::new();
+
+        while let Some(byte) = cursor.next_byte() {
+            bytes.push(byte);
+        }
+
+        assert_eq!(bytes, s.as_bytes());
+        assert_eq!(cursor.byte_offset(), s.len());
+
+        while let Some(byte) = cursor.prev_byte() {
+            assert_eq!(Some(byte), bytes.pop());
+        }
+
+        assert!(bytes.is_empty());
+        assert_eq!(cursor.byte_offset(), 0);
+    }
+}
+
+#[test]
+fn cursor_chars_forward_and_backward() {
+    for s in [TINY, SMALL, MEDIUM, LARGE, CURSED_LIPSUM] {
+        let r = Rope::from(s);
+
+        let mut cursor = r.cursor_at(0);
+        let mut chars = s.chars();
+
+        while let Some(ch) = cursor.next_char() {
+            assert_eq!(Some(ch), chars.next());
+            assert_eq!(cursor.byte_offset(), s.len() - chars.as_str().len());
+        }
+
+        assert_eq!(chars.next(), None);
+
+        let mut chars = s.chars();
+
+        while let Some(ch) = cursor.prev_char() {
+            assert_eq!(Some(ch), chars.next_back());
+            assert_eq!(cursor.byte_offset(), chars.as_str().len());
+        }
+
+        assert_eq!(chars.next_back(), None);
+    }
+}
+
+#[test]
+fn cursor_chars_inside_char() {
+    let r = Rope::from("a🐸b");
+
+    let mut cursor = r.cursor_at(2);
+    assert_eq!(cursor.next_char(), Some('🐸'));
+    assert_eq!(cursor.byte_offset(), 5);
+
+    let mut cursor = r.cursor_at(3);
+    assert_eq!(cursor.prev_char(), Some('🐸'));
+    assert_eq!(cursor.byte_offset(), 1);
+}
+
+#[test]
+fn cursor_lines_forward_and_backward() {
+    for s in [TINY, SMALL, MEDIUM, LARGE, CURSED_LIPSUM] {
+        let r = Rope::from(s);
+
+        let mut cursor = r.cursor_at(0);
+        let mut lines = s.split_inclusive('\n');
+        let mut line_offset = 0;
+
+        while let Some(line) = cursor.next_line() {
+            assert_eq!(Some(line.to_string().as_str()), lines.next());
+            line_offset += 1;
+
+            if cursor.byte_offset() < s.len() {
+                assert_eq!(cursor.line_offset(), line_offset);
+            }
+        }
+
+        assert_eq!(lines.next(), None);
+
+        let mut lines = s.split_inclusive('\n');
+
+        while let Some(line) = cursor.prev_line() {
+            assert_eq!(Some(line.to_string().as_str()), lines.next_back());
+        }
+
+        assert_eq!(lines.next_back(), None);
+    }
+}
+
+#[test]
+fn cursor_on_slice() {
+    let r = Rope::from(LARGE);
+
+    let (start, end) = (LARGE.len() / 3, LARGE.len() / 2);
+    let s = &LARGE[start..end];
+    let slice = r.byte_slice(start..end);
+
+    let mut cursor = slice.cursor_at(s.len());
+    let mut chars = s.chars();
+
+    while let Some(ch) = cursor.prev_char() {
+        assert_eq!(Some(ch), chars.next_back());
+    }
+
+    assert_eq!(chars.next_back(), None);
+    assert_eq!(
+        cursor.next_line().unwrap(),
+        s.split_inclusive('\n').next().unwrap()
+    );
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn cursor_random_walk() {
+    let mut rng = thread_rng();
+
+    for s in [LARGE, CURSED_LIPSUM] {
+        let r = Rope::from(s);
+
+        for _ in 0..10 {
+            let mut offset = random_char_boundary(&mut rng, s);
+
+            let mut cursor = r.cursor_at(offset);
+
+            for _ in 0..100 {
+                match rng.gen_range(0..6) {
+                    0 => {
+                        let expected = s[offset..].chars().next();
+                        assert_eq!(cursor.next_char(), expected);
+                        offset += expected.map_or(0, char::len_utf8);
+                    },
+
+                    1 => {
+                        let expected = s[..offset].chars().next_back();
+                        assert_eq!(cursor.prev_char(), expected);
+                        offset -= expected.map_or(0, char::len_utf8);
+                    },
+
+                    2 => {
+                        let end = s[offset..]
+                            .find('\n')
+                            .map_or(s.len(), |idx| offset + idx + 1);
+
+                        let line = cursor.next_line();
+
+                        if offset == s.len() {
+                            assert_eq!(line, None);
+                        } else {
+                            assert_eq!(line.unwrap(), s[offset..end]);
+                        }
+
+                        offset = end;
+                    },
+
+                    3 => {
+                        let start = prev_line_start(s, offset);
+
+                        let line = cursor.prev_line();
+
+                        if offset == 0 {
+                            assert_eq!(line, None);
+                        } else {
+                            assert_eq!(line.unwrap(), s[start..offset]);
+                        }
+
+                        offset = start;
+                    },
+
+                    4 => {
+                        let new_offset = random_char_boundary(&mut rng, s);
+
+                        cursor.seek(new_offset);
+                        offset = new_offset;
+                    },
+
+                    _ => {
+                        assert_eq!(
+                            cursor.line_offset(),
+                            s[..offset].matches('\n').count()
+                        );
+                    },
+                }
+
+                assert_eq!(cursor.byte_offset(), offset);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "graphemes")]
+#[test]
+fn cursor_graphemes_forward_and_backward() {
+    use unicode_segmentation::UnicodeSegmentation;
+
+    for s in [SMALL, CURSED_LIPSUM] {
+        let r = Rope::from(s);
+
+        let mut cursor = r.cursor_at(0);
+        let mut graphemes = s.graphemes(true);
+
+        while let Some(grapheme) = cursor.next_grapheme() {
+            assert_eq!(grapheme, graphemes.next().unwrap());
+        }
+
+        assert_eq!(graphemes.next(), None);
+
+        let mut graphemes = s.graphemes(true);
+
+        while let Some(grapheme) = cursor.prev_grapheme() {
+            assert_eq!(grapheme, graphemes.next_back().unwrap());
+        }
+
+        assert_eq!(graphemes.next_back(), None);
+    }
+}
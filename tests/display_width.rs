@@ -0,0 +1,217 @@
+mod common;
+
+#[cfg(feature = "unicode-width")]
+mod tests {
+    use std::ops::Range;
+
+    use crop::{Rope, RopeSlice};
+    use rand::{thread_rng, Rng};
+    use unicode_width::UnicodeWidthChar;
+
+    use crate::common::{
+        random_byte_range,
+        random_char_boundary,
+        CURSED_LIPSUM,
+        SMALL,
+        TEXT_EMOJI,
+    };
+
+    const WIDE: &str = "日本語のテキスト\r\nカタカナ🐸 e\u{301}\u{308}x\n\
+                        \t\u{200b}한국어 ｆｕｌｌ\u{3000}width\n\n\
+                        🇷🇸🐻\u{200d}❄\u{fe0f}";
+
+    fn width(ch: char) -> usize {
+        ch.width().unwrap_or(0)
+    }
+
+    /// Returns whether the char at the given byte offset of the string is a
+    /// line break, i.e. a `\n` or, with the `unicode-lines` feature, also a
+    /// `\r` that's not followed by a `\n` (the strings we test don't contain
+    /// any of the other line breaks).
+    fn is_line_break(s: &str, offset: usize) -> bool {
+        match s.as_bytes()[offset] {
+            b'\n' => true,
+            b'\r' => {
+                cfg!(feature = "unicode-lines")
+                    && s.as_bytes().get(offset + 1) != Some(&b'\n')
+            },
+            _ => false,
+        }
+    }
+
+    /// Splits the string into its lines, without their line terminators.
+    fn lines(s: &str) -> Vec<&str> {
+        let mut lines = Vec::new();
+        let mut line_start = 0;
+
+        for (offset, _) in s.char_indices() {
+            if is_line_break(s, offset) {
+                let line = &s[line_start..offset];
+                lines.push(line.strip_suffix('\r').unwrap_or(line));
+                line_start = offset + 1;
+            }
+        }
+
+        if line_start < s.len() {
+            lines.push(&s[line_start..]);
+        }
+
+        lines
+    }
+
+    /// Returns, for every char boundary of the string, its byte offset and
+    /// its column within its line.
+    fn columns(s: &str) -> Vec<(usize, usize)> {
+        let mut columns = Vec::new();
+        let mut column = 0;
+
+        for (offset, ch) in s.char_indices() {
+            columns.push((offset, column));
+            column =
+                if is_line_break(s, offset) { 0 } else { column + width(ch) };
+        }
+
+        columns.push((s.len(), column));
+        columns
+    }
+
+    fn byte_of_display_column(line: &str, column: usize) -> usize {
+        let mut width_so_far = 0;
+
+        for (offset, ch) in line.char_indices() {
+            width_so_far += width(ch);
+
+            if width_so_far > column {
+                return offset;
+            }
+        }
+
+        line.len()
+    }
+
+    fn assert_display_width(slice: RopeSlice<'_>, s: &str) {
+        for (offset, column) in columns(s) {
+            assert_eq!(
+                slice.display_column_of_byte(offset),
+                column,
+                "offset {offset}"
+            );
+        }
+
+        let lines = lines(s);
+
+        assert_eq!(slice.line_len(), lines.len());
+
+        for (line_index, line) in lines.into_iter().enumerate() {
+            let line_width = line.chars().map(width).sum::<usize>();
+
+            assert_eq!(slice.display_width(line_index), line_width);
+
+            let line_start = slice.byte_of_line(line_index);
+
+            for column in 0..=line_width {
+                assert_eq!(
+                    slice.byte_of_display_column(line_index, column),
+                    line_start + byte_of_display_column(line, column),
+                    "line {line_index}, column {column}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn display_width_empty() {
+        let r = Rope::new();
+        assert_eq!(r.display_column_of_byte(0), 0);
+
+        let r = Rope::from("\n");
+        assert_eq!(r.display_width(0), 0);
+        assert_eq!(r.byte_of_display_column(0, 0), 0);
+        assert_eq!(r.display_column_of_byte(1), 0);
+    }
+
+    #[test]
+    fn display_width_wide_and_zero_width() {
+        let r = Rope::from("a日e\u{301}🐸");
+
+        assert_eq!(r.display_width(0), 6);
+
+        assert_eq!(r.byte_of_display_column(0, 0), 0);
+        assert_eq!(r.byte_of_display_column(0, 1), 1);
+        assert_eq!(r.byte_of_display_column(0, 2), 1);
+        assert_eq!(r.byte_of_display_column(0, 3), 4);
+        assert_eq!(r.byte_of_display_column(0, 4), 7);
+        assert_eq!(r.byte_of_display_column(0, 5), 7);
+        assert_eq!(r.byte_of_display_column(0, 6), 11);
+
+        assert_eq!(r.display_column_of_byte(4), 3);
+        assert_eq!(r.display_column_of_byte(5), 4);
+        assert_eq!(r.display_column_of_byte(7), 4);
+    }
+
+    #[test]
+    fn display_width_matches_unicode_width() {
+        for s in [WIDE, CURSED_LIPSUM, TEXT_EMOJI] {
+            let r = Rope::from(s);
+            assert_display_width(r.byte_slice(..), s);
+        }
+    }
+
+    #[test]
+    fn display_width_after_edits() {
+        let mut rng = thread_rng();
+
+        let mut r = Rope::from(SMALL);
+        let mut s = String::from(SMALL);
+
+        for _ in 0..50 {
+            let offset = random_char_boundary(&mut rng, s.as_str());
+
+            let (len, _) =
+                WIDE.char_indices().nth(rng.gen_range(0..20)).unwrap();
+
+            let text = &WIDE[..len];
+
+            r.insert(offset, text);
+            s.insert_str(offset, text);
+        }
+
+        assert_display_width(r.byte_slice(..), &s);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn display_width_random_slices() {
+        let mut rng = thread_rng();
+
+        let s = WIDE.repeat(20);
+        let r = Rope::from(s.as_str());
+
+        for _ in 0..20 {
+            let Range { start, mut end } =
+                random_byte_range(&mut rng, s.as_str(), s.len());
+
+            // A slice ending between the two halves of a CRLF pair doesn't
+            // count its `\r` as a line break.
+            if s[..end].ends_with('\r') && s[end..].starts_with('\n') {
+                end += 1;
+            }
+
+            assert_display_width(r.byte_slice(start..end), &s[start..end]);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn byte_of_display_column_out_of_bounds() {
+        let r = Rope::from("日本\n");
+        let _ = r.byte_of_display_column(0, 5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn display_column_of_byte_not_char_boundary() {
+        let r = Rope::from("日本\n");
+        let _ = r.display_column_of_byte(1);
+    }
+}
@@ -0,0 +1,177 @@
+use crop::Rope;
+use rand::{thread_rng, Rng};
+
+mod common;
+
+use common::{
+    floor_char_boundary,
+    random_char_boundary,
+    CURSED_LIPSUM,
+    LARGE,
+    MEDIUM,
+    SMALL,
+    TEXT_EMOJI,
+    TINY,
+};
+
+#[test]
+fn find_empty_rope() {
+    let r = Rope::new();
+
+    assert_eq!(r.find("foo"), None);
+    assert_eq!(r.rfind('a'), None);
+    assert_eq!(r.find(""), Some(0..0));
+    assert_eq!(r.rfind(""), Some(0..0));
+
+    let mut matches = r.find_iter("");
+    assert_eq!(matches.next(), Some(0..0));
+    assert_eq!(matches.next(), None);
+}
+
+#[test]
+fn find_empty_pattern() {
+    let s = "a🐸\nb";
+    let r = Rope::from(s);
+
+    let expected = s.match_indices("").map(|(idx, _)| idx..idx);
+
+    assert_eq!(
+        r.find_iter("").collect::<Vec<_>>(),
+        expected.collect::<Vec<_>>()
+    );
+    assert_eq!(r.rfind(""), Some(s.len()..s.len()));
+}
+
+#[test]
+fn find_patterns() {
+    let r = Rope::from("foo bar 🐸 baz bar");
+    let bar = String::from("bar");
+
+    assert_eq!(r.find("bar"), Some(4..7));
+    assert_eq!(r.find(&bar), Some(4..7));
+    assert_eq!(r.rfind("bar"), Some(17..20));
+    assert_eq!(r.find('🐸'), Some(8..12));
+    assert_eq!(r.rfind('🐸'), Some(8..12));
+    assert_eq!(r.find('x'), None);
+    assert_eq!(r.find("bart"), None);
+    assert_eq!(r.find_iter('a').count(), 3);
+}
+
+#[test]
+fn find_iter_non_overlapping() {
+    let r = Rope::from("aaaaa");
+    assert_eq!(r.find_iter("aa").collect::<Vec<_>>(), [0..2, 2..4]);
+    assert_eq!(r.rfind("aa"), Some(3..5));
+}
+
+#[test]
+fn find_in_slice() {
+    let r = Rope::from("foo\nbar\nfoo\nbar\n");
+    let s = r.byte_slice(1..14);
+
+    assert_eq!(s.find("foo"), Some(7..10));
+    assert_eq!(s.rfind("bar"), Some(3..6));
+    assert_eq!(s.find("oo\nbar\nfoo\nba"), Some(0..13));
+    assert_eq!(s.find("bar\n"), Some(3..7));
+    assert_eq!(s.rfind("bar\n"), Some(3..7));
+}
+
+/// Tests matches that span two or more chunks, including the two halves of a
+/// chunk's gap buffer.
+#[test]
+fn find_across_chunks() {
+    for s in [TINY, SMALL, MEDIUM, LARGE, CURSED_LIPSUM] {
+        let mut r = Rope::from(s);
+
+        // Moves the gaps of the gap buffers around.
+        for offset in (0..s.len()).step_by(97) {
+            if s.is_char_boundary(offset) {
+                r.insert(offset, "a");
+                r.delete(offset..offset + 1);
+            }
+        }
+
+        let chunk_ends = r
+            .chunks()
+            .scan(0, |offset, chunk| {
+                *offset += chunk.len();
+                Some(*offset)
+            })
+            .collect::<Vec<_>>();
+
+        for &offset in chunk_ends.iter().step_by(chunk_ends.len() / 50 + 1) {
+            let start = floor_char_boundary(s, offset.saturating_sub(3));
+            let end = floor_char_boundary(s, (offset + 1500).min(s.len()));
+
+            let needle = &s[start..end];
+
+            assert_eq!(
+                r.find(needle),
+                s.find(needle).map(|i| i..i + needle.len())
+            );
+            assert_eq!(
+                r.rfind(needle),
+                s.rfind(needle).map(|i| i..i + needle.len())
+            );
+        }
+    }
+}
+
+#[test]
+fn find_iter_matches_str() {
+    for s in [TINY, SMALL, MEDIUM, LARGE, CURSED_LIPSUM, TEXT_EMOJI] {
+        let r = Rope::from(s);
+
+        for pattern in ["\n", "a", "the", "🐸", "\r\n", "ḽ", " a", "e a"] {
+            let expected = s
+                .match_indices(pattern)
+                .map(|(idx, m)| idx..idx + m.len())
+                .collect::<Vec<_>>();
+
+            assert_eq!(r.find_iter(pattern).collect::<Vec<_>>(), expected);
+            assert_eq!(r.find(pattern), expected.first().cloned());
+            assert_eq!(r.rfind(pattern), expected.last().cloned());
+        }
+    }
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn find_random() {
+    let mut rng = thread_rng();
+
+    for s in [LARGE, CURSED_LIPSUM] {
+        let r = Rope::from(s);
+
+        for _ in 0..100 {
+            let start = random_char_boundary(&mut rng, s);
+            let end = random_char_boundary(&mut rng, s);
+            let (start, end) = (start.min(end), start.max(end));
+
+            let slice_str = &s[start..end];
+            let slice = r.byte_slice(start..end);
+
+            let mut needle_start = random_char_boundary(&mut rng, slice_str);
+            let mut needle_end =
+                (needle_start + rng.gen_range(0..50)).min(slice_str.len());
+
+            while !slice_str.is_char_boundary(needle_end) {
+                needle_end -= 1;
+            }
+
+            if needle_start > needle_end {
+                needle_start = needle_end;
+            }
+
+            let needle = &slice_str[needle_start..needle_end];
+
+            let expected = slice_str
+                .match_indices(needle)
+                .map(|(idx, m)| idx..idx + m.len())
+                .collect::<Vec<_>>();
+
+            assert_eq!(slice.find_iter(needle).collect::<Vec<_>>(), expected);
+            assert_eq!(slice.rfind(needle), expected.last().cloned());
+        }
+    }
+}
@@ -0,0 +1,359 @@
+use std::ops::Range;
+use std::slice;
+
+use crop::{FoldMap, Rope};
+use rand::{thread_rng, Rng};
+
+mod common;
+
+use common::{random_byte_range, CURSED_LIPSUM, SMALL, TEXT_EMOJI};
+
+const PLACEHOLDER: &str = "⋯";
+
+/// Returns the text displayed when folding the given ranges of `s`.
+fn naive_folded(s: &str, folds: &[Range<usize>]) -> String {
+    let mut folded = String::new();
+    let mut offset = 0;
+
+    for fold in folds {
+        folded.push_str(&s[offset..fold.start]);
+        folded.push_str(PLACEHOLDER);
+        offset = fold.end;
+    }
+
+    folded.push_str(&s[offset..]);
+    folded
+}
+
+/// Returns the offset in the folded text of the given byte offset.
+fn naive_display_offset(folds: &[Range<usize>], byte_offset: usize) -> usize {
+    let mut display_offset = byte_offset;
+
+    for fold in folds {
+        if fold.end <= byte_offset {
+            display_offset -= fold.len();
+            display_offset += PLACEHOLDER.len();
+        } else if fold.start < byte_offset {
+            display_offset -= byte_offset - fold.start;
+        }
+    }
+
+    display_offset
+}
+
+/// Folds the given range, merging it with all the folds it overlaps with.
+fn naive_fold(folds: &mut Vec<Range<usize>>, mut new_fold: Range<usize>) {
+    if new_fold.is_empty() {
+        return;
+    }
+
+    folds.retain(|fold| {
+        let overlaps = fold.start < new_fold.end && new_fold.start < fold.end;
+
+        if overlaps {
+            new_fold.start = new_fold.start.min(fold.start);
+            new_fold.end = new_fold.end.max(fold.end);
+        }
+
+        !overlaps
+    });
+
+    let idx = folds.partition_point(|fold| fold.start < new_fold.start);
+
+    folds.insert(idx, new_fold);
+}
+
+/// Updates the folds after replacing `start..end` with `text_len` bytes.
+fn naive_edit(
+    folds: &mut Vec<Range<usize>>,
+    Range { start, end }: Range<usize>,
+    text_len: usize,
+) {
+    let shift = |offset: usize| offset - (end - start) + text_len;
+
+    for fold in folds.iter_mut() {
+        fold.start = if fold.start < start {
+            fold.start
+        } else if fold.start >= end {
+            shift(fold.start)
+        } else {
+            start + text_len
+        };
+
+        fold.end = if fold.end <= start {
+            fold.end
+        } else if fold.end > end {
+            shift(fold.end)
+        } else {
+            start
+        };
+    }
+
+    folds.retain(|fold| fold.start < fold.end);
+}
+
+/// Moves the start of every fold between the `\r` and the `\n` of a CRLF
+/// pair back to the `\r`, merging the folds that overlap as a result.
+#[cfg(feature = "unicode-lines")]
+fn naive_snap(s: &str, folds: &mut Vec<Range<usize>>) {
+    let mut snapped: Vec<Range<usize>> = Vec::new();
+
+    for mut fold in folds.drain(..) {
+        if s[..fold.start].ends_with('\r') && s[fold.start..].starts_with('\n')
+        {
+            fold.start -= 1;
+        }
+
+        match snapped.last_mut() {
+            Some(last) if last.end > fold.start => {
+                last.end = last.end.max(fold.end);
+            },
+            _ => snapped.push(fold),
+        }
+    }
+
+    *folds = snapped;
+}
+
+/// Checks the map against the naive model.
+fn assert_matches_naive(map: &FoldMap, r: &Rope, folds: &[Range<usize>]) {
+    let s = r.to_string();
+
+    let folded = naive_folded(&s, folds);
+
+    assert_eq!(map.folds().collect::<Vec<_>>(), folds);
+    assert_eq!(map.chunks(r).collect::<String>(), folded);
+    assert_eq!(map.display_len(), folded.len());
+
+    for (byte_offset, _) in s.char_indices().chain([(s.len(), ' ')]) {
+        let display_offset = naive_display_offset(folds, byte_offset);
+
+        assert_eq!(
+            map.display_offset_of_byte(byte_offset),
+            display_offset,
+            "byte offset {byte_offset}"
+        );
+
+        if !folds.iter().any(|fold| fold.contains(&byte_offset)) {
+            assert_eq!(
+                map.byte_of_display_offset(display_offset),
+                byte_offset
+            );
+        }
+    }
+
+    let folded = Rope::from(folded);
+
+    for line_index in 0..=r.line_of_byte(r.byte_len()) {
+        let display_offset =
+            naive_display_offset(folds, r.byte_of_line(line_index));
+
+        assert_eq!(
+            map.display_line_of_line(line_index),
+            folded.line_of_byte(display_offset),
+            "line {line_index}"
+        );
+    }
+
+    for display_line_index in 0..=folded.line_of_byte(folded.byte_len()) {
+        let display_offset = folded.byte_of_line(display_line_index);
+
+        let byte_offset = map.byte_of_display_offset(display_offset);
+
+        assert_eq!(
+            map.line_of_display_line(display_line_index),
+            r.line_of_byte(byte_offset),
+            "display line {display_line_index}"
+        );
+    }
+}
+
+#[test]
+fn fold_map_empty() {
+    let r = Rope::new();
+    let mut map = FoldMap::new(&r, PLACEHOLDER);
+
+    map.fold(&r, ..);
+
+    assert_eq!(map.folds().count(), 0);
+    assert_eq!(map.display_len(), 0);
+    assert_eq!(map.chunks(&r).count(), 0);
+    assert_eq!(map.display_line_of_line(0), 0);
+    assert_eq!(map.line_of_display_line(0), 0);
+}
+
+#[test]
+fn fold_map_whole_text() {
+    let r = Rope::from("foo\nbar\n");
+    let mut map = FoldMap::new(&r, PLACEHOLDER);
+
+    map.fold(&r, ..);
+
+    let folds = map.folds().collect::<Vec<_>>();
+
+    assert_eq!(folds.first(), Some(&(0..8)));
+    assert_matches_naive(&map, &r, &folds);
+    assert_eq!(map.display_line_of_line(2), 0);
+}
+
+#[test]
+fn fold_map_touching_folds() {
+    let r = Rope::from(SMALL);
+    let mut map = FoldMap::new(&r, PLACEHOLDER);
+
+    map.fold(&r, 10..20);
+    map.fold(&r, 20..30);
+    map.fold(&r, 0..10);
+
+    assert_matches_naive(&map, &r, &[0..10, 10..20, 20..30]);
+
+    // An empty range only unfolds the fold starting at its offset.
+    map.unfold(&r, 20..20);
+    assert_matches_naive(&map, &r, &[0..10, 10..20]);
+}
+
+/// Completing a CRLF pair right after a fold is counted as a single line
+/// break.
+#[test]
+fn fold_map_crlf_completed_after_fold() {
+    let mut r = Rope::from("a\rb");
+
+    let mut map = FoldMap::new(&r, PLACEHOLDER);
+    map.fold(&r, 0..2);
+
+    map.replace(&mut r, 2..2, "\n");
+    assert_matches_naive(&map, &r, slice::from_ref(&(0..2)));
+    assert_eq!(map.display_line_of_line(1), 1);
+}
+
+/// With the `unicode-lines` feature a fold never starts between the `\r` and
+/// the `\n` of a CRLF pair.
+#[cfg(feature = "unicode-lines")]
+#[test]
+fn fold_map_fold_starting_inside_crlf() {
+    let mut r = Rope::from("a\r\nb\rc");
+
+    let mut map = FoldMap::new(&r, PLACEHOLDER);
+
+    map.fold(&r, 2..4);
+    assert_matches_naive(&map, &r, slice::from_ref(&(1..4)));
+
+    // Completing a CRLF pair after a fold ending with its `\r` doesn't
+    // change the fold.
+    map.fold(&r, 4..5);
+    map.replace(&mut r, 5..5, "\n");
+    assert_matches_naive(&map, &r, &[1..4, 4..5]);
+
+    // Completing a CRLF pair before a fold starting with its `\n` moves the
+    // start of the fold.
+    let mut r = Rope::from("ab\nc");
+
+    let mut map = FoldMap::new(&r, PLACEHOLDER);
+    map.fold(&r, 2..3);
+
+    map.replace(&mut r, 2..2, "\r");
+    assert_matches_naive(&map, &r, slice::from_ref(&(2..4)));
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn fold_map_random_folds() {
+    let mut rng = thread_rng();
+
+    let r = Rope::from(SMALL.repeat(3));
+
+    let mut map = FoldMap::new(&r, PLACEHOLDER);
+
+    let mut folds = Vec::new();
+
+    for i in 0..100 {
+        let range = random_byte_range(&mut rng, &r, 60);
+
+        if rng.gen_bool(0.7) {
+            map.fold(&r, range.clone());
+            naive_fold(&mut folds, range);
+            #[cfg(feature = "unicode-lines")]
+            naive_snap(&r.to_string(), &mut folds);
+        } else {
+            map.unfold(&r, range.clone());
+            let (start, end) = (range.start, range.end.max(range.start + 1));
+            folds.retain(|fold| !(fold.start < end && start < fold.end));
+        }
+
+        if i % 10 == 0 {
+            assert_matches_naive(&map, &r, &folds);
+        }
+    }
+
+    assert_matches_naive(&map, &r, &folds);
+
+    map.unfold(&r, ..);
+    assert_matches_naive(&map, &r, &[]);
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn fold_map_random_edits() {
+    let mut rng = thread_rng();
+
+    let texts = [
+        "",
+        "\n",
+        "\r\n",
+        "\r",
+        " ",
+        "foo bar",
+        "a\nb\r\nc",
+        "\n\n\n",
+        CURSED_LIPSUM,
+        TEXT_EMOJI,
+    ];
+
+    let mut r = Rope::from(SMALL.repeat(3));
+
+    let mut map = FoldMap::new(&r, PLACEHOLDER);
+
+    let mut folds = Vec::new();
+
+    for i in 0..200 {
+        if rng.gen_bool(0.3) {
+            let range = random_byte_range(&mut rng, &r, 40);
+            map.fold(&r, range.clone());
+            naive_fold(&mut folds, range);
+        } else {
+            let range = random_byte_range(&mut rng, &r, 30);
+            let text = texts[rng.gen_range(0..texts.len())];
+            map.replace(&mut r, range.clone(), text);
+            naive_edit(&mut folds, range, text.len());
+        }
+
+        #[cfg(feature = "unicode-lines")]
+        naive_snap(&r.to_string(), &mut folds);
+
+        if i % 20 == 0 {
+            assert_matches_naive(&map, &r, &folds);
+        }
+    }
+
+    assert_matches_naive(&map, &r, &folds);
+
+    // Delete everything.
+    map.replace(&mut r, .., "");
+    assert_matches_naive(&map, &r, &[]);
+}
+
+#[test]
+#[should_panic]
+fn fold_map_empty_placeholder() {
+    let r = Rope::from("foo");
+    let _ = FoldMap::new(&r, "");
+}
+
+#[test]
+#[should_panic]
+fn fold_map_display_offset_out_of_bounds() {
+    let r = Rope::from("foo bar baz");
+    let mut map = FoldMap::new(&r, PLACEHOLDER);
+    map.fold(&r, 4..7);
+    let _ = map.byte_of_display_offset(12);
+}
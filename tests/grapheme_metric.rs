@@ -0,0 +1,289 @@
+mod common;
+
+#[cfg(feature = "grapheme-metric")]
+mod tests {
+    use std::ops::Range;
+
+    use crop::{Rope, RopeBuilder};
+    use rand::{thread_rng, Rng};
+    use unicode_segmentation::UnicodeSegmentation;
+
+    use crate::common::{
+        random_byte_range,
+        CURSED_LIPSUM,
+        LARGE,
+        TEXT,
+        TEXT_EMOJI,
+    };
+
+    /// Checks every grapheme conversion of the rope against the ones computed
+    /// on the equivalent string.
+    #[track_caller]
+    fn assert_graphemes(r: &Rope, s: &str) {
+        assert_eq!(r.grapheme_len(), s.graphemes(true).count());
+
+        for (grapheme_offset, (byte_offset, grapheme)) in
+            s.grapheme_indices(true).enumerate()
+        {
+            assert_eq!(r.byte_of_grapheme(grapheme_offset), byte_offset);
+            assert_eq!(r.grapheme_of_byte(byte_offset), grapheme_offset);
+            assert_eq!(
+                r.grapheme_slice(grapheme_offset..grapheme_offset + 1),
+                grapheme
+            );
+        }
+
+        assert_eq!(r.byte_of_grapheme(r.grapheme_len()), s.len());
+        assert_eq!(r.grapheme_of_byte(s.len()), r.grapheme_len());
+    }
+
+    #[test]
+    fn grapheme_len_0() {
+        let r = Rope::new();
+        assert_eq!(r.grapheme_len(), 0);
+        assert_eq!(r.byte_of_grapheme(0), 0);
+        assert_eq!(r.grapheme_of_byte(0), 0);
+    }
+
+    #[test]
+    fn grapheme_len_1() {
+        let r = Rope::from(TEXT);
+        assert_eq!(r.grapheme_len(), 103);
+
+        let r = Rope::from(TEXT_EMOJI);
+        assert_eq!(r.grapheme_len(), 107);
+    }
+
+    #[test]
+    fn grapheme_len_2() {
+        let r = Rope::from("🇷🇸🇮🇴\r\ne\u{301}🐻‍❄️");
+        assert_eq!(r.grapheme_len(), 5);
+    }
+
+    #[test]
+    fn grapheme_of_byte_inside_grapheme() {
+        let r = Rope::from("a🐻‍❄️b");
+
+        for byte_offset in 1..r.byte_len() - 1 {
+            assert_eq!(r.grapheme_of_byte(byte_offset), 1);
+        }
+
+        assert_eq!(r.grapheme_of_byte(r.byte_len() - 1), 2);
+    }
+
+    #[should_panic]
+    #[test]
+    fn byte_of_grapheme_out_of_bounds() {
+        let r = Rope::from("🇷🇸");
+        let _ = r.byte_of_grapheme(2);
+    }
+
+    #[should_panic]
+    #[test]
+    fn grapheme_slice_out_of_bounds() {
+        let r = Rope::from("e\u{301}");
+        let _ = r.grapheme_slice(..2);
+    }
+
+    #[test]
+    fn grapheme_conversions_cursed_lipsum() {
+        let r = Rope::from(CURSED_LIPSUM);
+        assert_graphemes(&r, CURSED_LIPSUM);
+    }
+
+    #[test]
+    fn grapheme_conversions_large() {
+        let r = Rope::from(LARGE);
+
+        assert_eq!(r.grapheme_len(), LARGE.graphemes(true).count());
+
+        for (grapheme_offset, (byte_offset, _)) in
+            LARGE.grapheme_indices(true).enumerate().step_by(97)
+        {
+            assert_eq!(r.byte_of_grapheme(grapheme_offset), byte_offset);
+            assert_eq!(r.grapheme_of_byte(byte_offset), grapheme_offset);
+        }
+    }
+
+    #[test]
+    fn grapheme_conversions_after_building() {
+        let mut builder = RopeBuilder::new();
+
+        for _ in 0..100 {
+            builder.append(CURSED_LIPSUM);
+        }
+
+        let r = builder.build();
+
+        r.assert_grapheme_invariants();
+
+        assert_eq!(
+            r.grapheme_len(),
+            100 * CURSED_LIPSUM.graphemes(true).count()
+        );
+    }
+
+    #[test]
+    fn grapheme_conversions_from_slice() {
+        let r = Rope::from(CURSED_LIPSUM);
+
+        // Start the slice between a "\r" and a "\n".
+        let start = CURSED_LIPSUM.find("\r\n").unwrap() + 1;
+        let s = &CURSED_LIPSUM[start..];
+
+        let r = Rope::from(r.byte_slice(start..));
+        r.assert_grapheme_invariants();
+        assert_graphemes(&r, s);
+    }
+
+    /// Tests converting byte and line slices, which slice the grapheme tree
+    /// of the `Rope` they come from, into `Rope`s.
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn grapheme_conversions_from_random_slices() {
+        let mut rng = thread_rng();
+
+        let s =
+            [CURSED_LIPSUM, TEXT_EMOJI, "🇷🇸🇷\r\r\n"].concat().repeat(4);
+        let r = Rope::from(s.as_str());
+
+        for _ in 0..50 {
+            let Range { start, end } =
+                random_byte_range(&mut rng, s.as_str(), s.len());
+
+            let slice = r.byte_slice(start..end);
+
+            let rope = Rope::from(slice);
+            rope.assert_grapheme_invariants();
+            assert_eq!(rope, &s[start..end]);
+
+            let lines = {
+                let start = rng.gen_range(0..=slice.line_len());
+                let end = rng.gen_range(start..=slice.line_len());
+                start..end
+            };
+
+            let line_slice = slice.line_slice(lines.clone());
+            let line_start = start + slice.byte_of_line(lines.start);
+
+            let rope = Rope::from(line_slice);
+            rope.assert_grapheme_invariants();
+            assert_eq!(
+                rope,
+                &s[line_start..line_start + line_slice.byte_len()]
+            );
+        }
+    }
+
+    #[test]
+    fn edits_merging_graphemes() {
+        let mut r = Rope::from("abe");
+        let mut s = String::from("abe");
+
+        // Adding a combining acute accent merges it with the "e".
+        r.insert(3, "\u{301}");
+        s.insert(3, '\u{301}');
+        r.assert_grapheme_invariants();
+        assert_graphemes(&r, &s);
+
+        // Adding a line feed after a carriage return merges them.
+        r.insert(0, "\r");
+        r.insert(1, "\n");
+        s.insert_str(0, "\r\n");
+        r.assert_grapheme_invariants();
+        assert_graphemes(&r, &s);
+
+        // Deleting the "e" leaves the accent on its own.
+        let e = s.find('e').unwrap();
+        r.delete(e..e + 1);
+        s.replace_range(e..e + 1, "");
+        r.assert_grapheme_invariants();
+        assert_graphemes(&r, &s);
+    }
+
+    #[test]
+    fn edits_changing_regional_indicators_parity() {
+        let flags = "🇷🇸🇮🇴🇷🇸🇮🇴🇷🇸🇮🇴";
+
+        let mut r = Rope::from(flags);
+        let mut s = String::from(flags);
+
+        assert_eq!(r.grapheme_len(), 6);
+
+        // Inserting a single regional indicator at the start shifts how all
+        // the following ones are paired up.
+        r.insert(0, "🇮");
+        s.insert(0, '🇮');
+        r.assert_grapheme_invariants();
+        assert_graphemes(&r, &s);
+
+        r.delete(..4);
+        s.replace_range(..4, "");
+        r.assert_grapheme_invariants();
+        assert_graphemes(&r, &s);
+    }
+
+    #[test]
+    fn edits_joining_emojis() {
+        let mut r = Rope::from("🐻x❄️");
+        let mut s = String::from("🐻x❄️");
+
+        assert_eq!(r.grapheme_len(), 3);
+
+        r.replace(4..5, "\u{200d}");
+        s.replace_range(4..5, "\u{200d}");
+        r.assert_grapheme_invariants();
+        assert_graphemes(&r, &s);
+        assert_eq!(r.grapheme_len(), 1);
+    }
+
+    #[test]
+    fn random_edits() {
+        let pieces = [
+            "a",
+            "bc",
+            "e\u{301}",
+            "\u{301}",
+            "\u{200d}",
+            "🐻",
+            "❄️",
+            "🇷",
+            "🇸",
+            "\r",
+            "\n",
+            "\r\n",
+            "ᄀ",
+            "ᅡ",
+            "ᆨ",
+            "こんにちは",
+        ];
+
+        let mut rng = thread_rng();
+
+        let mut r = Rope::new();
+        let mut s = String::new();
+
+        for _ in 0..500 {
+            let mut text = String::new();
+
+            for _ in 0..rng.gen_range(0..50) {
+                text.push_str(pieces[rng.gen_range(0..pieces.len())]);
+            }
+
+            let start = rng.gen_range(0..=s.len());
+            let end = rng.gen_range(start..=s.len().min(start + 40));
+
+            if !s.is_char_boundary(start) || !s.is_char_boundary(end) {
+                continue;
+            }
+
+            r.replace(start..end, &text);
+            s.replace_range(start..end, &text);
+
+            assert_eq!(r.grapheme_len(), s.graphemes(true).count());
+        }
+
+        r.assert_grapheme_invariants();
+        assert_graphemes(&r, &s);
+    }
+}
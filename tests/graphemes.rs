@@ -4,6 +4,11 @@ use std::borrow::Cow;
 #[cfg(feature = "graphemes")]
 use crop::Rope;
 
+mod common;
+
+#[cfg(feature = "graphemes")]
+use common::CURSED_LIPSUM;
+
 // TODO: remove the `#[ignore]`s once
 // https://github.com/unicode-rs/unicode-segmentation/issues/115 gets
 // resolved.
@@ -138,3 +143,106 @@ fn graphemes_is_boundary_out_of_bounds() {
     let r = Rope::from("🇷🇸🇮🇴");
     assert!(r.is_grapheme_boundary(17));
 }
+
+/// Checks `next_grapheme_boundary()` and `prev_grapheme_boundary()` at every
+/// byte offset against the grapheme boundaries of the equivalent string.
+#[cfg(feature = "graphemes")]
+#[track_caller]
+fn assert_grapheme_boundaries(
+    next: impl Fn(usize) -> usize,
+    prev: impl Fn(usize) -> usize,
+    s: &str,
+) {
+    use unicode_segmentation::UnicodeSegmentation;
+
+    let boundaries = s
+        .grapheme_indices(true)
+        .map(|(offset, _)| offset)
+        .chain(core::iter::once(s.len()))
+        .collect::<Vec<_>>();
+
+    for byte_offset in 0..=s.len() {
+        let expected_next = boundaries
+            .iter()
+            .copied()
+            .find(|&boundary| boundary > byte_offset)
+            .unwrap_or(s.len());
+
+        let expected_prev = boundaries
+            .iter()
+            .copied()
+            .rev()
+            .find(|&boundary| boundary < byte_offset)
+            .unwrap_or(0);
+
+        assert_eq!(next(byte_offset), expected_next, "{byte_offset}");
+        assert_eq!(prev(byte_offset), expected_prev, "{byte_offset}");
+    }
+}
+
+#[cfg(feature = "graphemes")]
+#[test]
+fn graphemes_next_prev_boundary_0() {
+    let r = Rope::new();
+    assert_eq!(r.next_grapheme_boundary(0), 0);
+    assert_eq!(r.prev_grapheme_boundary(0), 0);
+}
+
+#[cfg(feature = "graphemes")]
+#[test]
+fn graphemes_next_prev_boundary_1() {
+    let s = "e\u{301}\r\n🐻‍❄️각";
+    let r = Rope::from(s);
+    assert_grapheme_boundaries(
+        |offset| r.next_grapheme_boundary(offset),
+        |offset| r.prev_grapheme_boundary(offset),
+        s,
+    );
+}
+
+#[cfg(feature = "graphemes")]
+#[test]
+fn graphemes_next_prev_boundary_cursed_lipsum() {
+    let s = CURSED_LIPSUM.repeat(16);
+    let r = Rope::from(s.as_str());
+    assert_grapheme_boundaries(
+        |offset| r.next_grapheme_boundary(offset),
+        |offset| r.prev_grapheme_boundary(offset),
+        &s,
+    );
+}
+
+#[cfg(feature = "graphemes")]
+#[test]
+fn graphemes_next_prev_boundary_slice() {
+    let s = CURSED_LIPSUM.repeat(16);
+    let r = Rope::from(s.as_str());
+
+    // Start the slice between a "\r" and a "\n".
+    let start = s.find("\r\n").unwrap() + 1;
+    let end = s.len() - CURSED_LIPSUM.len() / 2;
+    let end = (end..).find(|&end| s.is_char_boundary(end)).unwrap();
+
+    let slice = r.byte_slice(start..end);
+    assert_grapheme_boundaries(
+        |offset| slice.next_grapheme_boundary(offset),
+        |offset| slice.prev_grapheme_boundary(offset),
+        &s[start..end],
+    );
+}
+
+#[cfg(feature = "graphemes")]
+#[should_panic]
+#[test]
+fn graphemes_next_boundary_out_of_bounds() {
+    let r = Rope::from("🇷🇸🇮🇴");
+    let _ = r.next_grapheme_boundary(17);
+}
+
+#[cfg(feature = "graphemes")]
+#[should_panic]
+#[test]
+fn graphemes_prev_boundary_out_of_bounds() {
+    let r = Rope::from("🇷🇸🇮🇴");
+    let _ = r.byte_slice(4..).prev_grapheme_boundary(13);
+}
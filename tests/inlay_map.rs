@@ -0,0 +1,233 @@
+use crop::{InlayMap, Rope};
+use rand::{thread_rng, Rng};
+
+mod common;
+
+use common::{
+    floor_char_boundary,
+    random_char_boundary,
+    CURSED_LIPSUM,
+    SMALL,
+    TEXT_EMOJI,
+};
+
+const INLAYS: [&str; 5] = [": u8", "a: ", "⟨…⟩", "\n", "🦀"];
+
+/// Returns the text displayed when splicing the given inlays into `s`.
+fn naive_displayed(s: &str, inlays: &[(usize, String)]) -> String {
+    let mut displayed = String::new();
+    let mut offset = 0;
+
+    for (anchor, text) in inlays {
+        displayed.push_str(&s[offset..*anchor]);
+        displayed.push_str(text);
+        offset = *anchor;
+    }
+
+    displayed.push_str(&s[offset..]);
+    displayed
+}
+
+/// Returns the offset in the displayed text of the given byte offset, i.e.
+/// the one right before the inlays anchored at it.
+fn naive_display_offset(
+    inlays: &[(usize, String)],
+    byte_offset: usize,
+) -> usize {
+    byte_offset
+        + inlays
+            .iter()
+            .filter(|(anchor, _)| *anchor < byte_offset)
+            .map(|(_, text)| text.len())
+            .sum::<usize>()
+}
+
+/// Updates the inlays after replacing `start..end` with `text_len` bytes.
+fn naive_edit(
+    inlays: &mut [(usize, String)],
+    start: usize,
+    end: usize,
+    text_len: usize,
+) {
+    for (anchor, _) in inlays.iter_mut() {
+        if *anchor >= end {
+            *anchor = *anchor - (end - start) + text_len;
+        } else if *anchor >= start {
+            *anchor = start + text_len;
+        }
+    }
+}
+
+/// Checks the map against the naive model.
+fn assert_matches_naive(
+    map: &InlayMap,
+    r: &Rope,
+    inlays: &[(usize, String)],
+) {
+    let s = r.to_string();
+
+    let displayed = naive_displayed(&s, inlays);
+
+    assert_eq!(
+        map.inlays().collect::<Vec<_>>(),
+        inlays
+            .iter()
+            .map(|(anchor, text)| (*anchor, text.as_str()))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(map.chunks(r).collect::<String>(), displayed);
+    assert_eq!(map.display_len(), displayed.len());
+
+    for (byte_offset, _) in s.char_indices().chain([(s.len(), ' ')]) {
+        let display_offset = naive_display_offset(inlays, byte_offset);
+
+        assert_eq!(
+            map.display_offset_of_byte(byte_offset),
+            display_offset,
+            "byte offset {byte_offset}"
+        );
+
+        assert_eq!(map.byte_of_display_offset(display_offset), byte_offset);
+    }
+
+    // Every offset inside an inlay is mapped to its anchor.
+    let mut display_offset = 0;
+    let mut byte_offset = 0;
+
+    for (anchor, text) in inlays {
+        display_offset += anchor - byte_offset;
+        byte_offset = *anchor;
+
+        for offset in display_offset..=display_offset + text.len() {
+            assert_eq!(map.byte_of_display_offset(offset), *anchor);
+        }
+
+        display_offset += text.len();
+    }
+}
+
+#[test]
+fn inlay_map_empty() {
+    let r = Rope::new();
+    let mut map = InlayMap::new(&r);
+
+    assert_eq!(map.display_len(), 0);
+    assert_eq!(map.chunks(&r).count(), 0);
+    assert_eq!(map.display_offset_of_byte(0), 0);
+    assert_eq!(map.byte_of_display_offset(0), 0);
+
+    map.insert(&r, 0, "foo");
+    map.insert(&r, 0, "");
+    map.insert(&r, 0, "bar");
+
+    let inlays = [(0, "foo".to_owned()), (0, "bar".to_owned())];
+    assert_matches_naive(&map, &r, &inlays);
+
+    map.remove(&r, ..);
+    assert_matches_naive(&map, &r, &[]);
+}
+
+#[test]
+fn inlay_map_edges() {
+    let mut r = Rope::from("foo");
+    let mut map = InlayMap::new(&r);
+
+    map.insert(&r, 3, "!");
+    map.insert(&r, 0, "?");
+
+    let mut inlays = vec![(0, "?".to_owned()), (3, "!".to_owned())];
+    assert_matches_naive(&map, &r, &inlays);
+
+    map.replace(&mut r, .., "");
+    naive_edit(&mut inlays, 0, 3, 0);
+    assert_matches_naive(&map, &r, &inlays);
+    assert_eq!(map.chunks(&r).collect::<String>(), "?!");
+
+    map.replace(&mut r, 0..0, "bar");
+    naive_edit(&mut inlays, 0, 0, 3);
+    assert_matches_naive(&map, &r, &inlays);
+    assert_eq!(map.chunks(&r).collect::<String>(), "bar?!");
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn inlay_map_random_edits() {
+    let mut rng = thread_rng();
+
+    let texts = [
+        "",
+        "\n",
+        "\r\n",
+        " ",
+        "foo bar",
+        "a\nb\r\nc",
+        CURSED_LIPSUM,
+        TEXT_EMOJI,
+    ];
+
+    let mut r = Rope::from(SMALL.repeat(3));
+
+    let mut map = InlayMap::new(&r);
+
+    let mut inlays: Vec<(usize, String)> = Vec::new();
+
+    for i in 0..300 {
+        let start = random_char_boundary(&mut rng, &r);
+        let end = random_char_boundary(&mut rng, &r);
+        let (start, end) = (start.min(end), start.max(end));
+
+        match rng.gen_range(0..3) {
+            0 => {
+                let text = INLAYS[rng.gen_range(0..INLAYS.len())];
+                map.insert(&r, start, text);
+                let idx = inlays.partition_point(|(a, _)| *a <= start);
+                inlays.insert(idx, (start, text.to_owned()));
+            },
+
+            1 if rng.gen_bool(0.2) => {
+                map.remove(&r, start..end);
+                inlays.retain(|(a, _)| !(start <= *a && *a <= end));
+            },
+
+            _ => {
+                let end = (start + (end - start) % 30).min(r.byte_len());
+                let end = floor_char_boundary(&r, end);
+                let text = texts[rng.gen_range(0..texts.len())];
+                map.replace(&mut r, start..end, text);
+                naive_edit(&mut inlays, start, end, text.len());
+            },
+        }
+
+        if i % 30 == 0 {
+            assert_matches_naive(&map, &r, &inlays);
+        }
+    }
+
+    assert_matches_naive(&map, &r, &inlays);
+
+    // Delete everything.
+    let len = r.byte_len();
+    map.replace(&mut r, .., "");
+    naive_edit(&mut inlays, 0, len, 0);
+    assert_matches_naive(&map, &r, &inlays);
+
+    map.remove(&r, ..);
+    assert_matches_naive(&map, &r, &[]);
+}
+
+#[test]
+#[should_panic]
+fn inlay_map_insert_out_of_bounds() {
+    let r = Rope::from("foo");
+    let mut map = InlayMap::new(&r);
+    map.insert(&r, 4, "bar");
+}
+
+#[test]
+#[should_panic]
+fn inlay_map_display_offset_out_of_bounds() {
+    let r = Rope::from("foo");
+    let mut map = InlayMap::new(&r);
+    map.insert(&r, 1, "bar");
+    let _ = map.byte_of_display_offset(7);
+}
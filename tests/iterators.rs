@@ -3,7 +3,15 @@ use rand::{thread_rng, Rng};
 
 mod common;
 
-use common::{CURSED_LIPSUM, LARGE, MEDIUM, SMALL, TINY};
+use common::{
+    floor_char_boundary,
+    random_char_boundary,
+    CURSED_LIPSUM,
+    LARGE,
+    MEDIUM,
+    SMALL,
+    TINY,
+};
 
 #[test]
 fn iter_bytes_empty() {
@@ -87,6 +95,7 @@ fn iter_bytes_both_ways() {
 }
 
 #[test]
+#[allow(clippy::needless_as_bytes)]
 fn iter_bytes_cursed() {
     let s = CURSED_LIPSUM;
     let r = Rope::from(s);
@@ -612,3 +621,296 @@ fn iter_raw_lines_over_random_slices() {
         }
     }
 }
+
+#[test]
+fn iter_at_start_and_end() {
+    let r = Rope::from("foo\nbar\n");
+
+    assert_eq!(r.chars_at(0).prev(), None);
+    assert_eq!(r.chars_at(0).collect::<String>(), "foo\nbar\n");
+    assert_eq!(r.chars_at(8).next(), None);
+    assert_eq!(r.chars_at(8).next_back(), None);
+
+    assert_eq!(r.bytes_at(8).len(), 0);
+    assert_eq!(r.lines_at(2).next(), None);
+    assert_eq!(r.lines_at(2).len(), 0);
+    assert_eq!(r.lines_at(2).next_back(), None);
+
+    let mut lines = r.lines_at(2);
+    assert_eq!(lines.prev().unwrap(), "bar");
+    assert_eq!(lines.prev().unwrap(), "foo");
+    assert_eq!(lines.prev(), None);
+
+    // Iterators not created at a position can't go back past their start.
+    assert_eq!(r.chars().prev(), None);
+    assert_eq!(r.lines().prev(), None);
+
+    let r = Rope::new();
+    assert_eq!(r.chunks_at(0).next(), None);
+    assert_eq!(r.chunks_at(0).next_back(), None);
+    assert_eq!(r.chunks_at(0).prev(), None);
+    assert_eq!(r.lines_at(0).next(), None);
+}
+
+#[test]
+#[should_panic]
+fn iter_chars_at_not_char_boundary() {
+    let r = Rope::from("🐸");
+    let _ = r.chars_at(1);
+}
+
+#[test]
+#[should_panic]
+fn iter_lines_at_out_of_bounds() {
+    let r = Rope::from("foo\nbar");
+    let _ = r.lines_at(3);
+}
+
+#[test]
+fn iter_at_ends_meet() {
+    let r = Rope::from("abcd");
+
+    let mut bytes = r.bytes_at(1);
+    assert_eq!(bytes.len(), 3);
+    assert_eq!(bytes.next(), Some(b'b'));
+    assert_eq!(bytes.next_back(), Some(b'd'));
+    assert_eq!(bytes.len(), 1);
+    assert_eq!(bytes.next_back(), Some(b'c'));
+    assert_eq!(bytes.next_back(), None);
+    assert_eq!(bytes.next(), None);
+    assert_eq!(bytes.len(), 0);
+
+    // Walking backward past the start doesn't affect the other two ends.
+    assert_eq!(bytes.prev(), Some(b'a'));
+    assert_eq!(bytes.prev(), None);
+    assert_eq!(bytes.next_back(), None);
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn iter_at_random_offsets() {
+    let mut rng = thread_rng();
+
+    for s in [TINY, SMALL, MEDIUM, LARGE, CURSED_LIPSUM] {
+        let r = Rope::from(s);
+
+        for _ in 0..10 {
+            let offset = random_char_boundary(&mut rng, s);
+
+            let (before, after) = s.split_at(offset);
+
+            let mut bytes = r.bytes_at(offset);
+            assert_eq!(bytes.len(), after.len());
+            assert_eq!(bytes.len(), bytes.clone().count());
+            assert!(bytes.clone().eq(after.bytes()));
+            assert!(bytes.clone().rev().eq(after.bytes().rev()));
+            assert!(core::iter::from_fn(|| bytes.prev())
+                .eq(before.bytes().rev()));
+
+            let mut chars = r.chars_at(offset);
+            assert!(chars.clone().eq(after.chars()));
+            assert!(chars.clone().rev().eq(after.chars().rev()));
+            assert!(core::iter::from_fn(|| chars.prev())
+                .eq(before.chars().rev()));
+
+            let mut chunks = r.chunks_at(offset);
+            assert_eq!(chunks.clone().collect::<String>(), after);
+            assert_eq!(chunks.clone().next().is_none(), after.is_empty());
+            assert!(chunks
+                .clone()
+                .rev()
+                .eq(chunks.clone().collect::<Vec<_>>().into_iter().rev()));
+            assert_eq!(
+                core::iter::from_fn(|| chunks.prev())
+                    .collect::<Vec<_>>()
+                    .into_iter()
+                    .rev()
+                    .collect::<String>(),
+                before
+            );
+
+            // Iterators over slices can also start at any offset.
+            let start = floor_char_boundary(s, rng.gen_range(0..=offset));
+            let slice = r.byte_slice(start..);
+            let mut chars = slice.chars_at(offset - start);
+            assert!(chars.clone().eq(after.chars()));
+            assert!(chars.clone().rev().eq(after.chars().rev()));
+            assert!(core::iter::from_fn(|| chars.prev())
+                .eq(s[start..offset].chars().rev()));
+        }
+    }
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn iter_lines_at_random() {
+    let mut rng = thread_rng();
+
+    for s in [TINY, SMALL, MEDIUM, LARGE, CURSED_LIPSUM] {
+        let r = Rope::from(s);
+        let lines = s.lines().collect::<Vec<_>>();
+
+        for _ in 0..10 {
+            let line_offset = rng.gen_range(0..=lines.len());
+
+            let mut iter = r.lines_at(line_offset);
+            assert_eq!(iter.len(), lines.len() - line_offset);
+            assert_eq!(iter.len(), iter.clone().count());
+            assert!(iter.clone().eq(lines[line_offset..].iter().copied()));
+            assert!(iter
+                .clone()
+                .rev()
+                .eq(lines[line_offset..].iter().rev().copied()));
+            assert!(core::iter::from_fn(|| iter.prev())
+                .eq(lines[..line_offset].iter().rev().copied()));
+
+            let slice_iter = r.byte_slice(..).lines_at(line_offset);
+            assert!(slice_iter.eq(lines[line_offset..].iter().copied()));
+        }
+    }
+}
+
+#[test]
+fn iter_lines_next_and_next_back_meet() {
+    let r = Rope::from("a\nb\nc\nd\n");
+
+    let mut lines = r.lines();
+    assert_eq!("a", lines.next().unwrap());
+    assert_eq!("d", lines.next_back().unwrap());
+    assert_eq!("b", lines.next().unwrap());
+    assert_eq!("c", lines.next_back().unwrap());
+    assert_eq!(None, lines.next());
+    assert_eq!(None, lines.next_back());
+}
+
+/// Returns the paragraphs of the string and their byte ranges.
+fn paragraphs(s: &str) -> Vec<(&str, std::ops::Range<usize>)> {
+    let mut paragraphs = Vec::new();
+    let mut current: Option<std::ops::Range<usize>> = None;
+    let mut offset = 0;
+
+    for raw_line in s.split_inclusive('\n') {
+        let line = raw_line.strip_suffix('\n').unwrap_or(raw_line);
+        let line = line.strip_suffix('\r').unwrap_or(line);
+
+        if line.chars().all(char::is_whitespace) {
+            if let Some(range) = current.take() {
+                paragraphs.push((&s[range.clone()], range));
+            }
+        } else {
+            let end = offset + line.len();
+            current.get_or_insert(offset..end).end = end;
+        }
+
+        offset += raw_line.len();
+    }
+
+    if let Some(range) = current {
+        paragraphs.push((&s[range.clone()], range));
+    }
+
+    paragraphs
+}
+
+#[test]
+fn iter_paragraphs_empty() {
+    let r = Rope::new();
+    assert_eq!(0, r.paragraphs().count());
+
+    let r = Rope::from("\n \n\t\r\n");
+    assert_eq!(0, r.paragraphs().count());
+    assert_eq!(0, r.byte_slice(1..).paragraphs().rev().count());
+}
+
+#[test]
+fn iter_paragraphs_0() {
+    let r = Rope::from("\n\nfoo\r\nbar\r\n\r\n  baz  \n \nqux");
+
+    let mut paragraphs = r.paragraphs();
+
+    let (foobar, range) = paragraphs.next().unwrap();
+    assert_eq!(foobar, "foo\r\nbar");
+    assert_eq!(range, 2..10);
+
+    let (qux, range) = paragraphs.next_back().unwrap();
+    assert_eq!(qux, "qux");
+    assert_eq!(range, 24..27);
+
+    let (baz, range) = paragraphs.next_back().unwrap();
+    assert_eq!(baz, "  baz  ");
+    assert_eq!(range, 14..21);
+
+    assert_eq!(None, paragraphs.next());
+    assert_eq!(None, paragraphs.next_back());
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn iter_paragraphs_over_random_slices() {
+    let mut rng = thread_rng();
+
+    for s in [TINY, SMALL, MEDIUM, LARGE] {
+        let r = Rope::from(s);
+
+        for _ in 0..20 {
+            let start = rng.gen_range(0..=r.byte_len());
+            let end = rng.gen_range(start..=r.byte_len());
+
+            let slice = r.byte_slice(start..end);
+            let expected = paragraphs(&s[start..end]);
+
+            assert_eq!(
+                slice
+                    .paragraphs()
+                    .map(|(p, r)| (p.to_string(), r))
+                    .collect::<Vec<_>>(),
+                expected
+                    .iter()
+                    .map(|(p, r)| (p.to_string(), r.clone()))
+                    .collect::<Vec<_>>()
+            );
+
+            assert_eq!(
+                slice
+                    .paragraphs()
+                    .rev()
+                    .map(|(p, r)| (p.to_string(), r))
+                    .collect::<Vec<_>>(),
+                expected
+                    .iter()
+                    .rev()
+                    .map(|(p, r)| (p.to_string(), r.clone()))
+                    .collect::<Vec<_>>()
+            );
+
+            let mut front = Vec::new();
+            let mut back = Vec::new();
+            let mut iter = slice.paragraphs();
+
+            loop {
+                let next = if rng.gen() {
+                    iter.next().map(|p| front.push(p))
+                } else {
+                    iter.next_back().map(|p| back.push(p))
+                };
+
+                if next.is_none() {
+                    break;
+                }
+            }
+
+            front.extend(back.into_iter().rev());
+
+            assert_eq!(
+                front
+                    .into_iter()
+                    .map(|(p, r)| (p.to_string(), r))
+                    .collect::<Vec<_>>(),
+                expected
+                    .into_iter()
+                    .map(|(p, r)| (p.to_string(), r))
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+}
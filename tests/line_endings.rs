@@ -0,0 +1,219 @@
+use std::collections::HashSet;
+
+use crop::{DetectedLineEnding, LineEnding, Rope, RopeBuilder};
+use rand::{thread_rng, Rng};
+
+mod common;
+
+use common::{CURSED_LIPSUM, LARGE};
+
+/// Returns the number of LFs, CRLFs and lone CRs in the string.
+fn count_line_endings(s: &str) -> (usize, usize, usize) {
+    let crlf = s.matches("\r\n").count();
+    let lf = s.matches('\n').count() - crlf;
+    let cr = s.matches('\r').count() - crlf;
+    (lf, crlf, cr)
+}
+
+/// Returns what `detect_line_ending()` should return for the given counts.
+fn detected(lf: usize, crlf: usize, cr: usize) -> DetectedLineEnding {
+    match (lf, crlf, cr) {
+        (0, 0, 0) => DetectedLineEnding::None,
+        (lf, 0, 0) => DetectedLineEnding::Lf(lf),
+        (0, crlf, 0) => DetectedLineEnding::CrLf(crlf),
+        (0, 0, cr) => DetectedLineEnding::Cr(cr),
+        (lf, crlf, cr) => DetectedLineEnding::Mixed { lf, crlf, cr },
+    }
+}
+
+/// Replaces every line ending in the string with the given one.
+fn normalize(s: &str, line_ending: LineEnding) -> String {
+    s.replace("\r\n", "\n")
+        .replace('\r', "\n")
+        .replace('\n', line_ending.as_str())
+}
+
+/// Returns a random string made up of the given pieces.
+fn random_text(pieces: &[&str], len: usize) -> String {
+    let mut rng = thread_rng();
+    let mut s = String::new();
+    for _ in 0..len {
+        s.push_str(pieces[rng.gen_range(0..pieces.len())]);
+    }
+    s
+}
+
+#[test]
+fn detect_line_ending_none() {
+    assert_eq!(Rope::new().detect_line_ending(), DetectedLineEnding::None);
+
+    let r = Rope::from("foo bar");
+    assert_eq!(r.detect_line_ending(), DetectedLineEnding::None);
+}
+
+#[test]
+fn detect_line_ending_uniform() {
+    let r = Rope::from("a\nb\nc\n");
+    assert_eq!(r.detect_line_ending(), DetectedLineEnding::Lf(3));
+
+    let r = Rope::from("a\r\nb\r\nc");
+    assert_eq!(r.detect_line_ending(), DetectedLineEnding::CrLf(2));
+
+    let r = Rope::from("\ra\rb\r");
+    assert_eq!(r.detect_line_ending(), DetectedLineEnding::Cr(3));
+}
+
+#[test]
+fn detect_line_ending_mixed() {
+    let r = Rope::from("a\nb\r\nc\rd\r\r\n");
+
+    assert_eq!(
+        r.detect_line_ending(),
+        DetectedLineEnding::Mixed { lf: 1, crlf: 2, cr: 2 }
+    );
+
+    assert_eq!(r.detect_line_ending().most_common(), Some(LineEnding::CrLf));
+}
+
+#[test]
+fn detect_line_ending_most_common_ties() {
+    let r = Rope::from("a\nb\r\n");
+    assert_eq!(r.detect_line_ending().most_common(), Some(LineEnding::Lf));
+
+    let r = Rope::from("a\rb\r\n");
+    assert_eq!(r.detect_line_ending().most_common(), Some(LineEnding::CrLf));
+}
+
+#[test]
+fn detect_line_ending_split_crlf() {
+    // With this much text some of the CRLF pairs are split across chunks.
+    let mut builder = RopeBuilder::new();
+
+    let mut s = String::new();
+
+    for _ in 0..1000 {
+        builder.append("aaa\r");
+        builder.append("\nbbb\r");
+        s.push_str("aaa\r\nbbb\r");
+    }
+
+    let r = builder.build();
+
+    assert_eq!(r, s);
+
+    assert_eq!(
+        r.detect_line_ending(),
+        DetectedLineEnding::Mixed { lf: 0, crlf: 1000, cr: 1000 }
+    );
+}
+
+#[test]
+fn detect_line_ending_random() {
+    let pieces = ["a", "bcd", "こんにちは", "\r", "\n", "\r\n"];
+
+    let s = random_text(&pieces, 20_000);
+
+    let r = Rope::from(s.as_str());
+
+    let (lf, crlf, cr) = count_line_endings(&s);
+
+    assert_eq!(r.detect_line_ending(), detected(lf, crlf, cr));
+
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let start = rng.gen_range(0..=s.len());
+        let end = rng.gen_range(start..=s.len());
+
+        if !s.is_char_boundary(start) || !s.is_char_boundary(end) {
+            continue;
+        }
+
+        let (lf, crlf, cr) = count_line_endings(&s[start..end]);
+
+        assert_eq!(
+            r.byte_slice(start..end).detect_line_ending(),
+            detected(lf, crlf, cr)
+        );
+    }
+}
+
+#[test]
+fn normalize_line_endings_small() {
+    let mut r = Rope::from("a\r\nb\rc\nd\r\r\n");
+
+    r.normalize_line_endings(LineEnding::Lf);
+    r.assert_invariants();
+    assert_eq!(r, "a\nb\nc\nd\n\n");
+
+    r.normalize_line_endings(LineEnding::Cr);
+    r.assert_invariants();
+    assert_eq!(r, "a\rb\rc\rd\r\r");
+
+    r.normalize_line_endings(LineEnding::CrLf);
+    r.assert_invariants();
+    assert_eq!(r, "a\r\nb\r\nc\r\nd\r\n\r\n");
+}
+
+#[test]
+fn normalize_line_endings_no_line_endings() {
+    let mut r = Rope::from(LARGE.replace(['\n', '\r'], " ").as_str());
+    let clone = r.clone();
+    r.normalize_line_endings(LineEnding::CrLf);
+    r.assert_invariants();
+    assert_eq!(r, clone);
+}
+
+#[test]
+fn normalize_line_endings_keeps_clones() {
+    let mut r = Rope::from(CURSED_LIPSUM);
+    let clone = r.clone();
+
+    r.normalize_line_endings(LineEnding::CrLf);
+    r.assert_invariants();
+
+    assert_eq!(r, normalize(CURSED_LIPSUM, LineEnding::CrLf));
+    assert_eq!(clone, CURSED_LIPSUM);
+}
+
+#[test]
+fn normalize_line_endings_shares_unchanged_chunks() {
+    let s = LARGE.replace(['\n', '\r'], " ");
+
+    let mut original = Rope::from(s.as_str());
+
+    for offset in [3 * s.len() / 4, s.len() / 2, s.len() / 4] {
+        original.insert(offset, "\r\n");
+    }
+
+    let mut r = original.clone();
+    r.normalize_line_endings(LineEnding::Lf);
+    r.assert_invariants();
+    assert_eq!(r.line_len(), 4);
+
+    let original_chunks =
+        original.chunks().map(str::as_ptr).collect::<HashSet<_>>();
+
+    let num_shared =
+        r.chunks().filter(|c| original_chunks.contains(&c.as_ptr())).count();
+
+    assert!(num_shared + 12 >= original_chunks.len());
+}
+
+#[test]
+fn normalize_line_endings_random() {
+    let pieces = ["a", "bcd", "こんにちは", "\r", "\n", "\r\n"];
+
+    for line_ending in [LineEnding::Lf, LineEnding::CrLf, LineEnding::Cr] {
+        let s = random_text(&pieces, 10_000);
+
+        let mut r = Rope::from(s.as_str());
+        r.normalize_line_endings(line_ending);
+        r.assert_grapheme_invariants();
+
+        let expected = normalize(&s, line_ending);
+
+        assert_eq!(r, expected);
+        assert_eq!(r.line_len(), Rope::from(expected.as_str()).line_len());
+    }
+}
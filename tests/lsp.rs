@@ -0,0 +1,241 @@
+mod common;
+
+#[cfg(feature = "lsp")]
+mod tests {
+    use crop::lsp::{ContentChange, LspError, Range, TextEdit};
+    use crop::{Point, PositionEncoding, Rope};
+    use rand::{thread_rng, Rng};
+
+    use crate::common::{random_byte_range, CURSED_LIPSUM};
+
+    const UTF16: PositionEncoding = PositionEncoding::Utf16;
+
+    fn range(start: (usize, usize), end: (usize, usize)) -> Range {
+        Range::new(Point::new(start.0, start.1), Point::new(end.0, end.1))
+    }
+
+    fn change(
+        start: (usize, usize),
+        end: (usize, usize),
+        text: &str,
+    ) -> ContentChange<'_> {
+        ContentChange { range: Some(range(start, end)), text }
+    }
+
+    fn edit(
+        start: (usize, usize),
+        end: (usize, usize),
+        new_text: &str,
+    ) -> TextEdit<'_> {
+        TextEdit { range: range(start, end), new_text }
+    }
+
+    #[test]
+    fn content_changes_are_sequential() {
+        let mut r = Rope::from("foo\nbar\n");
+
+        let changes = [
+            change((0, 0), (0, 0), "a\n"),
+            // This is "foo" on the second line after the first change.
+            change((1, 0), (1, 3), "b"),
+            change((2, 3), (2, 3), "🐸"),
+        ];
+
+        assert_eq!(r.apply_content_changes(changes, UTF16), Ok(()));
+        r.assert_invariants();
+        assert_eq!(r, "a\nb\nbar🐸\n");
+    }
+
+    #[test]
+    fn content_changes_full_document() {
+        let mut r = Rope::from("foo\nbar\n");
+
+        let changes = [
+            change((0, 0), (0, 3), "baz"),
+            ContentChange { range: None, text: "Hello\n" },
+            change((0, 5), (1, 0), ", World!"),
+        ];
+
+        assert_eq!(r.apply_content_changes(changes, UTF16), Ok(()));
+        r.assert_invariants();
+        assert_eq!(r, "Hello, World!");
+    }
+
+    #[test]
+    fn content_changes_invalid_range_leaves_rope_unchanged() {
+        let mut r = Rope::from("foo\nbar\n");
+
+        let changes = [
+            change((0, 0), (2, 0), ""),
+            // There's no line 1 after the first change.
+            change((1, 0), (2, 0), ""),
+        ];
+
+        assert_eq!(
+            r.apply_content_changes(changes, UTF16),
+            Err(LspError::LineOutOfBounds { index: 1, line: 1, line_len: 0 })
+        );
+        assert_eq!(r, "foo\nbar\n");
+
+        let changes = [change((1, 2), (1, 1), "")];
+
+        assert_eq!(
+            r.apply_content_changes(changes, UTF16),
+            Err(LspError::StartAfterEnd {
+                index: 0,
+                start: Point::new(1, 2),
+                end: Point::new(1, 1),
+            })
+        );
+        assert_eq!(r, "foo\nbar\n");
+    }
+
+    #[test]
+    fn columns_are_clamped_and_rounded_down() {
+        let mut r = Rope::from("a🐸b\r\nc");
+
+        // Column 2 is between the two code units of the frog.
+        let changes = [change((0, 2), (0, 42), "")];
+
+        assert_eq!(r.apply_content_changes(changes, UTF16), Ok(()));
+        assert_eq!(r, "a\r\nc");
+    }
+
+    #[test]
+    fn content_changes_encodings() {
+        let text = "a🐸b\n";
+
+        for (encoding, column) in [
+            (PositionEncoding::Utf8, 5),
+            (PositionEncoding::Utf16, 3),
+            (PositionEncoding::Utf32, 2),
+        ] {
+            let mut r = Rope::from(text);
+            let changes = [change((0, column), (0, column + 1), "c")];
+            assert_eq!(r.apply_content_changes(changes, encoding), Ok(()));
+            assert_eq!(r, "a🐸c\n");
+        }
+    }
+
+    #[test]
+    fn text_edits_are_simultaneous() {
+        let mut r = Rope::from("foo\nbar\nbaz\n");
+
+        let edits = [
+            edit((2, 0), (2, 3), "qux"),
+            edit((0, 0), (1, 0), ""),
+            edit((1, 3), (1, 3), "!"),
+        ];
+
+        assert_eq!(r.apply_text_edits(edits, UTF16), Ok(()));
+        r.assert_invariants();
+        assert_eq!(r, "bar!\nqux\n");
+    }
+
+    #[test]
+    fn text_edits_insertions_keep_their_order() {
+        let mut r = Rope::from("foo");
+
+        let edits = [
+            edit((0, 3), (0, 3), "b"),
+            edit((0, 0), (0, 3), "a"),
+            edit((0, 3), (0, 3), "c"),
+            edit((0, 3), (0, 3), "d"),
+        ];
+
+        assert_eq!(r.apply_text_edits(edits, UTF16), Ok(()));
+        assert_eq!(r, "abcd");
+    }
+
+    #[test]
+    fn text_edits_overlapping() {
+        let mut r = Rope::from("foo\nbar\n");
+
+        let edits = [
+            edit((1, 0), (1, 3), "a"),
+            edit((0, 0), (0, 1), "b"),
+            edit((0, 2), (1, 1), "c"),
+        ];
+
+        assert_eq!(
+            r.apply_text_edits(edits, UTF16),
+            Err(LspError::OverlappingEdits { first: 2, second: 0 })
+        );
+        assert_eq!(r, "foo\nbar\n");
+    }
+
+    #[test]
+    fn text_edits_invalid_range() {
+        let mut r = Rope::from("foo\nbar\n");
+
+        let edits = [edit((0, 0), (0, 1), ""), edit((3, 0), (3, 0), "baz")];
+
+        assert_eq!(
+            r.apply_text_edits(edits, UTF16),
+            Err(LspError::LineOutOfBounds { index: 1, line: 3, line_len: 2 })
+        );
+        assert_eq!(r, "foo\nbar\n");
+
+        // The line after the trailing newline is a valid position.
+        let edits = [edit((2, 0), (2, 0), "baz")];
+        assert_eq!(r.apply_text_edits(edits, UTF16), Ok(()));
+        assert_eq!(r, "foo\nbar\nbaz");
+    }
+
+    #[test]
+    fn lsp_error_display() {
+        let err = LspError::StartAfterEnd {
+            index: 3,
+            start: Point::new(1, 2),
+            end: Point::new(0, 4),
+        };
+
+        assert_eq!(
+            err.to_string(),
+            "invalid range at index 3: the start (1:2) is after the end (0:4)"
+        );
+
+        let err = LspError::OverlappingEdits { first: 0, second: 1 };
+
+        assert_eq!(
+            err.to_string(),
+            "the ranges of the text edits at index 0 and 1 overlap"
+        );
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn content_changes_random() {
+        let mut rng = thread_rng();
+
+        let mut r = Rope::from(CURSED_LIPSUM);
+        let mut s = String::from(CURSED_LIPSUM);
+
+        for _ in 0..100 {
+            let range = random_byte_range(&mut rng, s.as_str(), 100);
+            let (start, end) = (range.start, range.end);
+
+            // Points between a "\r" and a "\n" can't be expressed without
+            // being clamped before the "\r".
+            if s[..start].ends_with('\r') || s[..end].ends_with('\r') {
+                continue;
+            }
+
+            let text = ["", "a", "ü", "🐸\n", "\r\n"][rng.gen_range(0..5)];
+
+            let change = ContentChange {
+                range: Some(Range::new(
+                    r.point_of_byte(start, UTF16),
+                    r.point_of_byte(end, UTF16),
+                )),
+                text,
+            };
+
+            assert_eq!(r.apply_content_changes([change], UTF16), Ok(()));
+            s.replace_range(start..end, text);
+            assert_eq!(r, s);
+        }
+
+        r.assert_invariants();
+    }
+}
@@ -0,0 +1,146 @@
+use std::ops::Range;
+
+use crop::{Point, PositionEncoding, Rope};
+use rand::thread_rng;
+
+mod common;
+
+use common::{random_byte_range, random_char_boundary, CURSED_LIPSUM, LARGE};
+
+const ENCODINGS: [PositionEncoding; 3] =
+    [PositionEncoding::Utf8, PositionEncoding::Utf16, PositionEncoding::Utf32];
+
+/// Returns the length of the string in the given encoding.
+fn len_in(s: &str, encoding: PositionEncoding) -> usize {
+    match encoding {
+        PositionEncoding::Utf8 => s.len(),
+        PositionEncoding::Utf16 => s.encode_utf16().count(),
+        PositionEncoding::Utf32 => s.chars().count(),
+    }
+}
+
+/// Returns the point of the byte offset in the string, computed naively.
+fn point_of_byte(s: &str, byte_offset: usize, enc: PositionEncoding) -> Point {
+    let before = &s[..byte_offset];
+    let line = before.matches('\n').count();
+    let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+    Point::new(line, len_in(&before[line_start..], enc))
+}
+
+/// Returns whether the byte offset is between the `\r` and the `\n` of a
+/// CRLF pair, where points don't round-trip since their column is past the end
+/// of the line.
+fn splits_crlf(s: &str, byte_offset: usize) -> bool {
+    s[..byte_offset].ends_with('\r') && s[byte_offset..].starts_with('\n')
+}
+
+#[test]
+fn point_empty() {
+    let r = Rope::new();
+
+    for enc in ENCODINGS {
+        assert_eq!(r.point_of_byte(0, enc), Point::new(0, 0));
+        assert_eq!(r.byte_of_point(Point::new(0, 0), enc), 0);
+        assert_eq!(r.byte_of_point(Point::new(0, 10), enc), 0);
+    }
+}
+
+#[test]
+fn point_crlf() {
+    let r = Rope::from("foo\r\nbar");
+
+    for enc in ENCODINGS {
+        assert_eq!(r.point_of_byte(3, enc), Point::new(0, 3));
+        assert_eq!(r.point_of_byte(4, enc), Point::new(0, 4));
+        assert_eq!(r.point_of_byte(5, enc), Point::new(1, 0));
+
+        // Columns are clamped before the "\r\n".
+        assert_eq!(r.byte_of_point(Point::new(0, 4), enc), 3);
+        assert_eq!(r.byte_of_point(Point::new(1, 3), enc), 8);
+        assert_eq!(r.byte_of_point(Point::new(1, 4), enc), 8);
+    }
+}
+
+#[test]
+fn point_after_trailing_newline() {
+    let r = Rope::from("foo\n");
+
+    for enc in ENCODINGS {
+        assert_eq!(r.point_of_byte(4, enc), Point::new(1, 0));
+        assert_eq!(r.byte_of_point(Point::new(1, 0), enc), 4);
+        assert_eq!(r.byte_of_point(Point::new(1, 3), enc), 4);
+    }
+}
+
+#[test]
+fn point_rounds_down_inside_chars() {
+    let r = Rope::from("a🐸b");
+
+    assert_eq!(r.byte_of_point(Point::new(0, 3), PositionEncoding::Utf8), 1);
+    assert_eq!(r.byte_of_point(Point::new(0, 2), PositionEncoding::Utf16), 1);
+    assert_eq!(r.byte_of_point(Point::new(0, 3), PositionEncoding::Utf16), 5);
+}
+
+#[test]
+#[should_panic]
+fn byte_of_point_line_out_of_bounds() {
+    let r = Rope::from("foo\nbar");
+    let _ = r.byte_of_point(Point::new(3, 0), PositionEncoding::Utf16);
+}
+
+#[test]
+#[should_panic]
+fn point_of_byte_out_of_bounds() {
+    let r = Rope::from("foo\nbar");
+    let _ = r.point_of_byte(8, PositionEncoding::Utf16);
+}
+
+#[test]
+fn point_random() {
+    let mut rng = thread_rng();
+
+    for s in [CURSED_LIPSUM, LARGE] {
+        let r = Rope::from(s);
+
+        for _ in 0..100 {
+            let byte_offset = random_char_boundary(&mut rng, s);
+
+            for enc in ENCODINGS {
+                let point = point_of_byte(s, byte_offset, enc);
+                assert_eq!(r.point_of_byte(byte_offset, enc), point);
+
+                if !splits_crlf(s, byte_offset) {
+                    assert_eq!(r.byte_of_point(point, enc), byte_offset);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn point_slice_random() {
+    let mut rng = thread_rng();
+
+    let r = Rope::from(CURSED_LIPSUM);
+
+    for _ in 0..20 {
+        let Range { start, end } =
+            random_byte_range(&mut rng, CURSED_LIPSUM, CURSED_LIPSUM.len());
+
+        let s = &CURSED_LIPSUM[start..end];
+        let slice = r.byte_slice(start..end);
+
+        for _ in 0..10 {
+            let byte_offset = random_char_boundary(&mut rng, s);
+
+            for enc in ENCODINGS {
+                let point = point_of_byte(s, byte_offset, enc);
+                assert_eq!(slice.point_of_byte(byte_offset, enc), point);
+
+                if !splits_crlf(s, byte_offset) {
+                    assert_eq!(slice.byte_of_point(point, enc), byte_offset);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,311 @@
+mod common;
+
+#[cfg(feature = "regex")]
+mod tests {
+    use core::ops::Range;
+
+    use crop::regex::{Regex, RegexError};
+    use crop::Rope;
+    use rand::thread_rng;
+    use regex_automata::hybrid::dfa::DFA;
+    use regex_automata::nfa::thompson;
+    use regex_automata::{meta, Anchored, Input};
+
+    use crate::common::{
+        random_byte_range,
+        CURSED_LIPSUM,
+        MEDIUM,
+        SMALL,
+        TEXT_EMOJI,
+    };
+
+    const PATTERNS: &[&str] = &[
+        r"\d+",
+        r"[a-z]+",
+        r"\w+\s+\w+",
+        r"(?m)^\w+",
+        r"(?m)\w+$",
+        r"(?-u:\b)\w{3}(?-u:\b)",
+        r"e[^e]{0,40}e",
+        r"\r?\n",
+        r"🐸|ṁ|ệ",
+        r"a*",
+        r"",
+        r"(?s).{50,}",
+    ];
+
+    /// Returns the matches of the regex in the string, from front to back.
+    fn find_iter_str(re: &meta::Regex, s: &str) -> Vec<Range<usize>> {
+        re.find_iter(s).map(|m| m.range()).collect()
+    }
+
+    /// Returns the matches of the regex in the string, from back to front,
+    /// using a lazy DFA of the reversed regex over the whole string.
+    fn rfind_iter_str(
+        meta: &meta::Regex,
+        reverse: &DFA,
+        s: &str,
+    ) -> Vec<Range<usize>> {
+        let mut cache = reverse.create_cache();
+        let mut matches = Vec::<Range<usize>>::new();
+        let mut to = s.len();
+
+        loop {
+            let input = Input::new(s).range(..to);
+
+            let Some(start) =
+                reverse.try_search_rev(&mut cache, &input).unwrap()
+            else {
+                return matches;
+            };
+
+            let start = start.offset();
+
+            let input = Input::new(s).span(start..to).anchored(Anchored::Yes);
+            let end = meta.search(&input).unwrap().end();
+
+            if start == end
+                && (!s.is_char_boundary(end)
+                    || matches.last().map_or(false, |last| last.start == end))
+            {
+                match s[..end].chars().next_back() {
+                    Some(ch) => to = end - ch.len_utf8(),
+                    None => return matches,
+                }
+                continue;
+            }
+
+            to = start;
+            matches.push(start..end);
+        }
+    }
+
+    #[test]
+    fn regex_invalid() {
+        assert!(matches!(Regex::new(r"(foo"), Err(RegexError::Invalid(_))));
+        assert!(matches!(
+            Regex::new(r"\w+\b"),
+            Err(RegexError::Unsupported(_))
+        ));
+        assert_eq!(Regex::new(r"[a-z]+").unwrap().as_str(), "[a-z]+");
+    }
+
+    #[test]
+    fn regex_empty_rope() {
+        let r = Rope::new();
+
+        let re = Regex::new(r"\w+").unwrap();
+        assert_eq!(re.find(&r), None);
+        assert_eq!(re.rfind(&r), None);
+
+        let re = Regex::new(r"").unwrap();
+        assert_eq!(re.find_iter(&r).collect::<Vec<_>>(), [0..0, 0..0][..1]);
+        assert_eq!(re.rfind_iter(&r).collect::<Vec<_>>(), [0..0, 0..0][..1]);
+    }
+
+    #[test]
+    fn regex_empty_matches() {
+        let r = Rope::from("🐸a🐸");
+        let re = Regex::new(r"a*").unwrap();
+
+        assert_eq!(re.find_iter(&r).collect::<Vec<_>>(), [0..0, 4..5, 9..9]);
+        assert_eq!(re.rfind_iter(&r).collect::<Vec<_>>(), [9..9, 4..5, 0..0]);
+    }
+
+    #[test]
+    fn regex_look_around_at_slice_boundaries() {
+        let r = Rope::from("foo bar\nbaz");
+        let s = r.byte_slice(5..9);
+
+        // The slice is "ar\nb": `^` and `$` only see the text of the slice.
+        let re = Regex::new(r"(?m)^\w+").unwrap();
+        assert_eq!(re.find_iter(s).collect::<Vec<_>>(), [0..2, 3..4]);
+
+        let re = Regex::new(r"(?m)\w+$").unwrap();
+        assert_eq!(re.rfind_iter(s).collect::<Vec<_>>(), [3..4, 0..2]);
+    }
+
+    #[test]
+    fn regex_captures() {
+        let r = Rope::from("key = value\nfoo=bar\nbaz");
+        let re = Regex::new(r"(?m)^(?<key>\w+)\s*=\s*(?<value>\w+)?").unwrap();
+
+        let caps = re.captures_iter(&r).collect::<Vec<_>>();
+        assert_eq!(caps.len(), 2);
+
+        assert_eq!(caps[0].group_len(), 3);
+        assert_eq!(caps[0].get(0), Some(0..11));
+        assert_eq!(caps[0].name("key"), Some(0..3));
+        assert_eq!(caps[0].name("value"), Some(6..11));
+        assert_eq!(caps[1].get(1), Some(12..15));
+        assert_eq!(caps[1].get(2), Some(16..19));
+        assert_eq!(caps[1].get(3), None);
+        assert_eq!(caps[1].name("foo"), None);
+
+        let caps = re.rcaptures(r.byte_slice(4..)).unwrap();
+        assert_eq!(caps.name("key"), Some(8..11));
+        assert_eq!(caps.name("value"), Some(12..15));
+    }
+
+    #[test]
+    fn regex_captures_use_context() {
+        // The capture group is only matched if the `^` sees the newline
+        // before the match.
+        let r = Rope::from("a\nb");
+        let re = Regex::new(r"(?m)b|(^)b").unwrap();
+
+        let caps = re.captures(&r).unwrap();
+        assert_eq!(caps.get(0), Some(2..3));
+        assert_eq!(caps.get(1), None);
+
+        let re = Regex::new(r"(?m)(^)b|b").unwrap();
+        let caps = re.captures(&r).unwrap();
+        assert_eq!(caps.get(1), Some(2..2));
+    }
+
+    #[test]
+    fn regex_matches_meta_regex() {
+        for pattern in PATTERNS {
+            let re = Regex::new(pattern).unwrap();
+            let meta = meta::Regex::new(pattern).unwrap();
+
+            for s in [SMALL, CURSED_LIPSUM, TEXT_EMOJI] {
+                let r = Rope::from(s);
+
+                let expected = find_iter_str(&meta, s);
+                assert_eq!(re.find_iter(&r).collect::<Vec<_>>(), expected);
+                assert_eq!(re.find(&r), expected.first().cloned());
+
+                for caps in re.captures_iter(&r) {
+                    let range = caps.get(0).unwrap();
+
+                    let mut expected = meta.create_captures();
+                    meta.search_captures(
+                        &Input::new(s)
+                            .span(range.clone())
+                            .anchored(Anchored::Yes),
+                        &mut expected,
+                    );
+
+                    for group in 0..caps.group_len() {
+                        assert_eq!(
+                            caps.get(group),
+                            expected.get_group(group).map(|span| span.range())
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn regex_replace_all() {
+        let mut r = Rope::from("width = 42\nheight = 7\n");
+        let re = Regex::new(r"(?m)^(?<key>\w+) = (\d+)$").unwrap();
+
+        assert_eq!(r.replace_all(&re, "$2 is the ${key}, $$"), 2);
+        assert_eq!(r, "42 is the width, $\n7 is the height, $\n");
+        r.assert_invariants();
+
+        let re = Regex::new(r"a*").unwrap();
+        let mut r = Rope::from("🐸a🐸");
+        assert_eq!(r.replace_all(&re, "[$0]"), 3);
+        assert_eq!(r, "[]🐸[a]🐸[]");
+    }
+
+    #[test]
+    fn regex_replace_all_matches_meta_regex() {
+        for (pattern, replacement) in [
+            (r"(\w+)\s+(\w+)", "$2 $1"),
+            (r"\r?\n", "\n"),
+            (r"(?<frog>🐸)|ṁ", "<${frog}>"),
+            (r"e[^e]{0,40}e", ""),
+        ] {
+            let re = Regex::new(pattern).unwrap();
+            let meta = meta::Regex::new(pattern).unwrap();
+
+            for s in [SMALL, CURSED_LIPSUM, TEXT_EMOJI] {
+                let mut r = Rope::from(s);
+
+                let mut expected = String::new();
+                let mut last_end = 0;
+
+                for caps in meta.captures_iter(s) {
+                    let range = caps.get_match().unwrap().range();
+                    expected.push_str(&s[last_end..range.start]);
+                    caps.interpolate_string_into(
+                        s,
+                        replacement,
+                        &mut expected,
+                    );
+                    last_end = range.end;
+                }
+
+                expected.push_str(&s[last_end..]);
+
+                assert_eq!(
+                    r.replace_all(&re, replacement),
+                    meta.find_iter(s).count()
+                );
+                assert_eq!(r, expected);
+                r.assert_invariants();
+            }
+        }
+    }
+
+    /// Returns the lazy DFA of the reversed regex.
+    fn reverse_dfa(pattern: &str) -> DFA {
+        DFA::builder()
+            .thompson(thompson::Config::new().reverse(true))
+            .build(pattern)
+            .unwrap()
+    }
+
+    #[test]
+    fn regex_rfind_matches_reverse_dfa() {
+        for pattern in PATTERNS {
+            let re = Regex::new(pattern).unwrap();
+            let meta = meta::Regex::new(pattern).unwrap();
+            let reverse = reverse_dfa(pattern);
+
+            for s in [SMALL, CURSED_LIPSUM, TEXT_EMOJI] {
+                let r = Rope::from(s);
+                let expected = rfind_iter_str(&meta, &reverse, s);
+                assert_eq!(re.rfind_iter(&r).collect::<Vec<_>>(), expected);
+                assert_eq!(re.rfind(&r), expected.first().cloned());
+            }
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn regex_random_slices() {
+        let mut rng = thread_rng();
+
+        let r = Rope::from(MEDIUM);
+
+        for pattern in PATTERNS {
+            let re = Regex::new(pattern).unwrap();
+            let meta = meta::Regex::new(pattern).unwrap();
+            let reverse = reverse_dfa(pattern);
+
+            for _ in 0..5 {
+                let Range { start, end } =
+                    random_byte_range(&mut rng, MEDIUM, 5000);
+
+                let s = &MEDIUM[start..end];
+                let slice = r.byte_slice(start..end);
+
+                assert_eq!(
+                    re.find_iter(slice).collect::<Vec<_>>(),
+                    find_iter_str(&meta, s)
+                );
+
+                assert_eq!(
+                    re.rfind_iter(slice).collect::<Vec<_>>(),
+                    rfind_iter_str(&meta, &reverse, s)
+                );
+            }
+        }
+    }
+}
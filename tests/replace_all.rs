@@ -0,0 +1,106 @@
+use std::collections::HashSet;
+
+use crop::Rope;
+
+mod common;
+
+use common::{CURSED_LIPSUM, LARGE, MEDIUM, SMALL, TEXT_EMOJI, TINY};
+
+#[test]
+fn replace_all_empty_rope() {
+    let mut r = Rope::new();
+    assert_eq!(r.replace_all("foo", "bar"), 0);
+    assert_eq!(r, "");
+
+    assert_eq!(r.replace_all("", "bar"), 1);
+    assert_eq!(r, "bar");
+}
+
+#[test]
+fn replace_all_empty_pattern() {
+    let mut r = Rope::from("a🐸\n");
+    assert_eq!(r.replace_all("", "-"), 4);
+    assert_eq!(r, "-a-🐸-\n-");
+    r.assert_invariants();
+}
+
+#[test]
+fn replace_all_trailing_newline() {
+    let mut r = Rope::from("foo\nbar\n");
+    assert_eq!(r.replace_all('\n', ""), 2);
+    assert_eq!(r, "foobar");
+    assert_eq!(r.line_len(), 1);
+    r.assert_invariants();
+
+    assert_eq!(r.replace_all("bar", "\r\n"), 1);
+    assert_eq!(r, "foo\r\n");
+    assert_eq!(r.line_len(), 1);
+    r.assert_invariants();
+}
+
+#[test]
+fn replace_all_matches_str() {
+    for s in [TINY, SMALL, MEDIUM, LARGE, CURSED_LIPSUM, TEXT_EMOJI] {
+        for (pattern, replacement) in [
+            ("\n", "\r\n"),
+            ("a", ""),
+            ("the", "🐸"),
+            ("🐸", "frog"),
+            ("\r\n", "\n"),
+            ("e a", "$0"),
+        ] {
+            let mut r = Rope::from(s);
+
+            assert_eq!(
+                r.replace_all(pattern, replacement),
+                s.matches(pattern).count()
+            );
+            assert_eq!(r, s.replace(pattern, replacement));
+            r.assert_invariants();
+        }
+    }
+}
+
+/// Tests that the leaves without any match are still shared with a clone of
+/// the `Rope` after the replacement.
+#[test]
+fn replace_all_shares_untouched_leaves() {
+    let mut original = Rope::from(LARGE);
+    original.insert(LARGE.len() / 2, "🐸");
+
+    let mut r = original.clone();
+    assert_eq!(r.replace_all('🐸', "frog"), 1);
+
+    let original_chunks =
+        original.chunks().map(str::as_ptr).collect::<HashSet<_>>();
+
+    let num_shared =
+        r.chunks().filter(|c| original_chunks.contains(&c.as_ptr())).count();
+
+    // Only the leaf containing the match (plus possibly a neighbour it's
+    // rebalanced with) is copied.
+    assert!(num_shared + 4 >= original_chunks.len());
+}
+
+/// Tests that the leaves between several matches are shared with a clone of
+/// the `Rope` after the replacement.
+#[test]
+fn replace_all_shares_leaves_between_matches() {
+    let mut original = Rope::from(LARGE);
+
+    for offset in [3 * LARGE.len() / 4, LARGE.len() / 2, LARGE.len() / 4] {
+        original.insert(offset, "🐸");
+    }
+
+    let mut r = original.clone();
+    assert_eq!(r.replace_all('🐸', "frog"), 3);
+    r.assert_invariants();
+
+    let original_chunks =
+        original.chunks().map(str::as_ptr).collect::<HashSet<_>>();
+
+    let num_shared =
+        r.chunks().filter(|c| original_chunks.contains(&c.as_ptr())).count();
+
+    assert!(num_shared + 12 >= original_chunks.len());
+}
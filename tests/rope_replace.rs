@@ -3,7 +3,7 @@ use rand::Rng;
 
 mod common;
 
-use common::{LARGE, MEDIUM, SMALL, TEXT, TINY};
+use common::{random_char_boundary, LARGE, MEDIUM, SMALL, TEXT, TINY};
 
 #[test]
 fn insert_1() {
@@ -152,7 +152,7 @@ fn insert_small_random() {
         }
     }
 
-    rope.assert_invariants();
+    rope.assert_grapheme_invariants();
     assert_eq!(string, rope);
 }
 
@@ -180,6 +180,8 @@ fn insert_random() {
             r.assert_invariants();
             assert_eq!(s, r);
         }
+
+        r.assert_grapheme_invariants();
     }
 }
 #[test]
@@ -291,6 +293,8 @@ fn delete_random() {
             r.assert_invariants();
             assert_eq!(s, r);
         }
+
+        r.assert_grapheme_invariants();
     }
 }
 
@@ -349,6 +353,8 @@ fn replace_random() {
             r.assert_invariants();
             assert_eq!(s, r);
         }
+
+        r.assert_grapheme_invariants();
     }
 }
 
@@ -403,3 +409,326 @@ fn fix_crlf_4() {
 
     assert_eq!(r, "\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n\r\n");
 }
+
+#[test]
+fn insert_slice_small() {
+    let mut r = Rope::from("Hello!");
+    let earth = Rope::from(" Earth 🌎");
+
+    r.insert_slice(5, earth.byte_slice(..));
+    r.assert_invariants();
+    assert_eq!(r, "Hello Earth 🌎!");
+
+    r.insert_slice(0, earth.byte_slice(..0));
+    r.assert_invariants();
+    assert_eq!(r, "Hello Earth 🌎!");
+}
+
+#[test]
+fn insert_slice_of_itself() {
+    let mut r = Rope::from(LARGE);
+    let original = r.clone();
+
+    r.insert_slice(r.byte_len(), original.byte_slice(..));
+    r.assert_invariants();
+    assert_eq!(r, LARGE.repeat(2));
+
+    original.assert_invariants();
+    assert_eq!(original, LARGE);
+}
+
+#[test]
+fn insert_slice_trailing_newline() {
+    let mut r = Rope::from("foo");
+    let other = Rope::from(MEDIUM);
+    r.insert_slice(3, other.line_slice(..1));
+    r.assert_invariants();
+    assert_eq!(r.line_len(), 1);
+    assert_eq!(r.line(0), format!("foo{}", MEDIUM.lines().next().unwrap()));
+}
+
+/// Tests inserting a slice spanning several chunks between the "\r" and the
+/// "\n" of a CRLF grapheme, including when that grapheme starts a leaf of the
+/// grapheme tree.
+#[cfg_attr(miri, ignore)]
+#[test]
+fn insert_slice_inside_grapheme() {
+    let s = "\r\na".repeat(5000);
+    let text = SMALL.repeat(3);
+    let other = Rope::from(text.as_str());
+
+    for at in (1..400).filter(|&at| s[..at].ends_with('\r')) {
+        let mut r = Rope::from(s.as_str());
+        r.insert_slice(at, other.byte_slice(..));
+        r.assert_grapheme_invariants();
+        assert_eq!(r, format!("{}{text}{}", &s[..at], &s[at..]));
+
+        let mut r = Rope::from(s.as_str());
+        r.replace_with_slice(at..at + 3, other.byte_slice(..));
+        r.assert_grapheme_invariants();
+        assert_eq!(r, format!("{}{text}{}", &s[..at], &s[at + 3..]));
+    }
+}
+
+#[test]
+#[should_panic]
+fn replace_with_slice_not_char_boundary() {
+    let mut r = Rope::from("🐸🐸");
+    let other = Rope::from(LARGE);
+    r.replace_with_slice(2..4, other.byte_slice(..));
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn replace_with_slice_random() {
+    let mut rng = rand::thread_rng();
+
+    let other = Rope::from(LARGE);
+
+    for s in [TINY, SMALL, MEDIUM, LARGE] {
+        let mut r = Rope::from(s);
+        let mut s = s.to_owned();
+
+        for _ in 0..20 {
+            let replace_range = {
+                let start = rng.gen_range(0..=r.byte_len());
+                let end = rng.gen_range(start..=r.byte_len());
+                start..end
+            };
+
+            let slice_range = {
+                let start = rng.gen_range(0..=other.byte_len());
+                let end = rng.gen_range(start..=other.byte_len());
+                start..end
+            };
+
+            r.replace_with_slice(
+                replace_range.clone(),
+                other.byte_slice(slice_range.clone()),
+            );
+            s.replace_range(replace_range, &LARGE[slice_range]);
+
+            r.assert_invariants();
+            assert_eq!(s, r);
+            assert_eq!(r.line_len(), Rope::from(s.as_str()).line_len());
+        }
+
+        r.assert_grapheme_invariants();
+    }
+}
+
+#[test]
+fn remove_small() {
+    let mut r = Rope::from("foo\nbar\r\nbaz\n");
+
+    let removed = r.remove(4..9);
+    r.assert_invariants();
+    removed.assert_invariants();
+    assert_eq!(r, "foo\nbaz\n");
+    assert_eq!(removed, "bar\r\n");
+    assert_eq!(removed.line_len(), 1);
+
+    let removed = r.remove(..);
+    r.assert_invariants();
+    removed.assert_invariants();
+    assert_eq!(r, "");
+    assert_eq!(removed, "foo\nbaz\n");
+}
+
+#[test]
+#[should_panic]
+fn remove_out_of_bounds() {
+    let mut r = Rope::from("foo");
+    let _ = r.remove(2..4);
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn splice_random() {
+    let mut rng = rand::thread_rng();
+
+    for s in [TINY, SMALL, MEDIUM, LARGE] {
+        let mut r = Rope::from(s);
+        let mut s = s.to_owned();
+
+        for _ in 0..20 {
+            let replace_range = {
+                let start = rng.gen_range(0..=r.byte_len());
+                let end = rng.gen_range(start..=r.byte_len());
+                start..end
+            };
+
+            let replace_with = {
+                let start = rng.gen_range(0..=r.byte_len());
+                let end = rng.gen_range(start..=r.byte_len());
+                s[start..end].to_owned()
+            };
+
+            let clone = r.clone();
+
+            let removed = r.splice(replace_range.clone(), &replace_with);
+
+            removed.assert_invariants();
+            assert_eq!(removed, s[replace_range.clone()]);
+
+            s.replace_range(replace_range, &replace_with);
+
+            r.assert_invariants();
+            assert_eq!(s, r);
+
+            clone.assert_invariants();
+        }
+
+        r.assert_grapheme_invariants();
+    }
+}
+
+/// Tests splicing a range starting or ending between the "\r" and the "\n" of
+/// a CRLF grapheme.
+#[cfg_attr(miri, ignore)]
+#[test]
+fn splice_inside_grapheme() {
+    let s = "\r\na".repeat(5000);
+
+    for at in (1..400).filter(|&at| s[..at].ends_with('\r')) {
+        let mut r = Rope::from(s.as_str());
+
+        let removed = r.splice(at..at + 3, "x");
+        r.assert_grapheme_invariants();
+        removed.assert_grapheme_invariants();
+        assert_eq!(removed, "\na\r");
+        assert_eq!(r, format!("{}x{}", &s[..at], &s[at + 3..]));
+    }
+}
+
+/// Tests splicing ranges starting in the middle of a flag, which pairs up
+/// the regional indicators after it differently than in the original text.
+#[cfg_attr(miri, ignore)]
+#[test]
+fn splice_regional_indicators() {
+    let s = "🇮🇹🇫🇷a🇩🇪".repeat(50);
+
+    for start in (0..120).step_by(4).filter(|&i| s.is_char_boundary(i)) {
+        for len in [0, 4, 9, 37, 250] {
+            let end = (start + len).min(s.len());
+
+            if !s.is_char_boundary(end) {
+                continue;
+            }
+
+            let mut r = Rope::from(s.as_str());
+
+            let removed = r.splice(start..end, "🇮");
+            r.assert_grapheme_invariants();
+            removed.assert_grapheme_invariants();
+            assert_eq!(removed, s[start..end]);
+        }
+    }
+}
+
+#[test]
+fn apply_edits_inserts_at_same_offset() {
+    let mut r = Rope::from("foo bar");
+    r.apply_edits([(4..4, "a"), (4..7, "baz"), (4..4, "b"), (7..7, "!")]);
+    r.assert_invariants();
+    assert_eq!(r, "foo abbaz!");
+}
+
+#[test]
+fn apply_edits_empty() {
+    let mut r = Rope::from(TEXT);
+    r.apply_edits([]);
+    r.assert_invariants();
+    assert_eq!(r, TEXT);
+}
+
+#[test]
+#[should_panic]
+fn apply_edits_overlapping() {
+    let mut r = Rope::from("foo bar");
+    r.apply_edits([(0..3, "a"), (2..4, "b")]);
+}
+
+#[test]
+fn apply_edits_invalid_leaves_rope_unchanged() {
+    let mut r = Rope::from("foo 🐸");
+
+    let result =
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            r.apply_edits([(0..3, "bar"), (5..6, "")]);
+        }));
+
+    assert!(result.is_err());
+    assert_eq!(r, "foo 🐸");
+}
+
+/// Tests edits that change how the graphemes around and after them are
+/// segmented, e.g. splitting a CRLF or changing the parity of a sequence of
+/// regional indicators.
+#[test]
+fn apply_edits_resegment_graphemes() {
+    let s = "\r\na".repeat(200);
+
+    let mut r = Rope::from(s.as_str());
+    r.apply_edits((0..200).map(|i| (3 * i + 1..3 * i + 1, "\u{301}")));
+    r.assert_grapheme_invariants();
+    assert_eq!(r, "\r\u{301}\na".repeat(200));
+
+    let s = "🇮🇹".repeat(200);
+
+    let mut r = Rope::from(s.as_str());
+    r.apply_edits([
+        (0..4, ""),
+        (40..40, "🇮"),
+        (400..404, ""),
+        (800..804, ""),
+    ]);
+    r.assert_grapheme_invariants();
+
+    let mut expected = s.clone();
+    expected.replace_range(800..804, "");
+    expected.replace_range(400..404, "");
+    expected.insert(40, '🇮');
+    expected.replace_range(0..4, "");
+    assert_eq!(r, expected);
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn apply_edits_random() {
+    let mut rng = rand::thread_rng();
+
+    for s in [TINY, SMALL, MEDIUM, LARGE] {
+        let mut r = Rope::from(s);
+
+        let mut offsets = (0..100)
+            .map(|_| random_char_boundary(&mut rng, s))
+            .collect::<Vec<_>>();
+
+        offsets.sort_unstable();
+
+        let texts = ["", "a", "ü", "🐸\n", "\r\n"];
+
+        let mut edits = offsets
+            .chunks_exact(2)
+            .map(|range| {
+                (range[0]..range[1], texts[rng.gen_range(0..texts.len())])
+            })
+            .collect::<Vec<_>>();
+
+        // Apply the edits one by one from last to first, which doesn't
+        // require shifting the ranges.
+        let mut expected = s.to_owned();
+
+        for (range, text) in edits.iter().rev() {
+            expected.replace_range(range.clone(), text);
+        }
+
+        // The order of the edits shouldn't matter.
+        edits.reverse();
+
+        r.apply_edits(edits);
+        r.assert_grapheme_invariants();
+        assert_eq!(r, expected);
+    }
+}
@@ -0,0 +1,114 @@
+mod common;
+
+#[cfg(feature = "sentences")]
+mod tests {
+    use std::ops::Range;
+
+    use crop::{Rope, RopeSlice};
+    use rand::{thread_rng, Rng};
+    use unicode_segmentation::UnicodeSegmentation;
+
+    use crate::common::{
+        random_byte_range,
+        CURSED_LIPSUM,
+        MEDIUM,
+        SMALL,
+        TEXT_EMOJI,
+    };
+
+    /// Strings whose sentence boundaries depend on more than the two chars
+    /// around them.
+    const TRICKY: &[&str] = &[
+        "Mr. Smith went to Washington. He said \"hi.\" Then left.",
+        "etc. and so on. 3.14 is pi.  Really?!  (Yes.)  Ok",
+        "First line\nSecond line. Third\r\n\r\nFourth.\u{2029}Fifth",
+        "日本語の文。次の文！最後の文？",
+        "e\u{301}. A\u{200d}b. c.d. E.",
+    ];
+
+    fn assert_sentences(slice: RopeSlice<'_>, s: &str) {
+        assert_eq!(
+            slice.sentences().collect::<Vec<_>>(),
+            s.split_sentence_bounds().collect::<Vec<_>>()
+        );
+
+        let mut sentences = s.split_sentence_bounds().collect::<Vec<_>>();
+        sentences.reverse();
+
+        assert_eq!(slice.sentences().rev().collect::<Vec<_>>(), sentences);
+    }
+
+    #[test]
+    fn sentences_empty() {
+        let r = Rope::new();
+        assert_eq!(r.sentences().next(), None);
+        assert_eq!(r.sentences().next_back(), None);
+    }
+
+    #[test]
+    fn sentences_tricky() {
+        for s in TRICKY {
+            let r = Rope::from(*s);
+            assert_sentences(r.byte_slice(..), s);
+        }
+    }
+
+    #[test]
+    fn sentences_matches_unicode_segmentation() {
+        for s in [SMALL, MEDIUM, CURSED_LIPSUM, TEXT_EMOJI] {
+            let r = Rope::from(s);
+            assert_sentences(r.byte_slice(..), s);
+        }
+    }
+
+    #[test]
+    fn sentences_double_ended() {
+        let mut rng = thread_rng();
+
+        for s in [SMALL, CURSED_LIPSUM, TEXT_EMOJI] {
+            let r = Rope::from(s);
+
+            let expected = s.split_sentence_bounds().collect::<Vec<_>>();
+
+            let mut front = Vec::new();
+            let mut back = Vec::new();
+
+            let mut sentences = r.sentences();
+
+            loop {
+                let next = if rng.gen() {
+                    sentences.next().map(|sentence| front.push(sentence))
+                } else {
+                    sentences.next_back().map(|sentence| back.push(sentence))
+                };
+
+                if next.is_none() {
+                    break;
+                }
+            }
+
+            assert_eq!(sentences.next(), None);
+            assert_eq!(sentences.next_back(), None);
+
+            front.extend(back.into_iter().rev());
+            assert_eq!(front, expected);
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn sentences_random_slices() {
+        let mut rng = thread_rng();
+
+        for s in [MEDIUM, CURSED_LIPSUM] {
+            let r = Rope::from(s);
+
+            for _ in 0..20 {
+                let Range { start, end } =
+                    random_byte_range(&mut rng, s, 5000);
+
+                assert_sentences(r.byte_slice(start..end), &s[start..end]);
+            }
+        }
+    }
+}
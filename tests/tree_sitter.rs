@@ -0,0 +1,187 @@
+mod common;
+
+#[cfg(feature = "tree-sitter")]
+mod tests {
+    use std::ops::Range;
+
+    use crop::Rope;
+    use rand::{thread_rng, Rng};
+    use tree_sitter::{Parser, Point};
+
+    use crate::common::{random_byte_range, CURSED_LIPSUM, LARGE};
+
+    const RUST_SOURCE: &str = "fn main() {
+    let frog = \"🐸\";
+    println!(\"{frog}\");
+}
+
+struct Foo {
+    bar: u32,
+    baz: String,
+}
+
+impl Foo {
+    fn new() -> Self {
+        Self { bar: 0, baz: String::new() }
+    }
+}
+";
+
+    fn rust_parser() -> Parser {
+        let mut parser = Parser::new();
+        parser.set_language(tree_sitter_rust::language()).unwrap();
+        parser
+    }
+
+    /// Returns the tree-sitter point of the byte offset in the string,
+    /// computed naively.
+    fn point_of_byte(s: &str, byte_offset: usize) -> Point {
+        let before = &s[..byte_offset];
+        let row = before.matches('\n').count();
+        let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+        Point::new(row, byte_offset - line_start)
+    }
+
+    #[test]
+    fn reader_yields_whole_rope() {
+        for s in [LARGE, CURSED_LIPSUM] {
+            let r = Rope::from(s);
+            let mut read = r.tree_sitter_reader();
+
+            let mut offset = 0;
+            let mut bytes = Vec::new();
+
+            loop {
+                let chunk = read(offset, Point::default());
+
+                if chunk.is_empty() {
+                    break;
+                }
+
+                bytes.extend_from_slice(chunk);
+                offset += chunk.len();
+            }
+
+            assert_eq!(bytes, s.as_bytes());
+            assert!(read(s.len() + 10, Point::default()).is_empty());
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn reader_random_offsets() {
+        let mut rng = thread_rng();
+
+        let r = Rope::from(CURSED_LIPSUM);
+        let mut read = r.tree_sitter_reader();
+
+        for _ in 0..1000 {
+            let offset = rng.gen_range(0..CURSED_LIPSUM.len());
+            let chunk = read(offset, Point::default());
+            assert!(!chunk.is_empty());
+            assert!(CURSED_LIPSUM.as_bytes()[offset..].starts_with(chunk));
+        }
+    }
+
+    #[test]
+    fn edit_positions() {
+        let r = Rope::from("foo\nbar\r\nbaz");
+
+        let edit = r.tree_sitter_edit(5..10, "a\nbc");
+        assert_eq!(edit.start_byte, 5);
+        assert_eq!(edit.old_end_byte, 10);
+        assert_eq!(edit.new_end_byte, 9);
+        assert_eq!(edit.start_position, Point::new(1, 1));
+        assert_eq!(edit.old_end_position, Point::new(2, 1));
+        assert_eq!(edit.new_end_position, Point::new(2, 2));
+
+        let edit = r.tree_sitter_edit(12.., "");
+        assert_eq!(edit.start_position, Point::new(2, 3));
+        assert_eq!(edit.old_end_position, Point::new(2, 3));
+        assert_eq!(edit.new_end_position, Point::new(2, 3));
+    }
+
+    #[test]
+    fn edit_positions_only_count_newlines() {
+        // tree-sitter's rows are only delimited by "\n"s, even if the `Rope`
+        // also breaks lines on U+2028 with the `unicode-lines` feature.
+        let r = Rope::from("a\u{2028}b\nc\u{2028}d");
+
+        let edit = r.tree_sitter_edit(4..7, "x\u{2028}y");
+        assert_eq!(edit.start_position, Point::new(0, 4));
+        assert_eq!(edit.old_end_position, Point::new(1, 1));
+        assert_eq!(edit.new_end_position, Point::new(0, 9));
+
+        let edit = r.tree_sitter_edit(r.byte_len().., "\u{2028}\n");
+        assert_eq!(edit.start_position, Point::new(1, 5));
+        assert_eq!(edit.new_end_position, Point::new(2, 0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn edit_not_char_boundary() {
+        let r = Rope::from("🐸");
+        let _ = r.tree_sitter_edit(1..2, "");
+    }
+
+    #[test]
+    fn parse_matches_string() {
+        let mut parser = rust_parser();
+
+        let source = RUST_SOURCE.repeat(50);
+        let r = Rope::from(source.as_str());
+
+        let from_rope = r.tree_sitter_parse(&mut parser, None).unwrap();
+        let from_str = parser.parse(&source, None).unwrap();
+
+        assert!(!from_rope.root_node().has_error());
+        assert_eq!(
+            from_rope.root_node().to_sexp(),
+            from_str.root_node().to_sexp()
+        );
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn incremental_reparse_random() {
+        let mut rng = thread_rng();
+        let mut parser = rust_parser();
+
+        let mut s = RUST_SOURCE.repeat(20);
+        let mut r = Rope::from(s.as_str());
+        let mut tree = r.tree_sitter_parse(&mut parser, None).unwrap();
+
+        let texts =
+            ["", "x", " ", "\n", "\u{2028}", "🐸", "fn a() {}\n", "{", "}\n"];
+
+        for _ in 0..50 {
+            let Range { start, end } =
+                random_byte_range(&mut rng, s.as_str(), 20);
+
+            let text = texts[rng.gen_range(0..texts.len())];
+
+            let edit = r.tree_sitter_edit(start..end, text);
+
+            assert_eq!(edit.start_position, point_of_byte(&s, start));
+            assert_eq!(edit.old_end_position, point_of_byte(&s, end));
+
+            r.replace(start..end, text);
+            s.replace_range(start..end, text);
+
+            assert_eq!(
+                edit.new_end_position,
+                point_of_byte(&s, start + text.len())
+            );
+
+            tree.edit(&edit);
+            tree = r.tree_sitter_parse(&mut parser, Some(&tree)).unwrap();
+
+            let from_str = parser.parse(&s, None).unwrap();
+
+            assert_eq!(
+                tree.root_node().to_sexp(),
+                from_str.root_node().to_sexp()
+            );
+        }
+    }
+}
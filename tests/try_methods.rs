@@ -0,0 +1,309 @@
+use crop::{Rope, RopeError, Unit};
+use rand::{thread_rng, Rng};
+
+mod common;
+
+use common::{CURSED_LIPSUM, LARGE};
+
+/// Returns the error `try_byte_slice()` and `try_replace()` should return
+/// for the given byte range of the string, if any.
+fn byte_range_error(s: &str, start: usize, end: usize) -> Option<RopeError> {
+    if start > end {
+        Some(RopeError::StartAfterEnd { unit: Unit::Byte, start, end })
+    } else if end > s.len() {
+        Some(RopeError::OffsetOutOfBounds {
+            unit: Unit::Byte,
+            offset: end,
+            len: s.len(),
+        })
+    } else if !s.is_char_boundary(start) {
+        Some(RopeError::NotCharBoundary { byte_offset: start })
+    } else if !s.is_char_boundary(end) {
+        Some(RopeError::NotCharBoundary { byte_offset: end })
+    } else {
+        None
+    }
+}
+
+#[test]
+fn try_byte() {
+    let r = Rope::from("foo");
+    assert_eq!(r.try_byte(2), Ok(b'o'));
+    assert_eq!(
+        r.try_byte(3),
+        Err(RopeError::IndexOutOfBounds {
+            unit: Unit::Byte,
+            index: 3,
+            len: 3
+        })
+    );
+
+    let s = r.byte_slice(1..2);
+    assert_eq!(s.try_byte(0), Ok(b'o'));
+    assert_eq!(
+        s.try_byte(1),
+        Err(RopeError::IndexOutOfBounds {
+            unit: Unit::Byte,
+            index: 1,
+            len: 1
+        })
+    );
+}
+
+#[test]
+fn try_byte_slice_random() {
+    let r = Rope::from(CURSED_LIPSUM);
+    let mut rng = thread_rng();
+
+    for _ in 0..1000 {
+        let start = rng.gen_range(0..=CURSED_LIPSUM.len() + 10);
+        let end = rng.gen_range(0..=CURSED_LIPSUM.len() + 10);
+
+        match byte_range_error(CURSED_LIPSUM, start, end) {
+            Some(err) => assert_eq!(r.try_byte_slice(start..end), Err(err)),
+
+            None => {
+                let s = r.try_byte_slice(start..end).unwrap();
+                s.assert_invariants();
+                assert_eq!(s, &CURSED_LIPSUM[start..end]);
+            },
+        }
+    }
+}
+
+#[test]
+fn try_byte_slice_of_slice() {
+    let r = Rope::from("🗻∈🌏");
+    let s = r.byte_slice(4..);
+
+    assert_eq!(s.try_byte_slice(..3).unwrap(), "∈");
+    assert_eq!(
+        s.try_byte_slice(..4),
+        Err(RopeError::NotCharBoundary { byte_offset: 4 })
+    );
+    assert_eq!(
+        s.try_byte_slice(..8),
+        Err(RopeError::OffsetOutOfBounds {
+            unit: Unit::Byte,
+            offset: 8,
+            len: 7
+        })
+    );
+}
+
+#[test]
+fn try_line() {
+    let r = Rope::from(LARGE);
+    let lines = LARGE.lines().collect::<Vec<_>>();
+
+    for (idx, line) in lines.iter().enumerate() {
+        assert_eq!(r.try_line(idx).unwrap(), *line);
+        assert_eq!(r.byte_slice(..).try_line(idx).unwrap(), *line);
+    }
+
+    let err = RopeError::IndexOutOfBounds {
+        unit: Unit::Line,
+        index: lines.len(),
+        len: lines.len(),
+    };
+
+    assert_eq!(r.try_line(lines.len()), Err(err));
+    assert_eq!(r.byte_slice(..).try_line(lines.len()), Err(err));
+}
+
+#[allow(clippy::reversed_empty_ranges)]
+#[test]
+fn try_line_slice_and_offsets() {
+    let r = Rope::from("foo\nbär\r\nbaz\n");
+
+    assert_eq!(r.try_line_slice(1..2).unwrap(), "bär\r\n");
+    assert_eq!(
+        r.try_line_slice(2..1),
+        Err(RopeError::StartAfterEnd { unit: Unit::Line, start: 2, end: 1 })
+    );
+    assert_eq!(
+        r.try_line_slice(..4),
+        Err(RopeError::OffsetOutOfBounds {
+            unit: Unit::Line,
+            offset: 4,
+            len: 3
+        })
+    );
+
+    assert_eq!(r.try_byte_of_line(3), Ok(r.byte_len()));
+    assert_eq!(
+        r.try_byte_of_line(4),
+        Err(RopeError::OffsetOutOfBounds {
+            unit: Unit::Line,
+            offset: 4,
+            len: 3
+        })
+    );
+
+    assert_eq!(r.try_line_of_byte(4), Ok(1));
+    assert_eq!(
+        r.try_line_of_byte(6),
+        Err(RopeError::NotCharBoundary { byte_offset: 6 })
+    );
+    assert_eq!(
+        r.try_line_of_byte(15),
+        Err(RopeError::OffsetOutOfBounds {
+            unit: Unit::Byte,
+            offset: 15,
+            len: 14
+        })
+    );
+}
+
+#[test]
+fn try_replace_random() {
+    let mut r = Rope::from(CURSED_LIPSUM);
+    let mut s = String::from(CURSED_LIPSUM);
+    let mut rng = thread_rng();
+
+    for _ in 0..1000 {
+        let start = rng.gen_range(0..=s.len() + 10);
+        let end = rng.gen_range(start.saturating_sub(5)..=start + 20);
+        let text = ["", "a", "ü", "🐸\n"][rng.gen_range(0..4)];
+
+        match byte_range_error(&s, start, end) {
+            Some(err) => {
+                assert_eq!(r.try_replace(start..end, text), Err(err));
+                assert_eq!(r, s);
+            },
+
+            None => {
+                assert_eq!(r.try_replace(start..end, text), Ok(()));
+                s.replace_range(start..end, text);
+                assert_eq!(r, s);
+            },
+        }
+    }
+
+    r.assert_invariants();
+}
+
+#[allow(clippy::reversed_empty_ranges)]
+#[test]
+fn try_insert_and_delete() {
+    let mut r = Rope::from("Hello 🌎!");
+
+    assert_eq!(
+        r.try_insert(8, "a"),
+        Err(RopeError::NotCharBoundary { byte_offset: 8 })
+    );
+    assert_eq!(
+        r.try_insert(12, "a"),
+        Err(RopeError::OffsetOutOfBounds {
+            unit: Unit::Byte,
+            offset: 12,
+            len: 11
+        })
+    );
+    assert_eq!(r, "Hello 🌎!");
+
+    assert_eq!(r.try_insert(11, "!"), Ok(()));
+    assert_eq!(r, "Hello 🌎!!");
+
+    assert_eq!(
+        r.try_delete(7..),
+        Err(RopeError::NotCharBoundary { byte_offset: 7 })
+    );
+    assert_eq!(
+        r.try_delete(3..2),
+        Err(RopeError::StartAfterEnd { unit: Unit::Byte, start: 3, end: 2 })
+    );
+    assert_eq!(r, "Hello 🌎!!");
+
+    assert_eq!(r.try_delete(5..10), Ok(()));
+    assert_eq!(r, "Hello!!");
+}
+
+#[test]
+fn rope_error_display() {
+    let err = RopeError::OffsetOutOfBounds {
+        unit: Unit::Utf16CodeUnit,
+        offset: 4,
+        len: 2,
+    };
+
+    assert_eq!(
+        err.to_string(),
+        "UTF-16 offset out of bounds: the offset is 4 but the length is 2"
+    );
+
+    let err = RopeError::NotCharBoundary { byte_offset: 1 };
+    assert_eq!(err.to_string(), "byte offset 1 is not a char boundary");
+
+    let err = RopeError::StartAfterEnd { unit: Unit::Line, start: 2, end: 1 };
+    assert_eq!(
+        err.to_string(),
+        "line start after end: the start is 2 but the end is 1"
+    );
+}
+
+#[cfg(feature = "char-metric")]
+#[test]
+fn try_char_methods() {
+    let r = Rope::from("a🐸b");
+
+    assert_eq!(r.try_byte_of_char(3), Ok(6));
+    assert_eq!(r.try_char_of_byte(1), Ok(1));
+    assert_eq!(
+        r.try_char_of_byte(2),
+        Err(RopeError::NotCharBoundary { byte_offset: 2 })
+    );
+    assert_eq!(r.try_char_slice(1..).unwrap(), "🐸b");
+    assert_eq!(
+        r.byte_slice(1..).try_char_slice(..3),
+        Err(RopeError::OffsetOutOfBounds {
+            unit: Unit::Char,
+            offset: 3,
+            len: 2
+        })
+    );
+}
+
+#[cfg(feature = "utf16-metric")]
+#[test]
+fn try_utf16_methods() {
+    let r = Rope::from("a🐸b");
+
+    assert_eq!(r.try_byte_of_utf16_code_unit(4), Ok(6));
+    assert_eq!(
+        r.try_byte_of_utf16_code_unit(5),
+        Err(RopeError::OffsetOutOfBounds {
+            unit: Unit::Utf16CodeUnit,
+            offset: 5,
+            len: 4
+        })
+    );
+    assert_eq!(r.try_utf16_code_unit_of_byte(6), Ok(4));
+    assert_eq!(r.byte_slice(1..).try_utf16_slice(..2).unwrap(), "🐸");
+}
+
+#[cfg(feature = "grapheme-metric")]
+#[test]
+fn try_grapheme_methods() {
+    let r = Rope::from("e\u{301}🇷🇸");
+
+    assert_eq!(r.try_byte_of_grapheme(1), Ok(3));
+    assert_eq!(r.try_grapheme_of_byte(3), Ok(1));
+    assert_eq!(
+        r.try_grapheme_of_byte(12),
+        Err(RopeError::OffsetOutOfBounds {
+            unit: Unit::Byte,
+            offset: 12,
+            len: 11
+        })
+    );
+    assert_eq!(r.try_grapheme_slice(1..).unwrap(), "🇷🇸");
+    assert_eq!(
+        r.try_grapheme_slice(..3),
+        Err(RopeError::OffsetOutOfBounds {
+            unit: Unit::Grapheme,
+            offset: 3,
+            len: 2
+        })
+    );
+}
@@ -0,0 +1,342 @@
+mod common;
+
+#[cfg(feature = "unicode-lines")]
+mod tests {
+    use crop::{Rope, RopeBuilder};
+    use rand::{thread_rng, Rng};
+
+    use crate::common::CURSED_LIPSUM;
+
+    const LINE_BREAKS: [char; 7] = [
+        '\n', '\u{000B}', '\u{000C}', '\r', '\u{0085}', '\u{2028}',
+        '\u{2029}',
+    ];
+
+    /// Splits the string into its raw lines, i.e. including their line
+    /// terminators.
+    fn raw_lines(s: &str) -> Vec<&str> {
+        raw_lines_followed_by(s, "")
+    }
+
+    /// Like [`raw_lines`], but for a string that's followed by `next`, which
+    /// decides whether a trailing `\r` is a line break.
+    fn raw_lines_followed_by<'a>(s: &'a str, next: &str) -> Vec<&'a str> {
+        let mut lines = Vec::new();
+
+        let mut line_start = 0;
+
+        let mut chars = s.char_indices().peekable();
+
+        while let Some((idx, ch)) = chars.next() {
+            let is_break = match ch {
+                '\r' => match chars.peek() {
+                    Some(&(_, next_ch)) => next_ch != '\n',
+                    None => !next.starts_with('\n'),
+                },
+                _ => LINE_BREAKS.contains(&ch),
+            };
+
+            if is_break {
+                lines.push(&s[line_start..idx + ch.len_utf8()]);
+                line_start = idx + ch.len_utf8();
+            }
+        }
+
+        if line_start < s.len() {
+            lines.push(&s[line_start..]);
+        }
+
+        lines
+    }
+
+    /// Splits the string into its lines, without their line terminators (or
+    /// the `\r` of CRLF pairs).
+    fn lines(s: &str) -> Vec<&str> {
+        lines_followed_by(s, "")
+    }
+
+    /// Like [`lines`], but for a string that's followed by `next`.
+    fn lines_followed_by<'a>(s: &'a str, next: &str) -> Vec<&'a str> {
+        // A `\r` followed by a `\n` that's not in the string is part of the
+        // last line.
+        let ends_in_split_crlf = s.ends_with('\r') && next.starts_with('\n');
+
+        let raw_lines = raw_lines_followed_by(s, next);
+
+        let len = raw_lines.len();
+
+        raw_lines
+            .into_iter()
+            .enumerate()
+            .map(|(idx, line)| {
+                if idx + 1 == len && ends_in_split_crlf {
+                    return line;
+                }
+                match line.strip_suffix("\r\n") {
+                    Some(line) => line,
+                    None => line.strip_suffix(&LINE_BREAKS[..]).unwrap_or(line),
+                }
+            })
+            .collect()
+    }
+
+    #[track_caller]
+    fn assert_lines(r: &Rope, s: &str) {
+        let raw = raw_lines(s);
+        let lines = lines(s);
+
+        assert_eq!(r.line_len(), raw.len());
+
+        let mut line_start = 0;
+
+        for (idx, raw_line) in raw.iter().enumerate() {
+            assert_eq!(r.byte_of_line(idx), line_start);
+            assert_eq!(r.line_of_byte(line_start), idx);
+            assert_eq!(r.line_slice(idx..idx + 1), *raw_line);
+            assert_eq!(r.line(idx), lines[idx]);
+            line_start += raw_line.len();
+        }
+
+        assert_eq!(r.byte_of_line(raw.len()), s.len());
+
+        assert!(r.raw_lines().eq(raw.iter().copied()));
+        assert!(r.raw_lines().rev().eq(raw.iter().rev().copied()));
+        assert!(r.lines().eq(lines.iter().copied()));
+        assert!(r.lines().rev().eq(lines.iter().rev().copied()));
+    }
+
+    #[test]
+    fn unicode_lines_line_len() {
+        assert_eq!(Rope::from("a\u{000B}b").line_len(), 2);
+        assert_eq!(Rope::from("a\u{000C}b").line_len(), 2);
+        assert_eq!(Rope::from("a\u{0085}b").line_len(), 2);
+        assert_eq!(Rope::from("a\u{2028}b").line_len(), 2);
+        assert_eq!(Rope::from("a\u{2029}b").line_len(), 2);
+        assert_eq!(Rope::from("a\r\nb").line_len(), 2);
+
+        // The final line break doesn't count as a separate line.
+        assert_eq!(Rope::from("a\u{2028}").line_len(), 1);
+
+        // A lone "\r" is a line break.
+        assert_eq!(Rope::from("a\rb").line_len(), 2);
+        assert_eq!(Rope::from("a\r").line_len(), 1);
+        assert_eq!(Rope::from("a\r\r\nb").line_len(), 3);
+    }
+
+    #[test]
+    fn unicode_lines_lines() {
+        let r = Rope::from("a\u{000B}b\u{0085}c\r\nd\u{2029}");
+
+        let mut lines = r.lines();
+        assert_eq!("a", lines.next().unwrap());
+        assert_eq!("b", lines.next().unwrap());
+        assert_eq!("c", lines.next().unwrap());
+        assert_eq!("d", lines.next().unwrap());
+        assert_eq!(None, lines.next());
+
+        let mut raw_lines = r.raw_lines();
+        assert_eq!("a\u{000B}", raw_lines.next().unwrap());
+        assert_eq!("b\u{0085}", raw_lines.next().unwrap());
+        assert_eq!("c\r\n", raw_lines.next().unwrap());
+        assert_eq!("d\u{2029}", raw_lines.next().unwrap());
+        assert_eq!(None, raw_lines.next());
+    }
+
+    #[test]
+    fn unicode_lines_lone_cr() {
+        let r = Rope::from("a\rb\r\rc\r\n\r");
+        assert_eq!(r.line_len(), 5);
+        assert!(r.raw_lines().eq(["a\r", "b\r", "\r", "c\r\n", "\r"]));
+        assert!(r.lines().eq(["a", "b", "", "c", ""]));
+        assert!(r.lines().rev().eq(["", "c", "", "b", "a"]));
+        assert_eq!(r.byte_of_line(2), 4);
+        assert_eq!(r.line_of_byte(5), 3);
+        assert_lines(&r, "a\rb\r\rc\r\n\r");
+    }
+
+    #[test]
+    fn unicode_lines_cr_before_other_breaks() {
+        // Only a "\n" forms a pair with a preceding "\r".
+        let r = Rope::from("a\r\u{000C}b\r\u{2028}c\r\n");
+        assert_eq!(r.line_len(), 5);
+        assert!(r.lines().eq(["a", "", "b", "", "c"]));
+        assert!(r.lines().rev().eq(["c", "", "b", "", "a"]));
+    }
+
+    /// The lengths of the text before a CRLF pair for which the pair ends up
+    /// across a chunk boundary, both with the default chunk size and with
+    /// `small_chunks`.
+    fn lens_around_chunk_boundaries() -> impl Iterator<Item = usize> {
+        (0..16).chain(2030..2060)
+    }
+
+    #[test]
+    fn unicode_lines_crlf_split_by_append() {
+        for len in lens_around_chunk_boundaries() {
+            let s = format!("{}\r\nb", "a".repeat(len));
+            let mut r = Rope::from(&s[..len + 1]);
+            r.append(Rope::from("\nb"));
+            r.assert_invariants();
+            assert_lines(&r, &s);
+        }
+    }
+
+    #[test]
+    fn unicode_lines_crlf_split_by_builder() {
+        for len in lens_around_chunk_boundaries() {
+            let s = format!("{}\r\nb", "a".repeat(len));
+            let mut builder = RopeBuilder::new();
+            builder.append(&s[..len + 1]).append("\nb");
+            let r = builder.build();
+            r.assert_invariants();
+            assert_lines(&r, &s);
+        }
+    }
+
+    #[test]
+    fn unicode_lines_crlf_joined_by_edits() {
+        for len in lens_around_chunk_boundaries() {
+            // Inserting the "\n" after the "\r".
+            let s = format!("{}\r\nb", "a".repeat(len));
+            let mut r = Rope::from(format!("{}\rb", "a".repeat(len)));
+            r.insert(len + 1, "\n");
+            r.assert_invariants();
+            assert_lines(&r, &s);
+
+            // Deleting the text between the "\r" and the "\n".
+            let mut r = Rope::from(format!("{}\rxyz\nb", "a".repeat(len)));
+            r.delete(len + 1..len + 4);
+            r.assert_invariants();
+            assert_lines(&r, &s);
+
+            // Inserting the "\r" before the "\n".
+            let mut r = Rope::from(format!("{}\nb", "a".repeat(len)));
+            r.insert(len, "\r");
+            r.assert_invariants();
+            assert_lines(&r, &s);
+        }
+    }
+
+    #[test]
+    fn unicode_lines_slice_ending_in_split_crlf() {
+        let r = Rope::from("a\r\nb");
+
+        // The "\r" is part of the line when the "\n" isn't in the slice.
+        let s = r.byte_slice(..2);
+        assert_eq!(s.line_len(), 1);
+        assert_eq!(s.line(0), "a\r");
+        assert!(s.raw_lines().eq(["a\r"]));
+
+        // But it's a line break once the slice is turned into a `Rope`.
+        let r = Rope::from(s);
+        r.assert_invariants();
+        assert_lines(&r, "a\r");
+    }
+
+    #[test]
+    fn unicode_lines_slice() {
+        let r = Rope::from("foo\u{2028}bar\u{0085}baz");
+        let s = r.byte_slice(2..);
+        assert_eq!(s.line_len(), 3);
+        assert_eq!(s.line(0), "o");
+        assert_eq!(s.line(1), "bar");
+        assert_eq!(s.line(2), "baz");
+        assert_eq!(s.byte_of_line(2), "o\u{2028}bar\u{0085}".len());
+        assert_eq!(s.line_of_byte(s.byte_len()), 2);
+    }
+
+    #[test]
+    fn unicode_lines_cursed_lipsum() {
+        let s = CURSED_LIPSUM.replace('\n', "\u{2028}");
+        let r = Rope::from(s.as_str());
+        assert_lines(&r, &s);
+    }
+
+    #[test]
+    fn unicode_lines_random() {
+        let pieces = [
+            "a",
+            "bcd",
+            "こんにちは",
+            "\r",
+            "\n",
+            "\r\n",
+            "\u{000B}",
+            "\u{000C}",
+            "\u{0085}",
+            "\u{2028}",
+            "\u{2029}",
+        ];
+
+        let mut rng = thread_rng();
+
+        let mut s = String::new();
+
+        for _ in 0..5000 {
+            s.push_str(pieces[rng.gen_range(0..pieces.len())]);
+        }
+
+        let r = Rope::from(s.as_str());
+        r.assert_invariants();
+        assert_lines(&r, &s);
+
+        for _ in 0..10 {
+            let start = rng.gen_range(0..=s.len());
+            let end = rng.gen_range(start..=s.len());
+
+            if !s.is_char_boundary(start) || !s.is_char_boundary(end) {
+                continue;
+            }
+
+            let slice = r.byte_slice(start..end);
+            let (s, next) = (&s[start..end], &s[end..]);
+
+            let raw_lines = raw_lines_followed_by(s, next);
+            let lines = lines_followed_by(s, next);
+
+            assert_eq!(slice.line_len(), raw_lines.len());
+            assert!(slice.raw_lines().eq(raw_lines.iter().copied()));
+            assert!(slice.lines().eq(lines.iter().copied()));
+            assert!(slice.lines().rev().eq(lines.iter().rev().copied()));
+
+            let slice = Rope::from(slice);
+            slice.assert_invariants();
+            assert_lines(&slice, s);
+        }
+    }
+
+    #[test]
+    fn unicode_lines_random_edits() {
+        let pieces =
+            ["ab", "\r", "\n", "\r\n", "\u{000B}", "\u{0085}", "\u{2029}"];
+
+        let mut rng = thread_rng();
+
+        let mut r = Rope::new();
+        let mut s = String::new();
+
+        for _ in 0..1000 {
+            let mut text = String::new();
+
+            for _ in 0..rng.gen_range(0..20) {
+                text.push_str(pieces[rng.gen_range(0..pieces.len())]);
+            }
+
+            let start = rng.gen_range(0..=s.len());
+            let end = rng.gen_range(start..=s.len().min(start + 20));
+
+            if !s.is_char_boundary(start) || !s.is_char_boundary(end) {
+                continue;
+            }
+
+            r.replace(start..end, &text);
+            s.replace_range(start..end, &text);
+
+            assert_eq!(r.line_len(), raw_lines(&s).len());
+            r.assert_invariants();
+        }
+
+        r.assert_invariants();
+        assert_lines(&r, &s);
+    }
+}
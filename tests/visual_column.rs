@@ -0,0 +1,205 @@
+use std::ops::Range;
+
+use crop::{Rope, RopeSlice};
+use rand::{thread_rng, Rng};
+
+mod common;
+
+use common::{random_byte_range, CURSED_LIPSUM, SMALL, TEXT_EMOJI};
+
+const TABS: &str =
+    "\tfoo\tbar\n  \t\t baz\r\n\t日本\te\u{301}\u{301}\tx\n\n\t";
+
+/// Returns the units of the string, i.e. its graphemes if the `graphemes`
+/// feature is enabled and its chars otherwise.
+fn units(s: &str) -> Vec<&str> {
+    #[cfg(feature = "graphemes")]
+    {
+        use unicode_segmentation::UnicodeSegmentation;
+        s.graphemes(true).collect()
+    }
+
+    #[cfg(not(feature = "graphemes"))]
+    {
+        s.char_indices().map(|(i, ch)| &s[i..i + ch.len_utf8()]).collect()
+    }
+}
+
+fn width(unit: &str) -> usize {
+    #[cfg(feature = "unicode-width")]
+    {
+        use unicode_width::UnicodeWidthChar;
+        unit.chars().map(|ch| ch.width().unwrap_or(0)).sum()
+    }
+
+    #[cfg(not(feature = "unicode-width"))]
+    {
+        let _ = unit;
+        1
+    }
+}
+
+/// Returns the visual column at the start of every unit of the line, plus the
+/// one at its end.
+fn columns(line: &str, tab_width: usize) -> Vec<(usize, usize)> {
+    let mut columns = Vec::new();
+    let mut offset = 0;
+    let mut column = 0;
+
+    for unit in units(line) {
+        columns.push((offset, column));
+
+        column = if unit == "\t" {
+            (column / tab_width + 1) * tab_width
+        } else {
+            column + width(unit)
+        };
+
+        offset += unit.len();
+    }
+
+    columns.push((offset, column));
+    columns
+}
+
+fn assert_visual_columns(slice: RopeSlice<'_>, s: &str, tab_width: usize) {
+    for (line_index, line) in s.lines().enumerate() {
+        let line_start = slice.byte_of_line(line_index);
+        let columns = columns(line, tab_width);
+
+        for &(offset, column) in &columns {
+            assert_eq!(
+                slice.visual_column_of_byte(line_start + offset, tab_width),
+                column,
+                "line {line_index}, offset {offset}"
+            );
+        }
+
+        let (_, line_width) = *columns.last().unwrap();
+
+        for column in 0..line_width + 3 {
+            let expected = columns
+                .windows(2)
+                .find(|w| w[1].1 > column)
+                .map(|w| w[0].0)
+                .unwrap_or(line.len());
+
+            assert_eq!(
+                slice.byte_of_visual_column(line_index, column, tab_width),
+                line_start + expected,
+                "line {line_index}, column {column}"
+            );
+        }
+    }
+}
+
+#[test]
+fn visual_column_empty() {
+    let r = Rope::new();
+    assert_eq!(r.visual_column_of_byte(0, 4), 0);
+
+    let r = Rope::from("\t\n");
+    assert_eq!(r.visual_column_of_byte(1, 4), 4);
+    assert_eq!(r.visual_column_of_byte(2, 4), 0);
+    assert_eq!(r.byte_of_visual_column(0, 10, 4), 1);
+}
+
+#[test]
+fn visual_column_tabs() {
+    let r = Rope::from("a\tb  \tc\t\td");
+
+    assert_eq!(r.visual_column_of_byte(1, 4), 1);
+    assert_eq!(r.visual_column_of_byte(2, 4), 4);
+    assert_eq!(r.visual_column_of_byte(6, 4), 8);
+    assert_eq!(r.visual_column_of_byte(8, 4), 12);
+    assert_eq!(r.visual_column_of_byte(9, 4), 16);
+    assert_eq!(r.visual_column_of_byte(9, 1), 9);
+
+    assert_eq!(r.byte_of_visual_column(0, 3, 4), 1);
+    assert_eq!(r.byte_of_visual_column(0, 4, 4), 2);
+    assert_eq!(r.byte_of_visual_column(0, 13, 4), 8);
+    assert_eq!(r.byte_of_visual_column(0, 16, 4), 9);
+}
+
+#[test]
+fn visual_column_matches_naive() {
+    for s in [TABS, SMALL, CURSED_LIPSUM, TEXT_EMOJI] {
+        let r = Rope::from(s);
+
+        for tab_width in [1, 2, 4, 8] {
+            assert_visual_columns(r.byte_slice(..), s, tab_width);
+        }
+    }
+}
+
+/// With the `graphemes` feature a combining mark is part of the grapheme
+/// before it, so it can't be the start of a column.
+#[cfg(feature = "graphemes")]
+#[test]
+fn visual_column_counts_graphemes() {
+    let r = Rope::from("e\u{301}\tx\n");
+
+    assert_eq!(r.visual_column_of_byte(1, 4), 0);
+    assert_eq!(r.visual_column_of_byte(3, 4), 1);
+    assert_eq!(r.visual_column_of_byte(4, 4), 4);
+
+    assert_eq!(r.byte_of_visual_column(0, 0, 4), 0);
+    assert_eq!(r.byte_of_visual_column(0, 1, 4), 3);
+    assert_eq!(r.byte_of_visual_column(0, 4, 4), 4);
+}
+
+/// Without the `graphemes` feature a combining mark is a unit of its own.
+#[cfg(not(feature = "graphemes"))]
+#[test]
+fn visual_column_counts_chars() {
+    let r = Rope::from("e\u{301}\tx\n");
+
+    assert_eq!(r.visual_column_of_byte(1, 4), 1);
+    assert_eq!(r.visual_column_of_byte(4, 4), 4);
+
+    assert_eq!(r.byte_of_visual_column(0, 0, 4), 0);
+    assert_eq!(r.byte_of_visual_column(0, 4, 4), 4);
+
+    // The combining mark takes up a column of its own unless its display
+    // width is used.
+    #[cfg(not(feature = "unicode-width"))]
+    {
+        assert_eq!(r.visual_column_of_byte(3, 4), 2);
+        assert_eq!(r.byte_of_visual_column(0, 1, 4), 1);
+    }
+
+    #[cfg(feature = "unicode-width")]
+    {
+        assert_eq!(r.visual_column_of_byte(3, 4), 1);
+        assert_eq!(r.byte_of_visual_column(0, 1, 4), 3);
+    }
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn visual_column_random_slices() {
+    let mut rng = thread_rng();
+
+    let s = TABS.repeat(20);
+    let r = Rope::from(s.as_str());
+
+    for _ in 0..20 {
+        let Range { start, end } =
+            random_byte_range(&mut rng, s.as_str(), s.len());
+
+        let tab_width = rng.gen_range(1..=8);
+
+        assert_visual_columns(
+            r.byte_slice(start..end),
+            &s[start..end],
+            tab_width,
+        );
+    }
+}
+
+#[test]
+#[should_panic]
+fn visual_column_zero_tab_width() {
+    let r = Rope::from("\tfoo");
+    let _ = r.visual_column_of_byte(2, 0);
+}
@@ -0,0 +1,172 @@
+mod common;
+
+#[cfg(feature = "words")]
+mod tests {
+    use std::ops::Range;
+
+    use crop::{Rope, RopeSlice};
+    use rand::{thread_rng, Rng};
+    use unicode_segmentation::UnicodeSegmentation;
+
+    use crate::common::{
+        random_byte_range,
+        CURSED_LIPSUM,
+        MEDIUM,
+        SMALL,
+        TEXT_EMOJI,
+    };
+
+    /// Strings whose word boundaries depend on more than the two chars around
+    /// them.
+    const TRICKY: &[&str] = &[
+        "🇷🇸🇮🇴🇷🇸🇮 🇷🇸\n🇮🇴🇷",
+        "can't stop 3.14 won't 1,000,000 a.b.c \"quoted\"",
+        "  \t\u{301}a\u{200d}🐸  \u{301}\r\n\n \u{200d}",
+        "e\u{301}\u{301}x y\u{308}z  \u{2028} word",
+        "日本語のテキストカタカナ ひらがな漢字",
+        "foo_bar baz__qux ...",
+    ];
+
+    /// Returns the byte offsets of all the word boundaries of the string,
+    /// including its start and end.
+    fn boundaries(s: &str) -> Vec<usize> {
+        let mut boundaries = s
+            .split_word_bound_indices()
+            .map(|(offset, _)| offset)
+            .collect::<Vec<_>>();
+        boundaries.push(s.len());
+        boundaries
+    }
+
+    fn assert_words(slice: RopeSlice<'_>, s: &str) {
+        assert_eq!(
+            slice.word_bounds().collect::<Vec<_>>(),
+            s.split_word_bounds().collect::<Vec<_>>()
+        );
+
+        assert_eq!(
+            slice.word_bounds().rev().collect::<Vec<_>>(),
+            s.split_word_bounds().rev().collect::<Vec<_>>()
+        );
+
+        assert_eq!(
+            slice.words().collect::<Vec<_>>(),
+            s.unicode_words().collect::<Vec<_>>()
+        );
+
+        assert_eq!(
+            slice.words().rev().collect::<Vec<_>>(),
+            s.unicode_words().rev().collect::<Vec<_>>()
+        );
+    }
+
+    fn assert_boundary_at(
+        slice: RopeSlice<'_>,
+        boundaries: &[usize],
+        o: usize,
+    ) {
+        let next = boundaries
+            .iter()
+            .copied()
+            .find(|&b| b > o)
+            .unwrap_or(slice.byte_len());
+
+        let prev =
+            boundaries.iter().copied().rev().find(|&b| b < o).unwrap_or(0);
+
+        assert_eq!(slice.next_word_boundary(o), next, "offset {o}");
+        assert_eq!(slice.prev_word_boundary(o), prev, "offset {o}");
+    }
+
+    #[test]
+    fn words_empty() {
+        let r = Rope::new();
+        assert_eq!(r.word_bounds().next(), None);
+        assert_eq!(r.words().next_back(), None);
+        assert_eq!(r.next_word_boundary(0), 0);
+        assert_eq!(r.prev_word_boundary(0), 0);
+    }
+
+    #[test]
+    fn words_tricky() {
+        for s in TRICKY {
+            let r = Rope::from(*s);
+            assert_words(r.byte_slice(..), s);
+
+            let boundaries = boundaries(s);
+
+            for offset in 0..=s.len() {
+                assert_boundary_at(r.byte_slice(..), &boundaries, offset);
+            }
+        }
+    }
+
+    #[test]
+    fn words_matches_unicode_segmentation() {
+        for s in [SMALL, MEDIUM, CURSED_LIPSUM, TEXT_EMOJI] {
+            let r = Rope::from(s);
+            assert_words(r.byte_slice(..), s);
+        }
+    }
+
+    #[test]
+    fn words_double_ended() {
+        let mut rng = thread_rng();
+
+        for s in [SMALL, CURSED_LIPSUM, TEXT_EMOJI] {
+            let r = Rope::from(s);
+
+            let expected = s.split_word_bounds().collect::<Vec<_>>();
+
+            let mut front = Vec::new();
+            let mut back = Vec::new();
+
+            let mut bounds = r.word_bounds();
+
+            loop {
+                let next = if rng.gen() {
+                    bounds.next().map(|word| front.push(word))
+                } else {
+                    bounds.next_back().map(|word| back.push(word))
+                };
+
+                if next.is_none() {
+                    break;
+                }
+            }
+
+            assert_eq!(bounds.next(), None);
+            assert_eq!(bounds.next_back(), None);
+
+            front.extend(back.into_iter().rev());
+            assert_eq!(front, expected);
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn words_random_slices() {
+        let mut rng = thread_rng();
+
+        for s in [MEDIUM, CURSED_LIPSUM] {
+            let r = Rope::from(s);
+
+            for _ in 0..20 {
+                let Range { start, end } =
+                    random_byte_range(&mut rng, s, 5000);
+
+                let slice_str = &s[start..end];
+                let slice = r.byte_slice(start..end);
+
+                assert_words(slice, slice_str);
+
+                let boundaries = boundaries(slice_str);
+
+                for _ in 0..20 {
+                    let offset = rng.gen_range(0..=slice_str.len());
+                    assert_boundary_at(slice, &boundaries, offset);
+                }
+            }
+        }
+    }
+}
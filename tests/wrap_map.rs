@@ -0,0 +1,206 @@
+use crop::{Rope, WrapMap};
+use rand::{thread_rng, Rng};
+
+mod common;
+
+use common::{random_byte_range, CURSED_LIPSUM, MEDIUM, SMALL, TEXT_EMOJI};
+
+/// Returns the byte offsets at which an ASCII line without tabs is broken
+/// into rows `wrap_width` columns wide, placing one word at a time.
+fn naive_breaks(line: &str, wrap_width: usize) -> Vec<usize> {
+    let mut breaks = Vec::new();
+    let mut offset = 0;
+    let mut column = 0;
+
+    for piece in line.split_inclusive(|ch: char| ch.is_whitespace()) {
+        let word_len = piece.trim_end().len();
+
+        if column > 0 && column + word_len > wrap_width {
+            breaks.push(offset);
+            column = 0;
+        }
+
+        let mut word_offset = offset;
+        let mut word_left = word_len;
+
+        while column + word_left > wrap_width {
+            word_offset += wrap_width;
+            word_left -= wrap_width;
+            breaks.push(word_offset);
+        }
+
+        column += word_left + piece.len() - word_len;
+        offset += piece.len();
+    }
+
+    breaks
+}
+
+/// Checks the map against a freshly created one.
+fn assert_matches_new(map: &WrapMap, r: &Rope) {
+    let new = WrapMap::new(r, map.wrap_width(), map.tab_width());
+
+    assert_eq!(map.row_len(), new.row_len());
+
+    for row in 0..new.row_len() {
+        let byte_offset = new.byte_of_row(row);
+        assert_eq!(map.byte_of_row(row), byte_offset, "row {row}");
+        assert_eq!(map.line_of_row(row), new.line_of_row(row), "row {row}");
+        assert_eq!(map.row_of_byte(byte_offset), row);
+    }
+
+    for line in 0..=new.line_of_row(new.row_len() - 1) {
+        assert_eq!(map.rows_of_line(line), new.rows_of_line(line));
+        assert_eq!(map.row_of_line(line), new.row_of_line(line));
+    }
+}
+
+#[test]
+fn wrap_map_empty() {
+    let r = Rope::new();
+    let map = WrapMap::new(&r, 10, 4);
+
+    assert_eq!(map.row_len(), 1);
+    assert_eq!(map.rows_of_line(0), 1);
+    assert_eq!(map.byte_of_row(0), 0);
+    assert_eq!(map.row_of_byte(0), 0);
+}
+
+#[test]
+fn wrap_map_trailing_line_break() {
+    let r = Rope::from("foo\r\nbar\n");
+    let map = WrapMap::new(&r, 10, 4);
+
+    assert_eq!(map.row_len(), 3);
+    assert_eq!(map.byte_of_row(1), 5);
+    assert_eq!(map.byte_of_row(2), 9);
+    assert_eq!(map.row_of_byte(4), 0);
+    assert_eq!(map.row_of_byte(9), 2);
+    assert_eq!(map.rows_of_line(2), 1);
+}
+
+#[test]
+fn wrap_map_matches_naive() {
+    for (s, wrap_widths) in [(SMALL, [1, 5, 80]), (MEDIUM, [4, 20, 80])] {
+        let r = Rope::from(s);
+
+        for wrap_width in wrap_widths {
+            let map = WrapMap::new(&r, wrap_width, 4);
+
+            let mut row = 0;
+            let mut line_start = 0;
+
+            for (line_index, line) in s.split('\n').enumerate() {
+                let breaks = naive_breaks(line, wrap_width);
+
+                assert_eq!(map.row_of_line(line_index), row);
+                assert_eq!(map.rows_of_line(line_index), breaks.len() + 1);
+
+                for (idx, row_start) in breaks.into_iter().enumerate() {
+                    assert_eq!(
+                        map.byte_of_row(row + idx + 1),
+                        line_start + row_start,
+                        "line {line_index}, width {wrap_width}"
+                    );
+                }
+
+                row += map.rows_of_line(line_index);
+                line_start += line.len() + 1;
+            }
+
+            assert_eq!(map.row_len(), row);
+        }
+    }
+}
+
+#[test]
+fn wrap_map_set_wrap_width() {
+    let r = Rope::from(SMALL);
+
+    let mut map = WrapMap::new(&r, 10, 4);
+
+    for wrap_width in [1, 3, 40, 100] {
+        map.set_wrap_width(&r, wrap_width);
+        assert_eq!(map.wrap_width(), wrap_width);
+        assert_matches_new(&map, &r);
+    }
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn wrap_map_random_edits() {
+    let mut rng = thread_rng();
+
+    let texts = [
+        "",
+        "\n",
+        "\r\n",
+        "\t",
+        " ",
+        "foo bar",
+        "a\nb\r\nc",
+        "\n\n\n",
+        CURSED_LIPSUM,
+        TEXT_EMOJI,
+    ];
+
+    let s = SMALL.repeat(5);
+
+    for wrap_width in [3, 12, 40] {
+        let mut r = Rope::from(s.as_str());
+
+        let mut map = WrapMap::new(&r, wrap_width, 4);
+
+        for i in 0..100 {
+            let range = random_byte_range(&mut rng, &r, 30);
+
+            let text = texts[rng.gen_range(0..texts.len())];
+
+            map.replace(&mut r, range, text);
+
+            if i % 25 == 0 {
+                assert_matches_new(&map, &r);
+            }
+        }
+
+        assert_matches_new(&map, &r);
+
+        // Delete everything.
+        map.replace(&mut r, .., "");
+        assert_matches_new(&map, &r);
+        assert_eq!(map.row_len(), 1);
+    }
+}
+
+#[test]
+fn wrap_map_edit() {
+    let mut r = Rope::from(SMALL.repeat(20));
+
+    let mut map = WrapMap::new(&r, 30, 4);
+
+    let start = r.byte_of_line(100);
+    let end = r.byte_of_line(200);
+
+    r.delete(start..end);
+    map.edit(&r, start..end, 0);
+    assert_matches_new(&map, &r);
+
+    r.insert(start, "\tone two three four five six seven\n");
+    map.edit(&r, start..start, 35);
+    assert_matches_new(&map, &r);
+}
+
+#[test]
+#[should_panic]
+fn wrap_map_zero_wrap_width() {
+    let r = Rope::from("foo");
+    let _ = WrapMap::new(&r, 0, 4);
+}
+
+#[test]
+#[should_panic]
+fn wrap_map_row_out_of_bounds() {
+    let r = Rope::from("foo bar baz\n");
+    let map = WrapMap::new(&r, 8, 4);
+    let _ = map.byte_of_row(3);
+}